@@ -0,0 +1,26 @@
+//! Criterion benchmark for no-guess board generation.
+//!
+//! Times `solver::bench_generate()` on seeded boards at the three classic
+//! sizes, the same harness the tests bound loosely, so the numbers here and
+//! in CI measure one code path. Seeds start at 0 in every sample, so each
+//! measurement covers the same boards.
+//!
+//! Run with `cargo bench --bench generate`.
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use solver_wasm::solver::bench_generate;
+
+fn generate(c: &mut Criterion) {
+    let mut group = c.benchmark_group("generate");
+    // Expert boards take long enough that the default 100 samples drag on
+    group.sample_size(10);
+    for (name, width, height, bombs) in [("beginner", 9, 9, 10), ("intermediate", 16, 16, 40), ("expert", 30, 16, 99)] {
+        group.bench_function(name, |b| {
+            b.iter_custom(|iters| bench_generate(width, height, bombs, 0, iters as u32));
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, generate);
+criterion_main!(benches);
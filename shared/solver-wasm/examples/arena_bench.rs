@@ -0,0 +1,75 @@
+//! Allocation benchmark for `SolverArena`.
+//!
+//! Runs `exact_mine_probabilities()` repeatedly on one fixed mid-game
+//! position, once allocating fresh buffers per call and once reusing an
+//! arena, and reports the heap allocations and wall time of each.
+//!
+//! Run with `cargo run --release --example arena_bench`.
+
+use solver_wasm::arena::SolverArena;
+use solver_wasm::board;
+use solver_wasm::probability::exact_mine_probabilities;
+use solver_wasm::rng::WasmRng;
+use solver_wasm::solver::{SolveState, SolverConfig};
+use solver_wasm::types::NeighborCache;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
+
+struct CountingAlloc;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+const CALLS: usize = 500;
+
+fn main() {
+    let (width, height, bombs) = (30, 16, 99);
+    let nc = NeighborCache::new(width, height);
+    let mut rng = WasmRng::from_seed(1);
+    let result = board::generate_solvable_board_with_rng(
+        width, height, bombs, 15, 8, 1, 0, 1, &nc, &mut rng, |_, _, _, _, _| true,
+    );
+    // Basic rules only, so the stall leaves a wide frontier
+    let config = SolverConfig { max_strategy_level: 1, ..SolverConfig::default() };
+    let mut state = SolveState::from_start(&result.grid, &nc, 15, 8).with_config(config);
+    state.run_to_stall(&result.grid, &nc, bombs);
+    println!("position: {} revealed, {} flagged", state.revealed_count(), state.flag_count);
+
+    let run = |label: &str, arena: &mut Option<SolverArena>| {
+        let before = ALLOCATIONS.load(Ordering::Relaxed);
+        let start = Instant::now();
+        for _ in 0..CALLS {
+            let probs = exact_mine_probabilities(&state.visible, &state.flags, &nc, bombs, true, None, arena.as_mut());
+            std::hint::black_box(probs);
+        }
+        let allocations = ALLOCATIONS.load(Ordering::Relaxed) - before;
+        println!(
+            "{:>8}: {:>6.1} allocations/call, {:>8.1?}/call",
+            label,
+            allocations as f64 / CALLS as f64,
+            start.elapsed() / CALLS as u32,
+        );
+    };
+
+    run("fresh", &mut None);
+    run("arena", &mut Some(SolverArena::new()));
+}
@@ -0,0 +1,51 @@
+//! Timing benchmark for the subset strategy.
+//!
+//! Solves a fixed set of seeded intermediate boards with strategies capped at
+//! Subset, sharing one neighbor cache the way the generators do, so most of
+//! the time goes to the pairwise clue comparisons in `apply_subset_logic()`.
+//!
+//! Run with `cargo run --release --example subset_bench`.
+
+use solver_wasm::board;
+use solver_wasm::rng::WasmRng;
+use solver_wasm::solver::{Strategy, SolveState, SolverConfig};
+use solver_wasm::types::NeighborCache;
+use std::time::{Duration, Instant};
+
+const BOARDS: u64 = 500;
+const ROUNDS: usize = 10;
+
+fn main() {
+    let (width, height, bombs) = (16, 16, 40);
+    let nc = NeighborCache::new(width, height);
+    let boards: Vec<_> = (0..BOARDS)
+        .map(|seed| {
+            let mut rng = WasmRng::from_seed(seed);
+            board::generate_solvable_board_with_rng(
+                width, height, bombs, 8, 8, 2, 0, 1, &nc, &mut rng, |_, _, _, _, _| true,
+            )
+        })
+        .collect();
+
+    let config = SolverConfig { max_strategy_level: Strategy::Subset as u8, ..SolverConfig::default() };
+    // Report the fastest round; the others mostly measure scheduler noise
+    let mut best = Duration::MAX;
+    let mut subset_hits = 0;
+    for _ in 0..ROUNDS {
+        let start = Instant::now();
+        subset_hits = 0;
+        for result in &boards {
+            let mut state = SolveState::from_start(&result.grid, &nc, 8, 8).with_config(config.clone());
+            state.run_to_stall(&result.grid, &nc, bombs);
+            subset_hits += state.metrics.successes_of(Strategy::Subset);
+        }
+        best = best.min(start.elapsed());
+    }
+    println!(
+        "{} boards, {:.1} subset deductions/board, {:>8.1?}/board (best of {} rounds)",
+        BOARDS,
+        subset_hits as f64 / BOARDS as f64,
+        best / BOARDS as u32,
+        ROUNDS,
+    );
+}
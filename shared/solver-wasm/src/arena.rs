@@ -0,0 +1,39 @@
+//! Reusable scratch buffers for the frontier, tank and probability code.
+//!
+//! Each solver pass builds the same short-lived sets and vectors: the
+//! frontier, region BFS bookkeeping, constraint lookups and the enumerated
+//! configuration masks. A `SolverArena` keeps those allocations alive between
+//! calls so per-frame callers (`ProbabilitySession`, autoplay loops) only pay
+//! for them once. Functions take it as `Option<&mut SolverArena>`; `None`
+//! uses a fresh, empty arena, which allocates exactly as before.
+
+use std::collections::{HashMap, HashSet};
+
+/// Scratch buffers borrowed by the solver internals. Every user clears what
+/// it takes before use, so the contents between calls are meaningless.
+#[derive(Default)]
+pub struct SolverArena {
+    /// Frontier cells of the current position.
+    pub(crate) frontier: Vec<(usize, usize)>,
+    /// BFS stack for region grouping.
+    pub(crate) queue: Vec<(usize, usize)>,
+    /// Cells already placed in a region (grouping).
+    pub(crate) visited: HashSet<u32>,
+    /// Frontier membership (grouping).
+    pub(crate) members: HashSet<u32>,
+    /// Cells already queued (grouping) or clues already read (constraints).
+    pub(crate) seen: HashSet<u32>,
+    /// Region cell key to its index (constraints).
+    pub(crate) index_of: HashMap<u32, usize>,
+    /// Valid configuration masks of the region being enumerated.
+    pub(crate) masks: Vec<u32>,
+    /// Deduplication of the cells Gaussian elimination proved safe / mines.
+    pub(crate) safe_keys: HashSet<u32>,
+    pub(crate) mine_keys: HashSet<u32>,
+}
+
+impl SolverArena {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
@@ -4,36 +4,108 @@
 //! The key function is `generate_solvable_board()` which runs the entire
 //! retry loop inside WASM for maximum performance.
 
-use crate::rng::WasmRng;
-use crate::types::{Grid, Mines, NeighborCache};
+use crate::rng::{BoardRng, WasmRng};
+use crate::solver::{reveal_with, simulate_reveal};
+use crate::types::{board_cell_count, Blocked, Flags, BLOCKED, Grid, GridError, Mines, NeighborCache, NeighborWeights, SafeRect, SafeZone, VisibleGrid, MAX_BOARD_CELLS};
 
-/// Place mines randomly with a safe zone exclusion.
+/// Place mines randomly with safe zone exclusions.
 ///
 /// Mirrors the JS `placeMines()` inner loop: randomly place `bomb_count` mines,
-/// skipping cells within `safe_radius` of `(safe_x, safe_y)`.
-pub fn place_mines_random(
+/// skipping cells inside any of `safe_zones`.
+///
+/// `border_buffer` widens every zone by that many extra rings, so no mine
+/// touches the zone's edge and the opening cascade stays clean; 0 keeps the
+/// zones as given.
+///
+/// Fails if the random draws run out before all `bomb_count` mines are down,
+/// which happens when the free cells are nearly all needed.
+pub fn place_mines_random<R: BoardRng>(
     width: usize,
     height: usize,
     bomb_count: usize,
-    safe_x: usize,
-    safe_y: usize,
-    safe_radius: usize,
-    rng: &mut WasmRng,
-) -> Mines {
+    safe_zones: &[SafeZone],
+    border_buffer: usize,
+    rng: &mut R,
+) -> Result<Mines, String> {
+    let mut mines = Mines::new(width, height);
+    let rects: Vec<SafeRect> = safe_zones.iter()
+        .map(|z| SafeZone::new(z.x, z.y, z.radius.saturating_add(border_buffer)).into())
+        .collect();
+    fill_random_mines(&mut mines, bomb_count, &rects, None, rng)?;
+    Ok(mines)
+}
+
+/// `place_mines_random()` with a rectangular exclusion zone.
+pub fn place_mines_outside_rect<R: BoardRng>(
+    width: usize,
+    height: usize,
+    bomb_count: usize,
+    safe_rect: &SafeRect,
+    rng: &mut R,
+) -> Result<Mines, String> {
+    let mut mines = Mines::new(width, height);
+    fill_random_mines(&mut mines, bomb_count, std::slice::from_ref(safe_rect), None, rng)?;
+    Ok(mines)
+}
+
+/// Place mines at every cell in `fixed`, then `bomb_count - fixed.len()` more
+/// at random outside `safe_zone` and off the fixed cells.
+///
+/// Used for themed levels where some mines form a shape. Callers should run
+/// `validate_template()` first; duplicates in `fixed` count once. Fails like
+/// `place_mines_random()` when the random mines cannot all be placed.
+pub fn place_mines_with_template<R: BoardRng>(
+    width: usize,
+    height: usize,
+    bomb_count: usize,
+    fixed: &[(usize, usize)],
+    safe_zone: &SafeZone,
+    rng: &mut R,
+) -> Result<Mines, String> {
     let mut mines = Mines::new(width, height);
-    let mut placed = 0;
+    for &(x, y) in fixed {
+        mines.set(x, y, true);
+    }
+    fill_random_mines(&mut mines, bomb_count, &[SafeRect::from(*safe_zone)], None, rng)?;
+    Ok(mines)
+}
+
+/// `place_mines_random()` for an irregular board: no mine lands on a
+/// `blocked` cell or in `safe_zone`.
+pub fn place_mines_around_blocked<R: BoardRng>(
+    blocked: &Blocked,
+    bomb_count: usize,
+    safe_zone: &SafeZone,
+    rng: &mut R,
+) -> Result<Mines, String> {
+    let mut mines = Mines::new(blocked.width, blocked.height);
+    fill_random_mines(&mut mines, bomb_count, &[SafeRect::from(*safe_zone)], Some(blocked), rng)?;
+    Ok(mines)
+}
+
+/// Randomly add mines until `mines` holds `bomb_count`, skipping `safe_zones`
+/// and any `blocked` cells.
+///
+/// Gives up after a fixed number of draws rather than looping forever on a
+/// board with too few free cells.
+fn fill_random_mines<R: BoardRng>(
+    mines: &mut Mines,
+    bomb_count: usize,
+    safe_zones: &[SafeRect],
+    blocked: Option<&Blocked>,
+    rng: &mut R,
+) -> Result<(), String> {
+    let mut placed = mines.count();
     let mut attempts = 0;
     let max_placement_attempts = 100_000;
 
     while placed < bomb_count && attempts < max_placement_attempts {
         attempts += 1;
-        let x = rng.gen_range(width);
-        let y = rng.gen_range(height);
+        let x = rng.gen_range(mines.width);
+        let y = rng.gen_range(mines.height);
 
-        // Check exclusion zone
-        let dx = if x >= safe_x { x - safe_x } else { safe_x - x };
-        let dy = if y >= safe_y { y - safe_y } else { safe_y - y };
-        if dx <= safe_radius && dy <= safe_radius {
+        // Check exclusion zones
+        if safe_zones.iter().any(|z| z.contains(x, y)) || blocked.is_some_and(|b| b.get(x, y)) {
             continue;
         }
 
@@ -43,13 +115,222 @@ pub fn place_mines_random(
         }
     }
 
-    mines
+    if placed < bomb_count {
+        return Err(format!("Could only place {} of {} mines", placed, bomb_count));
+    }
+    Ok(())
+}
+
+/// Count the in-bounds cells covered by the safe zone around `(safe_x, safe_y)`.
+///
+/// Uses the same Chebyshev-distance test as `place_mines_random()`.
+pub fn safe_zone_size(
+    width: usize,
+    height: usize,
+    safe_x: usize,
+    safe_y: usize,
+    safe_radius: usize,
+) -> usize {
+    let x_min = safe_x.saturating_sub(safe_radius);
+    let x_max = safe_x.saturating_add(safe_radius).min(width.saturating_sub(1));
+    let y_min = safe_y.saturating_sub(safe_radius);
+    let y_max = safe_y.saturating_add(safe_radius).min(height.saturating_sub(1));
+    if width == 0 || height == 0 || x_min > x_max || y_min > y_max {
+        return 0;
+    }
+    (x_max - x_min + 1) * (y_max - y_min + 1)
+}
+
+/// Validate generation parameters before any mines are placed.
+///
+/// Returns a human-readable error when the request can never produce a valid
+/// board — most importantly when `bomb_count` cannot fit outside the safe zone.
+pub fn validate_board_params(
+    width: usize,
+    height: usize,
+    bomb_count: usize,
+    safe_x: usize,
+    safe_y: usize,
+    safe_radius: usize,
+) -> Result<(), String> {
+    if width == 0 || height == 0 {
+        return Err(format!("Invalid board dimensions {}x{}", width, height));
+    }
+    if board_cell_count(width, height).is_none() {
+        return Err(format!(
+            "Board dimensions {}x{} exceed the {}-cell limit",
+            width, height, MAX_BOARD_CELLS
+        ));
+    }
+    if safe_x >= width || safe_y >= height {
+        return Err(format!(
+            "Safe cell ({}, {}) is outside the {}x{} board",
+            safe_x, safe_y, width, height
+        ));
+    }
+
+    let available = width * height - safe_zone_size(width, height, safe_x, safe_y, safe_radius);
+    if bomb_count > available {
+        return Err(format!(
+            "Cannot place {} mines: only {} cells lie outside the safe zone",
+            bomb_count, available
+        ));
+    }
+
+    Ok(())
+}
+
+/// Validate irregular-board generation parameters: the start cell must be
+/// playable and `bomb_count` must fit in the cells that are neither blocked
+/// nor in the safe zone.
+pub fn validate_blocked_board(blocked: &Blocked, bomb_count: usize, safe_zone: &SafeZone) -> Result<(), String> {
+    let (width, height) = (blocked.width, blocked.height);
+    validate_board_params(width, height, bomb_count, safe_zone.x, safe_zone.y, safe_zone.radius)?;
+    if blocked.cells.len() != width * height {
+        return Err(format!("Blocked mask has {} cells, expected {}", blocked.cells.len(), width * height));
+    }
+    if blocked.get(safe_zone.x, safe_zone.y) {
+        return Err(format!("Safe cell ({}, {}) is blocked", safe_zone.x, safe_zone.y));
+    }
+
+    let zone = SafeRect::from(*safe_zone);
+    let available = (0..width * height)
+        .filter(|&i| blocked.cells[i] == 0 && !zone.contains(i / height, i % height))
+        .count();
+    if bomb_count > available {
+        return Err(format!(
+            "Cannot place {} mines: only {} unblocked cells lie outside the safe zone",
+            bomb_count, available
+        ));
+    }
+    Ok(())
+}
+
+/// Validate multi-start generation parameters: every zone center must be on
+/// the board and `bomb_count` must fit outside the union of the zones.
+pub fn validate_safe_zones(
+    width: usize,
+    height: usize,
+    bomb_count: usize,
+    safe_zones: &[SafeZone],
+) -> Result<(), String> {
+    if width == 0 || height == 0 {
+        return Err(format!("Invalid board dimensions {}x{}", width, height));
+    }
+    if board_cell_count(width, height).is_none() {
+        return Err(format!(
+            "Board dimensions {}x{} exceed the {}-cell limit",
+            width, height, MAX_BOARD_CELLS
+        ));
+    }
+    if safe_zones.is_empty() {
+        return Err("At least one safe zone is required".to_string());
+    }
+    for zone in safe_zones {
+        if zone.x >= width || zone.y >= height {
+            return Err(format!(
+                "Safe cell ({}, {}) is outside the {}x{} board",
+                zone.x, zone.y, width, height
+            ));
+        }
+    }
+
+    let mut available = 0;
+    for x in 0..width {
+        for y in 0..height {
+            if !safe_zones.iter().any(|z| z.contains(x, y)) {
+                available += 1;
+            }
+        }
+    }
+    if bomb_count > available {
+        return Err(format!(
+            "Cannot place {} mines: only {} cells lie outside the safe zones",
+            bomb_count, available
+        ));
+    }
+
+    Ok(())
+}
+
+/// Validate rectangular safe-zone parameters: the rectangle must lie on the
+/// board and `bomb_count` must fit outside it.
+pub fn validate_safe_rect(
+    width: usize,
+    height: usize,
+    bomb_count: usize,
+    safe_rect: &SafeRect,
+) -> Result<(), String> {
+    if width == 0 || height == 0 {
+        return Err(format!("Invalid board dimensions {}x{}", width, height));
+    }
+    let Some(total) = board_cell_count(width, height) else {
+        return Err(format!(
+            "Board dimensions {}x{} exceed the {}-cell limit",
+            width, height, MAX_BOARD_CELLS
+        ));
+    };
+    if safe_rect.x1 >= width || safe_rect.y1 >= height {
+        return Err(format!(
+            "Safe rectangle ({}, {})-({}, {}) extends outside the {}x{} board",
+            safe_rect.x0, safe_rect.y0, safe_rect.x1, safe_rect.y1, width, height
+        ));
+    }
+
+    let covered = (safe_rect.x1 - safe_rect.x0 + 1) * (safe_rect.y1 - safe_rect.y0 + 1);
+    let available = total - covered;
+    if bomb_count > available {
+        return Err(format!(
+            "Cannot place {} mines: only {} cells lie outside the safe rectangle",
+            bomb_count, available
+        ));
+    }
+
+    Ok(())
+}
+
+/// Validate templated generation parameters: the board and safe zone as in
+/// `validate_board_params()`, plus every fixed mine in bounds, outside the
+/// safe zone and listed once, with the random remainder fitting elsewhere.
+pub fn validate_template(
+    width: usize,
+    height: usize,
+    bomb_count: usize,
+    fixed: &[(usize, usize)],
+    safe_zone: &SafeZone,
+) -> Result<(), String> {
+    validate_board_params(width, height, bomb_count, safe_zone.x, safe_zone.y, safe_zone.radius)?;
+    if fixed.len() > bomb_count {
+        return Err(format!(
+            "{} fixed mines exceed the mine count of {}",
+            fixed.len(), bomb_count
+        ));
+    }
+
+    let mut seen = Mines::new(width, height);
+    for &(x, y) in fixed {
+        if x >= width || y >= height {
+            return Err(format!("Fixed mine ({}, {}) is outside the {}x{} board", x, y, width, height));
+        }
+        if safe_zone.contains(x, y) {
+            return Err(format!("Fixed mine ({}, {}) lies inside the safe zone", x, y));
+        }
+        if seen.get(x, y) {
+            return Err(format!("Fixed mine ({}, {}) is listed twice", x, y));
+        }
+        seen.set(x, y, true);
+    }
+
+    // validate_board_params already checked that bomb_count fits outside the
+    // safe zone, and fixed mines all lie outside it, so the remainder fits too
+    Ok(())
 }
 
 /// Calculate the neighbor mine counts for all non-mine cells.
 ///
 /// Returns a Grid where each non-mine cell contains the count of adjacent mines (0-8).
 /// Mine cells retain value 0 (the grid value for mine cells is not used by the solver).
+/// Cells blocked in the cache (`NeighborCache::with_blocked()`) get `BLOCKED`.
 pub fn calculate_numbers(mines: &Mines, neighbor_cache: &NeighborCache) -> Grid {
     let width = mines.width;
     let height = mines.height;
@@ -57,6 +338,10 @@ pub fn calculate_numbers(mines: &Mines, neighbor_cache: &NeighborCache) -> Grid
 
     for x in 0..width {
         for y in 0..height {
+            if neighbor_cache.is_blocked(x, y) {
+                grid.set(x, y, BLOCKED);
+                continue;
+            }
             if mines.get(x, y) {
                 continue;
             }
@@ -74,148 +359,2568 @@ pub fn calculate_numbers(mines: &Mines, neighbor_cache: &NeighborCache) -> Grid
     grid
 }
 
-/// Result of a board generation attempt.
-pub struct BoardResult {
-    /// Mine positions (flat, column-major).
-    pub mines: Mines,
-    /// Number grid (flat, column-major). Non-mine cells have values 0-8.
-    pub grid: Grid,
-    /// How many random layouts were tried before finding a solvable one.
-    pub attempts: u32,
-    /// True if a solvable board was found within max_attempts.
-    pub success: bool,
+/// True if every clue value 1..=8 appears on at least one non-mine cell.
+///
+/// Acceptance predicate for "sampler" boards. 7s and 8s are rare at typical
+/// densities, so requiring this raises generation attempt counts substantially.
+pub fn has_full_clue_spectrum(grid: &Grid, mines: &Mines) -> bool {
+    let mut seen = [false; 9];
+    for (i, &v) in grid.cells.iter().enumerate() {
+        if mines.cells[i] == 0 && (1..=8).contains(&v) {
+            seen[v as usize] = true;
+        }
+    }
+    seen[1..].iter().all(|&s| s)
 }
 
-/// Generate a solvable board by repeatedly placing mines and checking solvability.
-///
-/// This is the main entry point that replaces the entire `do { ... } while (!isSolvable)`
-/// loop from JS `Game.placeMines()`. Running the whole loop inside WASM avoids
-/// per-iteration JS↔WASM boundary crossings.
+/// Number of distinct clue values 1..=8 exposed by the opening position: every
+/// cell of the `safe_radius` zone around `(start_x, start_y)` revealed with
+/// cascade, as `SolveState::from_zones()` starts the solver.
 ///
-/// The `is_solvable_fn` parameter allows injecting the solver (which lives in solver.rs).
-/// This keeps board.rs decoupled from the solver implementation.
-pub fn generate_solvable_board<F>(
-    width: usize,
-    height: usize,
-    bomb_count: usize,
-    safe_x: usize,
-    safe_y: usize,
+/// Acceptance predicate for tutorial boards whose opening should show a variety
+/// of clues. The zone must be mine-free, as board generation guarantees.
+pub fn opening_clue_variety(
+    grid: &Grid,
+    neighbor_cache: &NeighborCache,
+    start_x: usize,
+    start_y: usize,
     safe_radius: usize,
-    max_attempts: u32,
+) -> usize {
+    let mut visible = VisibleGrid::new(grid.width, grid.height);
+    let flags = Flags::new(grid.width, grid.height);
+    let mut seen = [false; 9];
+    let zone = SafeRect::from(SafeZone::new(start_x, start_y, safe_radius));
+    for x in zone.x0..=zone.x1.min(grid.width.saturating_sub(1)) {
+        for y in zone.y0..=zone.y1.min(grid.height.saturating_sub(1)) {
+            reveal_with(grid, &mut visible, &flags, neighbor_cache, x, y, |cx, cy| {
+                let v = grid.get(cx, cy);
+                if (1..=8).contains(&v) {
+                    seen[v as usize] = true;
+                }
+            });
+        }
+    }
+    seen.iter().filter(|&&s| s).count()
+}
+
+/// Total of the clue numbers over every non-mine cell: the score of a fully
+/// cleared board in the "number golf" variant.
+pub fn total_clue_sum(grid: &Grid, mines: &Mines) -> u32 {
+    grid.cells.iter().zip(&mines.cells)
+        .filter(|&(&v, &m)| m == 0 && v > 0)
+        .map(|(&v, _)| v as u32)
+        .sum()
+}
+
+/// `calculate_numbers()` for the weighted-clue variant: each adjacent mine
+/// adds `diagonal_weight` or `orthogonal_weight` (see `NeighborWeights`), so
+/// clues can exceed 8. Sums past `i8::MAX` saturate.
+pub fn calculate_numbers_weighted(
+    mines: &Mines,
     neighbor_cache: &NeighborCache,
-    is_solvable_fn: F,
-) -> BoardResult
-where
-    F: Fn(&Grid, &Mines, &NeighborCache, usize, usize) -> bool,
-{
-    let mut rng = WasmRng::new();
-    let mut attempts: u32 = 0;
+    diagonal_weight: u8,
+    orthogonal_weight: u8,
+) -> Grid {
+    let weights = NeighborWeights::new(neighbor_cache, diagonal_weight, orthogonal_weight);
+    let mut grid = Grid::new(mines.width, mines.height);
+    for x in 0..mines.width {
+        for y in 0..mines.height {
+            if mines.get(x, y) {
+                continue;
+            }
+            let sum: u32 = neighbor_cache.get(x, y).iter()
+                .zip(weights.get(x, y))
+                .filter(|(&(nx, ny), _)| mines.get(nx, ny))
+                .map(|(_, &w)| w as u32)
+                .sum();
+            grid.set(x, y, sum.min(i8::MAX as u32) as i8);
+        }
+    }
+    grid
+}
 
-    loop {
-        attempts += 1;
+/// Cells whose value in `grid` disagrees with the neighbor count computed
+/// from `mines`, in column-major order. Mine cells are ignored, so a grid
+/// that stores them as -1 (or anything else) still verifies.
+///
+/// Catches corrupt imports of boards that arrive with their numbers.
+pub fn verify_numbers(grid: &Grid, mines: &Mines, neighbor_cache: &NeighborCache) -> Vec<(usize, usize)> {
+    let expected = calculate_numbers(mines, neighbor_cache);
+    let mut mismatches = Vec::new();
+    for x in 0..mines.width {
+        for y in 0..mines.height {
+            if !mines.get(x, y) && grid.get(x, y) != expected.get(x, y) {
+                mismatches.push((x, y));
+            }
+        }
+    }
+    mismatches
+}
 
-        let mines = place_mines_random(width, height, bomb_count, safe_x, safe_y, safe_radius, &mut rng);
-        let grid = calculate_numbers(&mines, neighbor_cache);
+/// Cells whose value would change if the mine at `from` moved to `to`, in
+/// column-major order, without moving it. That is `from` and `to`
+/// themselves plus every cell next to exactly one of them; a cell next to
+/// both loses one mine and gains one, so its clue stays the same.
+///
+/// Empty when the move is not one: `from` holds no mine, `to` already does,
+/// or either is off the board.
+pub fn mine_move_impact(
+    mines: &Mines,
+    neighbor_cache: &NeighborCache,
+    from: (usize, usize),
+    to: (usize, usize),
+) -> Vec<(usize, usize)> {
+    if !mines.in_bounds(from.0, from.1) || !mines.in_bounds(to.0, to.1)
+        || !mines.get(from.0, from.1) || mines.get(to.0, to.1)
+    {
+        return Vec::new();
+    }
+    let from_ring = neighbor_cache.get(from.0, from.1);
+    let to_ring = neighbor_cache.get(to.0, to.1);
+    let mut cells: Vec<(usize, usize)> = from_ring.iter()
+        .filter(|n| !to_ring.contains(n))
+        .chain(to_ring.iter().filter(|n| !from_ring.contains(n)))
+        .copied()
+        .chain([from, to])
+        .collect();
+    cells.sort_unstable();
+    cells.dedup();
+    cells
+}
 
-        if is_solvable_fn(&grid, &mines, neighbor_cache, safe_x, safe_y) {
-            return BoardResult {
-                mines,
-                grid,
-                attempts,
-                success: true,
-            };
+/// `calculate_numbers()` on a torus: clues count mines across the wrapped
+/// edges. Builds the toroidal neighbor cache internally.
+pub fn calculate_numbers_toroidal(mines: &Mines) -> Grid {
+    calculate_numbers(mines, &NeighborCache::new_toroidal(mines.width, mines.height))
+}
+
+/// Compute the 3BV (Bechtel's Board Benchmark Value) of a board.
+///
+/// The minimum number of clicks needed to clear the board without chording:
+/// one per zero-cascade opening, plus one per numbered cell that no opening
+/// reveals.
+pub fn compute_3bv(grid: &Grid, mines: &Mines, neighbor_cache: &NeighborCache) -> u32 {
+    let width = grid.width;
+    let height = grid.height;
+    let flags = Flags::new(width, height);
+    let mut visible = VisibleGrid::new(width, height);
+    let mut clicks = 0u32;
+
+    // Each unrevealed zero starts a new opening
+    for x in 0..width {
+        for y in 0..height {
+            if !mines.get(x, y) && grid.get(x, y) == 0 && visible.get(x, y) == -1 {
+                simulate_reveal(grid, &mut visible, &flags, neighbor_cache, x, y);
+                clicks += 1;
+            }
         }
+    }
 
-        if attempts >= max_attempts {
-            // Return the last attempted board (not solvable, but best effort)
-            return BoardResult {
-                mines,
-                grid,
-                attempts,
-                success: false,
-            };
+    // Numbered cells not touched by any opening need their own click;
+    // blocked cells stay hidden but need none
+    for x in 0..width {
+        for y in 0..height {
+            if !mines.get(x, y) && visible.get(x, y) == -1 && !neighbor_cache.is_blocked(x, y) {
+                clicks += 1;
+            }
         }
     }
+
+    clicks
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/// Label every cell with the zero-opening that reveals it.
+///
+/// Openings are numbered from 0 in column-major order of their first zero.
+/// Each id covers the connected zeros plus the numbered cells bordering them;
+/// a number bordering two openings keeps the lower id. All other cells are -1.
+pub fn opening_regions(grid: &Grid, mines: &Mines, neighbor_cache: &NeighborCache) -> Vec<i32> {
+    let width = grid.width;
+    let height = grid.height;
+    let flags = Flags::new(width, height);
+    let mut visible = VisibleGrid::new(width, height);
+    let mut regions = vec![-1i32; width * height];
+    let mut next_id = 0;
 
-    #[test]
-    fn test_place_mines_count() {
-        let mut rng = WasmRng::from_seed(42);
-        let mines = place_mines_random(30, 16, 99, 15, 8, 1, &mut rng);
-        assert_eq!(mines.count(), 99);
+    for x in 0..width {
+        for y in 0..height {
+            if mines.get(x, y) || grid.get(x, y) != 0 || visible.get(x, y) != -1 { continue; }
+            reveal_with(grid, &mut visible, &flags, neighbor_cache, x, y, |cx, cy| regions[cx * height + cy] = next_id);
+            next_id += 1;
+        }
     }
 
-    #[test]
-    fn test_place_mines_safe_zone() {
-        let mut rng = WasmRng::from_seed(42);
-        let mines = place_mines_random(10, 10, 20, 5, 5, 2, &mut rng);
+    regions
+}
 
-        // No mines within radius 2 of (5,5)
-        for x in 3..=7 {
-            for y in 3..=7 {
-                assert!(!mines.get(x, y), "Mine found in safe zone at ({}, {})", x, y);
+/// Zero cells whose whole neighborhood is zero too: the interior of the
+/// openings, where a first click is sure to cascade. Column-major order.
+///
+/// Needs no mine layout. `calculate_numbers()` leaves mines at 0, but a mine
+/// seen only by zeros would need every neighbor to be a mine as well, so
+/// only a board packed with mines can slip one in.
+pub fn forced_cascade_cells(grid: &Grid, neighbor_cache: &NeighborCache) -> Vec<(usize, usize)> {
+    let mut cells = Vec::new();
+    for x in 0..grid.width {
+        for y in 0..grid.height {
+            let neighbors = neighbor_cache.get(x, y);
+            if grid.get(x, y) == 0 && !neighbors.is_empty() && neighbors.iter().all(|&(nx, ny)| grid.get(nx, ny) == 0) {
+                cells.push((x, y));
             }
         }
-        assert_eq!(mines.count(), 20);
     }
+    cells
+}
 
-    #[test]
-    fn test_calculate_numbers_simple() {
-        let nc = NeighborCache::new(3, 3);
-        let mut mines = Mines::new(3, 3);
-        // Place a single mine at (1, 1) center
-        mines.set(1, 1, true);
+/// Histogram of first-click reveal sizes over every safe cell: `(size, cells)`
+/// pairs, by increasing size, where `cells` clicks would each reveal `size` cells.
+///
+/// A numbered cell reveals itself; a zero reveals its whole opening, the
+/// connected zeros plus every number bordering them, as `simulate_reveal()`
+/// would on a fresh board. Each opening is walked once and its size shared by
+/// all its zeros, so this stays linear in the board size. Needs the mine
+/// layout since mines read 0 in the grid; blocked cells are skipped.
+pub fn opening_size_distribution(grid: &Grid, mines: &Mines, neighbor_cache: &NeighborCache) -> Vec<(usize, u32)> {
+    let height = grid.height;
+    let n = grid.cells.len();
+    let is_zero = |i: usize| mines.cells[i] == 0 && grid.cells[i] == 0;
 
-        let grid = calculate_numbers(&mines, &nc);
+    // opening_of[i]: index into sizes for zero cells; border_seen stamps
+    // numbered cells with the last opening that counted them
+    let mut opening_of = vec![usize::MAX; n];
+    let mut border_seen = vec![usize::MAX; n];
+    let mut sizes: Vec<usize> = Vec::new();
+    let mut stack = Vec::new();
+    for start in 0..n {
+        if !is_zero(start) || opening_of[start] != usize::MAX { continue; }
+        let id = sizes.len();
+        let mut size = 0;
+        opening_of[start] = id;
+        stack.push(start);
+        while let Some(i) = stack.pop() {
+            size += 1;
+            for &(nx, ny) in neighbor_cache.get(i / height, i % height) {
+                let j = nx * height + ny;
+                if is_zero(j) {
+                    if opening_of[j] == usize::MAX {
+                        opening_of[j] = id;
+                        stack.push(j);
+                    }
+                } else if border_seen[j] != id {
+                    border_seen[j] = id;
+                    size += 1;
+                }
+            }
+        }
+        sizes.push(size);
+    }
 
-        // All 8 neighbors should be 1
-        assert_eq!(grid.get(0, 0), 1);
-        assert_eq!(grid.get(0, 1), 1);
-        assert_eq!(grid.get(0, 2), 1);
-        assert_eq!(grid.get(1, 0), 1);
-        assert_eq!(grid.get(1, 2), 1);
-        assert_eq!(grid.get(2, 0), 1);
-        assert_eq!(grid.get(2, 1), 1);
-        assert_eq!(grid.get(2, 2), 1);
+    let mut histogram: std::collections::BTreeMap<usize, u32> = std::collections::BTreeMap::new();
+    for i in 0..n {
+        if mines.cells[i] != 0 || grid.cells[i] == BLOCKED { continue; }
+        let size = if is_zero(i) { sizes[opening_of[i]] } else { 1 };
+        *histogram.entry(size).or_insert(0) += 1;
     }
+    histogram.into_iter().collect()
+}
 
-    #[test]
-    fn test_calculate_numbers_corner_mine() {
-        let nc = NeighborCache::new(3, 3);
-        let mut mines = Mines::new(3, 3);
-        // Mine at corner (0, 0)
-        mines.set(0, 0, true);
+/// Pick the start cell whose safe-zone reveal opens the most cells.
+///
+/// Candidates are cells whose whole 3×3 safe zone is mine-free, matching the
+/// reveal `is_solvable()` performs. Each candidate's opening is simulated on a
+/// fresh visible grid. Ties go to the first candidate in column-major order.
+/// If no cell has a mine-free zone, falls back to the best non-mine cell.
+pub fn best_start_position(mines: &Mines, grid: &Grid, neighbor_cache: &NeighborCache) -> (usize, usize) {
+    let width = grid.width;
+    let height = grid.height;
+    let flags = Flags::new(width, height);
 
-        let grid = calculate_numbers(&mines, &nc);
+    let mut best = (0, 0);
+    let mut best_score: Option<(bool, usize)> = None;
 
-        // (1,0), (0,1), (1,1) should be 1, others 0
-        assert_eq!(grid.get(1, 0), 1);
-        assert_eq!(grid.get(0, 1), 1);
-        assert_eq!(grid.get(1, 1), 1);
-        assert_eq!(grid.get(2, 0), 0);
-        assert_eq!(grid.get(2, 2), 0);
-    }
+    for x in 0..width {
+        for y in 0..height {
+            if mines.get(x, y) { continue; }
+            let zone_safe = neighbor_cache.get(x, y).iter().all(|&(nx, ny)| !mines.get(nx, ny));
 
-    #[test]
-    fn test_generate_solvable_board_always_solvable() {
-        let nc = NeighborCache::new(5, 5);
-        // Trivial solver that always returns true
-        let result = generate_solvable_board(5, 5, 3, 2, 2, 1, 100, &nc, |_, _, _, _, _| true);
-        assert!(result.success);
-        assert_eq!(result.attempts, 1); // Should succeed on first try
-        assert_eq!(result.mines.count(), 3);
+            let mut visible = VisibleGrid::new(width, height);
+            simulate_reveal(grid, &mut visible, &flags, neighbor_cache, x, y);
+            if zone_safe {
+                for &(nx, ny) in neighbor_cache.get(x, y) {
+                    simulate_reveal(grid, &mut visible, &flags, neighbor_cache, nx, ny);
+                }
+            }
+            let revealed = visible.cells.iter().filter(|&&v| v != -1).count();
+
+            // Mine-free zones always beat fallback candidates
+            let score = (zone_safe, revealed);
+            if best_score.is_none_or(|b| score > b) {
+                best_score = Some(score);
+                best = (x, y);
+            }
+        }
     }
 
-    #[test]
-    fn test_generate_solvable_board_never_solvable() {
-        let nc = NeighborCache::new(5, 5);
-        // Solver that always returns false
-        let result = generate_solvable_board(5, 5, 3, 2, 2, 1, 10, &nc, |_, _, _, _, _| false);
-        assert!(!result.success);
-        assert_eq!(result.attempts, 10);
+    best
+}
+
+/// Group mines into clusters of mutually adjacent mines (8-connectivity).
+///
+/// Clusters are discovered in column-major order by BFS over the mine set;
+/// each cluster lists its mines in BFS order starting from its first mine.
+pub fn mine_clusters(mines: &Mines, neighbor_cache: &NeighborCache) -> Vec<Vec<(usize, usize)>> {
+    let width = mines.width;
+    let height = mines.height;
+    let mut visited = vec![false; width * height];
+    let mut clusters = Vec::new();
+
+    for x in 0..width {
+        for y in 0..height {
+            if !mines.get(x, y) || visited[x * height + y] { continue; }
+
+            visited[x * height + y] = true;
+            let mut cluster = vec![(x, y)];
+            let mut head = 0;
+            while head < cluster.len() {
+                let (cx, cy) = cluster[head];
+                head += 1;
+                for &(nx, ny) in neighbor_cache.get(cx, cy) {
+                    let idx = nx * height + ny;
+                    if mines.get(nx, ny) && !visited[idx] {
+                        visited[idx] = true;
+                        cluster.push((nx, ny));
+                    }
+                }
+            }
+            clusters.push(cluster);
+        }
+    }
+
+    clusters
+}
+
+/// Count the guess islands left after solving from `(start_x, start_y)`.
+///
+/// An island is an 8-connected component of the cells the solver could
+/// neither reveal nor flag, counted only if none of its cells touches a
+/// revealed cell: no clue bears on any of them and the player can only guess
+/// their way in. A stalled region that borders a clue is not an island, even
+/// where its interior is out of the clues' reach.
+pub fn count_guess_islands(
+    grid: &Grid,
+    mines: &Mines,
+    neighbor_cache: &NeighborCache,
+    start_x: usize,
+    start_y: usize,
+) -> usize {
+    let width = grid.width;
+    let height = grid.height;
+    let mut state = crate::solver::SolveState::from_start(grid, neighbor_cache, start_x, start_y);
+    state.run_to_stall(grid, neighbor_cache, mines.count());
+
+    let unresolved = |x: usize, y: usize| state.visible.get(x, y) == -1 && !state.flags.get(x, y);
+    let revealed = |x: usize, y: usize| !matches!(state.visible.get(x, y), -1 | BLOCKED);
+
+    let mut visited = vec![false; width * height];
+    let mut islands = 0;
+    for x in 0..width {
+        for y in 0..height {
+            if visited[x * height + y] || !unresolved(x, y) { continue; }
+
+            let mut touches_clue = false;
+            visited[x * height + y] = true;
+            let mut stack = vec![(x, y)];
+            while let Some((cx, cy)) = stack.pop() {
+                for &(nx, ny) in neighbor_cache.get(cx, cy) {
+                    touches_clue |= revealed(nx, ny);
+                    let idx = nx * height + ny;
+                    if !visited[idx] && unresolved(nx, ny) {
+                        visited[idx] = true;
+                        stack.push((nx, ny));
+                    }
+                }
+            }
+            if !touches_clue {
+                islands += 1;
+            }
+        }
+    }
+
+    islands
+}
+
+/// Move the mine at `from` to a random empty cell outside `safe_zone`, for a
+/// forgiving restart after an unlucky first click. Cells blocked in
+/// `neighbor_cache` are never chosen.
+///
+/// The target is drawn uniformly from the eligible cells in column-major
+/// order, so a seeded `rng` makes the move reproducible. Returns the new
+/// position, or `None` (leaving `mines` untouched) if `from` holds no mine or
+/// no eligible cell exists. The caller recomputes the numbers.
+pub fn relocate_mine<R: BoardRng>(
+    mines: &mut Mines,
+    neighbor_cache: &NeighborCache,
+    from: (usize, usize),
+    safe_zone: &SafeZone,
+    rng: &mut R,
+) -> Option<(usize, usize)> {
+    let (fx, fy) = from;
+    if !mines.in_bounds(fx, fy) || !mines.get(fx, fy) {
+        return None;
+    }
+
+    let mut eligible = Vec::new();
+    for x in 0..mines.width {
+        for y in 0..mines.height {
+            if mines.get(x, y) || (x, y) == from || safe_zone.contains(x, y) || neighbor_cache.is_blocked(x, y) { continue; }
+            eligible.push((x, y));
+        }
+    }
+    if eligible.is_empty() {
+        return None;
+    }
+
+    let target = eligible[rng.gen_range(eligible.len())];
+    mines.set(fx, fy, false);
+    mines.set(target.0, target.1, true);
+    Some(target)
+}
+
+/// Serialize a board as `{"width":W,"height":H,"mines":[[x,y],...]}`.
+///
+/// Mines are listed column-major. Numbers are not stored; `from_json()`
+/// recomputes them.
+pub fn to_json(mines: &Mines) -> String {
+    let (width, height) = (mines.width, mines.height);
+    let mut out = format!("{{\"width\":{},\"height\":{},\"mines\":[", width, height);
+    let mut first = true;
+    for x in 0..width {
+        for y in 0..height {
+            if !mines.get(x, y) { continue; }
+            if !first { out.push(','); }
+            first = false;
+            out.push_str(&format!("[{},{}]", x, y));
+        }
+    }
+    out.push_str("]}");
+    out
+}
+
+/// Parse a board written by `to_json()` (or any tool using the same shape)
+/// and recompute its numbers.
+///
+/// Unknown members are ignored. Returns an error for malformed JSON, invalid
+/// dimensions, and mine coordinates that are out of bounds or repeated.
+pub fn from_json(s: &str) -> Result<(Mines, Grid), String> {
+    let doc = crate::json::parse(s)?;
+    let field = |key: &str| doc.get(key).ok_or_else(|| format!("Missing \"{}\"", key));
+    let dimension = |key: &str| {
+        field(key)?.as_usize().ok_or_else(|| format!("\"{}\" must be a non-negative integer", key))
+    };
+    let width = dimension("width")?;
+    let height = dimension("height")?;
+    if width == 0 || height == 0 {
+        return Err(format!("Invalid board dimensions {}x{}", width, height));
+    }
+    let neighbor_cache = NeighborCache::try_new(width, height).ok_or_else(|| {
+        format!("Board dimensions {}x{} exceed the {}-cell limit", width, height, MAX_BOARD_CELLS)
+    })?;
+
+    let crate::json::JsonValue::Array(entries) = field("mines")? else {
+        return Err("\"mines\" must be an array".to_string());
+    };
+    let mut mines = Mines::new(width, height);
+    for entry in entries {
+        let pair = match entry {
+            crate::json::JsonValue::Array(pair) if pair.len() == 2 => pair,
+            _ => return Err("Each mine must be an [x, y] pair".to_string()),
+        };
+        let (Some(x), Some(y)) = (pair[0].as_usize(), pair[1].as_usize()) else {
+            return Err("Mine coordinates must be non-negative integers".to_string());
+        };
+        if x >= width || y >= height {
+            return Err(format!("Mine ({}, {}) is outside the {}x{} board", x, y, width, height));
+        }
+        if mines.get(x, y) {
+            return Err(format!("Mine ({}, {}) is listed twice", x, y));
+        }
+        mines.set(x, y, true);
+    }
+
+    let grid = calculate_numbers(&mines, &neighbor_cache);
+    Ok((mines, grid))
+}
+
+/// Validate a user-authored flat mine array and compute its numbers.
+///
+/// `mines_flat` is column-major with one byte per cell, 1 for a mine and 0
+/// otherwise. Rejects zero or oversized dimensions, a length that doesn't
+/// match them, any other cell value, and a board with no safe cell.
+pub fn load_board(width: usize, height: usize, mines_flat: &[u8]) -> Result<(Mines, Grid), String> {
+    if width == 0 || height == 0 {
+        return Err(format!("Invalid board dimensions {}x{}", width, height));
+    }
+    let neighbor_cache = NeighborCache::try_new(width, height).ok_or_else(|| {
+        format!("Board dimensions {}x{} exceed the {}-cell limit", width, height, MAX_BOARD_CELLS)
+    })?;
+    if mines_flat.len() != width * height {
+        return Err(format!(
+            "Mine array has {} cells, expected {} for {}x{}",
+            mines_flat.len(), width * height, width, height
+        ));
+    }
+    if let Some(i) = mines_flat.iter().position(|&m| m > 1) {
+        return Err(format!("Cell ({}, {}) has invalid mine value {}", i / height, i % height, mines_flat[i]));
+    }
+
+    let mines = Mines { width, height, cells: mines_flat.to_vec() };
+    if mines.count() == width * height {
+        return Err("Board has no safe cell".to_string());
+    }
+    let grid = calculate_numbers(&mines, &neighbor_cache);
+    Ok((mines, grid))
+}
+
+// ─── Shareable encoding ─────────────────────────────────────────────────────
+
+/// Format version written as the first byte of a shareable code.
+const SHARE_VERSION: u8 = 1;
+
+/// Why a shareable board code could not be decoded.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DecodeError {
+    /// A character outside the base64url alphabet.
+    InvalidCharacter,
+    /// The payload is shorter or longer than its header requires.
+    InvalidLength,
+    /// The code was written by an unknown format version.
+    UnsupportedVersion(u8),
+    /// Zero or oversized dimensions in the header.
+    InvalidDimensions,
+    /// The trailing checksum does not match (typo or truncation).
+    ChecksumMismatch,
+}
+
+impl std::fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DecodeError::InvalidCharacter => write!(f, "Invalid character in board code"),
+            DecodeError::InvalidLength => write!(f, "Board code has the wrong length"),
+            DecodeError::UnsupportedVersion(v) => write!(f, "Unsupported board code version {}", v),
+            DecodeError::InvalidDimensions => write!(f, "Board code has invalid dimensions"),
+            DecodeError::ChecksumMismatch => write!(f, "Board code checksum mismatch"),
+        }
+    }
+}
+
+const BASE64URL: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+/// Encode a board's mines as a short URL-safe string.
+///
+/// Layout before base64url (no padding): version byte, width and height as
+/// little-endian `u16`, the mine bitmap packed LSB-first in column-major
+/// order, then a one-byte wrapping sum of everything before it. An Expert
+/// board (30×16) encodes to 88 characters. Dimensions that `board_cell_count()`
+/// rejects are an error; every accepted side fits the `u16` header.
+pub fn encode_shareable(width: usize, height: usize, mines: &Mines) -> Result<String, GridError> {
+    board_cell_count(width, height).ok_or(GridError::TooLarge { width, height })?;
+    let mut bytes = vec![SHARE_VERSION];
+    bytes.extend_from_slice(&(width as u16).to_le_bytes());
+    bytes.extend_from_slice(&(height as u16).to_le_bytes());
+
+    let mut packed = vec![0u8; (width * height).div_ceil(8)];
+    for (i, &m) in mines.cells.iter().enumerate().take(width * height) {
+        if m != 0 { packed[i / 8] |= 1 << (i % 8); }
+    }
+    bytes.extend(packed);
+    bytes.push(bytes.iter().fold(0u8, |acc, &b| acc.wrapping_add(b)));
+
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = chunk.iter().enumerate().fold(0u32, |acc, (i, &b)| acc | (b as u32) << (16 - 8 * i));
+        for i in 0..=chunk.len() {
+            out.push(BASE64URL[(n >> (18 - 6 * i)) as usize & 63] as char);
+        }
+    }
+    Ok(out)
+}
+
+/// Decode a string produced by `encode_shareable()`.
+pub fn decode_shareable(s: &str) -> Result<(usize, usize, Mines), DecodeError> {
+    if s.len() % 4 == 1 {
+        return Err(DecodeError::InvalidLength);
+    }
+    let mut bytes = Vec::with_capacity(s.len() * 3 / 4);
+    for chunk in s.as_bytes().chunks(4) {
+        let mut n = 0u32;
+        for (i, &c) in chunk.iter().enumerate() {
+            let v = BASE64URL.iter().position(|&b| b == c).ok_or(DecodeError::InvalidCharacter)?;
+            n |= (v as u32) << (18 - 6 * i);
+        }
+        for i in 0..chunk.len() - 1 {
+            bytes.push((n >> (16 - 8 * i)) as u8);
+        }
+    }
+
+    let (&checksum, body) = bytes.split_last().ok_or(DecodeError::InvalidLength)?;
+    if body.len() < 5 {
+        return Err(DecodeError::InvalidLength);
+    }
+    if body[0] != SHARE_VERSION {
+        return Err(DecodeError::UnsupportedVersion(body[0]));
+    }
+    let width = u16::from_le_bytes([body[1], body[2]]) as usize;
+    let height = u16::from_le_bytes([body[3], body[4]]) as usize;
+    if width == 0 || height == 0 || board_cell_count(width, height).is_none() {
+        return Err(DecodeError::InvalidDimensions);
+    }
+    let packed = &body[5..];
+    if packed.len() != (width * height).div_ceil(8) {
+        return Err(DecodeError::InvalidLength);
+    }
+    if body.iter().fold(0u8, |acc, &b| acc.wrapping_add(b)) != checksum {
+        return Err(DecodeError::ChecksumMismatch);
+    }
+
+    let mut mines = Mines::new(width, height);
+    for (i, cell) in mines.cells.iter_mut().enumerate() {
+        *cell = (packed[i / 8] >> (i % 8)) & 1;
+    }
+    Ok((width, height, mines))
+}
+
+/// Find the mines a board's solvability depends on.
+///
+/// For each mine, removes it (recomputing the numbers) and re-runs
+/// `is_solvable()` from `(start_x, start_y)`. A mine is essential when its
+/// removal changes the verdict; the rest can be dropped without affecting
+/// whether the board is solvable. Offline puzzle-design tool: one full solve
+/// per mine is far too slow for generation.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn essential_mines(
+    mines: &Mines,
+    neighbor_cache: &NeighborCache,
+    start_x: usize,
+    start_y: usize,
+) -> Vec<(usize, usize)> {
+    let grid = calculate_numbers(mines, neighbor_cache);
+    let baseline = crate::solver::is_solvable(&grid, mines, neighbor_cache, start_x, start_y);
+    let mut essential = Vec::new();
+
+    for x in 0..mines.width {
+        for y in 0..mines.height {
+            if !mines.get(x, y) { continue; }
+            let mut reduced = mines.clone();
+            reduced.set(x, y, false);
+            let reduced_grid = calculate_numbers(&reduced, neighbor_cache);
+            if crate::solver::is_solvable(&reduced_grid, &reduced, neighbor_cache, start_x, start_y) != baseline {
+                essential.push((x, y));
+            }
+        }
+    }
+
+    essential
+}
+
+/// Why a board generation attempt did not succeed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GenerationFailure {
+    /// The parameters were rejected before generation started.
+    InvalidParams,
+    /// A layout could not fit all the mines; no board was built.
+    MinePlacementFailed,
+    /// Every layout within `max_attempts` failed the solvability check.
+    NoSolvableFound,
+}
+
+impl GenerationFailure {
+    /// Name used in the JS result objects.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            GenerationFailure::InvalidParams => "invalidParams",
+            GenerationFailure::MinePlacementFailed => "minePlacementFailed",
+            GenerationFailure::NoSolvableFound => "noSolvableFound",
+        }
+    }
+}
+
+/// Result of a board generation attempt.
+pub struct BoardResult {
+    /// Mine positions (flat, column-major).
+    pub mines: Mines,
+    /// Number grid (flat, column-major). Non-mine cells have values 0-8.
+    pub grid: Grid,
+    /// How many random layouts were tried before finding a solvable one.
+    pub attempts: u32,
+    /// True if a solvable board was found within max_attempts.
+    pub success: bool,
+    /// Set when no board could be built: the parameters were rejected or
+    /// mine placement failed.
+    pub error: Option<String>,
+    /// Why generation failed; `None` on success.
+    pub failure: Option<GenerationFailure>,
+}
+
+impl BoardResult {
+    /// An empty, failed result for `width` x `height`.
+    fn failed(width: usize, height: usize, attempts: u32, failure: GenerationFailure, error: String) -> Self {
+        BoardResult {
+            mines: Mines::new(width, height),
+            grid: Grid::new(width, height),
+            attempts,
+            success: false,
+            error: Some(error),
+            failure: Some(failure),
+        }
+    }
+
+    /// The result of a finished search that ended on `mines`/`grid`.
+    fn finished(mines: Mines, grid: Grid, attempts: u32, success: bool) -> Self {
+        let failure = (!success).then_some(GenerationFailure::NoSolvableFound);
+        BoardResult { mines, grid, attempts, success, error: None, failure }
+    }
+}
+
+/// Generate a solvable board by repeatedly placing mines and checking solvability.
+///
+/// This is the main entry point that replaces the entire `do { ... } while (!isSolvable)`
+/// loop from JS `Game.placeMines()`. Running the whole loop inside WASM avoids
+/// per-iteration JS↔WASM boundary crossings.
+///
+/// The `is_solvable_fn` parameter allows injecting the solver (which lives in solver.rs).
+/// This keeps board.rs decoupled from the solver implementation.
+#[allow(clippy::too_many_arguments)]
+pub fn generate_solvable_board<F>(
+    width: usize,
+    height: usize,
+    bomb_count: usize,
+    safe_x: usize,
+    safe_y: usize,
+    safe_radius: usize,
+    border_buffer: usize,
+    max_attempts: u32,
+    neighbor_cache: &NeighborCache,
+    is_solvable_fn: F,
+) -> BoardResult
+where
+    F: Fn(&Grid, &Mines, &NeighborCache, usize, usize) -> bool,
+{
+    let mut rng = WasmRng::new();
+    generate_solvable_board_with_rng(
+        width, height, bomb_count, safe_x, safe_y, safe_radius, border_buffer,
+        max_attempts, neighbor_cache, &mut rng, is_solvable_fn,
+    )
+}
+
+/// Same as `generate_solvable_board()`, drawing mine positions from `rng`.
+/// With a seeded RNG the result is fully reproducible.
+#[allow(clippy::too_many_arguments)]
+pub fn generate_solvable_board_with_rng<R, F>(
+    width: usize,
+    height: usize,
+    bomb_count: usize,
+    safe_x: usize,
+    safe_y: usize,
+    safe_radius: usize,
+    border_buffer: usize,
+    max_attempts: u32,
+    neighbor_cache: &NeighborCache,
+    rng: &mut R,
+    is_solvable_fn: F,
+) -> BoardResult
+where
+    R: BoardRng,
+    F: Fn(&Grid, &Mines, &NeighborCache, usize, usize) -> bool,
+{
+    generate_accepted_board_with_rng(
+        width, height, bomb_count, safe_x, safe_y, safe_radius, border_buffer,
+        max_attempts, neighbor_cache, rng, is_solvable_fn, |_, _, _| true,
+    )
+}
+
+/// `generate_solvable_board()` with an extra acceptance test.
+///
+/// `accept_fn` runs only on layouts that already pass `is_solvable_fn`, so
+/// a board must satisfy both; use it for criteria such as 3BV range or
+/// opening count without writing a new generator. Put cheap filters in
+/// `is_solvable_fn` ahead of the solve instead if they reject most layouts.
+#[allow(clippy::too_many_arguments)]
+pub fn generate_accepted_board<F, A>(
+    width: usize,
+    height: usize,
+    bomb_count: usize,
+    safe_x: usize,
+    safe_y: usize,
+    safe_radius: usize,
+    border_buffer: usize,
+    max_attempts: u32,
+    neighbor_cache: &NeighborCache,
+    is_solvable_fn: F,
+    accept_fn: A,
+) -> BoardResult
+where
+    F: Fn(&Grid, &Mines, &NeighborCache, usize, usize) -> bool,
+    A: Fn(&Grid, &Mines, &NeighborCache) -> bool,
+{
+    let mut rng = WasmRng::new();
+    generate_accepted_board_with_rng(
+        width, height, bomb_count, safe_x, safe_y, safe_radius, border_buffer,
+        max_attempts, neighbor_cache, &mut rng, is_solvable_fn, accept_fn,
+    )
+}
+
+/// Same as `generate_accepted_board()`, drawing mine positions from `rng`.
+#[allow(clippy::too_many_arguments)]
+pub fn generate_accepted_board_with_rng<R, F, A>(
+    width: usize,
+    height: usize,
+    bomb_count: usize,
+    safe_x: usize,
+    safe_y: usize,
+    safe_radius: usize,
+    border_buffer: usize,
+    max_attempts: u32,
+    neighbor_cache: &NeighborCache,
+    rng: &mut R,
+    is_solvable_fn: F,
+    accept_fn: A,
+) -> BoardResult
+where
+    R: BoardRng,
+    F: Fn(&Grid, &Mines, &NeighborCache, usize, usize) -> bool,
+    A: Fn(&Grid, &Mines, &NeighborCache) -> bool,
+{
+    let exclusion_radius = safe_radius.saturating_add(border_buffer);
+    if let Err(error) = validate_board_params(width, height, bomb_count, safe_x, safe_y, exclusion_radius) {
+        return BoardResult::failed(width, height, 0, GenerationFailure::InvalidParams, error);
+    }
+
+    let mut attempts: u32 = 0;
+
+    loop {
+        attempts += 1;
+
+        let zones = [SafeZone::new(safe_x, safe_y, safe_radius)];
+        let mines = match place_mines_random(width, height, bomb_count, &zones, border_buffer, rng) {
+            Ok(mines) => mines,
+            Err(error) => {
+                return BoardResult::failed(width, height, attempts, GenerationFailure::MinePlacementFailed, error);
+            }
+        };
+        let grid = calculate_numbers(&mines, neighbor_cache);
+
+        if is_solvable_fn(&grid, &mines, neighbor_cache, safe_x, safe_y) && accept_fn(&grid, &mines, neighbor_cache) {
+            return BoardResult::finished(mines, grid, attempts, true);
+        }
+
+        if attempts >= max_attempts {
+            // Return the last attempted board (not accepted, but best effort)
+            return BoardResult::finished(mines, grid, attempts, false);
+        }
+    }
+}
+
+/// Seed for the `chunk`-th run of `reseed_interval` attempts. Chunk 0 is the
+/// base seed itself; later chunks step by the 64-bit golden ratio, which
+/// keeps them apart from the neighbouring seeds the batch generators use.
+fn reseed_seed(base_seed: u64, chunk: u64) -> u64 {
+    base_seed.wrapping_add(chunk.wrapping_mul(0x9E37_79B9_7F4A_7C15))
+}
+
+/// `generate_accepted_board()` from `WasmRng::from_seed(base_seed)`, switching
+/// to a fresh RNG every `reseed_interval` attempts.
+///
+/// On very hard parameters one RNG stream can keep sampling layouts that fail
+/// the same way; restarting from derived seeds spreads the attempts over more
+/// of the board space. The result depends only on the arguments, and
+/// `attempts` counts across all the RNGs. `None` (or 0) never reseeds.
+#[allow(clippy::too_many_arguments)]
+pub fn generate_reseeding_board<F, A>(
+    width: usize,
+    height: usize,
+    bomb_count: usize,
+    safe_x: usize,
+    safe_y: usize,
+    safe_radius: usize,
+    border_buffer: usize,
+    max_attempts: u32,
+    neighbor_cache: &NeighborCache,
+    base_seed: u64,
+    reseed_interval: Option<u32>,
+    is_solvable_fn: F,
+    accept_fn: A,
+) -> BoardResult
+where
+    F: Fn(&Grid, &Mines, &NeighborCache, usize, usize) -> bool,
+    A: Fn(&Grid, &Mines, &NeighborCache) -> bool,
+{
+    reseeding(max_attempts, base_seed, reseed_interval, |budget, rng| {
+        generate_accepted_board_with_rng(
+            width, height, bomb_count, safe_x, safe_y, safe_radius, border_buffer,
+            budget, neighbor_cache, rng, &is_solvable_fn, &accept_fn,
+        )
+    })
+}
+
+/// `generate_blocked_board()` with the seeding of `generate_reseeding_board()`.
+#[allow(clippy::too_many_arguments)]
+pub fn generate_reseeding_blocked_board<F>(
+    blocked: &Blocked,
+    bomb_count: usize,
+    safe_zone: &SafeZone,
+    border_buffer: usize,
+    max_attempts: u32,
+    neighbor_cache: &NeighborCache,
+    base_seed: u64,
+    reseed_interval: Option<u32>,
+    is_solvable_fn: F,
+) -> BoardResult
+where
+    F: Fn(&Grid, &Mines, &NeighborCache, usize, usize) -> bool,
+{
+    reseeding(max_attempts, base_seed, reseed_interval, |budget, rng| {
+        generate_blocked_board(
+            blocked, bomb_count, safe_zone, border_buffer, budget, neighbor_cache, rng, &is_solvable_fn,
+        )
+    })
+}
+
+/// Run `generate(budget, rng)` on a fresh RNG per chunk of `reseed_interval`
+/// attempts until it stops exhausting its budget or `max_attempts` is spent.
+fn reseeding<G>(max_attempts: u32, base_seed: u64, reseed_interval: Option<u32>, mut generate: G) -> BoardResult
+where
+    G: FnMut(u32, &mut WasmRng) -> BoardResult,
+{
+    let interval = reseed_interval.filter(|&n| n > 0).unwrap_or(u32::MAX);
+    let mut attempts: u32 = 0;
+    let mut chunk = 0;
+
+    loop {
+        let mut rng = WasmRng::from_seed(reseed_seed(base_seed, chunk));
+        let budget = interval.min(max_attempts.saturating_sub(attempts)).max(1);
+        let mut result = generate(budget, &mut rng);
+        attempts += result.attempts;
+        let exhausted = result.failure == Some(GenerationFailure::NoSolvableFound);
+        if !exhausted || attempts >= max_attempts {
+            result.attempts = attempts;
+            return result;
+        }
+        chunk += 1;
+    }
+}
+
+/// Generate a solvable board, dropping one mine whenever `max_attempts_per_level`
+/// layouts at the current count all fail, for casual modes where a quick
+/// board matters more than exact difficulty.
+///
+/// Returns the board and the mine count it was generated with (at most
+/// `target_bombs`); `attempts` totals every level tried. With valid parameters
+/// this always succeeds, since a board with no mines is trivially solvable.
+pub fn generate_with_density_fallback(
+    width: usize,
+    height: usize,
+    target_bombs: usize,
+    safe_zone: &SafeZone,
+    max_attempts_per_level: u32,
+    neighbor_cache: &NeighborCache,
+) -> (BoardResult, usize) {
+    let mut rng = WasmRng::new();
+    generate_with_density_fallback_with_rng(
+        width, height, target_bombs, safe_zone, max_attempts_per_level, neighbor_cache, &mut rng,
+    )
+}
+
+/// `generate_with_density_fallback()` with a caller-supplied RNG. A level
+/// whose mines could not be placed steps down like one with no solvable
+/// layout.
+pub fn generate_with_density_fallback_with_rng<R: BoardRng>(
+    width: usize,
+    height: usize,
+    target_bombs: usize,
+    safe_zone: &SafeZone,
+    max_attempts_per_level: u32,
+    neighbor_cache: &NeighborCache,
+    rng: &mut R,
+) -> (BoardResult, usize) {
+    let mut bombs = target_bombs;
+    let mut total_attempts: u32 = 0;
+
+    loop {
+        let mut result = generate_solvable_board_with_rng(
+            width, height, bombs, safe_zone.x, safe_zone.y, safe_zone.radius, 0,
+            max_attempts_per_level, neighbor_cache, rng, crate::solver::is_solvable,
+        );
+        total_attempts = total_attempts.saturating_add(result.attempts);
+        result.attempts = total_attempts;
+
+        // Rejected parameters are the caller's mistake; don't mask them with fewer mines
+        if result.success || result.failure == Some(GenerationFailure::InvalidParams) || bombs == 0 {
+            return (result, bombs);
+        }
+        bombs -= 1;
+    }
+}
+
+/// Generate a board that needs exactly one guess, for teaching probability.
+///
+/// Accepts only layouts that fail `is_solvable()` and have `min_guesses() == 1`,
+/// and returns the guess point of the final board alongside it so the tutorial
+/// can highlight the stall.
+pub fn generate_guess_required_board(
+    width: usize,
+    height: usize,
+    bomb_count: usize,
+    safe_zone: &SafeZone,
+    max_attempts: u32,
+    neighbor_cache: &NeighborCache,
+) -> (BoardResult, crate::solver::GuessPoint) {
+    generate_guess_required_board_with_rng(
+        width, height, bomb_count, safe_zone, max_attempts, neighbor_cache, &mut WasmRng::new(),
+    )
+}
+
+/// Same as `generate_guess_required_board()`, drawing mine positions from `rng`.
+pub fn generate_guess_required_board_with_rng<R: BoardRng>(
+    width: usize,
+    height: usize,
+    bomb_count: usize,
+    safe_zone: &SafeZone,
+    max_attempts: u32,
+    neighbor_cache: &NeighborCache,
+    rng: &mut R,
+) -> (BoardResult, crate::solver::GuessPoint) {
+    let result = generate_solvable_board_with_rng(
+        width, height, bomb_count, safe_zone.x, safe_zone.y, safe_zone.radius, 0,
+        max_attempts, neighbor_cache, rng,
+        // min_guesses() is 0 exactly when is_solvable() holds, so one solve covers both checks
+        |grid, mines, nc, sx, sy| crate::solver::min_guesses(grid, mines, nc, sx, sy) == 1,
+    );
+    let guess_point = crate::solver::solve_until_guess(&result.grid, &result.mines, neighbor_cache, safe_zone.x, safe_zone.y);
+    (result, guess_point)
+}
+
+/// `generate_solvable_board()` that also rates the board it returns.
+///
+/// Each attempt is checked with `DifficultyReport::solve()`, whose escalating
+/// solve both accepts the board and rates it, so no solve runs after
+/// generation. `None` if no attempt passed.
+pub fn generate_rated_board<R: BoardRng>(
+    width: usize,
+    height: usize,
+    bomb_count: usize,
+    safe_zone: &SafeZone,
+    max_attempts: u32,
+    neighbor_cache: &NeighborCache,
+    rng: &mut R,
+) -> (BoardResult, Option<crate::solver::DifficultyReport>) {
+    let report = std::cell::RefCell::new(None);
+    let result = generate_solvable_board_with_rng(
+        width, height, bomb_count, safe_zone.x, safe_zone.y, safe_zone.radius, 0,
+        max_attempts, neighbor_cache, rng,
+        |grid, mines, nc, sx, sy| {
+            *report.borrow_mut() = crate::solver::DifficultyReport::solve(grid, mines, nc, sx, sy);
+            report.borrow().is_some()
+        },
+    );
+    let report = report.into_inner().filter(|_| result.success);
+    (result, report)
+}
+
+/// Monte Carlo estimate of the fraction of random layouts that pass `is_solvable()`.
+///
+/// Layout `i` is placed with seed `i`, so the estimate is reproducible. Offline
+/// helper for precomputing difficulty curves; returns 0.0 for invalid
+/// parameters or zero samples.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn estimate_solvable_fraction(
+    width: usize,
+    height: usize,
+    bomb_count: usize,
+    safe_zone: &SafeZone,
+    samples: u32,
+    neighbor_cache: &NeighborCache,
+) -> f32 {
+    if samples == 0
+        || validate_board_params(width, height, bomb_count, safe_zone.x, safe_zone.y, safe_zone.radius).is_err()
+    {
+        return 0.0;
+    }
+
+    let solvable = (0..samples as u64)
+        .filter(|&seed| {
+            let mut rng = WasmRng::from_seed(seed);
+            let Ok(mines) = place_mines_random(width, height, bomb_count, std::slice::from_ref(safe_zone), 0, &mut rng) else {
+                return false;
+            };
+            let grid = calculate_numbers(&mines, neighbor_cache);
+            crate::solver::is_solvable(&grid, &mines, neighbor_cache, safe_zone.x, safe_zone.y)
+        })
+        .count();
+    solvable as f32 / samples as f32
+}
+
+/// `generate_solvable_board()` that also logs how close each attempt came.
+///
+/// Entry `i` is the fraction of safe cells the solver cleared on attempt
+/// `i + 1` (see `solver::progress()`), so the last entry is 1.0 on success
+/// and the log has `result.attempts` entries. Offline research helper for
+/// relating solvability to attempt count.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn generate_with_attempt_log(
+    width: usize,
+    height: usize,
+    bomb_count: usize,
+    safe_zone: &SafeZone,
+    max_attempts: u32,
+    neighbor_cache: &NeighborCache,
+) -> (BoardResult, Vec<f32>) {
+    let log = std::cell::RefCell::new(Vec::new());
+    let mut rng = WasmRng::new();
+    let result = generate_solvable_board_with_rng(
+        width, height, bomb_count, safe_zone.x, safe_zone.y, safe_zone.radius, 0,
+        max_attempts, neighbor_cache, &mut rng,
+        |grid, _, nc, sx, sy| {
+            let mut state = crate::solver::SolveState::from_start(grid, nc, sx, sy);
+            state.run_to_stall(grid, nc, bomb_count);
+            log.borrow_mut().push(crate::solver::progress(&state.visible, nc, bomb_count));
+            state.is_complete(bomb_count)
+        },
+    );
+    (result, log.into_inner())
+}
+
+/// Solvable fraction above which `min_solvable_safe_radius()` accepts a radius:
+/// about one layout in twenty, so generation finds a board in a few dozen tries.
+#[cfg(not(target_arch = "wasm32"))]
+const TUNING_SOLVABLE_FRACTION: f32 = 0.05;
+
+/// Smallest safe-zone radius around the board center at which random layouts
+/// are solvable often enough to generate from, for auto-tuning the opening.
+///
+/// Tries radius 1, 2, ... with `estimate_solvable_fraction()` over `samples`
+/// seeded layouts and returns the first whose fraction exceeds
+/// `TUNING_SOLVABLE_FRACTION`. This is a Monte Carlo estimate, so the answer is
+/// approximate: a radius just below the threshold may still generate, given
+/// enough attempts. `None` once the zone leaves too few cells for the mines.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn min_solvable_safe_radius(
+    width: usize,
+    height: usize,
+    bomb_count: usize,
+    samples: u32,
+    neighbor_cache: &NeighborCache,
+) -> Option<usize> {
+    let (cx, cy) = (width / 2, height / 2);
+    (1..=width.max(height))
+        .take_while(|&r| validate_board_params(width, height, bomb_count, cx, cy, r).is_ok())
+        .find(|&r| {
+            let zone = SafeZone::new(cx, cy, r);
+            estimate_solvable_fraction(width, height, bomb_count, &zone, samples, neighbor_cache)
+                > TUNING_SOLVABLE_FRACTION
+        })
+}
+
+/// Generate a solvable board that keeps every zone in `safe_zones` mine-free,
+/// for co-op games where several players open the board at once.
+///
+/// `is_solvable_fn` receives all the zones so it can reveal each opening.
+#[allow(clippy::too_many_arguments)]
+pub fn generate_multi_start_board<R, F>(
+    width: usize,
+    height: usize,
+    bomb_count: usize,
+    safe_zones: &[SafeZone],
+    max_attempts: u32,
+    neighbor_cache: &NeighborCache,
+    rng: &mut R,
+    is_solvable_fn: F,
+) -> BoardResult
+where
+    R: BoardRng,
+    F: Fn(&Grid, &Mines, &NeighborCache, &[SafeZone]) -> bool,
+{
+    if let Err(error) = validate_safe_zones(width, height, bomb_count, safe_zones) {
+        return BoardResult::failed(width, height, 0, GenerationFailure::InvalidParams, error);
+    }
+
+    let mut attempts: u32 = 0;
+
+    loop {
+        attempts += 1;
+
+        let mines = match place_mines_random(width, height, bomb_count, safe_zones, 0, rng) {
+            Ok(mines) => mines,
+            Err(error) => {
+                return BoardResult::failed(width, height, attempts, GenerationFailure::MinePlacementFailed, error);
+            }
+        };
+        let grid = calculate_numbers(&mines, neighbor_cache);
+        let success = is_solvable_fn(&grid, &mines, neighbor_cache, safe_zones);
+
+        if success || attempts >= max_attempts {
+            return BoardResult::finished(mines, grid, attempts, success);
+        }
+    }
+}
+
+/// Generate a solvable board whose rectangular opening `safe_rect` is
+/// mine-free, e.g. a whole row cleared for the first click.
+///
+/// `is_solvable_fn` receives the rectangle so it can reveal all of it.
+#[allow(clippy::too_many_arguments)]
+pub fn generate_rect_board<R, F>(
+    width: usize,
+    height: usize,
+    bomb_count: usize,
+    safe_rect: &SafeRect,
+    max_attempts: u32,
+    neighbor_cache: &NeighborCache,
+    rng: &mut R,
+    is_solvable_fn: F,
+) -> BoardResult
+where
+    R: BoardRng,
+    F: Fn(&Grid, &Mines, &NeighborCache, &SafeRect) -> bool,
+{
+    if let Err(error) = validate_safe_rect(width, height, bomb_count, safe_rect) {
+        return BoardResult::failed(width, height, 0, GenerationFailure::InvalidParams, error);
+    }
+
+    let mut attempts: u32 = 0;
+
+    loop {
+        attempts += 1;
+
+        let mines = match place_mines_outside_rect(width, height, bomb_count, safe_rect, rng) {
+            Ok(mines) => mines,
+            Err(error) => {
+                return BoardResult::failed(width, height, attempts, GenerationFailure::MinePlacementFailed, error);
+            }
+        };
+        let grid = calculate_numbers(&mines, neighbor_cache);
+        let success = is_solvable_fn(&grid, &mines, neighbor_cache, safe_rect);
+
+        if success || attempts >= max_attempts {
+            return BoardResult::finished(mines, grid, attempts, success);
+        }
+    }
+}
+
+/// Generate a solvable board with holes: `blocked` cells hold no mine, read
+/// `BLOCKED` in the grid and are ignored by the solver.
+///
+/// `neighbor_cache` should come from `NeighborCache::with_blocked(blocked)`,
+/// which is what writes the `BLOCKED` sentinel. `border_buffer` keeps that many
+/// extra rings around `safe_zone` mine-free, as in `generate_solvable_board()`.
+#[allow(clippy::too_many_arguments)]
+pub fn generate_blocked_board<R, F>(
+    blocked: &Blocked,
+    bomb_count: usize,
+    safe_zone: &SafeZone,
+    border_buffer: usize,
+    max_attempts: u32,
+    neighbor_cache: &NeighborCache,
+    rng: &mut R,
+    is_solvable_fn: F,
+) -> BoardResult
+where
+    R: BoardRng,
+    F: Fn(&Grid, &Mines, &NeighborCache, usize, usize) -> bool,
+{
+    let (width, height) = (blocked.width, blocked.height);
+    let exclusion = SafeZone::new(safe_zone.x, safe_zone.y, safe_zone.radius.saturating_add(border_buffer));
+    if let Err(error) = validate_blocked_board(blocked, bomb_count, &exclusion) {
+        return BoardResult::failed(width, height, 0, GenerationFailure::InvalidParams, error);
+    }
+
+    let mut attempts: u32 = 0;
+
+    loop {
+        attempts += 1;
+
+        let mines = match place_mines_around_blocked(blocked, bomb_count, &exclusion, rng) {
+            Ok(mines) => mines,
+            Err(error) => {
+                return BoardResult::failed(width, height, attempts, GenerationFailure::MinePlacementFailed, error);
+            }
+        };
+        let grid = calculate_numbers(&mines, neighbor_cache);
+        let success = is_solvable_fn(&grid, &mines, neighbor_cache, safe_zone.x, safe_zone.y);
+
+        if success || attempts >= max_attempts {
+            return BoardResult::finished(mines, grid, attempts, success);
+        }
+    }
+}
+
+/// True if clicking the center of `zone` cascades over every cell of the zone.
+pub fn opening_covers_zone(grid: &Grid, neighbor_cache: &NeighborCache, zone: &SafeZone) -> bool {
+    let flags = Flags::new(grid.width, grid.height);
+    let mut visible = VisibleGrid::new(grid.width, grid.height);
+    simulate_reveal(grid, &mut visible, &flags, neighbor_cache, zone.x, zone.y);
+
+    let x_max = zone.x.saturating_add(zone.radius).min(grid.width.saturating_sub(1));
+    let y_max = zone.y.saturating_add(zone.radius).min(grid.height.saturating_sub(1));
+    (zone.x.saturating_sub(zone.radius)..=x_max)
+        .all(|x| (zone.y.saturating_sub(zone.radius)..=y_max).all(|y| visible.get(x, y) != -1))
+}
+
+/// Generate a solvable board whose first click is forgiving: the whole
+/// `mercy_radius` zone around `(safe_x, safe_y)` is mine-free, the click there
+/// cascades over all of it, and `is_solvable_fn` is handed that zone so the
+/// solver starts from the same opening the player gets.
+///
+/// Mine exclusion alone already makes the cascade cover the zone; the explicit
+/// `opening_covers_zone()` check keeps that guarantee if placement changes.
+#[allow(clippy::too_many_arguments)]
+pub fn generate_mercy_board<R, F>(
+    width: usize,
+    height: usize,
+    bomb_count: usize,
+    safe_x: usize,
+    safe_y: usize,
+    mercy_radius: usize,
+    max_attempts: u32,
+    neighbor_cache: &NeighborCache,
+    rng: &mut R,
+    is_solvable_fn: F,
+) -> BoardResult
+where
+    R: BoardRng,
+    F: Fn(&Grid, &Mines, &NeighborCache, &[SafeZone]) -> bool,
+{
+    if let Err(error) = validate_board_params(width, height, bomb_count, safe_x, safe_y, mercy_radius) {
+        return BoardResult::failed(width, height, 0, GenerationFailure::InvalidParams, error);
+    }
+
+    let zones = [SafeZone::new(safe_x, safe_y, mercy_radius)];
+    let mut attempts: u32 = 0;
+
+    loop {
+        attempts += 1;
+
+        let mines = match place_mines_random(width, height, bomb_count, &zones, 0, rng) {
+            Ok(mines) => mines,
+            Err(error) => {
+                return BoardResult::failed(width, height, attempts, GenerationFailure::MinePlacementFailed, error);
+            }
+        };
+        let grid = calculate_numbers(&mines, neighbor_cache);
+        let success = opening_covers_zone(&grid, neighbor_cache, &zones[0])
+            && is_solvable_fn(&grid, &mines, neighbor_cache, &zones);
+
+        if success || attempts >= max_attempts {
+            return BoardResult::finished(mines, grid, attempts, success);
+        }
+    }
+}
+
+/// Generate a solvable board containing every mine in `fixed`, placing the
+/// rest at random with `place_mines_with_template()`.
+#[allow(clippy::too_many_arguments)]
+pub fn generate_templated_board<R, F>(
+    width: usize,
+    height: usize,
+    bomb_count: usize,
+    fixed: &[(usize, usize)],
+    safe_zone: &SafeZone,
+    max_attempts: u32,
+    neighbor_cache: &NeighborCache,
+    rng: &mut R,
+    is_solvable_fn: F,
+) -> BoardResult
+where
+    R: BoardRng,
+    F: Fn(&Grid, &Mines, &NeighborCache, usize, usize) -> bool,
+{
+    if let Err(error) = validate_template(width, height, bomb_count, fixed, safe_zone) {
+        return BoardResult::failed(width, height, 0, GenerationFailure::InvalidParams, error);
+    }
+
+    let mut attempts: u32 = 0;
+
+    loop {
+        attempts += 1;
+
+        let mines = match place_mines_with_template(width, height, bomb_count, fixed, safe_zone, rng) {
+            Ok(mines) => mines,
+            Err(error) => {
+                return BoardResult::failed(width, height, attempts, GenerationFailure::MinePlacementFailed, error);
+            }
+        };
+        let grid = calculate_numbers(&mines, neighbor_cache);
+        let success = is_solvable_fn(&grid, &mines, neighbor_cache, safe_zone.x, safe_zone.y);
+
+        if success || attempts >= max_attempts {
+            return BoardResult::finished(mines, grid, attempts, success);
+        }
+    }
+}
+
+/// Generate `count` boards with seeds `base_seed`, `base_seed + 1`, ...
+///
+/// All boards share one `NeighborCache`. Returns `(seed, result)` pairs in
+/// seed order, so the whole set is reproducible from `base_seed`. Each board
+/// draws from its own `WasmRng::from_seed(base_seed + i)`, so board `i` can be
+/// regenerated alone however many attempts the boards before it took.
+#[allow(clippy::too_many_arguments)]
+pub fn generate_solvable_board_batch<F>(
+    width: usize,
+    height: usize,
+    bomb_count: usize,
+    safe_x: usize,
+    safe_y: usize,
+    safe_radius: usize,
+    max_attempts: u32,
+    base_seed: u64,
+    count: usize,
+    neighbor_cache: &NeighborCache,
+    is_solvable_fn: F,
+) -> Vec<(u64, BoardResult)>
+where
+    F: Fn(&Grid, &Mines, &NeighborCache, usize, usize) -> bool,
+{
+    (0..count as u64)
+        .map(|i| {
+            let seed = base_seed.wrapping_add(i);
+            let mut rng = WasmRng::from_seed(seed);
+            let result = generate_solvable_board_with_rng(
+                width, height, bomb_count, safe_x, safe_y, safe_radius, 0,
+                max_attempts, neighbor_cache, &mut rng, &is_solvable_fn,
+            );
+            (seed, result)
+        })
+        .collect()
+}
+
+/// Source cell of `(x, y)` under dihedral transform `t` of a `w`×`h` board.
+///
+/// 0 is the identity, 1 mirrors x, 2 mirrors y, 3 rotates 180°; 4-7 swap the
+/// dimensions: 4 transposes, 5 rotates 90° counterclockwise, 6 clockwise,
+/// 7 transposes across the other diagonal.
+fn transform_source(t: u8, w: usize, h: usize, x: usize, y: usize) -> (usize, usize) {
+    match t {
+        0 => (x, y),
+        1 => (w - 1 - x, y),
+        2 => (x, h - 1 - y),
+        3 => (w - 1 - x, h - 1 - y),
+        4 => (y, x),
+        5 => (w - 1 - y, x),
+        6 => (y, h - 1 - x),
+        _ => (w - 1 - y, h - 1 - x),
+    }
+}
+
+/// FNV-1a over the dimensions and cells of `mines` under transform `t`.
+fn transform_hash(mines: &Mines, t: u8) -> u64 {
+    let (w, h) = (mines.width, mines.height);
+    let (tw, th) = if t < 4 { (w, h) } else { (h, w) };
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    let mut feed = |byte: u8| {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+    };
+    for b in (tw as u32).to_le_bytes().into_iter().chain((th as u32).to_le_bytes()) {
+        feed(b);
+    }
+    for x in 0..tw {
+        for y in 0..th {
+            let (sx, sy) = transform_source(t, w, h, x, y);
+            feed(mines.cells[sx * h + sy]);
+        }
+    }
+    hash
+}
+
+/// Symmetry-invariant hash of a mine layout.
+///
+/// Hashes the layout under all eight rotations and reflections (FNV-1a over
+/// the transformed dimensions and cells) and keeps the smallest, so boards that
+/// are dihedral images of each other hash equal.
+pub fn canonical_hash(mines: &Mines) -> u64 {
+    (0..8u8).map(|t| transform_hash(mines, t)).min().unwrap_or(u64::MAX)
+}
+
+/// `mines` under dihedral transform `t` (numbered as in `transform_source()`;
+/// 4-7 swap width and height).
+pub fn apply_transform(mines: &Mines, t: u8) -> Mines {
+    let (w, h) = (mines.width, mines.height);
+    let (tw, th) = if t < 4 { (w, h) } else { (h, w) };
+    let mut out = Mines::new(tw, th);
+    for x in 0..tw {
+        for y in 0..th {
+            let (sx, sy) = transform_source(t, w, h, x, y);
+            out.cells[x * th + y] = mines.cells[sx * h + sy];
+        }
+    }
+    out
+}
+
+/// The transform `canonicalize()` applies: among those that keep the board's
+/// dimensions (all eight on a square board, the identity, both mirrors and
+/// the 180° rotation otherwise), the one with the smallest transformed hash,
+/// ties broken by the transformed cells and then the lowest number.
+pub fn canonical_transform(mines: &Mines) -> u8 {
+    let count = if mines.width == mines.height { 8 } else { 4 };
+    (0..count)
+        .min_by_key(|&t| (transform_hash(mines, t), apply_transform(mines, t).cells))
+        .unwrap_or(0)
+}
+
+/// The representative of `mines` among its same-sized rotations and
+/// reflections, so symmetric boards can be stored once.
+pub fn canonicalize(mines: &Mines) -> Mines {
+    apply_transform(mines, canonical_transform(mines))
+}
+
+/// True if `a` and `b` are the same puzzle: equal dimensions and a mine in
+/// exactly the same cells. Any non-zero cell counts as a mine, as in
+/// `Mines::get()`; differing dimensions or a cell buffer that doesn't match its
+/// dimensions give `false`.
+pub fn mines_equal(a: &Mines, b: &Mines) -> bool {
+    a.width == b.width
+        && a.height == b.height
+        && a.width.checked_mul(a.height) == Some(a.cells.len())
+        && b.cells.len() == a.cells.len()
+        && a.cells.iter().zip(&b.cells).all(|(&ca, &cb)| (ca != 0) == (cb != 0))
+}
+
+/// Like `generate_solvable_board_batch()`, but no two boards are rotations or
+/// reflections of each other (compared by `canonical_hash()`).
+///
+/// Seeds are tried in order from `base_seed`; a seed whose board duplicates an
+/// earlier one is skipped. At most `count * 8` seeds are skipped in total, so
+/// a space too small for `count` unique boards returns fewer.
+#[allow(clippy::too_many_arguments)]
+pub fn generate_unique_batch<F>(
+    width: usize,
+    height: usize,
+    bomb_count: usize,
+    safe_x: usize,
+    safe_y: usize,
+    safe_radius: usize,
+    max_attempts: u32,
+    base_seed: u64,
+    count: usize,
+    neighbor_cache: &NeighborCache,
+    is_solvable_fn: F,
+) -> Vec<(u64, BoardResult)>
+where
+    F: Fn(&Grid, &Mines, &NeighborCache, usize, usize) -> bool,
+{
+    let mut boards = Vec::with_capacity(count);
+    let mut seen = std::collections::HashSet::new();
+    let mut skips_left = count.saturating_mul(8);
+    let mut seed = base_seed;
+
+    while boards.len() < count {
+        let mut rng = WasmRng::from_seed(seed);
+        let result = generate_solvable_board_with_rng(
+            width, height, bomb_count, safe_x, safe_y, safe_radius, 0,
+            max_attempts, neighbor_cache, &mut rng, &is_solvable_fn,
+        );
+        if result.error.is_some() {
+            boards.push((seed, result));
+            break;
+        }
+        if seen.insert(canonical_hash(&result.mines)) {
+            boards.push((seed, result));
+        } else if skips_left == 0 {
+            break;
+        } else {
+            skips_left -= 1;
+        }
+        seed = seed.wrapping_add(1);
+    }
+
+    boards
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_place_mines_count() {
+        let mut rng = WasmRng::from_seed(42);
+        let mines = place_mines_random(30, 16, 99, &[SafeZone::new(15, 8, 1)], 0, &mut rng).unwrap();
+        assert_eq!(mines.count(), 99);
+    }
+
+    #[test]
+    fn test_place_mines_safe_zone() {
+        let mut rng = WasmRng::from_seed(42);
+        let mines = place_mines_random(10, 10, 20, &[SafeZone::new(5, 5, 2)], 0, &mut rng).unwrap();
+
+        // No mines within radius 2 of (5,5)
+        for x in 3..=7 {
+            for y in 3..=7 {
+                assert!(!mines.get(x, y), "Mine found in safe zone at ({}, {})", x, y);
+            }
+        }
+        assert_eq!(mines.count(), 20);
+    }
+
+    #[test]
+    fn test_place_mines_border_buffer() {
+        // Radius 1 plus a one-ring buffer: the 5x5 block around (5,5) stays clear
+        // on every seed, with 75 of the other 75 cells filled
+        for seed in 0..20 {
+            let mut rng = WasmRng::from_seed(seed);
+            let mines = place_mines_random(10, 10, 75, &[SafeZone::new(5, 5, 1)], 1, &mut rng).unwrap();
+            for x in 3..=7 {
+                for y in 3..=7 {
+                    assert!(!mines.get(x, y), "Mine found in buffer ring at ({}, {})", x, y);
+                }
+            }
+            assert_eq!(mines.count(), 75);
+        }
+
+        // The generator validates against the buffered zone
+        let nc = NeighborCache::new(10, 10);
+        let result = generate_solvable_board(10, 10, 76, 5, 5, 1, 1, 10, &nc, |_, _, _, _, _| true);
+        assert!(result.error.is_some());
+    }
+
+    #[test]
+    fn test_place_mines_with_template() {
+        // A diagonal line of fixed mines plus random fill
+        let fixed: Vec<(usize, usize)> = (0..8).map(|i| (i, i)).collect();
+        let zone = SafeZone::new(12, 3, 1);
+        assert!(validate_template(16, 16, 40, &fixed, &zone).is_ok());
+
+        let mut rng = WasmRng::from_seed(7);
+        let mines = place_mines_with_template(16, 16, 40, &fixed, &zone, &mut rng).unwrap();
+        assert_eq!(mines.count(), 40);
+        for &(x, y) in &fixed {
+            assert!(mines.get(x, y), "Fixed mine missing at ({}, {})", x, y);
+        }
+        for x in 11..=13 {
+            for y in 2..=4 {
+                assert!(!mines.get(x, y));
+            }
+        }
+
+        // Fixed mines may not sit in the safe zone or exceed the count
+        assert!(validate_template(16, 16, 40, &[(12, 3)], &zone).is_err());
+        assert!(validate_template(16, 16, 4, &fixed, &zone).is_err());
+        assert!(validate_template(16, 16, 40, &[(1, 1), (1, 1)], &zone).is_err());
+    }
+
+    #[test]
+    fn test_relocate_mine() {
+        let mut rng = WasmRng::from_seed(3);
+        let zone = SafeZone::new(4, 4, 1);
+        let mut mines = place_mines_random(9, 9, 10, &[zone], 0, &mut rng).unwrap();
+        let nc = NeighborCache::new(9, 9);
+        let from = (0..9).flat_map(|x| (0..9).map(move |y| (x, y))).find(|&(x, y)| mines.get(x, y)).unwrap();
+        let before = mines.clone();
+
+        let to = relocate_mine(&mut mines, &nc, from, &zone, &mut rng).unwrap();
+        assert_eq!(mines.count(), 10);
+        assert!(!before.get(to.0, to.1), "target ({}, {}) was already a mine", to.0, to.1);
+        assert!(!zone.contains(to.0, to.1));
+        assert!(mines.get(to.0, to.1) && !mines.get(from.0, from.1));
+
+        // Same seed, same move
+        let mut replay = before.clone();
+        let mut rng2 = WasmRng::from_seed(3);
+        place_mines_random(9, 9, 10, &[zone], 0, &mut rng2).unwrap();
+        assert_eq!(relocate_mine(&mut replay, &nc, from, &zone, &mut rng2), Some(to));
+
+        // No mine at the source: nothing moves
+        assert_eq!(relocate_mine(&mut mines, &nc, from, &zone, &mut rng), None);
+
+        // 3x1 with the middle blocked and the far end safe: nowhere to go
+        let mut blocked = Blocked::new(3, 1);
+        blocked.set(1, 0, true);
+        let mut strip = Mines::new(3, 1);
+        strip.set(0, 0, true);
+        let far_end = SafeZone::new(2, 0, 0);
+        assert_eq!(relocate_mine(&mut strip, &NeighborCache::with_blocked(&blocked), (0, 0), &far_end, &mut rng), None);
+        assert!(strip.get(0, 0));
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let mut rng = WasmRng::from_seed(5);
+        let nc = NeighborCache::new(9, 7);
+        let mines = place_mines_random(9, 7, 12, &[SafeZone::new(4, 3, 1)], 0, &mut rng).unwrap();
+        let grid = calculate_numbers(&mines, &nc);
+
+        let json = to_json(&mines);
+        assert!(json.starts_with(r#"{"width":9,"height":7,"mines":[["#));
+        let (parsed_mines, parsed_grid) = from_json(&json).unwrap();
+        assert_eq!(parsed_mines.cells, mines.cells);
+        assert_eq!(parsed_grid.cells, grid.cells);
+    }
+
+    #[test]
+    fn test_from_json_rejects_bad_input() {
+        assert!(from_json(r#"{"width":3,"height":3,"mines":[[1,1]]}"#).is_ok());
+        for bad in [
+            r#"{"width":3,"height":3,"mines":[[3,0]]}"#,
+            r#"{"width":3,"height":3,"mines":[[1,1],[1,1]]}"#,
+            r#"{"width":3,"height":3,"mines":[[1]]}"#,
+            r#"{"width":3,"height":-1,"mines":[]}"#,
+            r#"{"width":3,"mines":[]}"#,
+            r#"{"width":3,"height":3,"mines":[[0.5,1]]}"#,
+            r#"{"width":100000,"height":100000,"mines":[]}"#,
+            r#"{"width":3,"height":3,"mines":"#,
+        ] {
+            assert!(from_json(bad).is_err(), "accepted {}", bad);
+        }
+    }
+
+    #[test]
+    fn test_shareable_round_trip() {
+        let mut rng = WasmRng::from_seed(8);
+        let mines = place_mines_random(30, 16, 99, &[SafeZone::new(15, 8, 1)], 0, &mut rng).unwrap();
+        let code = encode_shareable(30, 16, &mines).unwrap();
+        assert_eq!(code.len(), 88);
+        assert!(code.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_'));
+
+        let (w, h, decoded) = decode_shareable(&code).unwrap();
+        assert_eq!((w, h), (30, 16));
+        assert_eq!(decoded.cells, mines.cells);
+
+        // Odd sizes leave a partial final byte
+        let mut small = Mines::new(3, 3);
+        small.set(2, 1, true);
+        let (_, _, decoded) = decode_shareable(&encode_shareable(3, 3, &small).unwrap()).unwrap();
+        assert_eq!(decoded.cells, small.cells);
+
+        // A side too long for the header is refused, not truncated
+        let long = Mines::new(70_000, 1);
+        assert_eq!(encode_shareable(70_000, 1, &long), Err(GridError::TooLarge { width: 70_000, height: 1 }));
+    }
+
+    #[test]
+    fn test_shareable_rejects_corrupt_codes() {
+        let mut rng = WasmRng::from_seed(8);
+        let mines = place_mines_random(9, 9, 10, &[], 0, &mut rng).unwrap();
+        let code = encode_shareable(9, 9, &mines).unwrap();
+
+        // One character changed in the bitmap
+        let mut flipped = code.clone().into_bytes();
+        flipped[10] = if flipped[10] == b'A' { b'B' } else { b'A' };
+        let flipped = String::from_utf8(flipped).unwrap();
+        assert_eq!(decode_shareable(&flipped).err(), Some(DecodeError::ChecksumMismatch));
+
+        assert_eq!(decode_shareable(&code[..code.len() - 4]).err(), Some(DecodeError::InvalidLength));
+        assert_eq!(decode_shareable(&format!("{}*", &code[1..])).err(), Some(DecodeError::InvalidCharacter));
+        assert_eq!(decode_shareable("").err(), Some(DecodeError::InvalidLength));
+        assert!(matches!(decode_shareable(&code.replacen('A', "C", 1)), Err(DecodeError::UnsupportedVersion(_))));
+    }
+
+    #[test]
+    fn test_canonical_hash_is_symmetry_invariant() {
+        let mut a = Mines::new(4, 3);
+        a.set(0, 0, true);
+        a.set(1, 2, true);
+        // Rotated 180 degrees
+        let mut b = Mines::new(4, 3);
+        b.set(3, 2, true);
+        b.set(2, 0, true);
+        assert_eq!(canonical_hash(&a), canonical_hash(&b));
+
+        let mut c = Mines::new(4, 3);
+        c.set(0, 0, true);
+        c.set(1, 1, true);
+        assert_ne!(canonical_hash(&a), canonical_hash(&c));
+    }
+
+    #[test]
+    fn test_canonicalize_matches_rotations() {
+        // 180 degrees on a non-square board
+        let mut a = Mines::new(4, 3);
+        a.set(0, 0, true);
+        a.set(1, 2, true);
+        let b = apply_transform(&a, 3);
+        assert!(b.get(3, 2) && b.get(2, 0));
+        assert_eq!(canonicalize(&a).cells, canonicalize(&b).cells);
+        assert_eq!(canonical_hash(&canonicalize(&a)), canonical_hash(&a));
+
+        // Quarter turns on a square board
+        let mut sq = Mines::new(4, 4);
+        sq.set(0, 1, true);
+        sq.set(2, 3, true);
+        sq.set(3, 3, true);
+        let canonical = canonicalize(&sq);
+        for t in 0..8 {
+            assert_eq!(canonicalize(&apply_transform(&sq, t)).cells, canonical.cells, "transform {}", t);
+        }
+    }
+
+    #[test]
+    fn test_generate_unique_batch_stops_when_space_is_exhausted() {
+        // A single mine on 4x4 is a corner, edge or centre board up to symmetry
+        let nc = NeighborCache::new(4, 4);
+        let boards = generate_unique_batch(4, 4, 1, 0, 0, 1, 1, 0, 5, &nc, |_, _, _, _, _| true);
+        assert_eq!(boards.len(), 3);
+        let hashes: std::collections::HashSet<u64> =
+            boards.iter().map(|(_, b)| canonical_hash(&b.mines)).collect();
+        assert_eq!(hashes.len(), 3);
+    }
+
+    #[test]
+    fn test_count_guess_islands() {
+        // A wall of mines at x = 3 cuts off x = 4..6, which hides one more mine
+        // that no clue can reach
+        let nc = NeighborCache::new(7, 3);
+        let mut mines = Mines::new(7, 3);
+        for y in 0..3 {
+            mines.set(3, y, true);
+        }
+        mines.set(6, 1, true);
+        let grid = calculate_numbers(&mines, &nc);
+        assert_eq!(count_guess_islands(&grid, &mines, &nc, 0, 1), 1);
+
+        let mut open = Mines::new(7, 3);
+        open.set(6, 1, true);
+        let grid = calculate_numbers(&open, &nc);
+        assert_eq!(count_guess_islands(&grid, &open, &nc, 0, 1), 0);
+
+        // 8x2 stalled on a 50/50 at x = 3: the cells behind it are out of every
+        // clue's reach, but they belong to the region bordering the clues
+        let nc = NeighborCache::new(8, 2);
+        let mut mines = Mines::new(8, 2);
+        for (x, y) in [(3, 0), (6, 0), (7, 1)] {
+            mines.set(x, y, true);
+        }
+        let grid = calculate_numbers(&mines, &nc);
+        assert_eq!(count_guess_islands(&grid, &mines, &nc, 0, 0), 0);
+    }
+
+    #[test]
+    fn test_mines_equal() {
+        let mut a = Mines::new(3, 2);
+        a.set(1, 1, true);
+        let mut b = Mines::new(3, 2);
+        b.set(1, 1, true);
+        assert!(mines_equal(&a, &b));
+
+        b.set(0, 0, true);
+        assert!(!mines_equal(&a, &b));
+
+        // Same cell buffer, different shape
+        let c = Mines { width: 2, height: 3, cells: a.cells.clone() };
+        assert!(!mines_equal(&a, &c));
+        assert!(!mines_equal(&a, &Mines::new(4, 4)));
+    }
+
+    #[test]
+    fn test_load_board_validates_input() {
+        let (mines, grid) = load_board(3, 2, &[1, 0, 0, 0, 0, 0]).unwrap();
+        assert_eq!(mines.count(), 1);
+        assert_eq!(grid.get(1, 1), 1);
+        assert_eq!(grid.get(2, 0), 0);
+
+        let Err(error) = load_board(3, 2, &[1, 0, 0, 0, 0]) else { panic!("length mismatch accepted") };
+        assert!(error.contains("has 5 cells, expected 6"), "{}", error);
+        assert!(load_board(3, 2, &[0, 0, 2, 0, 0, 0]).is_err());
+        assert!(load_board(1, 2, &[1, 1]).is_err());
+        assert!(load_board(0, 2, &[]).is_err());
+    }
+
+    /// Deterministic stand-in RNG yielding 0, 1, 2, ... modulo `max`.
+    struct CountingRng(usize);
+
+    impl BoardRng for CountingRng {
+        fn gen_range(&mut self, max: usize) -> usize {
+            self.0 += 1;
+            (self.0 - 1) % max
+        }
+    }
+
+    #[test]
+    fn test_place_mines_with_injected_rng() {
+        // Alternating x/y draws from 0,1,2,... visit (0,1), (2,3), (0,1), ... on
+        // a 3x4 board; the zone at (0,1) rejects the first draw
+        let mut rng = CountingRng(0);
+        let mines = place_mines_random(3, 4, 2, &[SafeZone::new(0, 1, 0)], 0, &mut rng).unwrap();
+        assert_eq!(mines.count(), 2);
+        assert!(!mines.get(0, 1));
+        assert!(mines.get(2, 3));
+    }
+
+    #[test]
+    fn test_mine_placement_failure_is_reported() {
+        // An rng stuck on (0, 0) can never place a second mine
+        struct StuckRng;
+        impl BoardRng for StuckRng {
+            fn gen_range(&mut self, _max: usize) -> usize { 0 }
+        }
+        assert!(place_mines_random(3, 4, 2, &[], 0, &mut StuckRng).is_err());
+
+        let nc = NeighborCache::new(3, 4);
+        let result = generate_solvable_board_with_rng(3, 4, 2, 0, 1, 0, 0, 10, &nc, &mut StuckRng, |_, _, _, _, _| true);
+        assert!(!result.success);
+        assert_eq!(result.failure, Some(GenerationFailure::MinePlacementFailed));
+        assert_eq!(result.mines.count(), 0);
+
+        let mut rng = CountingRng(0);
+        let result = generate_solvable_board_with_rng(3, 4, 2, 0, 1, 0, 0, 3, &nc, &mut rng, |_, _, _, _, _| false);
+        assert_eq!(result.failure, Some(GenerationFailure::NoSolvableFound));
+        assert_eq!(result.attempts, 3);
+    }
+
+    #[test]
+    fn test_calculate_numbers_simple() {
+        let nc = NeighborCache::new(3, 3);
+        let mut mines = Mines::new(3, 3);
+        // Place a single mine at (1, 1) center
+        mines.set(1, 1, true);
+
+        let grid = calculate_numbers(&mines, &nc);
+
+        // All 8 neighbors should be 1
+        assert_eq!(grid.get(0, 0), 1);
+        assert_eq!(grid.get(0, 1), 1);
+        assert_eq!(grid.get(0, 2), 1);
+        assert_eq!(grid.get(1, 0), 1);
+        assert_eq!(grid.get(1, 2), 1);
+        assert_eq!(grid.get(2, 0), 1);
+        assert_eq!(grid.get(2, 1), 1);
+        assert_eq!(grid.get(2, 2), 1);
+    }
+
+    #[test]
+    fn test_calculate_numbers_corner_mine() {
+        let nc = NeighborCache::new(3, 3);
+        let mut mines = Mines::new(3, 3);
+        // Mine at corner (0, 0)
+        mines.set(0, 0, true);
+
+        let grid = calculate_numbers(&mines, &nc);
+
+        // (1,0), (0,1), (1,1) should be 1, others 0
+        assert_eq!(grid.get(1, 0), 1);
+        assert_eq!(grid.get(0, 1), 1);
+        assert_eq!(grid.get(1, 1), 1);
+        assert_eq!(grid.get(2, 0), 0);
+        assert_eq!(grid.get(2, 2), 0);
+    }
+
+    #[test]
+    fn test_verify_numbers_reports_wrong_cell() {
+        let nc = NeighborCache::new(3, 3);
+        let mut mines = Mines::new(3, 3);
+        mines.set(0, 0, true);
+        let mut grid = calculate_numbers(&mines, &nc);
+        grid.set(0, 0, -1);
+        assert!(verify_numbers(&grid, &mines, &nc).is_empty());
+
+        grid.set(2, 1, 3);
+        assert_eq!(verify_numbers(&grid, &mines, &nc), vec![(2, 1)]);
+    }
+
+    #[test]
+    fn test_calculate_numbers_weighted() {
+        // Mines at (0,0) and (1,0): the center sees one diagonal and one orthogonal
+        let nc = NeighborCache::new(3, 3);
+        let mut mines = Mines::new(3, 3);
+        mines.set(0, 0, true);
+        mines.set(1, 0, true);
+        let grid = calculate_numbers_weighted(&mines, &nc, 2, 1);
+        assert_eq!(grid.get(1, 1), 3);
+        assert_eq!(grid.get(2, 0), 1);
+        assert_eq!(grid.get(2, 1), 2);
+        assert_eq!(grid.get(0, 1), 3);
+        assert_eq!(grid.get(0, 2), 0);
+        assert_eq!(calculate_numbers_weighted(&mines, &nc, 1, 1).cells, calculate_numbers(&mines, &nc).cells);
+    }
+
+    #[test]
+    fn test_mine_move_impact_skips_shared_neighbors() {
+        let nc = NeighborCache::new(4, 3);
+        let mut mines = Mines::new(4, 3);
+        mines.set(0, 1, true);
+
+        let impact = mine_move_impact(&mines, &nc, (0, 1), (1, 1));
+        assert_eq!(impact, vec![(0, 1), (1, 1), (2, 0), (2, 1), (2, 2)]);
+
+        // Only the clues in the listed cells differ after the real move
+        let before = calculate_numbers(&mines, &nc);
+        mines.set(0, 1, false);
+        mines.set(1, 1, true);
+        let after = calculate_numbers(&mines, &nc);
+        for x in 0..4 {
+            for y in 0..3 {
+                let changed = before.get(x, y) != after.get(x, y) || x < 2 && y == 1;
+                assert_eq!(changed, impact.contains(&(x, y)), "cell ({}, {})", x, y);
+            }
+        }
+
+        assert!(mine_move_impact(&mines, &nc, (0, 1), (2, 2)).is_empty());
+    }
+
+    #[test]
+    fn test_generate_guess_required_board() {
+        let nc = NeighborCache::new(9, 9);
+        let zone = SafeZone::new(4, 4, 1);
+        let mut rng = WasmRng::from_seed(4);
+        let (result, guess_point) = generate_guess_required_board_with_rng(9, 9, 10, &zone, 500, &nc, &mut rng);
+        assert!(result.success);
+        assert!(!crate::solver::is_solvable(&result.grid, &result.mines, &nc, 4, 4));
+        assert_eq!(crate::solver::min_guesses(&result.grid, &result.mines, &nc, 4, 4), 1);
+        assert!(matches!(guess_point, crate::solver::GuessPoint::StuckAt { .. }));
+    }
+
+    #[test]
+    fn test_opening_size_distribution_matches_fresh_reveals() {
+        let nc = NeighborCache::new(16, 16);
+        let mut rng = WasmRng::from_seed(3);
+        let board = generate_solvable_board_with_rng(16, 16, 40, 8, 8, 1, 0, 1, &nc, &mut rng, |_, _, _, _, _| true);
+
+        // One fresh reveal per safe cell, the quadratic way
+        let flags = Flags::new(16, 16);
+        let mut expected: std::collections::BTreeMap<usize, u32> = std::collections::BTreeMap::new();
+        for x in 0..16 {
+            for y in 0..16 {
+                if board.mines.get(x, y) { continue; }
+                let mut visible = VisibleGrid::new(16, 16);
+                simulate_reveal(&board.grid, &mut visible, &flags, &nc, x, y);
+                *expected.entry(visible.cells.iter().filter(|&&v| v != -1).count()).or_insert(0) += 1;
+            }
+        }
+        let distribution = opening_size_distribution(&board.grid, &board.mines, &nc);
+        assert_eq!(distribution, expected.into_iter().collect::<Vec<_>>());
+        assert_eq!(distribution.iter().map(|&(_, c)| c).sum::<u32>(), 256 - 40);
+    }
+
+    #[test]
+    fn test_total_clue_sum() {
+        // A corner mine on 3x3 touches three cells
+        let nc = NeighborCache::new(3, 3);
+        let mut mines = Mines::new(3, 3);
+        mines.set(0, 0, true);
+        assert_eq!(total_clue_sum(&calculate_numbers(&mines, &nc), &mines), 3);
+        mines.set(1, 1, true);
+        // (1,0), (0,1) see both mines; the other five see only the centre
+        assert_eq!(total_clue_sum(&calculate_numbers(&mines, &nc), &mines), 2 + 2 + 5);
+    }
+
+    #[test]
+    fn test_opening_clue_variety() {
+        // Mines in the bottom-right corner and at (4,1): the opening from (0,0)
+        // is bordered by 1s, the 2 at (3,2) and the 3 at (3,3)
+        let mut mines = Mines::new(5, 5);
+        for (x, y) in [(4, 4), (4, 3), (3, 4), (4, 1)] {
+            mines.set(x, y, true);
+        }
+        let nc = NeighborCache::new(5, 5);
+        let grid = calculate_numbers(&mines, &nc);
+        assert_eq!((grid.get(3, 3), grid.get(3, 2), grid.get(3, 1)), (3, 2, 1));
+        assert_eq!(opening_clue_variety(&grid, &nc, 0, 0, 0), 3);
+
+        // A number start reveals only itself
+        assert_eq!(opening_clue_variety(&grid, &nc, 4, 2, 0), 1);
+
+        // 8x3 split by a blocked column at x=3 with mines down x=7: the start's
+        // own cascade stays left of the wall, the radius-2 zone reaches past it
+        let mut blocked = Blocked::new(8, 3);
+        let mut mines = Mines::new(8, 3);
+        for y in 0..3 {
+            blocked.set(3, y, true);
+            mines.set(7, y, true);
+        }
+        let nc = NeighborCache::with_blocked(&blocked);
+        let grid = calculate_numbers(&mines, &nc);
+        assert_eq!(opening_clue_variety(&grid, &nc, 2, 1, 0), 0);
+        assert_eq!(opening_clue_variety(&grid, &nc, 2, 1, 2), 2);
+    }
+
+    #[test]
+    fn test_has_full_clue_spectrum() {
+        // Renders as "*****1 / *76*42 / ***5*3 / *8*7** / ******"
+        let mut mines = Mines::from_rows(&["*****.", "*..*..", "***.*.", "*.*.**", "******"]);
+        let nc = NeighborCache::new(6, 5);
+        let grid = calculate_numbers(&mines, &nc);
+        assert!(has_full_clue_spectrum(&grid, &mines));
+
+        // Freeing the 8's corner neighbor turns it into a 7 and drops the 8
+        mines.set(0, 4, false);
+        let grid = calculate_numbers(&mines, &nc);
+        assert!(!has_full_clue_spectrum(&grid, &mines));
+    }
+
+    #[test]
+    fn test_calculate_numbers_toroidal_corner() {
+        let mut mines = Mines::new(5, 5);
+        mines.set(0, 0, true);
+        let grid = calculate_numbers_toroidal(&mines);
+
+        // The opposite corner touches (0,0) across both wrapped edges
+        assert_eq!(grid.get(4, 4), 1);
+        assert_eq!(grid.get(4, 0), 1);
+        assert_eq!(grid.get(0, 4), 1);
+        assert_eq!(grid.get(2, 2), 0);
+        assert_eq!(calculate_numbers(&mines, &NeighborCache::new(5, 5)).get(4, 4), 0);
+    }
+
+    #[test]
+    fn test_toroidal_cascade_crosses_wrap_edge() {
+        // 6x3 torus walled off by mines in column 2: the zeros in columns 0,
+        // 4 and 5 only connect across the left/right seam
+        let mut mines = Mines::new(6, 3);
+        for y in 0..3 {
+            mines.set(2, y, true);
+        }
+        let grid = calculate_numbers_toroidal(&mines);
+        let flags = Flags::new(6, 3);
+
+        let mut visible = VisibleGrid::new(6, 3);
+        simulate_reveal(&grid, &mut visible, &flags, &NeighborCache::new_toroidal(6, 3), 0, 1);
+        for y in 0..3 {
+            assert_eq!(visible.get(5, y), 0);
+            assert_eq!(visible.get(4, y), 0);
+            assert_eq!(visible.get(3, y), 3);
+            assert_eq!(visible.get(2, y), -1);
+        }
+
+        // The flat cache stops at the seam
+        let mut flat = VisibleGrid::new(6, 3);
+        simulate_reveal(&grid, &mut flat, &flags, &NeighborCache::new(6, 3), 0, 1);
+        assert_eq!(flat.get(5, 1), -1);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    fn test_toroidal_solve_is_consistent() {
+        // Solving with the same cache must never contradict the wrapped layout
+        let mut mines = Mines::new(5, 5);
+        mines.set(0, 0, true);
+        let grid = calculate_numbers_toroidal(&mines);
+        let nc = NeighborCache::new_toroidal(5, 5);
+        assert_eq!(nc.get(2, 2).len(), 8);
+        assert!(crate::solver::is_solvable_checked(&grid, &mines, &nc, 2, 2));
+    }
+
+    #[test]
+    fn test_blocked_cell_is_ignored_by_numbers_and_solver() {
+        // 4x3 with a hole at (1,1) and the mine at (3,0)
+        let mut blocked = Blocked::new(4, 3);
+        blocked.set(1, 1, true);
+        let nc = NeighborCache::with_blocked(&blocked);
+        assert!(nc.get(0, 0).iter().all(|&n| n != (1, 1)));
+        assert!(nc.get(1, 1).is_empty());
+
+        let mut mines = Mines::new(4, 3);
+        mines.set(3, 0, true);
+        let grid = calculate_numbers(&mines, &nc);
+        assert_eq!(grid.get(1, 1), BLOCKED);
+        assert_eq!(grid.get(2, 0), 1);
+        assert_eq!(grid.get(0, 0), 0);
+
+        // The hole is never hidden, so the solve completes without it
+        assert!(crate::solver::is_solvable(&grid, &mines, &nc, 0, 0));
+        let mut state = crate::solver::SolveState::from_start(&grid, &nc, 0, 0);
+        state.run_to_stall(&grid, &nc, 1);
+        assert_eq!(state.visible.get(1, 1), BLOCKED);
+
+        // Generation keeps every mine off the hole
+        let mut rng = WasmRng::from_seed(1);
+        let zone = SafeZone::new(0, 2, 0);
+        for _ in 0..20 {
+            let result = generate_blocked_board(&blocked, 8, &zone, 0, 1, &nc, &mut rng, |_, _, _, _, _| true);
+            assert!(result.success && !result.mines.get(1, 1));
+            assert_eq!(result.grid.get(1, 1), BLOCKED);
+        }
+        assert!(generate_blocked_board(&blocked, 11, &zone, 0, 1, &nc, &mut rng, |_, _, _, _, _| true).error.is_some());
+    }
+
+    #[test]
+    fn test_blocked_board_honours_border_buffer_and_reseeding() {
+        // 8x8 with a hole in the far corner, safe cell (3,3) plus one buffer ring
+        let mut blocked = Blocked::new(8, 8);
+        blocked.set(7, 7, true);
+        let nc = NeighborCache::with_blocked(&blocked);
+        let zone = SafeZone::new(3, 3, 1);
+        let mut rng = WasmRng::from_seed(2);
+        for _ in 0..20 {
+            let result = generate_blocked_board(&blocked, 20, &zone, 1, 1, &nc, &mut rng, |_, _, _, _, _| true);
+            assert!(result.success);
+            assert!((1..=5).all(|x| (1..=5).all(|y| !result.mines.get(x, y))));
+        }
+        // The buffer counts against the free cells: 63 - 25 leaves room for 38
+        assert!(generate_blocked_board(&blocked, 39, &zone, 1, 1, &nc, &mut rng, |_, _, _, _, _| true).error.is_some());
+
+        let generate = |interval| generate_reseeding_blocked_board(
+            &blocked, 10, &zone, 0, 200, &nc, 5, interval, crate::solver::is_solvable,
+        );
+        let (a, b) = (generate(Some(3)), generate(Some(3)));
+        assert_eq!((a.mines.cells, a.attempts), (b.mines.cells, b.attempts));
+        let failed = generate_reseeding_blocked_board(
+            &blocked, 10, &zone, 0, 7, &nc, 5, Some(3), |_, _, _, _, _| false,
+        );
+        assert_eq!((failed.success, failed.attempts), (false, 7));
+    }
+
+    #[test]
+    fn test_forced_cascade_cells_are_opening_interior() {
+        // 6x5 with a single mine in the corner: everything two steps away is interior
+        let nc = NeighborCache::new(6, 5);
+        let mut mines = Mines::new(6, 5);
+        mines.set(5, 4, true);
+        let grid = calculate_numbers(&mines, &nc);
+        let cells = forced_cascade_cells(&grid, &nc);
+        assert_eq!(cells.len(), 30 - 4 - 5);
+        assert!(cells.iter().all(|&(x, y)| x < 3 || y < 2));
+        assert!(cells.contains(&(0, 0)) && !cells.contains(&(4, 2)) && !cells.contains(&(5, 4)));
+    }
+
+    #[test]
+    fn test_compute_3bv() {
+        // 5x1 strip with a mine in the middle: "0 1 * 1 0"
+        let nc = NeighborCache::new(5, 1);
+        let mut mines = Mines::new(5, 1);
+        mines.set(2, 0, true);
+        let grid = calculate_numbers(&mines, &nc);
+        // Two openings, each swallowing its adjacent "1"
+        assert_eq!(compute_3bv(&grid, &mines, &nc), 2);
+
+        // 3x1 strip "* 1 *": no openings, a single numbered cell
+        let nc = NeighborCache::new(3, 1);
+        let mut mines = Mines::new(3, 1);
+        mines.set(0, 0, true);
+        mines.set(2, 0, true);
+        let grid = calculate_numbers(&mines, &nc);
+        assert_eq!(compute_3bv(&grid, &mines, &nc), 1);
+
+        // Mine-free 3x3 with the centre blocked: the hole is not a click
+        let mut blocked = Blocked::new(3, 3);
+        blocked.set(1, 1, true);
+        let nc = NeighborCache::with_blocked(&blocked);
+        let mines = Mines::new(3, 3);
+        let grid = calculate_numbers(&mines, &nc);
+        assert_eq!(compute_3bv(&grid, &mines, &nc), 1);
+    }
+
+    #[test]
+    fn test_opening_regions() {
+        // 5x1 strip "0 1 * 1 0": two openings, the mine stays unlabeled
+        let mut mines = Mines::new(5, 1);
+        mines.set(2, 0, true);
+        let nc = NeighborCache::new(5, 1);
+        let grid = calculate_numbers(&mines, &nc);
+        assert_eq!(opening_regions(&grid, &mines, &nc), vec![0, 0, -1, 1, 1]);
+
+        // "0 1 * 2 * 1 0": the "2" between the mines is in no opening
+        let mut mines = Mines::new(7, 1);
+        mines.set(2, 0, true);
+        mines.set(4, 0, true);
+        let nc = NeighborCache::new(7, 1);
+        let grid = calculate_numbers(&mines, &nc);
+        assert_eq!(opening_regions(&grid, &mines, &nc), vec![0, 0, -1, -1, -1, 1, 1]);
+    }
+
+    #[test]
+    fn test_best_start_position_prefers_large_opening() {
+        // 7x3 with a mine wall at x=2: the right side offers the bigger opening
+        let nc = NeighborCache::new(7, 3);
+        let mut mines = Mines::new(7, 3);
+        for y in 0..3 {
+            mines.set(2, y, true);
+        }
+        let grid = calculate_numbers(&mines, &nc);
+
+        let (x, y) = best_start_position(&mines, &grid, &nc);
+        assert!(x >= 4, "expected a start on the open side, got ({}, {})", x, y);
+        assert!(nc.get(x, y).iter().all(|&(nx, ny)| !mines.get(nx, ny)));
+    }
+
+    #[test]
+    fn test_mine_clusters() {
+        let nc = NeighborCache::new(6, 6);
+        let mut mines = Mines::new(6, 6);
+        // Diagonal pair counts as one cluster
+        mines.set(0, 0, true);
+        mines.set(1, 1, true);
+        // Isolated mine
+        mines.set(4, 0, true);
+        // L-shape
+        mines.set(3, 4, true);
+        mines.set(3, 5, true);
+        mines.set(4, 5, true);
+
+        let clusters = mine_clusters(&mines, &nc);
+        let mut sizes: Vec<usize> = clusters.iter().map(|c| c.len()).collect();
+        sizes.sort_unstable();
+        assert_eq!(sizes, vec![1, 2, 3]);
+        assert_eq!(clusters.iter().map(|c| c.len()).sum::<usize>(), mines.count());
+    }
+
+    #[test]
+    #[cfg(not(target_arch = "wasm32"))]
+    fn test_essential_mines() {
+        // 5x4 board, start (0,0): dropping (1,2) leaves a guess, the other two are removable
+        let mut mines = Mines::new(5, 4);
+        mines.set(0, 2, true);
+        mines.set(1, 2, true);
+        mines.set(3, 2, true);
+        let nc = NeighborCache::new(5, 4);
+        assert_eq!(essential_mines(&mines, &nc, 0, 0), vec![(1, 2)]);
+    }
+
+    #[test]
+    #[cfg(not(target_arch = "wasm32"))]
+    fn test_estimate_solvable_fraction() {
+        let nc = NeighborCache::new(9, 9);
+        let zone = SafeZone::new(4, 4, 1);
+        // Two mines on a 9x9 board almost never force a guess
+        let sparse = estimate_solvable_fraction(9, 9, 2, &zone, 50, &nc);
+        assert!(sparse > 0.95, "sparse fraction {}", sparse);
+        assert_eq!(estimate_solvable_fraction(9, 9, 2, &zone, 50, &nc), sparse);
+        assert_eq!(estimate_solvable_fraction(9, 9, 81, &zone, 50, &nc), 0.0);
+    }
+
+    #[test]
+    #[cfg(not(target_arch = "wasm32"))]
+    fn test_generate_with_attempt_log() {
+        let nc = NeighborCache::new(9, 9);
+        let zone = SafeZone::new(4, 4, 1);
+        let (result, log) = generate_with_attempt_log(9, 9, 25, &zone, 20, &nc);
+        assert_eq!(log.len(), result.attempts as usize);
+        assert!(log.iter().all(|f| (0.0..=1.0).contains(f)));
+        assert_eq!(log.last() == Some(&1.0), result.success);
+    }
+
+    #[test]
+    fn test_generate_rated_board_matches_difficulty_rating() {
+        let nc = NeighborCache::new(16, 16);
+        let zone = SafeZone::new(8, 8, 1);
+        for seed in 0..12 {
+            let mut rng = WasmRng::from_seed(seed);
+            let (result, report) = generate_rated_board(16, 16, 40, &zone, 500, &nc, &mut rng);
+            assert!(result.success);
+            let report = report.unwrap();
+            let rating = crate::solver::difficulty_rating(&result.grid, &result.mines, &nc, 8, 8);
+            assert_eq!(Some(report.rating), rating, "seed {}", seed);
+            assert!(report.metrics.iterations > 0);
+        }
+    }
+
+    #[test]
+    #[cfg(not(target_arch = "wasm32"))]
+    fn test_min_solvable_safe_radius() {
+        let nc = NeighborCache::new(9, 9);
+        assert_eq!(min_solvable_safe_radius(9, 9, 10, 40, &nc), Some(1));
+        // 25 mines almost always force a guess from a 3x3 opening, rarely from 5x5
+        assert_eq!(min_solvable_safe_radius(9, 9, 25, 40, &nc), Some(2));
+        assert_eq!(min_solvable_safe_radius(9, 9, 35, 40, &nc), None);
+    }
+
+    #[test]
+    fn test_generate_with_density_fallback() {
+        // 5x2 with the left 2x2 open: five mines in the six free cells never
+        // leave enough clues, but four do when the two safe cells share x = 2
+        let nc = NeighborCache::new(5, 2);
+        let (result, bombs) = generate_with_density_fallback(5, 2, 5, &SafeZone::new(0, 0, 1), 500, &nc);
+        assert!(result.success);
+        assert!(bombs < 5);
+        assert_eq!(result.mines.count(), bombs);
+        assert!(result.attempts > 500);
+
+        let (result, bombs) = generate_with_density_fallback(5, 2, 20, &SafeZone::new(0, 0, 1), 500, &nc);
+        assert_eq!(result.failure, Some(GenerationFailure::InvalidParams));
+        assert_eq!(bombs, 20);
+
+        // An rng stuck on (0, 0) cannot place two mines; that level steps down too
+        struct StuckRng;
+        impl BoardRng for StuckRng {
+            fn gen_range(&mut self, _max: usize) -> usize { 0 }
+        }
+        let nc = NeighborCache::new(3, 4);
+        let (result, bombs) = generate_with_density_fallback_with_rng(3, 4, 2, &SafeZone::new(0, 1, 0), 10, &nc, &mut StuckRng);
+        assert!(result.success);
+        assert!(bombs < 2);
+        assert_eq!(result.mines.count(), bombs);
+    }
+
+    #[test]
+    fn test_generate_solvable_board_always_solvable() {
+        let nc = NeighborCache::new(5, 5);
+        // Trivial solver that always returns true
+        let result = generate_solvable_board(5, 5, 3, 2, 2, 1, 0, 100, &nc, |_, _, _, _, _| true);
+        assert!(result.success);
+        assert_eq!(result.attempts, 1); // Should succeed on first try
+        assert_eq!(result.mines.count(), 3);
+    }
+
+    #[test]
+    fn test_generate_solvable_board_never_solvable() {
+        let nc = NeighborCache::new(5, 5);
+        // Solver that always returns false
+        let result = generate_solvable_board(5, 5, 3, 2, 2, 1, 0, 10, &nc, |_, _, _, _, _| false);
+        assert!(!result.success);
+        assert_eq!(result.attempts, 10);
+    }
+
+    #[test]
+    fn test_generate_accepted_board_needs_both_checks() {
+        let nc = NeighborCache::new(5, 5);
+        let mut rng = WasmRng::from_seed(7);
+        let result = generate_accepted_board_with_rng(
+            5, 5, 3, 2, 2, 1, 0, 1000, &nc, &mut rng,
+            |_, _, _, _, _| true, |_, mines, _| mines.get(0, 0),
+        );
+        assert!(result.success);
+        assert!(result.mines.get(0, 0));
+
+        // Acceptance only runs on solvable layouts
+        let result = generate_accepted_board(
+            5, 5, 3, 2, 2, 1, 0, 10, &nc,
+            |_, _, _, _, _| false, |_, _, _| panic!("accept_fn ran on an unsolvable layout"),
+        );
+        assert!(!result.success);
+        assert_eq!(result.attempts, 10);
+    }
+
+    #[test]
+    fn test_generate_rect_board_keeps_strip_open() {
+        // Clear the whole bottom row of a 9x9 board
+        let strip = SafeRect::new(0, 8, 8, 8);
+        let nc = NeighborCache::new(9, 9);
+        let mut rng = WasmRng::from_seed(3);
+        let result = generate_rect_board(9, 9, 10, &strip, 50, &nc, &mut rng, crate::solver::is_solvable_rect);
+        assert!(result.error.is_none());
+        assert_eq!(result.mines.count(), 10);
+        for x in 0..9 {
+            assert!(!result.mines.get(x, 8), "Mine found in safe strip at ({}, 8)", x);
+        }
+
+        let state = crate::solver::SolveState::from_rects(&result.grid, &nc, &[strip]);
+        for x in 0..9 {
+            assert_ne!(state.visible.get(x, 8), -1, "strip cell ({}, 8) not revealed at start", x);
+        }
+
+        assert!(validate_safe_rect(9, 9, 10, &SafeRect::new(0, 9, 8, 9)).is_err());
+        assert!(validate_safe_rect(9, 9, 73, &strip).is_err());
+    }
+
+    #[test]
+    fn test_generate_multi_start_board_keeps_both_zones_open() {
+        let zones = [SafeZone::new(3, 3, 2), SafeZone::new(12, 12, 2)];
+        let nc = NeighborCache::new(16, 16);
+        let mut rng = WasmRng::from_seed(5);
+        let result = generate_multi_start_board(16, 16, 30, &zones, 50, &nc, &mut rng, crate::solver::is_solvable_multi_start);
+        assert!(result.error.is_none());
+        assert_eq!(result.mines.count(), 30);
+
+        let flags = Flags::new(16, 16);
+        for zone in &zones {
+            for x in 0..16 {
+                for y in 0..16 {
+                    if zone.contains(x, y) {
+                        assert!(!result.mines.get(x, y), "Mine found in safe zone at ({}, {})", x, y);
+                    }
+                }
+            }
+
+            // A radius-2 zone makes the center a zero whose cascade opens the whole zone
+            let mut visible = VisibleGrid::new(16, 16);
+            simulate_reveal(&result.grid, &mut visible, &flags, &nc, zone.x, zone.y);
+            for x in 0..16 {
+                for y in 0..16 {
+                    if zone.contains(x, y) {
+                        assert_ne!(visible.get(x, y), -1);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_generate_mercy_board_opens_whole_zone() {
+        let nc = NeighborCache::new(16, 16);
+        let mut rng = WasmRng::from_seed(9);
+        let result = generate_mercy_board(16, 16, 30, 8, 8, 2, 50, &nc, &mut rng, crate::solver::is_solvable_multi_start);
+        assert!(result.error.is_none());
+        assert_eq!(result.mines.count(), 30);
+
+        let zone = SafeZone::new(8, 8, 2);
+        for x in 6..=10 {
+            for y in 6..=10 {
+                assert!(!result.mines.get(x, y), "Mine found in mercy zone at ({}, {})", x, y);
+            }
+        }
+        assert!(opening_covers_zone(&result.grid, &nc, &zone));
+    }
+
+    #[test]
+    fn test_reseeding_board_is_deterministic() {
+        let nc = NeighborCache::new(9, 9);
+        let generate = |interval| generate_reseeding_board(
+            9, 9, 10, 4, 4, 1, 0, 200, &nc, 5, interval, crate::solver::is_solvable, |_, _, _| true,
+        );
+        let (a, b) = (generate(Some(3)), generate(Some(3)));
+        assert!(a.success);
+        assert_eq!((a.mines.cells, a.attempts), (b.mines.cells, b.attempts));
+
+        // Without reseeding it is the plain seeded generator
+        let plain = generate_solvable_board_with_rng(
+            9, 9, 10, 4, 4, 1, 0, 200, &nc, &mut WasmRng::from_seed(5), crate::solver::is_solvable,
+        );
+        assert_eq!(generate(None).mines.cells, plain.mines.cells);
+
+        // Attempts add up across the reseeds
+        let failed = generate_reseeding_board(
+            9, 9, 10, 4, 4, 1, 0, 7, &nc, 5, Some(3), |_, _, _, _, _| false, |_, _, _| true,
+        );
+        assert_eq!((failed.success, failed.attempts), (false, 7));
+    }
+
+    #[test]
+    fn test_generate_batch_reproducible() {
+        let nc = NeighborCache::new(9, 9);
+        let a = generate_solvable_board_batch(9, 9, 10, 4, 4, 1, 50, 7, 3, &nc, |_, _, _, _, _| true);
+        let b = generate_solvable_board_batch(9, 9, 10, 4, 4, 1, 50, 7, 3, &nc, |_, _, _, _, _| true);
+        assert_eq!(a.len(), 3);
+        let seeds: Vec<u64> = a.iter().map(|(s, _)| *s).collect();
+        assert_eq!(seeds, vec![7, 8, 9]);
+        for ((_, ra), (_, rb)) in a.iter().zip(&b) {
+            assert_eq!(ra.mines.cells, rb.mines.cells);
+        }
+        assert_ne!(a[0].1.mines.cells, a[1].1.mines.cells);
+    }
+
+    #[test]
+    fn test_batch_board_regenerates_alone() {
+        // A real solver check, so boards take differing numbers of attempts
+        let nc = NeighborCache::new(9, 9);
+        let batch = generate_solvable_board_batch(9, 9, 10, 4, 4, 1, 50, 20, 5, &nc, crate::solver::is_solvable);
+        let mut rng = WasmRng::from_seed(20 + 3);
+        let alone = generate_solvable_board_with_rng(
+            9, 9, 10, 4, 4, 1, 0, 50, &nc, &mut rng, crate::solver::is_solvable,
+        );
+        let (seed, board) = &batch[3];
+        assert_eq!(*seed, 23);
+        assert_eq!(board.mines.cells, alone.mines.cells);
+        assert_eq!(board.attempts, alone.attempts);
+    }
+
+    #[test]
+    fn test_safe_zone_size_clipped() {
+        assert_eq!(safe_zone_size(10, 10, 5, 5, 1), 9);
+        assert_eq!(safe_zone_size(10, 10, 0, 0, 1), 4);
+        assert_eq!(safe_zone_size(3, 3, 1, 1, 5), 9);
+    }
+
+    #[test]
+    fn test_generate_rejects_over_dense_request() {
+        let nc = NeighborCache::new(5, 5);
+        // 25 cells - 9 safe = 16 available, 17 mines can never fit
+        let result = generate_solvable_board(5, 5, 17, 2, 2, 1, 0, 100, &nc, |_, _, _, _, _| {
+            panic!("solver must not run for rejected parameters")
+        });
+        assert!(!result.success);
+        assert_eq!(result.attempts, 0);
+        assert!(result.error.is_some());
+
+        // Exactly filling the available cells is still allowed
+        assert!(validate_board_params(5, 5, 16, 2, 2, 1).is_ok());
+        assert!(validate_board_params(5, 5, 3, 7, 2, 1).is_err());
+        assert!(validate_board_params(usize::MAX, 2, 0, 0, 0, 1).is_err());
     }
 }
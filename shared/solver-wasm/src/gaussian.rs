@@ -4,7 +4,9 @@
 //! Solves the frontier as a system of linear equations: Ax = b,
 //! where x are hidden cells (0=safe, 1=mine), A is connectivity, b is effective clues.
 
+use crate::arena::SolverArena;
 use crate::types::{cell_key, Flags, NeighborCache, VisibleGrid};
+use std::cell::RefCell;
 use std::collections::HashSet;
 
 /// Result from Gaussian elimination: cells proven to be safe or mines.
@@ -21,16 +23,42 @@ const MAX_COMPONENT_SIZE: usize = 50;
 const EPS: f32 = 0.001;
 const EPS_TINY: f32 = 0.000001;
 
+/// Components with at least this many variables are eliminated in sparse form.
+const SPARSE_MIN_VARS: usize = 24;
+
+/// Sparse elimination switches to dense once this fraction of the
+/// M × (N+1) matrix is non-zero.
+const DENSIFY_FILL_RATIO: f32 = 0.25;
+
+thread_local! {
+    /// Per-cell frontier marks reused by `get_connected_components`, grown to
+    /// the largest board seen. Every entry is 0 between calls.
+    static FRONTIER_MARKS: RefCell<Vec<u8>> = const { RefCell::new(Vec::new()) };
+}
+
+/// One matrix row as `(column, coeff)` pairs sorted by column; column N is the target.
+type SparseRow = Vec<(usize, f32)>;
+
+/// How `solve_component` stores its matrix during elimination.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Elimination {
+    Dense,
+    Sparse,
+}
+
 /// Solve the frontier using Gaussian Elimination.
 ///
 /// 1. Decompose frontier into connected components
 /// 2. Solve each component (with windowing for large ones)
-/// 3. Aggregate results
+/// 3. Aggregate results, sorted by `(x, y)`
+///
+/// `arena` lends the deduplication sets; `None` allocates them per call.
 pub fn solve(
     visible: &VisibleGrid,
     flags: &Flags,
     frontier: &[(usize, usize)],
     nc: &NeighborCache,
+    arena: Option<&mut SolverArena>,
 ) -> GaussianResult {
     if frontier.is_empty() {
         return GaussianResult { progress: false, safe: vec![], mines: vec![] };
@@ -42,8 +70,10 @@ pub fn solve(
     // 1. Decompose into connected components
     let components = get_connected_components(visible, frontier, nc, width, height);
 
-    let mut all_safe: HashSet<u32> = HashSet::new();
-    let mut all_mines: HashSet<u32> = HashSet::new();
+    let mut local = SolverArena::default();
+    let SolverArena { safe_keys: all_safe, mine_keys: all_mines, .. } = arena.unwrap_or(&mut local);
+    all_safe.clear();
+    all_mines.clear();
     let mut safe_list = Vec::new();
     let mut mine_list = Vec::new();
 
@@ -76,23 +106,49 @@ pub fn solve(
         }
     }
 
+    // Windowing and row reasoning emit cells in elimination order; sort for reproducibility
+    safe_list.sort_unstable();
+    mine_list.sort_unstable();
+
     let progress = !safe_list.is_empty() || !mine_list.is_empty();
     GaussianResult { progress, safe: safe_list, mines: mine_list }
 }
 
 /// Decompose the frontier into connected components using BFS.
 /// Two frontier cells are connected if they share a constraint (adjacent to the same clue).
+///
+/// The visit marks live in a thread-local buffer that is cleared cell by cell
+/// afterwards, so a call costs O(frontier) instead of O(width * height).
 fn get_connected_components(
     visible: &VisibleGrid,
     frontier: &[(usize, usize)],
     nc: &NeighborCache,
     width: usize,
     height: usize,
+) -> Vec<Vec<(usize, usize)>> {
+    FRONTIER_MARKS.with(|marks| {
+        let mut frontier_map = marks.borrow_mut();
+        if frontier_map.len() < width * height {
+            frontier_map.resize(width * height, 0);
+        }
+        let components = bfs_components(visible, frontier, nc, height, &mut frontier_map);
+        for &(x, y) in frontier {
+            frontier_map[x * height + y] = 0;
+        }
+        components
+    })
+}
+
+/// BFS over `frontier`, with `frontier_map` all zero on entry.
+fn bfs_components(
+    visible: &VisibleGrid,
+    frontier: &[(usize, usize)],
+    nc: &NeighborCache,
+    height: usize,
+    frontier_map: &mut [u8],
 ) -> Vec<Vec<(usize, usize)>> {
     let mut components = Vec::new();
     // 0 = not frontier, 1 = frontier (unvisited), 2 = visited
-    let mut frontier_map = vec![0u8; width * height];
-
     for &(x, y) in frontier {
         frontier_map[x * height + y] = 1;
     }
@@ -178,119 +234,89 @@ fn solve_large_component(
 }
 
 /// Solve a single connected component using Gaussian elimination.
+///
+/// Large components use the sparse path; results are identical either way.
 fn solve_component(
     visible: &VisibleGrid,
     flags: &Flags,
     component: &[(usize, usize)],
     nc: &NeighborCache,
 ) -> GaussianResult {
-    let width = visible.width;
-    let height = visible.height;
+    let mode = if component.len() >= SPARSE_MIN_VARS { Elimination::Sparse } else { Elimination::Dense };
+    solve_component_with(visible, flags, component, nc, mode)
+}
+
+fn solve_component_with(
+    visible: &VisibleGrid,
+    flags: &Flags,
+    component: &[(usize, usize)],
+    nc: &NeighborCache,
+    mode: Elimination,
+) -> GaussianResult {
     let num_vars = component.len();
     if num_vars == 0 {
         return GaussianResult { progress: false, safe: vec![], mines: vec![] };
     }
 
-    // 1. Build variable index map: (x,y) -> column index
-    let mut var_index_map = vec![-1i16; width * height];
-    for (i, &(x, y)) in component.iter().enumerate() {
-        var_index_map[x * height + y] = i as i16;
-    }
-
-    // 2. Build equations from clues adjacent to component variables
-    struct Equation {
-        neighbors: Vec<usize>, // variable indices
-        target: f32,
-    }
-    let mut equations: Vec<Equation> = Vec::new();
-    let mut processed_clues = vec![false; width * height];
-
-    for &(cx, cy) in component {
-        for &(nx, ny) in nc.get(cx, cy) {
-            let val = visible.get(nx, ny);
-            if val <= 0 { continue; }
-
-            let clue_idx = nx * height + ny;
-            if processed_clues[clue_idx] { continue; }
-            processed_clues[clue_idx] = true;
-
-            let mut eq_neighbors = Vec::new();
-            let mut flagged_count = 0i8;
-            let mut valid = true;
-
-            for &(cnx, cny) in nc.get(nx, ny) {
-                if flags.get(cnx, cny) {
-                    flagged_count += 1;
-                } else if visible.get(cnx, cny) == -1 {
-                    let v_idx = var_index_map[cnx * height + cny];
-                    if v_idx != -1 {
-                        eq_neighbors.push(v_idx as usize);
-                    } else {
-                        // Hidden neighbor not in our component — can't use this clue
-                        valid = false;
-                        break;
-                    }
-                }
-            }
-
-            if valid {
-                equations.push(Equation {
-                    neighbors: eq_neighbors,
-                    target: (val - flagged_count) as f32,
-                });
-            }
-        }
-    }
-
+    // 1-2. Variables are the component cells; one equation per usable clue
+    let equations = build_equations(visible, flags, component, nc);
     if equations.is_empty() {
         return GaussianResult { progress: false, safe: vec![], mines: vec![] };
     }
 
-    // 3. Construct matrix M × (N+1)
+    // 3. Construct matrix M × (N+1) and compute RREF
     let m = equations.len();
     let n = num_vars;
-    let cols = n + 1;
-    let mut matrix: Vec<Vec<f32>> = Vec::with_capacity(m);
-
-    for eq in &equations {
-        let mut row = vec![0.0f32; cols];
-        for &idx in &eq.neighbors {
-            row[idx] = 1.0;
+    let rows: Vec<SparseRow> = match mode {
+        Elimination::Dense => {
+            let cols = n + 1;
+            let mut matrix: Vec<Vec<f32>> = Vec::with_capacity(m);
+            for eq in &equations {
+                let mut row = vec![0.0f32; cols];
+                for &idx in &eq.vars {
+                    row[idx] = 1.0;
+                }
+                row[n] = eq.target as f32;
+                matrix.push(row);
+            }
+            compute_rref(&mut matrix, m, n);
+            matrix.iter().map(|row| dense_to_sparse(row)).collect()
         }
-        row[n] = eq.target;
-        matrix.push(row);
-    }
-
-    // 4. Compute RREF
-    compute_rref(&mut matrix, m, n);
+        Elimination::Sparse => {
+            let mut rows: Vec<SparseRow> = equations.iter().map(|eq| {
+                let mut row: SparseRow = eq.vars.iter().map(|&idx| (idx, 1.0)).collect();
+                row.sort_unstable_by_key(|&(c, _)| c);
+                if eq.target != 0 { row.push((n, eq.target as f32)); }
+                row
+            }).collect();
+            compute_rref_sparse(&mut rows, m, n);
+            rows
+        }
+    };
 
-    // 5. Reason about results
+    // 4. Reason about results
     let mut safe = Vec::new();
     let mut mines = Vec::new();
 
-    for row in &matrix {
-        let target = row[n];
+    for row in &rows {
+        let target = row.last().filter(|&&(c, _)| c == n).map_or(0.0, |&(_, t)| t);
         let mut min_val: f32 = 0.0;
         let mut max_val: f32 = 0.0;
-        let mut has_nonzero = false;
-        let mut vars_in_row: Vec<usize> = Vec::new();
+        let mut vars_in_row: Vec<(usize, f32)> = Vec::new();
 
-        for j in 0..n {
-            let coeff = row[j];
-            if coeff.abs() > EPS {
-                has_nonzero = true;
+        for &(j, coeff) in row {
+            if j < n && coeff.abs() > EPS {
                 if coeff > 0.0 { max_val += coeff; }
                 else { min_val += coeff; }
-                vars_in_row.push(j);
+                vars_in_row.push((j, coeff));
             }
         }
 
-        if !has_nonzero { continue; }
+        if vars_in_row.is_empty() { continue; }
 
         if (target - min_val).abs() < EPS {
             // All negative coeffs are MINES, positive are SAFE
-            for &idx in &vars_in_row {
-                let coeff = row[idx];
+            for &(idx, coeff) in &vars_in_row {
                 let cell = component[idx];
                 if coeff < 0.0 {
                     mines.push(cell);
@@ -300,8 +326,7 @@ fn solve_component(
             }
         } else if (target - max_val).abs() < EPS {
             // All positive coeffs are MINES, negative are SAFE
-            for &idx in &vars_in_row {
-                let coeff = row[idx];
+            for &(idx, coeff) in &vars_in_row {
                 let cell = component[idx];
                 if coeff > 0.0 {
                     mines.push(cell);
@@ -322,36 +347,140 @@ fn solve_component(
     GaussianResult { progress, safe, mines }
 }
 
+/// One clue as a linear equation: the sum of the `vars` (column indices into
+/// the component) equals `target`, the clue minus its flagged neighbors.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SystemEquation {
+    pub vars: Vec<usize>,
+    pub target: i32,
+}
+
+/// The system built for one connected component, before elimination.
+/// Variable `i` is the hidden cell `cells[i]`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ComponentSystem {
+    pub cells: Vec<(usize, usize)>,
+    pub equations: Vec<SystemEquation>,
+}
+
+/// Every component's linear system, for debugging the Gaussian strategy.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SystemDump {
+    pub components: Vec<ComponentSystem>,
+}
+
+/// The linear systems `solve()` would eliminate, one per connected component.
+///
+/// Components above `MAX_COMPONENT_SIZE` are dumped whole; `solve()` splits
+/// those into overlapping windows and builds each window's system the same way.
+pub fn export_system(
+    visible: &VisibleGrid,
+    flags: &Flags,
+    frontier: &[(usize, usize)],
+    nc: &NeighborCache,
+) -> SystemDump {
+    if frontier.is_empty() {
+        return SystemDump::default();
+    }
+
+    let components = get_connected_components(visible, frontier, nc, visible.width, visible.height)
+        .into_iter()
+        .map(|cells| {
+            let equations = build_equations(visible, flags, &cells, nc);
+            ComponentSystem { cells, equations }
+        })
+        .collect();
+    SystemDump { components }
+}
+
+/// One equation per revealed clue touching `component`, skipping clues with a
+/// hidden neighbor outside it. Clues are taken in order of first contact.
+fn build_equations(
+    visible: &VisibleGrid,
+    flags: &Flags,
+    component: &[(usize, usize)],
+    nc: &NeighborCache,
+) -> Vec<SystemEquation> {
+    let width = visible.width;
+    let height = visible.height;
+
+    // Variable index map: (x,y) -> column index
+    let mut var_index_map = vec![-1i32; width * height];
+    for (i, &(x, y)) in component.iter().enumerate() {
+        var_index_map[x * height + y] = i as i32;
+    }
+
+    let mut equations: Vec<SystemEquation> = Vec::new();
+    let mut processed_clues = vec![false; width * height];
+
+    for &(cx, cy) in component {
+        for &(nx, ny) in nc.get(cx, cy) {
+            let val = visible.get(nx, ny);
+            if val <= 0 { continue; }
+
+            let clue_idx = nx * height + ny;
+            if processed_clues[clue_idx] { continue; }
+            processed_clues[clue_idx] = true;
+
+            let mut eq_vars = Vec::new();
+            let mut flagged_count = 0i8;
+            let mut valid = true;
+
+            for &(cnx, cny) in nc.get(nx, ny) {
+                if flags.get(cnx, cny) {
+                    flagged_count += 1;
+                } else if visible.get(cnx, cny) == -1 {
+                    let v_idx = var_index_map[cnx * height + cny];
+                    if v_idx != -1 {
+                        eq_vars.push(v_idx as usize);
+                    } else {
+                        // Hidden neighbor not in our component — can't use this clue
+                        valid = false;
+                        break;
+                    }
+                }
+            }
+
+            // An over-flagged clue would give a negative target; a flag is wrong
+            if valid && flagged_count <= val {
+                equations.push(SystemEquation { vars: eq_vars, target: (val - flagged_count) as i32 });
+            }
+        }
+    }
+
+    equations
+}
+
 /// Compute Reduced Row Echelon Form (RREF) in-place.
 /// Standard Gaussian elimination with partial pivoting.
 fn compute_rref(matrix: &mut [Vec<f32>], m: usize, n: usize) {
-    let mut lead = 0usize;
+    compute_rref_from(matrix, m, n, 0, 0);
+}
 
-    for r in 0..m {
-        if n <= lead { return; }
+/// RREF starting at pivot row `start_row` and column `start_lead`; rows above
+/// are already reduced. Lets the sparse path hand over mid-elimination.
+///
+/// Walks the columns, not the rows: a column with no pivot left in the rows
+/// still to reduce is skipped and the same row tries the next one. When the
+/// columns run out, the rows left are all zero on the variable side.
+#[allow(clippy::needless_range_loop)]
+fn compute_rref_from(matrix: &mut [Vec<f32>], m: usize, n: usize, start_row: usize, start_lead: usize) {
+    let mut r = start_row;
 
-        let mut i = r;
-        while matrix[i][lead].abs() < EPS_TINY {
-            i += 1;
-            if m == i {
-                i = r;
-                lead += 1;
-                if n == lead { return; }
-            }
-        }
+    for lead in start_lead..n {
+        if r == m { return; }
+
+        // Partial pivoting: the largest entry in the column (first on ties, so
+        // 0/1 matrices keep their pivot order)
+        let i = (r..m).fold(r, |best, k| if matrix[k][lead].abs() > matrix[best][lead].abs() { k } else { best });
+        if matrix[i][lead].abs() < EPS_TINY { continue; }
 
         if i != r {
             matrix.swap(i, r);
         }
 
-        let val = matrix[r][lead];
-        if val.abs() < EPS_TINY {
-            lead += 1;
-            continue; // r will not be incremented because this is a for loop — need to handle differently
-        }
-
         // Normalize pivot row
-        let inv = 1.0 / val;
+        let inv = 1.0 / matrix[r][lead];
         for j in 0..=n {
             matrix[r][j] *= inv;
         }
@@ -368,7 +497,96 @@ fn compute_rref(matrix: &mut [Vec<f32>], m: usize, n: usize) {
             }
         }
 
-        lead += 1;
+        r += 1;
+    }
+}
+
+/// Significant entries of a dense row, in sparse form (target included).
+fn dense_to_sparse(row: &[f32]) -> SparseRow {
+    row.iter().enumerate().filter(|&(_, &v)| v.abs() >= EPS_TINY).map(|(j, &v)| (j, v)).collect()
+}
+
+/// Coefficient of `col` in a sparse row (0 if absent).
+fn sparse_get(row: &[(usize, f32)], col: usize) -> f32 {
+    row.binary_search_by_key(&col, |&(c, _)| c).map_or(0.0, |i| row[i].1)
+}
+
+/// `a - factor * b`, dropping entries that cancel below `EPS_TINY`.
+fn sparse_sub_scaled(a: &[(usize, f32)], b: &[(usize, f32)], factor: f32) -> SparseRow {
+    let mut out = Vec::with_capacity(a.len() + b.len());
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() || j < b.len() {
+        let (col, val) = if j == b.len() || (i < a.len() && a[i].0 < b[j].0) {
+            i += 1;
+            a[i - 1]
+        } else if i == a.len() || b[j].0 < a[i].0 {
+            j += 1;
+            (b[j - 1].0, -factor * b[j - 1].1)
+        } else {
+            i += 1;
+            j += 1;
+            (a[i - 1].0, a[i - 1].1 - factor * b[j - 1].1)
+        };
+        if val.abs() >= EPS_TINY { out.push((col, val)); }
+    }
+    out
+}
+
+/// Sparse counterpart of `compute_rref()`: same pivot order, so the reduced
+/// rows match the dense result. Falls back to dense elimination once fill-in
+/// exceeds `DENSIFY_FILL_RATIO`.
+fn compute_rref_sparse(rows: &mut [SparseRow], m: usize, n: usize) {
+    let fill_limit = ((m * (n + 1)) as f32 * DENSIFY_FILL_RATIO) as usize;
+    let mut r = 0usize;
+    let mut fill_checked = None;
+
+    for lead in 0..n {
+        if r == m { return; }
+
+        // Fill-in only grows when a pivot eliminates, so check once per row
+        if fill_checked != Some(r) {
+            fill_checked = Some(r);
+            let nnz: usize = rows.iter().map(|row| row.len()).sum();
+            if nnz > fill_limit {
+                let mut dense: Vec<Vec<f32>> = rows.iter().map(|row| {
+                    let mut d = vec![0.0f32; n + 1];
+                    for &(c, v) in row { d[c] = v; }
+                    d
+                }).collect();
+                compute_rref_from(&mut dense, m, n, r, lead);
+                for (row, d) in rows.iter_mut().zip(&dense) {
+                    *row = dense_to_sparse(d);
+                }
+                return;
+            }
+        }
+
+        let pivot_abs = |k: usize| sparse_get(&rows[k], lead).abs();
+        let i = (r..m).fold(r, |best, k| if pivot_abs(k) > pivot_abs(best) { k } else { best });
+        if pivot_abs(i) < EPS_TINY { continue; }
+
+        if i != r {
+            rows.swap(i, r);
+        }
+
+        // Normalize pivot row
+        let inv = 1.0 / sparse_get(&rows[r], lead);
+        for entry in rows[r].iter_mut() {
+            entry.1 *= inv;
+        }
+
+        // Eliminate all other rows
+        let pivot = rows[r].clone();
+        for (k, row) in rows.iter_mut().enumerate().take(m) {
+            if k != r {
+                let factor = sparse_get(row, lead);
+                if factor.abs() > EPS_TINY {
+                    *row = sparse_sub_scaled(row, &pivot, factor);
+                }
+            }
+        }
+
+        r += 1;
     }
 }
 
@@ -376,6 +594,51 @@ fn compute_rref(matrix: &mut [Vec<f32>], m: usize, n: usize) {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_export_system() {
+        // 3x1 strip "1 ? 1": one variable (1,0) that both clues see
+        let mut visible = VisibleGrid::new(3, 1);
+        visible.set(0, 0, 1);
+        visible.set(2, 0, 1);
+        let flags = Flags::new(3, 1);
+        let nc = NeighborCache::new(3, 1);
+
+        let dump = export_system(&visible, &flags, &[(1, 0)], &nc);
+        assert_eq!(dump.components, vec![ComponentSystem {
+            cells: vec![(1, 0)],
+            equations: vec![
+                SystemEquation { vars: vec![0], target: 1 },
+                SystemEquation { vars: vec![0], target: 1 },
+            ],
+        }]);
+        assert!(export_system(&visible, &flags, &[], &nc).components.is_empty());
+    }
+
+    #[test]
+    fn test_build_equations_indexes_past_i16() {
+        // 200x200 board with one clue at (0,0) whose neighbors are placed at
+        // the very end of the component, beyond what an i16 index could hold
+        let mut visible = VisibleGrid::new(200, 200);
+        visible.set(0, 0, 1);
+        let flags = Flags::new(200, 200);
+        let nc = NeighborCache::new(200, 200);
+
+        let near = [(0, 1), (1, 0), (1, 1)];
+        let mut component: Vec<(usize, usize)> = (0..200)
+            .flat_map(|x| (0..200).map(move |y| (x, y)))
+            .filter(|&c| c != (0, 0) && !near.contains(&c))
+            .collect();
+        component.extend(near);
+        let last = component.len();
+
+        let equations = build_equations(&visible, &flags, &component, &nc);
+        assert_eq!(equations.len(), 1);
+        let mut vars = equations[0].vars.clone();
+        vars.sort_unstable();
+        assert_eq!(vars, vec![last - 3, last - 2, last - 1]);
+        assert_eq!(equations[0].target, 1);
+    }
+
     #[test]
     fn test_rref_simple() {
         // Simple 2x2 system: x + y = 1, x = 1  => x=1, y=0
@@ -393,6 +656,30 @@ mod tests {
         assert!((matrix[1][2] - 0.0).abs() < EPS);
     }
 
+    #[test]
+    fn test_rref_partial_pivoting() {
+        // A near-zero leading entry left over from earlier elimination:
+        // 4.7e-6·x + y = 1, x + y = 1, so x = 0 and y = 1. Pivoting on the tiny
+        // entry scales its row by ~2e5 and x comes back as 0.0156, outside EPS,
+        // so x would not classify as safe; pivoting on the largest entry is exact
+        let mut matrix = vec![
+            vec![0.0000047, 1.0, 1.0],
+            vec![1.0, 1.0, 1.0],
+        ];
+        compute_rref(&mut matrix, 2, 2);
+        assert!((matrix[0][0] - 1.0).abs() < EPS);
+        assert!(matrix[0][2].abs() < EPS, "x = {}", matrix[0][2]);
+        assert!((matrix[1][2] - 1.0).abs() < EPS, "y = {}", matrix[1][2]);
+
+        let mut rows: Vec<SparseRow> = vec![
+            vec![(0, 0.0000047), (1, 1.0), (2, 1.0)],
+            vec![(0, 1.0), (1, 1.0), (2, 1.0)],
+        ];
+        compute_rref_sparse(&mut rows, 2, 2);
+        assert!(sparse_get(&rows[0], 2).abs() < EPS);
+        assert!((sparse_get(&rows[1], 2) - 1.0).abs() < EPS);
+    }
+
     #[test]
     fn test_rref_underdetermined() {
         // x + y = 1 (underdetermined, 1 eq, 2 vars)
@@ -405,6 +692,65 @@ mod tests {
         assert!((matrix[0][2] - 1.0).abs() < EPS);
     }
 
+    /// A 50-wide strip: row 0 revealed clues, row 1 one 50-variable component.
+    fn make_strip_frontier() -> (VisibleGrid, Flags, NeighborCache, Vec<(usize, usize)>) {
+        let width = 50;
+        let nc = NeighborCache::new(width, 2);
+        let mut mines = crate::types::Mines::new(width, 2);
+        for x in 0..width {
+            if x % 3 == 0 || x % 7 == 2 {
+                mines.set(x, 1, true);
+            }
+        }
+        let grid = crate::board::calculate_numbers(&mines, &nc);
+        let mut visible = VisibleGrid::new(width, 2);
+        for x in 0..width {
+            visible.set(x, 0, grid.get(x, 0));
+        }
+        let component: Vec<(usize, usize)> = (0..width).map(|x| (x, 1)).collect();
+        (visible, Flags::new(width, 2), nc, component)
+    }
+
+    #[test]
+    fn test_sparse_matches_dense_on_large_component() {
+        let (visible, flags, nc, component) = make_strip_frontier();
+        let dense = solve_component_with(&visible, &flags, &component, &nc, Elimination::Dense);
+        let sparse = solve_component_with(&visible, &flags, &component, &nc, Elimination::Sparse);
+        assert!(dense.progress);
+        assert_eq!(sparse.safe, dense.safe);
+        assert_eq!(sparse.mines, dense.mines);
+    }
+
+    #[test]
+    fn test_solve_output_sorted_and_stable() {
+        let (visible, flags, nc, component) = make_strip_frontier();
+        // Feed the frontier in reverse so discovery order differs from (x, y) order
+        let frontier: Vec<(usize, usize)> = component.iter().rev().copied().collect();
+        let a = solve(&visible, &flags, &frontier, &nc, None);
+        let b = solve(&visible, &flags, &frontier, &nc, Some(&mut SolverArena::new()));
+        assert!(a.progress);
+        assert_eq!(a.safe, b.safe);
+        assert_eq!(a.mines, b.mines);
+        assert!(a.safe.windows(2).all(|w| w[0] < w[1]));
+        assert!(a.mines.windows(2).all(|w| w[0] < w[1]));
+    }
+
+    #[test]
+    fn test_sparse_rref_densifies_on_fill_in() {
+        // A fully dense 3x3 system exceeds the fill ratio immediately
+        let mut rows: Vec<SparseRow> = vec![
+            vec![(0, 1.0), (1, 1.0), (2, 1.0)],
+            vec![(0, 1.0), (2, 1.0)],
+            vec![(1, 1.0), (2, 1.0)],
+        ];
+        compute_rref_sparse(&mut rows, 3, 2);
+        let mut dense = vec![vec![1.0, 1.0, 1.0], vec![1.0, 0.0, 1.0], vec![0.0, 1.0, 1.0]];
+        compute_rref(&mut dense, 3, 2);
+        for (row, d) in rows.iter().zip(&dense) {
+            assert_eq!(row, &dense_to_sparse(d));
+        }
+    }
+
     #[test]
     fn test_component_detection() {
         // Create a simple 3x3 grid with center revealed as "1"
@@ -425,4 +771,35 @@ mod tests {
         assert_eq!(components.len(), 1);
         assert_eq!(components[0].len(), 8);
     }
+
+    #[test]
+    fn test_components_match_fresh_buffer_across_boards() {
+        // Reusing the marks across boards of different sizes must give the
+        // same partition as a freshly zeroed buffer every time
+        use crate::board::{calculate_numbers, place_mines_random};
+        use crate::rng::WasmRng;
+        use crate::solver::get_frontier;
+        use crate::types::SafeZone;
+
+        let mut rng = WasmRng::from_seed(99);
+        for &(w, h, bombs) in &[(30, 16, 99), (9, 9, 10), (16, 16, 40), (30, 16, 60)] {
+            let nc = NeighborCache::new(w, h);
+            let mines = place_mines_random(w, h, bombs, &[SafeZone::new(w / 2, h / 2, 1)], 0, &mut rng).unwrap();
+            let grid = calculate_numbers(&mines, &nc);
+            let mut visible = VisibleGrid::new(w, h);
+            for x in 0..w {
+                for y in 0..h {
+                    if !mines.get(x, y) && (x + y) % 3 == 0 { visible.set(x, y, grid.get(x, y)); }
+                }
+            }
+            let flags = Flags::new(w, h);
+            let frontier = get_frontier(&visible, &flags, &nc);
+
+            let reused = get_connected_components(&visible, &frontier, &nc, w, h);
+            let mut fresh_map = vec![0u8; w * h];
+            let fresh = bfs_components(&visible, &frontier, &nc, h, &mut fresh_map);
+            assert_eq!(reused, fresh);
+            assert_eq!(reused.iter().map(Vec::len).sum::<usize>(), frontier.len());
+        }
+    }
 }
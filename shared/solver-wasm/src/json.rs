@@ -0,0 +1,269 @@
+//! Minimal JSON reader for the board interchange format.
+//!
+//! Only what `board::from_json()` needs: a complete JSON value parser with no
+//! dependencies, so the crate does not pull in `serde_json`. Writing is done
+//! by hand at the call site since the output shape is fixed.
+
+/// A parsed JSON value. Object members keep their source order.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum JsonValue {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<JsonValue>),
+    Object(Vec<(String, JsonValue)>),
+}
+
+impl JsonValue {
+    /// The member `key` of an object, if present.
+    pub(crate) fn get(&self, key: &str) -> Option<&JsonValue> {
+        match self {
+            JsonValue::Object(members) => members.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    /// The value as a non-negative integer, if it is one.
+    pub(crate) fn as_usize(&self) -> Option<usize> {
+        match *self {
+            JsonValue::Number(n) if n >= 0.0 && n.fract() == 0.0 && n <= u32::MAX as f64 => Some(n as usize),
+            _ => None,
+        }
+    }
+}
+
+/// Nesting deeper than this is rejected instead of risking the stack.
+const MAX_DEPTH: usize = 64;
+
+/// Parse a complete JSON document. Trailing non-whitespace is an error.
+pub(crate) fn parse(input: &str) -> Result<JsonValue, String> {
+    let mut parser = Parser { bytes: input.as_bytes(), pos: 0 };
+    let value = parser.value(0)?;
+    parser.skip_ws();
+    if parser.pos != parser.bytes.len() {
+        return Err(format!("Unexpected trailing data at byte {}", parser.pos));
+    }
+    Ok(value)
+}
+
+struct Parser<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn skip_ws(&mut self) {
+        while let Some(b' ' | b'\t' | b'\n' | b'\r') = self.bytes.get(self.pos) {
+            self.pos += 1;
+        }
+    }
+
+    fn error<T>(&self, what: &str) -> Result<T, String> {
+        Err(format!("{} at byte {}", what, self.pos))
+    }
+
+    fn expect(&mut self, byte: u8) -> Result<(), String> {
+        self.skip_ws();
+        if self.bytes.get(self.pos) == Some(&byte) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            self.error(&format!("Expected '{}'", byte as char))
+        }
+    }
+
+    fn literal(&mut self, word: &str, value: JsonValue) -> Result<JsonValue, String> {
+        if self.bytes[self.pos..].starts_with(word.as_bytes()) {
+            self.pos += word.len();
+            Ok(value)
+        } else {
+            self.error("Invalid literal")
+        }
+    }
+
+    fn value(&mut self, depth: usize) -> Result<JsonValue, String> {
+        if depth > MAX_DEPTH {
+            return self.error("Nesting too deep");
+        }
+        self.skip_ws();
+        match self.bytes.get(self.pos) {
+            Some(b'{') => self.object(depth),
+            Some(b'[') => self.array(depth),
+            Some(b'"') => self.string().map(JsonValue::String),
+            Some(b't') => self.literal("true", JsonValue::Bool(true)),
+            Some(b'f') => self.literal("false", JsonValue::Bool(false)),
+            Some(b'n') => self.literal("null", JsonValue::Null),
+            Some(b'-' | b'0'..=b'9') => self.number(),
+            Some(_) => self.error("Unexpected character"),
+            None => self.error("Unexpected end of input"),
+        }
+    }
+
+    fn object(&mut self, depth: usize) -> Result<JsonValue, String> {
+        self.pos += 1; // '{'
+        let mut members = Vec::new();
+        self.skip_ws();
+        if self.bytes.get(self.pos) == Some(&b'}') {
+            self.pos += 1;
+            return Ok(JsonValue::Object(members));
+        }
+        loop {
+            self.skip_ws();
+            if self.bytes.get(self.pos) != Some(&b'"') {
+                return self.error("Expected object key");
+            }
+            let key = self.string()?;
+            self.expect(b':')?;
+            let value = self.value(depth + 1)?;
+            members.push((key, value));
+
+            self.skip_ws();
+            match self.bytes.get(self.pos) {
+                Some(b',') => self.pos += 1,
+                Some(b'}') => { self.pos += 1; return Ok(JsonValue::Object(members)); }
+                _ => return self.error("Expected ',' or '}'"),
+            }
+        }
+    }
+
+    fn array(&mut self, depth: usize) -> Result<JsonValue, String> {
+        self.pos += 1; // '['
+        let mut items = Vec::new();
+        self.skip_ws();
+        if self.bytes.get(self.pos) == Some(&b']') {
+            self.pos += 1;
+            return Ok(JsonValue::Array(items));
+        }
+        loop {
+            items.push(self.value(depth + 1)?);
+            self.skip_ws();
+            match self.bytes.get(self.pos) {
+                Some(b',') => self.pos += 1,
+                Some(b']') => { self.pos += 1; return Ok(JsonValue::Array(items)); }
+                _ => return self.error("Expected ',' or ']'"),
+            }
+        }
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        self.pos += 1; // opening quote
+        let mut out = String::new();
+        loop {
+            let start = self.pos;
+            while let Some(&b) = self.bytes.get(self.pos) {
+                if b == b'"' || b == b'\\' || b < 0x20 { break; }
+                self.pos += 1;
+            }
+            // The input is a &str and we only stop on ASCII, so this slice is valid UTF-8
+            out.push_str(std::str::from_utf8(&self.bytes[start..self.pos]).unwrap_or_default());
+
+            match self.bytes.get(self.pos) {
+                Some(b'"') => { self.pos += 1; return Ok(out); }
+                Some(b'\\') => {
+                    self.pos += 1;
+                    let escaped = match self.bytes.get(self.pos) {
+                        Some(b'"') => '"',
+                        Some(b'\\') => '\\',
+                        Some(b'/') => '/',
+                        Some(b'b') => '\u{8}',
+                        Some(b'f') => '\u{c}',
+                        Some(b'n') => '\n',
+                        Some(b'r') => '\r',
+                        Some(b't') => '\t',
+                        Some(b'u') => self.unicode_escape()?,
+                        _ => return self.error("Invalid escape"),
+                    };
+                    self.pos += 1;
+                    out.push(escaped);
+                }
+                Some(_) => return self.error("Control character in string"),
+                None => return self.error("Unterminated string"),
+            }
+        }
+    }
+
+    /// The four hex digits after the `u` at `self.pos`, as a code unit.
+    fn hex4(&self) -> Option<u32> {
+        self.bytes.get(self.pos + 1..self.pos + 5)
+            .and_then(|h| std::str::from_utf8(h).ok())
+            .and_then(|h| u32::from_str_radix(h, 16).ok())
+    }
+
+    /// Decode the `\uXXXX` escape whose `u` is at `self.pos`, leaving `pos`
+    /// on its last digit. A high surrogate must be followed by an escaped
+    /// low surrogate, and the pair decodes to one character.
+    fn unicode_escape(&mut self) -> Result<char, String> {
+        let Some(unit) = self.hex4() else {
+            return self.error("Invalid unicode escape");
+        };
+        self.pos += 4;
+        let code = match unit {
+            0xD800..=0xDBFF => {
+                if !self.bytes[self.pos + 1..].starts_with(b"\\u") {
+                    return self.error("Unpaired surrogate");
+                }
+                self.pos += 2;
+                let low = match self.hex4() {
+                    Some(low @ 0xDC00..=0xDFFF) => low,
+                    _ => return self.error("Unpaired surrogate"),
+                };
+                self.pos += 4;
+                0x10000 + ((unit - 0xD800) << 10) + (low - 0xDC00)
+            }
+            0xDC00..=0xDFFF => return self.error("Unpaired surrogate"),
+            _ => unit,
+        };
+        char::from_u32(code).map_or_else(|| self.error("Invalid unicode escape"), Ok)
+    }
+
+    fn number(&mut self) -> Result<JsonValue, String> {
+        let start = self.pos;
+        while let Some(b'-' | b'+' | b'.' | b'e' | b'E' | b'0'..=b'9') = self.bytes.get(self.pos) {
+            self.pos += 1;
+        }
+        std::str::from_utf8(&self.bytes[start..self.pos])
+            .ok()
+            .and_then(|s| s.parse::<f64>().ok())
+            .filter(|n| n.is_finite())
+            .map(JsonValue::Number)
+            .map_or_else(|| Err(format!("Invalid number at byte {}", start)), Ok)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_nested_values() {
+        let v = parse(r#" {"a": [1, -2.5e1, true, null], "b": "x\"A"} "#).unwrap();
+        assert_eq!(
+            v.get("a"),
+            Some(&JsonValue::Array(vec![
+                JsonValue::Number(1.0),
+                JsonValue::Number(-25.0),
+                JsonValue::Bool(true),
+                JsonValue::Null,
+            ]))
+        );
+        assert_eq!(v.get("b"), Some(&JsonValue::String("x\"A".to_string())));
+    }
+
+    #[test]
+    fn test_parse_surrogate_pair() {
+        assert_eq!(parse(r#""\uD83D\uDE00!""#), Ok(JsonValue::String("\u{1F600}!".to_string())));
+        assert_eq!(parse(r#""\u00e9""#), Ok(JsonValue::String("é".to_string())));
+        for bad in [r#""\uD83D""#, r#""\uD83Dx""#, r#""\uD83D\u0041""#, r#""\uDE00""#] {
+            assert!(parse(bad).is_err(), "accepted {}", bad);
+        }
+    }
+
+    #[test]
+    fn test_parse_rejects_malformed() {
+        for bad in ["", "{", "[1,]", "{\"a\" 1}", "[1] 2", "\"abc", "tru", "1e999"] {
+            assert!(parse(bad).is_err(), "accepted {:?}", bad);
+        }
+        assert!(parse(&"[".repeat(MAX_DEPTH + 2)).is_err());
+    }
+}
@@ -3,9 +3,15 @@
 //! Exports high-level functions callable from JavaScript via wasm-bindgen.
 //! All grid data is passed as flat `Int8Array` / `Uint8Array` in column-major
 //! layout: `cells[x * height + y]` maps to JS `grid[x][y]`.
+//!
+//! Boards larger than `types::MAX_BOARD_CELLS` are rejected: the generators
+//! return `{ success: false, error }`, every other export throws a JS `Error`.
 
+pub mod arena;
 pub mod board;
 pub mod gaussian;
+mod json;
+pub mod probability;
 pub mod rng;
 pub mod solver;
 pub mod types;
@@ -15,10 +21,223 @@ pub mod types;
 #[cfg(target_arch = "wasm32")]
 mod wasm_exports {
     use wasm_bindgen::prelude::*;
-    use crate::types::{Flags, Grid, Mines, NeighborCache, VisibleGrid};
-    use crate::{board, solver};
+    use crate::types::{board_cell_count, Blocked, Flags, Grid, GridError, Mines, NeighborCache, SafeRect, SafeZone, VisibleGrid, MAX_BOARD_CELLS};
+    use crate::{board, gaussian, probability, solver};
+
+    impl From<GridError> for JsValue {
+        fn from(error: GridError) -> Self {
+            js_sys::Error::new(&error.to_string()).into()
+        }
+    }
+
+    /// Set a property on a JS object, ignoring failures instead of aborting the module.
+    fn set_field(obj: &js_sys::Object, key: &str, val: &JsValue) {
+        let _ = js_sys::Reflect::set(obj, &key.into(), val);
+    }
+
+    /// JS `Error` for dimensions beyond `MAX_BOARD_CELLS` (or overflowing `usize`).
+    fn board_too_large(width: usize, height: usize) -> JsValue {
+        let msg = format!("Board dimensions {}x{} exceed the {}-cell limit", width, height, MAX_BOARD_CELLS);
+        js_sys::Error::new(&msg).into()
+    }
+
+    /// Build the neighbor cache, or throw for oversized dimensions.
+    fn neighbor_cache(width: usize, height: usize) -> Result<NeighborCache, JsValue> {
+        NeighborCache::try_new(width, height).ok_or_else(|| board_too_large(width, height))
+    }
+
+    /// Read an optional boolean property, falling back to `default`.
+    fn get_bool(obj: &js_sys::Object, key: &str, default: bool) -> bool {
+        js_sys::Reflect::get(obj, &key.into()).ok().and_then(|v| v.as_bool()).unwrap_or(default)
+    }
+
+    /// Read an optional numeric property, falling back to `default`.
+    fn get_f64(obj: &js_sys::Object, key: &str, default: f64) -> f64 {
+        js_sys::Reflect::get(obj, &key.into()).ok().and_then(|v| v.as_f64()).unwrap_or(default)
+    }
+
+    /// Decode a flat `[x, y, kind]` move list, `kind` 0 = reveal, 1 = flag toggle.
+    fn parse_moves(moves: &[u32]) -> Result<Vec<(usize, usize, solver::MoveKind)>, JsValue> {
+        if !moves.len().is_multiple_of(3) {
+            return Err(js_sys::Error::new("moves must be a flat array of [x, y, kind] triples").into());
+        }
+        Ok(moves
+            .chunks_exact(3)
+            .map(|m| {
+                let kind = if m[2] == 1 { solver::MoveKind::ToggleFlag } else { solver::MoveKind::Reveal };
+                (m[0] as usize, m[1] as usize, kind)
+            })
+            .collect())
+    }
+
+    /// `{ success, failure?, attempts, grid: Int8Array, mines: Uint8Array }`, the
+    /// shape every generator export returns.
+    fn board_result_to_js(result: &board::BoardResult) -> js_sys::Object {
+        let obj = js_sys::Object::new();
+        set_field(&obj, "success", &result.success.into());
+        if let Some(failure) = result.failure {
+            set_field(&obj, "failure", &failure.as_str().into());
+        }
+        set_field(&obj, "attempts", &result.attempts.into());
+
+        let grid_arr = js_sys::Int8Array::new_with_length(result.grid.cells.len() as u32);
+        grid_arr.copy_from(&result.grid.cells);
+        set_field(&obj, "grid", &grid_arr.into());
+
+        let mines_arr = js_sys::Uint8Array::new_with_length(result.mines.cells.len() as u32);
+        mines_arr.copy_from(&result.mines.cells);
+        set_field(&obj, "mines", &mines_arr.into());
+        obj
+    }
+
+    /// `{ solved: true }` or
+    /// `{ solved: false, revealedFraction, frontierComponents, bestGuess: { x, y, probability, adjacencyScore } | null }`.
+    fn guess_point_to_js(point: &solver::GuessPoint) -> js_sys::Object {
+        let obj = js_sys::Object::new();
+        match *point {
+            solver::GuessPoint::FullySolved => {
+                set_field(&obj, "solved", &true.into());
+            }
+            solver::GuessPoint::StuckAt { revealed_fraction, frontier_components, best_guess } => {
+                set_field(&obj, "solved", &false.into());
+                set_field(&obj, "revealedFraction", &revealed_fraction.into());
+                set_field(&obj, "frontierComponents", &(frontier_components as u32).into());
+                let guess = match best_guess {
+                    Some(g) => {
+                        let g_obj = js_sys::Object::new();
+                        set_field(&g_obj, "x", &(g.x as u32).into());
+                        set_field(&g_obj, "y", &(g.y as u32).into());
+                        set_field(&g_obj, "probability", &g.mine_probability.into());
+                        set_field(&g_obj, "adjacencyScore", &g.adjacency_score.into());
+                        g_obj.into()
+                    }
+                    None => JsValue::NULL,
+                };
+                set_field(&obj, "bestGuess", &guess);
+            }
+        }
+        obj
+    }
+
+    /// `[{ strategy, flagged: [[x, y], ...], revealed: [[x, y], ...] }]`, one entry per step.
+    fn solve_steps_to_js(steps: &[solver::SolveStep]) -> js_sys::Array {
+        let arr = js_sys::Array::new();
+        for step in steps {
+            let obj = js_sys::Object::new();
+            set_field(&obj, "strategy", &(step.strategy as u8).into());
+            set_field(&obj, "flagged", &pairs_to_js(&step.flagged).into());
+            set_field(&obj, "revealed", &pairs_to_js(&step.revealed).into());
+            arr.push(&obj);
+        }
+        arr
+    }
+
+    /// `[[x, y], ...]`, one pair per cell.
+    fn pairs_to_js(cells: &[(usize, usize)]) -> js_sys::Array {
+        cells.iter().map(|&(x, y)| JsValue::from(js_sys::Array::of2(&(x as u32).into(), &(y as u32).into()))).collect()
+    }
+
+    /// `{ action: "reveal" | "flag", x, y }`.
+    fn action_to_js(action: solver::Action) -> js_sys::Object {
+        let (kind, x, y) = match action {
+            solver::Action::Reveal(x, y) => ("reveal", x, y),
+            solver::Action::Flag(x, y) => ("flag", x, y),
+        };
+        let obj = js_sys::Object::new();
+        set_field(&obj, "action", &kind.into());
+        set_field(&obj, "x", &(x as u32).into());
+        set_field(&obj, "y", &(y as u32).into());
+        obj
+    }
+
+    /// `[{ action: "reveal" | "flag", x, y }]`, one entry per action.
+    fn actions_to_js(actions: &[solver::Action]) -> js_sys::Array {
+        actions.iter().map(|&action| JsValue::from(action_to_js(action))).collect()
+    }
+
+    /// Read the `actions_to_js()` shape back; throws on an unknown `action` or a bad coordinate.
+    fn actions_from_js(arr: &js_sys::Array) -> Result<Vec<solver::Action>, JsValue> {
+        arr.iter()
+            .map(|item| {
+                let obj = js_sys::Object::from(item);
+                let coord = |key: &str| {
+                    let v = get_f64(&obj, key, -1.0);
+                    if v >= 0.0 && v.fract() == 0.0 && v <= u32::MAX as f64 { Ok(v as usize) } else {
+                        Err(JsValue::from(js_sys::Error::new(&format!("action {} must be a non-negative integer", key))))
+                    }
+                };
+                let (x, y) = (coord("x")?, coord("y")?);
+                match js_sys::Reflect::get(&obj, &"action".into())?.as_string().as_deref() {
+                    Some("reveal") => Ok(solver::Action::Reveal(x, y)),
+                    Some("flag") => Ok(solver::Action::Flag(x, y)),
+                    _ => Err(js_sys::Error::new("action must be \"reveal\" or \"flag\"").into()),
+                }
+            })
+            .collect()
+    }
+
+    /// Build a `SolverConfig` from an optional JS object like
+    /// `{ knowMineCount: false, clueTolerance: 1, maxStrategyLevel: 2 }`. Missing fields keep their defaults.
+    /// `booleanClues` (one byte per cell, 1 = boolean clue) sets the clue-kind overlay;
+    /// `deepContradiction: true` lets proof by contradiction use subset reasoning.
+    fn solver_config_from_js(config: Option<js_sys::Object>) -> solver::SolverConfig {
+        let mut out = solver::SolverConfig::default();
+        if let Some(obj) = config {
+            out.know_mine_count = get_bool(&obj, "knowMineCount", out.know_mine_count);
+            out.clue_tolerance = get_f64(&obj, "clueTolerance", out.clue_tolerance as f64) as u8;
+            out.max_strategy_level = get_f64(&obj, "maxStrategyLevel", out.max_strategy_level as f64) as u8;
+            out.deep_contradiction = get_bool(&obj, "deepContradiction", out.deep_contradiction);
+            out.deadline_ms = js_sys::Reflect::get(&obj, &"deadlineMs".into()).ok().and_then(|v| v.as_f64());
+            if let Ok(kinds) = js_sys::Reflect::get(&obj, &"booleanClues".into()) {
+                if kinds.is_object() {
+                    let kinds = js_sys::Uint8Array::new(&kinds).to_vec();
+                    out.clue_kinds = Some(kinds.iter().map(|&k| {
+                        if k != 0 { solver::ClueKind::Boolean } else { solver::ClueKind::Exact }
+                    }).collect());
+                }
+            }
+        }
+        out
+    }
+
+    /// The optional `blocked` mask (a `Uint8Array`, nonzero = not part of the
+    /// board) of an export's config object.
+    fn blocked_from_js(config: Option<&js_sys::Object>, width: usize, height: usize) -> Result<Option<Blocked>, GridError> {
+        let Some(value) = config.and_then(|obj| js_sys::Reflect::get(obj, &"blocked".into()).ok()) else {
+            return Ok(None);
+        };
+        if !value.is_object() {
+            return Ok(None);
+        }
+        Blocked::from_flat(width, height, &js_sys::Uint8Array::new(&value).to_vec()).map(Some)
+    }
+
+    /// Apply an export's optional `blocked` mask: the masked cells of `grid`
+    /// become `BLOCKED`, and the cache leaves them out of every neighborhood.
+    fn blocked_grid_and_cache(mut grid: Grid, blocked: Option<Blocked>) -> Result<(Grid, NeighborCache), JsValue> {
+        let (width, height) = (grid.width, grid.height);
+        let nc = match blocked {
+            Some(blocked) => {
+                for (cell, &b) in grid.cells.iter_mut().zip(&blocked.cells) {
+                    if b != 0 { *cell = crate::types::BLOCKED; }
+                }
+                NeighborCache::with_blocked(&blocked)
+            }
+            None => neighbor_cache(width, height)?,
+        };
+        Ok((grid, nc))
+    }
 
-    /// Check if a board is solvable without guessing.
+    /// The cache for an export's optional flat `blocked` mask.
+    fn blocked_cache(width: usize, height: usize, blocked: Option<Vec<u8>>) -> Result<NeighborCache, JsValue> {
+        match blocked {
+            Some(cells) => Ok(NeighborCache::with_blocked(&Blocked::from_flat(width, height, &cells)?)),
+            None => neighbor_cache(width, height),
+        }
+    }
+
+    /// Check if a board is solvable without guessing. Cells set in the
+    /// optional `blocked` mask are holes in the board, as in `isSolvableWithConfig`.
     #[wasm_bindgen(js_name = "isSolvable")]
     pub fn wasm_is_solvable(
         width: usize,
@@ -27,16 +246,200 @@ mod wasm_exports {
         mines_flat: &[u8],
         start_x: usize,
         start_y: usize,
-    ) -> bool {
-        let grid = Grid { width, height, cells: grid_flat.to_vec() };
-        let mines = Mines { width, height, cells: mines_flat.to_vec() };
-        let nc = NeighborCache::new(width, height);
-        solver::is_solvable(&grid, &mines, &nc, start_x, start_y)
+        blocked: Option<Vec<u8>>,
+    ) -> Result<bool, JsValue> {
+        let mines = Mines::from_flat(width, height, mines_flat)?;
+        let blocked = blocked.map(|cells| Blocked::from_flat(width, height, &cells)).transpose()?;
+        let (grid, nc) = blocked_grid_and_cache(Grid::from_flat(width, height, grid_flat)?, blocked)?;
+        Ok(solver::is_solvable(&grid, &mines, &nc, start_x, start_y))
+    }
+
+    /// Stable hash of the solver's full deduction trace, as 16 hex digits
+    /// (a JS number cannot hold all 64 bits). Equal before and after a
+    /// refactor means the solver still deduces the same cells at the same levels.
+    #[wasm_bindgen(js_name = "solutionFingerprint")]
+    pub fn wasm_solution_fingerprint(
+        width: usize,
+        height: usize,
+        grid_flat: &[i8],
+        mines_flat: &[u8],
+        start_x: usize,
+        start_y: usize,
+    ) -> Result<String, JsValue> {
+        let grid = Grid::from_flat(width, height, grid_flat)?;
+        let mines = Mines::from_flat(width, height, mines_flat)?;
+        let nc = neighbor_cache(width, height)?;
+        Ok(format!("{:016x}", solver::solution_fingerprint(&grid, &mines, &nc, start_x, start_y)))
+    }
+
+    /// Check solvability with solver options: `{ knowMineCount?: bool, clueTolerance?: number, maxStrategyLevel?: number, deadlineMs?: number, blocked?: Uint8Array }`.
+    /// A board whose solve runs past `deadlineMs` counts as not solvable.
+    /// Cells set in `blocked` are holes in the board: never hidden, never counted.
+    #[wasm_bindgen(js_name = "isSolvableWithConfig")]
+    pub fn wasm_is_solvable_with_config(
+        width: usize,
+        height: usize,
+        grid_flat: &[i8],
+        mines_flat: &[u8],
+        start_x: usize,
+        start_y: usize,
+        config: Option<js_sys::Object>,
+    ) -> Result<bool, JsValue> {
+        let mines = Mines::from_flat(width, height, mines_flat)?;
+        let (grid, nc) = blocked_grid_and_cache(
+            Grid::from_flat(width, height, grid_flat)?,
+            blocked_from_js(config.as_ref(), width, height)?,
+        )?;
+        let config = solver_config_from_js(config);
+        Ok(solver::is_solvable_with_config(&grid, &mines, &nc, start_x, start_y, &config))
+    }
+
+    /// True if the opening plus basic counting rules alone clear the board.
+    #[wasm_bindgen(js_name = "isTriviallySolved")]
+    pub fn wasm_is_trivially_solved(
+        width: usize,
+        height: usize,
+        grid_flat: &[i8],
+        mines_flat: &[u8],
+        start_x: usize,
+        start_y: usize,
+    ) -> Result<bool, JsValue> {
+        let grid = Grid::from_flat(width, height, grid_flat)?;
+        let mines = Mines::from_flat(width, height, mines_flat)?;
+        let nc = neighbor_cache(width, height)?;
+        Ok(solver::is_trivially_solved_by_opening(&grid, &mines, &nc, start_x, start_y))
+    }
+
+    /// True if the board is solvable using only strategies up to `max_level`
+    /// (1 = basic, 2 = subset, 3 = Gaussian, 4 = contradiction, 5 = tank, 6 = global count).
+    #[wasm_bindgen(js_name = "solvableWithMaxStrategy")]
+    pub fn wasm_solvable_with_max_strategy(
+        width: usize,
+        height: usize,
+        grid_flat: &[i8],
+        mines_flat: &[u8],
+        start_x: usize,
+        start_y: usize,
+        max_level: u8,
+    ) -> Result<bool, JsValue> {
+        let grid = Grid::from_flat(width, height, grid_flat)?;
+        let mines = Mines::from_flat(width, height, mines_flat)?;
+        let nc = neighbor_cache(width, height)?;
+        Ok(solver::solvable_with_max_strategy(&grid, &mines, &nc, start_x, start_y, max_level))
+    }
+
+    /// Cells logic settles from the start with strategies up to `level` (numbered as
+    /// in `solvableWithMaxStrategy`) but not with the cheaper ones, as `[x, y]` pairs:
+    /// the moves a trainer should highlight for that technique.
+    #[wasm_bindgen(js_name = "cellsRequiringStrategy")]
+    pub fn wasm_cells_requiring_strategy(
+        width: usize,
+        height: usize,
+        grid_flat: &[i8],
+        mines_flat: &[u8],
+        start_x: usize,
+        start_y: usize,
+        level: u8,
+    ) -> Result<js_sys::Array, JsValue> {
+        let strategy = match level {
+            1 => solver::Strategy::Basic,
+            2 => solver::Strategy::Subset,
+            3 => solver::Strategy::Gaussian,
+            4 => solver::Strategy::Contradiction,
+            5 => solver::Strategy::Tank,
+            6 => solver::Strategy::GlobalCount,
+            _ => return Err(js_sys::Error::new("level must be a strategy level from 1 to 6").into()),
+        };
+        let grid = Grid::from_flat(width, height, grid_flat)?;
+        let mines = Mines::from_flat(width, height, mines_flat)?;
+        let nc = neighbor_cache(width, height)?;
+
+        Ok(pairs_to_js(&solver::cells_requiring_strategy(&grid, &mines, &nc, start_x, start_y, strategy)))
+    }
+
+    /// True if the board cannot be solved with basic, subset and Gaussian logic
+    /// alone but can be with every strategy (hard-but-fair challenge boards).
+    #[wasm_bindgen(js_name = "requiresAdvancedStrategy")]
+    pub fn wasm_requires_advanced_strategy(
+        width: usize,
+        height: usize,
+        grid_flat: &[i8],
+        mines_flat: &[u8],
+        start_x: usize,
+        start_y: usize,
+    ) -> Result<bool, JsValue> {
+        let grid = Grid::from_flat(width, height, grid_flat)?;
+        let mines = Mines::from_flat(width, height, mines_flat)?;
+        let nc = neighbor_cache(width, height)?;
+        Ok(solver::requires_advanced_strategy(&grid, &mines, &nc, start_x, start_y))
+    }
+
+    /// Difficulty badge in one call: `{ noGuess, firstGuessRisk }`, where
+    /// `firstGuessRisk` is the mine probability of the safest guess at the
+    /// first stall, or `null` for no-guess boards.
+    #[wasm_bindgen(js_name = "solvabilitySummary")]
+    pub fn wasm_solvability_summary(
+        width: usize,
+        height: usize,
+        grid_flat: &[i8],
+        mines_flat: &[u8],
+        start_x: usize,
+        start_y: usize,
+    ) -> Result<JsValue, JsValue> {
+        let grid = Grid::from_flat(width, height, grid_flat)?;
+        let mines = Mines::from_flat(width, height, mines_flat)?;
+        let nc = neighbor_cache(width, height)?;
+        let summary = solver::solvability_summary(&grid, &mines, &nc, start_x, start_y);
+
+        let obj = js_sys::Object::new();
+        set_field(&obj, "noGuess", &summary.no_guess.into());
+        let risk = summary.first_guess_risk.map_or(JsValue::NULL, JsValue::from);
+        set_field(&obj, "firstGuessRisk", &risk);
+        Ok(obj.into())
+    }
+
+    /// Solve from the start position until the first required guess.
+    /// Returns `{ solved: true }` or
+    /// `{ solved: false, revealedFraction, frontierComponents, bestGuess: { x, y, probability, adjacencyScore } | null }`.
+    /// Takes the same optional `blocked` mask as `isSolvable`.
+    #[wasm_bindgen(js_name = "solveUntilGuess")]
+    pub fn wasm_solve_until_guess(
+        width: usize,
+        height: usize,
+        grid_flat: &[i8],
+        mines_flat: &[u8],
+        start_x: usize,
+        start_y: usize,
+        blocked: Option<Vec<u8>>,
+    ) -> Result<JsValue, JsValue> {
+        let mines = Mines::from_flat(width, height, mines_flat)?;
+        let blocked = blocked.map(|cells| Blocked::from_flat(width, height, &cells)).transpose()?;
+        let (grid, nc) = blocked_grid_and_cache(Grid::from_flat(width, height, grid_flat)?, blocked)?;
+
+        Ok(guess_point_to_js(&solver::solve_until_guess(&grid, &mines, &nc, start_x, start_y)).into())
     }
 
     /// Generate a solvable board (No-Guess mode).
-    /// Returns JS object: `{ success: bool, attempts: u32, grid: Int8Array, mines: Uint8Array }`
+    /// Returns JS object: `{ success: bool, attempts: u32, grid: Int8Array, mines: Uint8Array }`,
+    /// or `{ success: false, error: string }` if the parameters are invalid.
+    /// Failed results also carry `failure`: `"minePlacementFailed"` (no board was built)
+    /// or `"noSolvableFound"` (the grid is the last unsolvable attempt). The other
+    /// generators set it the same way.
+    /// The optional `config` (`{ knowMineCount?: bool, clueTolerance?: number, maxStrategyLevel?: number }`) is applied to the solvability check.
+    /// With `requireFullClueSpectrum: true`, boards must also show every clue 1–8;
+    /// 7s and 8s are rare at typical densities, so expect far more attempts.
+    /// `minOpeningClueVariety: k` requires the revealed safe zone to expose at
+    /// least `k` distinct clue values (tutorial levels).
+    /// `borderBuffer: n` keeps `n` extra rings around the safe zone mine-free too.
+    /// Built-in acceptance criteria, checked after the solve: `min3bv` / `max3bv`
+    /// bound the board's 3BV, and `maxOpenings` caps its number of zero openings.
+    /// `blocked: Uint8Array` cuts holes in the board: no mine is placed there,
+    /// the grid reads `BLOCKED` (-2) and the solver skips them.
+    /// `seed: n` makes the result reproducible, and `reseedInterval: n` restarts
+    /// the RNG from a seed derived from it every `n` attempts, to cover more of
+    /// the board space on very hard parameters (drawn from entropy without `seed`).
     #[wasm_bindgen(js_name = "generateSolvableBoard")]
+    #[allow(clippy::too_many_arguments)]
     pub fn wasm_generate_solvable_board(
         width: usize,
         height: usize,
@@ -45,73 +448,2298 @@ mod wasm_exports {
         safe_y: usize,
         safe_radius: usize,
         max_attempts: u32,
+        config: Option<js_sys::Object>,
     ) -> JsValue {
-        let nc = NeighborCache::new(width, height);
+        let obj = js_sys::Object::new();
+        let border_buffer = config.as_ref().map_or(0.0, |obj| get_f64(obj, "borderBuffer", 0.0)).max(0.0) as usize;
 
-        let result = board::generate_solvable_board(
-            width, height, bomb_count, safe_x, safe_y, safe_radius,
-            max_attempts, &nc,
-            |grid, mines, nc, sx, sy| solver::is_solvable(grid, mines, nc, sx, sy),
+        if let Err(error) = board::validate_board_params(width, height, bomb_count, safe_x, safe_y, safe_radius.saturating_add(border_buffer)) {
+            set_field(&obj, "success", &false.into());
+            set_field(&obj, "error", &error.into());
+            return obj.into();
+        }
+
+        let blocked = match blocked_from_js(config.as_ref(), width, height) {
+            Ok(blocked) => blocked,
+            Err(error) => {
+                set_field(&obj, "success", &false.into());
+                set_field(&obj, "error", &error.to_string().into());
+                return obj.into();
+            }
+        };
+        let nc = match &blocked {
+            Some(blocked) => NeighborCache::with_blocked(blocked),
+            None => NeighborCache::new(width, height),
+        };
+        let full_spectrum = config.as_ref().is_some_and(|obj| get_bool(obj, "requireFullClueSpectrum", false));
+        let min_variety = config.as_ref().map_or(0.0, |obj| get_f64(obj, "minOpeningClueVariety", 0.0)).max(0.0) as usize;
+        let limit = |key: &str, default: f64| config.as_ref().map_or(default, |obj| get_f64(obj, key, default));
+        let min_3bv = limit("min3bv", 0.0);
+        let max_3bv = limit("max3bv", f64::INFINITY);
+        let max_openings = limit("maxOpenings", f64::INFINITY);
+        let seed = config.as_ref().map(|obj| get_f64(obj, "seed", -1.0)).filter(|&s| s >= 0.0).map(|s| s as u64);
+        let reseed_interval = config.as_ref().map_or(0.0, |obj| get_f64(obj, "reseedInterval", 0.0)).max(0.0) as u32;
+        let config = solver_config_from_js(config);
+
+        let solvable = |grid: &Grid, mines: &Mines, nc: &NeighborCache, sx: usize, sy: usize| {
+            // The histogram and opening checks are far cheaper than a solve, so run them first
+            (!full_spectrum || board::has_full_clue_spectrum(grid, mines))
+                && (min_variety == 0 || board::opening_clue_variety(grid, nc, sx, sy, safe_radius) >= min_variety)
+                && solver::is_solvable_with_config(grid, mines, nc, sx, sy, &config)
+        };
+        let accept = |grid: &Grid, mines: &Mines, nc: &NeighborCache| {
+            let bv_ok = (min_3bv <= 0.0 && max_3bv.is_infinite()) || {
+                let bv = board::compute_3bv(grid, mines, nc) as f64;
+                bv >= min_3bv && bv <= max_3bv
+            };
+            bv_ok && (max_openings.is_infinite() || {
+                let openings = board::opening_regions(grid, mines, nc).into_iter().max().map_or(0, |id| id + 1);
+                openings as f64 <= max_openings
+            })
+        };
+        let reseeding = seed.is_some() || reseed_interval > 0;
+        let base_seed = || seed.unwrap_or_else(|| crate::rng::WasmRng::new().gen_range(u32::MAX as usize) as u64);
+        let zone = SafeZone::new(safe_x, safe_y, safe_radius);
+        let result = match &blocked {
+            Some(blocked) if reseeding => board::generate_reseeding_blocked_board(
+                blocked, bomb_count, &zone, border_buffer, max_attempts, &nc, base_seed(), Some(reseed_interval),
+                |grid, mines, nc, sx, sy| solvable(grid, mines, nc, sx, sy) && accept(grid, mines, nc),
+            ),
+            Some(blocked) => board::generate_blocked_board(
+                blocked, bomb_count, &zone, border_buffer, max_attempts, &nc, &mut crate::rng::WasmRng::new(),
+                |grid, mines, nc, sx, sy| solvable(grid, mines, nc, sx, sy) && accept(grid, mines, nc),
+            ),
+            None if reseeding => board::generate_reseeding_board(
+                width, height, bomb_count, safe_x, safe_y, safe_radius, border_buffer, max_attempts, &nc,
+                base_seed(), Some(reseed_interval), solvable, accept,
+            ),
+            None => board::generate_accepted_board(
+                width, height, bomb_count, safe_x, safe_y, safe_radius, border_buffer,
+                max_attempts, &nc, solvable, accept,
+            ),
+        };
+
+        board_result_to_js(&result).into()
+    }
+
+    /// Generate a solvable board, dropping one mine and retrying whenever
+    /// `max_attempts_per_level` layouts in a row fail (casual modes).
+    /// Returns `{ success, attempts, grid, mines, bombCount }` where `bombCount` is
+    /// the mine count actually used, or `{ success: false, error }` if the
+    /// parameters are invalid.
+    #[wasm_bindgen(js_name = "generateWithFallback")]
+    pub fn wasm_generate_with_fallback(
+        width: usize,
+        height: usize,
+        target_bombs: usize,
+        safe_x: usize,
+        safe_y: usize,
+        safe_radius: usize,
+        max_attempts_per_level: u32,
+    ) -> JsValue {
+        let obj = js_sys::Object::new();
+
+        if let Err(error) = board::validate_board_params(width, height, target_bombs, safe_x, safe_y, safe_radius) {
+            set_field(&obj, "success", &false.into());
+            set_field(&obj, "error", &error.into());
+            return obj.into();
+        }
+
+        let nc = NeighborCache::new(width, height);
+        let zone = SafeZone::new(safe_x, safe_y, safe_radius);
+        let (result, bomb_count) = board::generate_with_density_fallback(
+            width, height, target_bombs, &zone, max_attempts_per_level, &nc,
         );
 
+        let obj = board_result_to_js(&result);
+        set_field(&obj, "bombCount", &(bomb_count as u32).into());
+
+        obj.into()
+    }
+
+    /// Generate a board that needs exactly one guess (guess-training mode).
+    /// Returns `{ success, attempts, grid, mines, guessPoint }` where `guessPoint` is
+    /// the final board's first stall in the `solveUntilGuess` shape, or
+    /// `{ success: false, error }` if the parameters are invalid.
+    #[wasm_bindgen(js_name = "generateGuessRequiredBoard")]
+    pub fn wasm_generate_guess_required_board(
+        width: usize,
+        height: usize,
+        bomb_count: usize,
+        safe_x: usize,
+        safe_y: usize,
+        safe_radius: usize,
+        max_attempts: u32,
+    ) -> JsValue {
         let obj = js_sys::Object::new();
-        js_sys::Reflect::set(&obj, &"success".into(), &result.success.into()).unwrap();
-        js_sys::Reflect::set(&obj, &"attempts".into(), &result.attempts.into()).unwrap();
 
-        let grid_arr = js_sys::Int8Array::new_with_length(result.grid.cells.len() as u32);
-        grid_arr.copy_from(&result.grid.cells);
-        js_sys::Reflect::set(&obj, &"grid".into(), &grid_arr.into()).unwrap();
+        if let Err(error) = board::validate_board_params(width, height, bomb_count, safe_x, safe_y, safe_radius) {
+            set_field(&obj, "success", &false.into());
+            set_field(&obj, "error", &error.into());
+            return obj.into();
+        }
 
-        let mines_arr = js_sys::Uint8Array::new_with_length(result.mines.cells.len() as u32);
-        mines_arr.copy_from(&result.mines.cells);
-        js_sys::Reflect::set(&obj, &"mines".into(), &mines_arr.into()).unwrap();
+        let nc = NeighborCache::new(width, height);
+        let zone = SafeZone::new(safe_x, safe_y, safe_radius);
+        let (result, guess_point) = board::generate_guess_required_board(
+            width, height, bomb_count, &zone, max_attempts, &nc,
+        );
+
+        let obj = board_result_to_js(&result);
+        set_field(&obj, "guessPoint", &guess_point_to_js(&guess_point).into());
 
         obj.into()
     }
 
-    /// Calculate neighbor mine counts for all cells.
-    #[wasm_bindgen(js_name = "calculateNumbers")]
-    pub fn wasm_calculate_numbers(
+    /// `generateSolvableBoard` (without `config`) that also rates the board:
+    /// `{ success, attempts, grid, mines, difficulty? }`, where `difficulty` is
+    /// `{ rating, iterations, successes }` for successful boards: `rating` is the
+    /// hardest strategy level (1–6) the board needs and `successes` a `Uint32Array`
+    /// of each strategy's progress count. The acceptance solve raises its
+    /// strategy cap a level at a time and rates the board as it goes, so no
+    /// solve runs after generation.
+    #[wasm_bindgen(js_name = "generateSolvableBoardRated")]
+    pub fn wasm_generate_solvable_board_rated(
         width: usize,
         height: usize,
-        mines_flat: &[u8],
-    ) -> js_sys::Int8Array {
-        let mines = Mines { width, height, cells: mines_flat.to_vec() };
+        bomb_count: usize,
+        safe_x: usize,
+        safe_y: usize,
+        safe_radius: usize,
+        max_attempts: u32,
+    ) -> JsValue {
+        let obj = js_sys::Object::new();
+        if let Err(error) = board::validate_board_params(width, height, bomb_count, safe_x, safe_y, safe_radius) {
+            set_field(&obj, "success", &false.into());
+            set_field(&obj, "error", &error.into());
+            return obj.into();
+        }
+
         let nc = NeighborCache::new(width, height);
-        let grid = board::calculate_numbers(&mines, &nc);
+        let (result, report) = board::generate_rated_board(
+            width, height, bomb_count, &SafeZone::new(safe_x, safe_y, safe_radius), max_attempts, &nc,
+            &mut crate::rng::WasmRng::new(),
+        );
 
-        let arr = js_sys::Int8Array::new_with_length(grid.cells.len() as u32);
-        arr.copy_from(&grid.cells);
-        arr
+        let obj = board_result_to_js(&result);
+        if let Some(report) = report {
+            let difficulty = js_sys::Object::new();
+            set_field(&difficulty, "rating", &(report.rating as u8).into());
+            set_field(&difficulty, "iterations", &report.metrics.iterations.into());
+            let successes = js_sys::Uint32Array::new_with_length(6);
+            successes.copy_from(&report.metrics.successes);
+            set_field(&difficulty, "successes", &successes.into());
+            set_field(&obj, "difficulty", &difficulty.into());
+        }
+        obj.into()
     }
 
-    /// Get a hint (best safe cell to reveal).
-    /// Returns JS object `{ x, y, score }` or `null`.
-    #[wasm_bindgen(js_name = "getHint")]
-    pub fn wasm_get_hint(
+    /// Generate `count` solvable boards from seeds `base_seed`, `base_seed + 1`, ...
+    /// in one call, sharing one neighbor cache.
+    /// Returns an array of `{ success, attempts, grid, mines, seed }` objects, or
+    /// `{ success: false, error }` if the parameters are invalid.
+    #[wasm_bindgen(js_name = "generateSolvableBoardBatch")]
+    #[allow(clippy::too_many_arguments)]
+    pub fn wasm_generate_solvable_board_batch(
         width: usize,
         height: usize,
-        grid_flat: &[i8],
-        visible_flat: &[i8],
-        flags_flat: &[u8],
-        mines_flat: &[u8],
+        bomb_count: usize,
+        safe_x: usize,
+        safe_y: usize,
+        safe_radius: usize,
+        max_attempts: u32,
+        base_seed: u32,
+        count: u32,
     ) -> JsValue {
-        let grid = Grid { width, height, cells: grid_flat.to_vec() };
-        let visible = VisibleGrid { width, height, cells: visible_flat.to_vec() };
-        let flags = Flags { width, height, cells: flags_flat.to_vec() };
-        let mines = Mines { width, height, cells: mines_flat.to_vec() };
+        if let Err(error) = board::validate_board_params(width, height, bomb_count, safe_x, safe_y, safe_radius) {
+            let obj = js_sys::Object::new();
+            set_field(&obj, "success", &false.into());
+            set_field(&obj, "error", &error.into());
+            return obj.into();
+        }
+
         let nc = NeighborCache::new(width, height);
+        let results = board::generate_solvable_board_batch(
+            width, height, bomb_count, safe_x, safe_y, safe_radius,
+            max_attempts, base_seed as u64, count as usize, &nc,
+            solver::is_solvable,
+        );
 
-        match solver::get_hint(&grid, &visible, &flags, &mines, &nc) {
-            Some(hint) => {
-                let obj = js_sys::Object::new();
-                js_sys::Reflect::set(&obj, &"x".into(), &(hint.x as u32).into()).unwrap();
-                js_sys::Reflect::set(&obj, &"y".into(), &(hint.y as u32).into()).unwrap();
-                js_sys::Reflect::set(&obj, &"score".into(), &hint.score.into()).unwrap();
-                obj.into()
-            }
-            None => JsValue::NULL,
+        let arr = js_sys::Array::new();
+        for (seed, result) in results {
+            let obj = board_result_to_js(&result);
+            set_field(&obj, "seed", &(seed as f64).into());
+            arr.push(&obj);
+        }
+        arr.into()
+    }
+
+    /// Generate up to `count` solvable boards, no two of which are rotations or
+    /// reflections of each other. Same result layout as `generateSolvableBoardBatch`;
+    /// fewer than `count` boards come back if the board space runs out.
+    #[wasm_bindgen(js_name = "generateUniqueBatch")]
+    #[allow(clippy::too_many_arguments)]
+    pub fn wasm_generate_unique_batch(
+        width: usize,
+        height: usize,
+        bomb_count: usize,
+        safe_x: usize,
+        safe_y: usize,
+        safe_radius: usize,
+        max_attempts: u32,
+        base_seed: u32,
+        count: u32,
+    ) -> JsValue {
+        if let Err(error) = board::validate_board_params(width, height, bomb_count, safe_x, safe_y, safe_radius) {
+            let obj = js_sys::Object::new();
+            set_field(&obj, "success", &false.into());
+            set_field(&obj, "error", &error.into());
+            return obj.into();
+        }
+
+        let nc = NeighborCache::new(width, height);
+        let results = board::generate_unique_batch(
+            width, height, bomb_count, safe_x, safe_y, safe_radius,
+            max_attempts, base_seed as u64, count as usize, &nc,
+            solver::is_solvable,
+        );
+
+        let arr = js_sys::Array::new();
+        for (seed, result) in results {
+            let obj = board_result_to_js(&result);
+            set_field(&obj, "seed", &(seed as f64).into());
+            arr.push(&obj);
+        }
+        arr.into()
+    }
+
+    /// Generate a solvable board containing a fixed mine template (themed levels).
+    /// `fixed_flat` holds `[x0, y0, x1, y1, ...]`; the remaining mines are placed
+    /// at random outside the safe zone. Returns `{ success, attempts, grid, mines }`,
+    /// or `{ success: false, error }` if the parameters are invalid.
+    #[wasm_bindgen(js_name = "generateTemplatedBoard")]
+    #[allow(clippy::too_many_arguments)]
+    pub fn wasm_generate_templated_board(
+        width: usize,
+        height: usize,
+        bomb_count: usize,
+        fixed_flat: &[u32],
+        safe_x: usize,
+        safe_y: usize,
+        safe_radius: usize,
+        max_attempts: u32,
+    ) -> JsValue {
+        let obj = js_sys::Object::new();
+
+        if !fixed_flat.len().is_multiple_of(2) {
+            set_field(&obj, "success", &false.into());
+            set_field(&obj, "error", &"Fixed mine coordinates must come in (x, y) pairs".into());
+            return obj.into();
+        }
+        let fixed: Vec<(usize, usize)> = fixed_flat
+            .chunks_exact(2)
+            .map(|p| (p[0] as usize, p[1] as usize))
+            .collect();
+        let zone = SafeZone::new(safe_x, safe_y, safe_radius);
+        if let Err(error) = board::validate_template(width, height, bomb_count, &fixed, &zone) {
+            set_field(&obj, "success", &false.into());
+            set_field(&obj, "error", &error.into());
+            return obj.into();
+        }
+
+        let nc = NeighborCache::new(width, height);
+        let mut rng = crate::rng::WasmRng::new();
+        let result = board::generate_templated_board(
+            width, height, bomb_count, &fixed, &zone, max_attempts, &nc, &mut rng,
+            solver::is_solvable,
+        );
+
+        board_result_to_js(&result).into()
+    }
+
+    /// Generate a solvable board whose rectangle `(x0, y0)`-`(x1, y1)` (corners
+    /// inclusive) is mine-free and revealed at the start, e.g. a whole row.
+    /// Returns `{ success, attempts, grid, mines }`, or `{ success: false, error }`
+    /// if the parameters are invalid.
+    #[wasm_bindgen(js_name = "generateRectBoard")]
+    #[allow(clippy::too_many_arguments)]
+    pub fn wasm_generate_rect_board(
+        width: usize,
+        height: usize,
+        bomb_count: usize,
+        x0: usize,
+        y0: usize,
+        x1: usize,
+        y1: usize,
+        max_attempts: u32,
+    ) -> JsValue {
+        let obj = js_sys::Object::new();
+
+        let rect = SafeRect::new(x0, y0, x1, y1);
+        if let Err(error) = board::validate_safe_rect(width, height, bomb_count, &rect) {
+            set_field(&obj, "success", &false.into());
+            set_field(&obj, "error", &error.into());
+            return obj.into();
         }
+
+        let nc = NeighborCache::new(width, height);
+        let mut rng = crate::rng::WasmRng::new();
+        let result = board::generate_rect_board(
+            width, height, bomb_count, &rect, max_attempts, &nc, &mut rng,
+            solver::is_solvable_rect,
+        );
+
+        board_result_to_js(&result).into()
+    }
+
+    /// Generate a solvable board with several mine-free openings (co-op starts).
+    /// Zone `i` is centered on `(centers_x[i], centers_y[i])` with radius `radii[i]`.
+    /// Returns `{ success, attempts, grid, mines }`, or `{ success: false, error }`
+    /// if the parameters are invalid.
+    #[wasm_bindgen(js_name = "generateMultiStartBoard")]
+    pub fn wasm_generate_multi_start_board(
+        width: usize,
+        height: usize,
+        bomb_count: usize,
+        centers_x: &[u32],
+        centers_y: &[u32],
+        radii: &[u32],
+        max_attempts: u32,
+    ) -> JsValue {
+        let obj = js_sys::Object::new();
+
+        if centers_x.len() != centers_y.len() || centers_x.len() != radii.len() {
+            set_field(&obj, "success", &false.into());
+            set_field(&obj, "error", &"Safe zone arrays must have the same length".into());
+            return obj.into();
+        }
+        let zones: Vec<SafeZone> = centers_x
+            .iter()
+            .zip(centers_y)
+            .zip(radii)
+            .map(|((&x, &y), &r)| SafeZone::new(x as usize, y as usize, r as usize))
+            .collect();
+        if let Err(error) = board::validate_safe_zones(width, height, bomb_count, &zones) {
+            set_field(&obj, "success", &false.into());
+            set_field(&obj, "error", &error.into());
+            return obj.into();
+        }
+
+        let nc = NeighborCache::new(width, height);
+        let mut rng = crate::rng::WasmRng::new();
+        let result = board::generate_multi_start_board(
+            width, height, bomb_count, &zones, max_attempts, &nc, &mut rng,
+            solver::is_solvable_multi_start,
+        );
+
+        set_field(&obj, "success", &result.success.into());
+        if let Some(failure) = result.failure {
+            set_field(&obj, "failure", &failure.as_str().into());
+        }
+        if let Some(error) = result.error {
+            set_field(&obj, "error", &error.into());
+            return obj.into();
+        }
+        set_field(&obj, "attempts", &result.attempts.into());
+
+        let grid_arr = js_sys::Int8Array::new_with_length(result.grid.cells.len() as u32);
+        grid_arr.copy_from(&result.grid.cells);
+        set_field(&obj, "grid", &grid_arr.into());
+
+        let mines_arr = js_sys::Uint8Array::new_with_length(result.mines.cells.len() as u32);
+        mines_arr.copy_from(&result.mines.cells);
+        set_field(&obj, "mines", &mines_arr.into());
+
+        obj.into()
+    }
+
+    /// Generate a solvable board whose whole `mercyRadius` zone around the
+    /// first click is mine-free and opened by that click (touch-friendly starts).
+    /// Returns `{ success, attempts, grid, mines }`, or `{ success: false, error }`
+    /// if the parameters are invalid.
+    #[wasm_bindgen(js_name = "generateMercyBoard")]
+    pub fn wasm_generate_mercy_board(
+        width: usize,
+        height: usize,
+        bomb_count: usize,
+        safe_x: usize,
+        safe_y: usize,
+        mercy_radius: usize,
+        max_attempts: u32,
+    ) -> JsValue {
+        let obj = js_sys::Object::new();
+
+        if let Err(error) = board::validate_board_params(width, height, bomb_count, safe_x, safe_y, mercy_radius) {
+            set_field(&obj, "success", &false.into());
+            set_field(&obj, "error", &error.into());
+            return obj.into();
+        }
+
+        let nc = NeighborCache::new(width, height);
+        let mut rng = crate::rng::WasmRng::new();
+        let result = board::generate_mercy_board(
+            width, height, bomb_count, safe_x, safe_y, mercy_radius, max_attempts, &nc, &mut rng,
+            solver::is_solvable_multi_start,
+        );
+
+        set_field(&obj, "success", &result.success.into());
+        if let Some(failure) = result.failure {
+            set_field(&obj, "failure", &failure.as_str().into());
+        }
+        if let Some(error) = result.error {
+            set_field(&obj, "error", &error.into());
+            return obj.into();
+        }
+        set_field(&obj, "attempts", &result.attempts.into());
+
+        let grid_arr = js_sys::Int8Array::new_with_length(result.grid.cells.len() as u32);
+        grid_arr.copy_from(&result.grid.cells);
+        set_field(&obj, "grid", &grid_arr.into());
+
+        let mines_arr = js_sys::Uint8Array::new_with_length(result.mines.cells.len() as u32);
+        mines_arr.copy_from(&result.mines.cells);
+        set_field(&obj, "mines", &mines_arr.into());
+
+        obj.into()
+    }
+
+    /// Calculate neighbor mine counts for all cells.
+    /// Throws if `mines_flat` doesn't hold `width * height` cells.
+    #[wasm_bindgen(js_name = "calculateNumbers")]
+    pub fn wasm_calculate_numbers(
+        width: usize,
+        height: usize,
+        mines_flat: &[u8],
+    ) -> Result<js_sys::Int8Array, JsValue> {
+        let nc = neighbor_cache(width, height)?;
+        let mines = Mines::from_flat(width, height, mines_flat)?;
+        let grid = board::calculate_numbers(&mines, &nc);
+
+        let arr = js_sys::Int8Array::new_with_length(grid.cells.len() as u32);
+        arr.copy_from(&grid.cells);
+        Ok(arr)
+    }
+
+    /// Validate a user-authored board and compute its numbers in one call.
+    /// Returns `{ ok: true, grid: Int8Array, mineCount }`, or `{ ok: false, error }`
+    /// for a length mismatch, a cell value other than 0/1, bad dimensions or a
+    /// board with no safe cell.
+    #[wasm_bindgen(js_name = "loadBoard")]
+    pub fn wasm_load_board(width: usize, height: usize, mines_flat: &[u8]) -> JsValue {
+        let obj = js_sys::Object::new();
+        match board::load_board(width, height, mines_flat) {
+            Ok((mines, grid)) => {
+                set_field(&obj, "ok", &true.into());
+                let grid_arr = js_sys::Int8Array::new_with_length(grid.cells.len() as u32);
+                grid_arr.copy_from(&grid.cells);
+                set_field(&obj, "grid", &grid_arr.into());
+                set_field(&obj, "mineCount", &(mines.count() as u32).into());
+            }
+            Err(error) => {
+                set_field(&obj, "ok", &false.into());
+                set_field(&obj, "error", &error.into());
+            }
+        }
+        obj.into()
+    }
+
+    /// Compute the 3BV (minimum clicks to clear without chording) of a board.
+    #[wasm_bindgen(js_name = "compute3bv")]
+    pub fn wasm_compute_3bv(
+        width: usize,
+        height: usize,
+        grid_flat: &[i8],
+        mines_flat: &[u8],
+    ) -> Result<u32, JsValue> {
+        let grid = Grid::from_flat(width, height, grid_flat)?;
+        let mines = Mines::from_flat(width, height, mines_flat)?;
+        let nc = neighbor_cache(width, height)?;
+        Ok(board::compute_3bv(&grid, &mines, &nc))
+    }
+
+    /// Histogram of how many cells each safe cell would reveal as a first click,
+    /// as `[size, cells]` pairs by increasing size.
+    #[wasm_bindgen(js_name = "openingSizeDistribution")]
+    pub fn wasm_opening_size_distribution(
+        width: usize,
+        height: usize,
+        grid_flat: &[i8],
+        mines_flat: &[u8],
+    ) -> Result<js_sys::Array, JsValue> {
+        let grid = Grid::from_flat(width, height, grid_flat)?;
+        let mines = Mines::from_flat(width, height, mines_flat)?;
+        let nc = neighbor_cache(width, height)?;
+
+        let arr = js_sys::Array::new();
+        for (size, cells) in board::opening_size_distribution(&grid, &mines, &nc) {
+            arr.push(&js_sys::Array::of2(&(size as u32).into(), &cells.into()));
+        }
+        Ok(arr)
+    }
+
+    /// Cells whose number in `grid` disagrees with the mine layout, as `[x, y]` pairs.
+    /// Mine cells are not checked.
+    #[wasm_bindgen(js_name = "verifyNumbers")]
+    pub fn wasm_verify_numbers(
+        width: usize,
+        height: usize,
+        grid_flat: &[i8],
+        mines_flat: &[u8],
+    ) -> Result<js_sys::Array, JsValue> {
+        let grid = Grid::from_flat(width, height, grid_flat)?;
+        let mines = Mines::from_flat(width, height, mines_flat)?;
+        let nc = neighbor_cache(width, height)?;
+        Ok(pairs_to_js(&board::verify_numbers(&grid, &mines, &nc)))
+    }
+
+    /// Cells whose value would change if the mine at `(fromX, fromY)` moved to
+    /// `(toX, toY)`, as `[x, y]` pairs; empty if that is not a valid move.
+    #[wasm_bindgen(js_name = "mineMoveImpact")]
+    pub fn wasm_mine_move_impact(
+        width: usize,
+        height: usize,
+        mines_flat: &[u8],
+        from_x: usize,
+        from_y: usize,
+        to_x: usize,
+        to_y: usize,
+    ) -> Result<js_sys::Array, JsValue> {
+        let mines = Mines::from_flat(width, height, mines_flat)?;
+        let nc = neighbor_cache(width, height)?;
+        Ok(pairs_to_js(&board::mine_move_impact(&mines, &nc, (from_x, from_y), (to_x, to_y))))
+    }
+
+    /// True if two boards have the same dimensions and mines in exactly the same cells;
+    /// throws if an array does not match its dimensions.
+    #[wasm_bindgen(js_name = "minesEqual")]
+    pub fn wasm_mines_equal(
+        width_a: usize,
+        height_a: usize,
+        mines_a: &[u8],
+        width_b: usize,
+        height_b: usize,
+        mines_b: &[u8],
+    ) -> Result<bool, JsValue> {
+        let a = Mines::from_flat(width_a, height_a, mines_a)?;
+        let b = Mines::from_flat(width_b, height_b, mines_b)?;
+        Ok(board::mines_equal(&a, &b))
+    }
+
+    /// The representative of a layout among its same-sized rotations and
+    /// reflections: `{ mines, transform }`, where `transform` is 0 identity,
+    /// 1 mirror x, 2 mirror y, 3 rotate 180°, and on square boards 4 transpose,
+    /// 5 rotate 90° counterclockwise, 6 clockwise, 7 anti-transpose.
+    #[wasm_bindgen(js_name = "canonicalizeBoard")]
+    pub fn wasm_canonicalize_board(width: usize, height: usize, mines_flat: &[u8]) -> Result<JsValue, JsValue> {
+        let mines = Mines::from_flat(width, height, mines_flat)?;
+        let transform = board::canonical_transform(&mines);
+        let canonical = board::apply_transform(&mines, transform);
+
+        let obj = js_sys::Object::new();
+        let mines_arr = js_sys::Uint8Array::new_with_length(canonical.cells.len() as u32);
+        mines_arr.copy_from(&canonical.cells);
+        set_field(&obj, "mines", &mines_arr.into());
+        set_field(&obj, "transform", &transform.into());
+        Ok(obj.into())
+    }
+
+    /// Move the mine at `(fromX, fromY)` to a random empty cell outside the safe zone.
+    /// The same `seed` always picks the same target. Returns
+    /// `{ x, y, mines, grid }` with the numbers recomputed, or `null` if there
+    /// is no mine at the source or no room to move it.
+    #[wasm_bindgen(js_name = "relocateMine")]
+    #[allow(clippy::too_many_arguments)]
+    pub fn wasm_relocate_mine(
+        width: usize,
+        height: usize,
+        mines_flat: &[u8],
+        from_x: usize,
+        from_y: usize,
+        safe_x: usize,
+        safe_y: usize,
+        safe_radius: usize,
+        seed: u32,
+    ) -> Result<JsValue, JsValue> {
+        let mut mines = Mines::from_flat(width, height, mines_flat)?;
+        let nc = neighbor_cache(width, height)?;
+        let zone = SafeZone::new(safe_x, safe_y, safe_radius);
+        let mut rng = crate::rng::WasmRng::from_seed(seed as u64);
+
+        let Some((x, y)) = board::relocate_mine(&mut mines, &nc, (from_x, from_y), &zone, &mut rng) else {
+            return Ok(JsValue::NULL);
+        };
+        let grid = board::calculate_numbers(&mines, &nc);
+
+        let obj = js_sys::Object::new();
+        set_field(&obj, "x", &(x as u32).into());
+        set_field(&obj, "y", &(y as u32).into());
+
+        let mines_arr = js_sys::Uint8Array::new_with_length(mines.cells.len() as u32);
+        mines_arr.copy_from(&mines.cells);
+        set_field(&obj, "mines", &mines_arr.into());
+
+        let grid_arr = js_sys::Int8Array::new_with_length(grid.cells.len() as u32);
+        grid_arr.copy_from(&grid.cells);
+        set_field(&obj, "grid", &grid_arr.into());
+        Ok(obj.into())
+    }
+
+    /// Serialize a board as `{"width","height","mines":[[x,y],...]}` JSON.
+    #[wasm_bindgen(js_name = "toJson")]
+    pub fn wasm_to_json(width: usize, height: usize, mines_flat: &[u8]) -> Result<String, JsValue> {
+        let mines = Mines::from_flat(width, height, mines_flat)?;
+        Ok(board::to_json(&mines))
+    }
+
+    /// Parse board JSON and recompute its numbers.
+    /// Returns `{ width, height, grid, mines }`; throws on malformed input.
+    #[wasm_bindgen(js_name = "fromJson")]
+    pub fn wasm_from_json(json: &str) -> Result<JsValue, JsValue> {
+        let (mines, grid) = board::from_json(json).map_err(|e| JsValue::from(js_sys::Error::new(&e)))?;
+
+        let obj = js_sys::Object::new();
+        set_field(&obj, "width", &(mines.width as u32).into());
+        set_field(&obj, "height", &(mines.height as u32).into());
+
+        let grid_arr = js_sys::Int8Array::new_with_length(grid.cells.len() as u32);
+        grid_arr.copy_from(&grid.cells);
+        set_field(&obj, "grid", &grid_arr.into());
+
+        let mines_arr = js_sys::Uint8Array::new_with_length(mines.cells.len() as u32);
+        mines_arr.copy_from(&mines.cells);
+        set_field(&obj, "mines", &mines_arr.into());
+        Ok(obj.into())
+    }
+
+    /// Encode a board's mines as a short base64url string for share links.
+    #[wasm_bindgen(js_name = "encodeShareable")]
+    pub fn wasm_encode_shareable(width: usize, height: usize, mines_flat: &[u8]) -> Result<String, JsValue> {
+        let mines = Mines::from_flat(width, height, mines_flat)?;
+        Ok(board::encode_shareable(width, height, &mines)?)
+    }
+
+    /// Decode a share code. Returns `{ width, height, grid, mines }` with the
+    /// numbers recomputed; throws if the code is corrupt.
+    #[wasm_bindgen(js_name = "decodeShareable")]
+    pub fn wasm_decode_shareable(code: &str) -> Result<JsValue, JsValue> {
+        let (width, height, mines) = board::decode_shareable(code)
+            .map_err(|e| JsValue::from(js_sys::Error::new(&e.to_string())))?;
+        let nc = neighbor_cache(width, height)?;
+        let grid = board::calculate_numbers(&mines, &nc);
+
+        let obj = js_sys::Object::new();
+        set_field(&obj, "width", &(width as u32).into());
+        set_field(&obj, "height", &(height as u32).into());
+
+        let grid_arr = js_sys::Int8Array::new_with_length(grid.cells.len() as u32);
+        grid_arr.copy_from(&grid.cells);
+        set_field(&obj, "grid", &grid_arr.into());
+
+        let mines_arr = js_sys::Uint8Array::new_with_length(mines.cells.len() as u32);
+        mines_arr.copy_from(&mines.cells);
+        set_field(&obj, "mines", &mines_arr.into());
+        Ok(obj.into())
+    }
+
+    /// Label each cell with the id of the zero-opening that reveals it (-1 if none).
+    #[wasm_bindgen(js_name = "openingRegions")]
+    pub fn wasm_opening_regions(
+        width: usize,
+        height: usize,
+        grid_flat: &[i8],
+        mines_flat: &[u8],
+    ) -> Result<js_sys::Int32Array, JsValue> {
+        let grid = Grid::from_flat(width, height, grid_flat)?;
+        let mines = Mines::from_flat(width, height, mines_flat)?;
+        let nc = neighbor_cache(width, height)?;
+        let regions = board::opening_regions(&grid, &mines, &nc);
+
+        let arr = js_sys::Int32Array::new_with_length(regions.len() as u32);
+        arr.copy_from(&regions);
+        Ok(arr)
+    }
+
+    /// Zero cells surrounded only by zeros, where a first click always
+    /// cascades, as `[x, y]` pairs.
+    #[wasm_bindgen(js_name = "forcedCascadeCells")]
+    pub fn wasm_forced_cascade_cells(width: usize, height: usize, grid_flat: &[i8]) -> Result<js_sys::Array, JsValue> {
+        let grid = Grid::from_flat(width, height, grid_flat)?;
+        let nc = neighbor_cache(width, height)?;
+        Ok(pairs_to_js(&board::forced_cascade_cells(&grid, &nc)))
+    }
+
+    /// Find the start cell whose safe-zone reveal produces the largest cascade.
+    /// Returns JS object `{ x, y }`.
+    #[wasm_bindgen(js_name = "bestStartPosition")]
+    pub fn wasm_best_start_position(
+        width: usize,
+        height: usize,
+        grid_flat: &[i8],
+        mines_flat: &[u8],
+    ) -> Result<JsValue, JsValue> {
+        let grid = Grid::from_flat(width, height, grid_flat)?;
+        let mines = Mines::from_flat(width, height, mines_flat)?;
+        let nc = neighbor_cache(width, height)?;
+        let (x, y) = board::best_start_position(&mines, &grid, &nc);
+
+        let obj = js_sys::Object::new();
+        set_field(&obj, "x", &(x as u32).into());
+        set_field(&obj, "y", &(y as u32).into());
+        Ok(obj.into())
+    }
+
+    /// Group mines into clusters of mutually adjacent mines.
+    /// Returns an array of clusters, each an array of `[x, y]` pairs.
+    #[wasm_bindgen(js_name = "mineClusters")]
+    pub fn wasm_mine_clusters(
+        width: usize,
+        height: usize,
+        mines_flat: &[u8],
+    ) -> Result<js_sys::Array, JsValue> {
+        let mines = Mines::from_flat(width, height, mines_flat)?;
+        let nc = neighbor_cache(width, height)?;
+
+        let out = js_sys::Array::new();
+        for cluster in board::mine_clusters(&mines, &nc) {
+            out.push(&pairs_to_js(&cluster));
+        }
+        Ok(out)
+    }
+
+    /// Frontier uncertainty in bits after the opening and a full logical solve:
+    /// 0 for no-guess boards, growing with the guessing left. A smooth
+    /// difficulty scalar to show next to the strategy-based rating.
+    #[wasm_bindgen(js_name = "openingEntropy")]
+    pub fn wasm_opening_entropy(
+        width: usize,
+        height: usize,
+        grid_flat: &[i8],
+        mines_flat: &[u8],
+        start_x: usize,
+        start_y: usize,
+    ) -> Result<f32, JsValue> {
+        let grid = Grid::from_flat(width, height, grid_flat)?;
+        let mines = Mines::from_flat(width, height, mines_flat)?;
+        let nc = neighbor_cache(width, height)?;
+        Ok(solver::opening_entropy(&grid, &mines, &nc, start_x, start_y))
+    }
+
+    /// Count the groups of cells left hidden after the logical solve that no
+    /// revealed clue touches (pure-guess islands). Generators can reject boards
+    /// where this is non-zero.
+    #[wasm_bindgen(js_name = "countGuessIslands")]
+    pub fn wasm_count_guess_islands(
+        width: usize,
+        height: usize,
+        grid_flat: &[i8],
+        mines_flat: &[u8],
+        start_x: usize,
+        start_y: usize,
+    ) -> Result<u32, JsValue> {
+        let grid = Grid::from_flat(width, height, grid_flat)?;
+        let mines = Mines::from_flat(width, height, mines_flat)?;
+        let nc = neighbor_cache(width, height)?;
+        Ok(board::count_guess_islands(&grid, &mines, &nc, start_x, start_y) as u32)
+    }
+
+    /// Apply a player reveal at `(x, y)`. Mutates `visible_flat` in place.
+    /// Returns `{ changes: [{ x, y, value }], hitMine, won }`.
+    #[wasm_bindgen(js_name = "playMove")]
+    #[allow(clippy::too_many_arguments)]
+    pub fn wasm_play_move(
+        width: usize,
+        height: usize,
+        grid_flat: &[i8],
+        mines_flat: &[u8],
+        visible_flat: &mut [i8],
+        flags_flat: &[u8],
+        x: usize,
+        y: usize,
+        reveal_all_mines: bool,
+    ) -> Result<JsValue, JsValue> {
+        let grid = Grid::from_flat(width, height, grid_flat)?;
+        let mines = Mines::from_flat(width, height, mines_flat)?;
+        let mut visible = VisibleGrid::from_flat(width, height, visible_flat)?;
+        let flags = Flags::from_flat(width, height, flags_flat)?;
+        let nc = neighbor_cache(width, height)?;
+
+        let result = solver::play_move(&grid, &mines, &mut visible, &flags, &nc, x, y, reveal_all_mines);
+        visible_flat.copy_from_slice(&visible.cells);
+
+        let changes = js_sys::Array::new();
+        for &(cx, cy) in &result.revealed_cells {
+            let change = js_sys::Object::new();
+            set_field(&change, "x", &(cx as u32).into());
+            set_field(&change, "y", &(cy as u32).into());
+            set_field(&change, "value", &visible.get(cx, cy).into());
+            changes.push(&change);
+        }
+
+        let obj = js_sys::Object::new();
+        set_field(&obj, "changes", &changes.into());
+        set_field(&obj, "hitMine", &result.hit_mine.into());
+        set_field(&obj, "won", &result.won.into());
+        Ok(obj.into())
+    }
+
+    /// Once every mine is flagged, reveal all remaining hidden unflagged cells,
+    /// updating `visible_flat`. Flags are checked against `minesFlat` when
+    /// given, otherwise against the revealed clues. Returns the revealed
+    /// cells as `[x, y]` pairs; empty if the flags don't check out.
+    #[wasm_bindgen(js_name = "autoComplete")]
+    pub fn wasm_auto_complete(
+        width: usize,
+        height: usize,
+        grid_flat: &[i8],
+        visible_flat: &mut [i8],
+        flags_flat: &[u8],
+        bomb_count: usize,
+        mines_flat: Option<Vec<u8>>,
+    ) -> Result<js_sys::Array, JsValue> {
+        let grid = Grid::from_flat(width, height, grid_flat)?;
+        let mut visible = VisibleGrid::from_flat(width, height, visible_flat)?;
+        let flags = Flags::from_flat(width, height, flags_flat)?;
+        let mines = mines_flat.map(|cells| Mines::from_flat(width, height, &cells)).transpose()?;
+        let nc = neighbor_cache(width, height)?;
+
+        let revealed = solver::auto_complete(&grid, &mut visible, &flags, &nc, bomb_count, mines.as_ref());
+        visible_flat.copy_from_slice(&visible.cells);
+
+        let out = js_sys::Array::new();
+        for (x, y) in revealed {
+            let pair = js_sys::Array::new();
+            pair.push(&(x as u32).into());
+            pair.push(&(y as u32).into());
+            out.push(&pair);
+        }
+        Ok(out)
+    }
+
+    /// Unflag and reveal every flagged cell logic proves safe (forgiving modes),
+    /// updating `visible_flat` and `flags_flat`. Returns the corrected cells as
+    /// `[x, y]` pairs.
+    #[wasm_bindgen(js_name = "correctWrongFlags")]
+    pub fn wasm_correct_wrong_flags(
+        width: usize,
+        height: usize,
+        grid_flat: &[i8],
+        visible_flat: &mut [i8],
+        flags_flat: &mut [u8],
+        bomb_count: usize,
+    ) -> Result<js_sys::Array, JsValue> {
+        let grid = Grid::from_flat(width, height, grid_flat)?;
+        let mut visible = VisibleGrid::from_flat(width, height, visible_flat)?;
+        let mut flags = Flags::from_flat(width, height, flags_flat)?;
+        let nc = neighbor_cache(width, height)?;
+
+        let corrected = solver::correct_wrong_flags(&grid, &mut visible, &mut flags, &nc, bomb_count);
+        visible_flat.copy_from_slice(&visible.cells);
+        flags_flat.copy_from_slice(&flags.cells);
+
+        Ok(pairs_to_js(&corrected))
+    }
+
+    /// Reveal `(x, y)` breadth-first for a ripple animation, updating `visible_flat`.
+    /// Returns a flat `Uint32Array` of `[x, y, distance]` triples in reveal order,
+    /// where `distance` is the BFS ring the cell was opened in (0 = the click).
+    #[wasm_bindgen(js_name = "revealRippled")]
+    pub fn wasm_reveal_rippled(
+        width: usize,
+        height: usize,
+        grid_flat: &[i8],
+        visible_flat: &mut [i8],
+        flags_flat: &[u8],
+        x: usize,
+        y: usize,
+    ) -> Result<js_sys::Uint32Array, JsValue> {
+        let grid = Grid::from_flat(width, height, grid_flat)?;
+        let mut visible = VisibleGrid::from_flat(width, height, visible_flat)?;
+        let flags = Flags::from_flat(width, height, flags_flat)?;
+        let nc = neighbor_cache(width, height)?;
+
+        let ripple = solver::simulate_reveal_bfs(&grid, &mut visible, &flags, &nc, x, y);
+        visible_flat.copy_from_slice(&visible.cells);
+
+        let flat: Vec<u32> = ripple.iter().flat_map(|&(cx, cy, d)| [cx as u32, cy as u32, d]).collect();
+        let arr = js_sys::Uint32Array::new_with_length(flat.len() as u32);
+        arr.copy_from(&flat);
+        Ok(arr)
+    }
+
+    /// Chord every satisfied number until no more chords fire, updating `visible_flat`.
+    /// Returns `{ changes: [{ x, y, value }], hitMine, won }` like `playMove`;
+    /// a mine opened through a wrong flag appears in `changes` with the exploded value.
+    #[wasm_bindgen(js_name = "chordAll")]
+    pub fn wasm_chord_all(
+        width: usize,
+        height: usize,
+        grid_flat: &[i8],
+        mines_flat: &[u8],
+        visible_flat: &mut [i8],
+        flags_flat: &[u8],
+    ) -> Result<JsValue, JsValue> {
+        let grid = Grid::from_flat(width, height, grid_flat)?;
+        let mines = Mines::from_flat(width, height, mines_flat)?;
+        let mut visible = VisibleGrid::from_flat(width, height, visible_flat)?;
+        let flags = Flags::from_flat(width, height, flags_flat)?;
+        let nc = neighbor_cache(width, height)?;
+
+        let result = solver::chord_all(&grid, &mines, &mut visible, &flags, &nc);
+        visible_flat.copy_from_slice(&visible.cells);
+
+        let changes = js_sys::Array::new();
+        for &(cx, cy) in &result.revealed_cells {
+            let change = js_sys::Object::new();
+            set_field(&change, "x", &(cx as u32).into());
+            set_field(&change, "y", &(cy as u32).into());
+            set_field(&change, "value", &visible.get(cx, cy).into());
+            changes.push(&change);
+        }
+
+        let obj = js_sys::Object::new();
+        set_field(&obj, "changes", &changes.into());
+        set_field(&obj, "hitMine", &result.hit_mine.into());
+        set_field(&obj, "won", &result.won.into());
+        Ok(obj.into())
+    }
+
+    /// Survival probability of every guess in a recorded game, multiplied
+    /// together: lower means luckier, 1 means no guesses. `moves` is encoded as
+    /// for `validateReplay`.
+    #[wasm_bindgen(js_name = "replayLuck")]
+    pub fn wasm_replay_luck(
+        width: usize,
+        height: usize,
+        mines_flat: &[u8],
+        moves: &[u32],
+    ) -> Result<f32, JsValue> {
+        let mines = Mines::from_flat(width, height, mines_flat)?;
+        let nc = neighbor_cache(width, height)?;
+        Ok(solver::replay_luck(&mines, &nc, &parse_moves(moves)?))
+    }
+
+    /// Replay a recorded game from a fresh board.
+    /// `moves` is a flat array of `[x, y, kind]` triples, `kind` 0 = reveal, 1 = flag toggle.
+    /// Returns `{ valid, won, movesApplied, illegalMove: index | null, reason: string | null }`;
+    /// `valid` means no move was illegal.
+    #[wasm_bindgen(js_name = "validateReplay")]
+    pub fn wasm_validate_replay(
+        width: usize,
+        height: usize,
+        mines_flat: &[u8],
+        moves: &[u32],
+    ) -> Result<JsValue, JsValue> {
+        let mines = Mines::from_flat(width, height, mines_flat)?;
+        let nc = neighbor_cache(width, height)?;
+        let result = solver::validate_replay(&mines, &nc, &parse_moves(moves)?);
+        let obj = js_sys::Object::new();
+        set_field(&obj, "valid", &result.illegal.is_none().into());
+        set_field(&obj, "won", &result.won.into());
+        set_field(&obj, "movesApplied", &(result.moves_applied as u32).into());
+        match result.illegal {
+            Some((index, reason)) => {
+                let reason = match reason {
+                    solver::IllegalMove::OutOfBounds => "outOfBounds",
+                    solver::IllegalMove::AlreadyRevealed => "alreadyRevealed",
+                    solver::IllegalMove::RevealFlagged => "revealFlagged",
+                    solver::IllegalMove::FlagRevealed => "flagRevealed",
+                    solver::IllegalMove::HitMine => "hitMine",
+                    solver::IllegalMove::AfterWin => "afterWin",
+                };
+                set_field(&obj, "illegalMove", &(index as u32).into());
+                set_field(&obj, "reason", &reason.into());
+            }
+            None => {
+                set_field(&obj, "illegalMove", &JsValue::NULL);
+                set_field(&obj, "reason", &JsValue::NULL);
+            }
+        }
+        Ok(obj.into())
+    }
+
+    /// Minimum reveal clicks still needed to win (3BV over the hidden cells).
+    #[wasm_bindgen(js_name = "expectedRemainingClicks")]
+    pub fn wasm_expected_remaining_clicks(
+        width: usize,
+        height: usize,
+        grid_flat: &[i8],
+        mines_flat: &[u8],
+        visible_flat: &[i8],
+    ) -> Result<u32, JsValue> {
+        let grid = Grid::from_flat(width, height, grid_flat)?;
+        let mines = Mines::from_flat(width, height, mines_flat)?;
+        let visible = VisibleGrid::from_flat(width, height, visible_flat)?;
+        let nc = neighbor_cache(width, height)?;
+        Ok(solver::expected_remaining_clicks(&grid, &mines, &visible, &nc))
+    }
+
+    /// Non-mine cells still hidden; 0 exactly when the game is won. Cells set
+    /// in the optional `blocked` mask are not counted.
+    #[wasm_bindgen(js_name = "remainingSafeCount")]
+    pub fn wasm_remaining_safe_count(
+        width: usize,
+        height: usize,
+        visible_flat: &[i8],
+        mines_flat: &[u8],
+        blocked: Option<Vec<u8>>,
+    ) -> Result<u32, JsValue> {
+        let visible = VisibleGrid::from_flat(width, height, visible_flat)?;
+        let mines = Mines::from_flat(width, height, mines_flat)?;
+        let nc = blocked_cache(width, height, blocked)?;
+        Ok(solver::remaining_safe_count(&visible, &mines, &nc))
+    }
+
+    /// Fraction of safe cells revealed, in [0, 1]; exactly 1.0 on a win.
+    /// Cells set in the optional `blocked` mask are not counted.
+    #[wasm_bindgen(js_name = "progress")]
+    pub fn wasm_progress(
+        width: usize,
+        height: usize,
+        visible_flat: &[i8],
+        bomb_count: usize,
+        blocked: Option<Vec<u8>>,
+    ) -> Result<f32, JsValue> {
+        let visible = VisibleGrid::from_flat(width, height, visible_flat)?;
+        let nc = blocked_cache(width, height, blocked)?;
+        Ok(solver::progress(&visible, &nc, bomb_count))
+    }
+
+    /// Total of the revealed clue numbers (the "number golf" score).
+    #[wasm_bindgen(js_name = "visibleClueSum")]
+    pub fn wasm_visible_clue_sum(width: usize, height: usize, visible_flat: &[i8]) -> Result<u32, JsValue> {
+        let visible = VisibleGrid::from_flat(width, height, visible_flat)?;
+        Ok(solver::visible_clue_sum(&visible))
+    }
+
+    /// Total of the clue numbers over every safe cell of the solved board.
+    #[wasm_bindgen(js_name = "totalClueSum")]
+    pub fn wasm_total_clue_sum(
+        width: usize,
+        height: usize,
+        grid_flat: &[i8],
+        mines_flat: &[u8],
+    ) -> Result<u32, JsValue> {
+        let grid = Grid::from_flat(width, height, grid_flat)?;
+        let mines = Mines::from_flat(width, height, mines_flat)?;
+        Ok(board::total_clue_sum(&grid, &mines))
+    }
+
+    /// How many extra cells revealing the safe cell `(x, y)` makes determinable.
+    #[wasm_bindgen(js_name = "hintValue")]
+    #[allow(clippy::too_many_arguments)]
+    pub fn wasm_hint_value(
+        width: usize,
+        height: usize,
+        grid_flat: &[i8],
+        visible_flat: &[i8],
+        flags_flat: &[u8],
+        bomb_count: usize,
+        x: usize,
+        y: usize,
+    ) -> Result<u32, JsValue> {
+        let grid = Grid::from_flat(width, height, grid_flat)?;
+        let visible = VisibleGrid::from_flat(width, height, visible_flat)?;
+        let flags = Flags::from_flat(width, height, flags_flat)?;
+        let nc = neighbor_cache(width, height)?;
+        Ok(solver::hint_value(&grid, &visible, &flags, &nc, bomb_count, x, y))
+    }
+
+    /// Every action logic allows from the current position, in discovery order.
+    /// Returns an array of `{ action: "reveal" | "flag", x, y }` objects.
+    #[wasm_bindgen(js_name = "allDeterminateActions")]
+    pub fn wasm_all_determinate_actions(
+        width: usize,
+        height: usize,
+        grid_flat: &[i8],
+        visible_flat: &[i8],
+        flags_flat: &[u8],
+        bomb_count: usize,
+    ) -> Result<js_sys::Array, JsValue> {
+        let grid = Grid::from_flat(width, height, grid_flat)?;
+        let visible = VisibleGrid::from_flat(width, height, visible_flat)?;
+        let flags = Flags::from_flat(width, height, flags_flat)?;
+        let nc = neighbor_cache(width, height)?;
+
+        Ok(actions_to_js(&solver::all_determinate_actions(&grid, &visible, &flags, &nc, bomb_count)))
+    }
+
+    /// Both branches of guessing `(x, y)`, for a "what if" explorer:
+    /// `{ mine: { probability }, safe: { probability, valueDistribution, value?, revealed?, closure? } }`.
+    /// `valueDistribution` is a `Float32Array` of the chances the cell shows 0–8
+    /// if safe. On this board's layout, a safe cell also gets its `value`, the
+    /// `[x, y]` pairs the reveal opens and the `allDeterminateActions` that follow.
+    /// Returns `null` if the cell is open or flagged.
+    #[wasm_bindgen(js_name = "guessOutcomes")]
+    #[allow(clippy::too_many_arguments)]
+    pub fn wasm_guess_outcomes(
+        width: usize,
+        height: usize,
+        grid_flat: &[i8],
+        mines_flat: &[u8],
+        visible_flat: &[i8],
+        flags_flat: &[u8],
+        bomb_count: usize,
+        x: usize,
+        y: usize,
+    ) -> Result<JsValue, JsValue> {
+        let grid = Grid::from_flat(width, height, grid_flat)?;
+        let mines = Mines::from_flat(width, height, mines_flat)?;
+        let visible = VisibleGrid::from_flat(width, height, visible_flat)?;
+        let flags = Flags::from_flat(width, height, flags_flat)?;
+        let nc = neighbor_cache(width, height)?;
+
+        let Some(outcomes) = solver::guess_outcomes(&grid, &mines, &visible, &flags, &nc, bomb_count, x, y) else {
+            return Ok(JsValue::NULL);
+        };
+        let mine = js_sys::Object::new();
+        set_field(&mine, "probability", &outcomes.mine_probability.into());
+
+        let safe = js_sys::Object::new();
+        set_field(&safe, "probability", &(1.0 - outcomes.mine_probability).into());
+        let dist = js_sys::Float32Array::new_with_length(outcomes.value_distribution.len() as u32);
+        dist.copy_from(&outcomes.value_distribution);
+        set_field(&safe, "valueDistribution", &dist.into());
+        if let Some(value) = outcomes.value {
+            set_field(&safe, "value", &value.into());
+            set_field(&safe, "revealed", &pairs_to_js(&outcomes.revealed).into());
+            set_field(&safe, "closure", &actions_to_js(&outcomes.closure).into());
+        }
+
+        let obj = js_sys::Object::new();
+        set_field(&obj, "mine", &mine.into());
+        set_field(&obj, "safe", &safe.into());
+        Ok(obj.into())
+    }
+
+    /// Every hidden cell logic can prove is a mine, including ones the solve never
+    /// needed to flag, for a "complete flagging" assist. Returns `[x, y]` pairs;
+    /// cells already flagged are left out.
+    #[wasm_bindgen(js_name = "flagAllProvable")]
+    pub fn wasm_flag_all_provable(
+        width: usize,
+        height: usize,
+        grid_flat: &[i8],
+        visible_flat: &[i8],
+        flags_flat: &[u8],
+        bomb_count: usize,
+    ) -> Result<js_sys::Array, JsValue> {
+        let grid = Grid::from_flat(width, height, grid_flat)?;
+        let visible = VisibleGrid::from_flat(width, height, visible_flat)?;
+        let flags = Flags::from_flat(width, height, flags_flat)?;
+        let nc = neighbor_cache(width, height)?;
+
+        Ok(pairs_to_js(&solver::flag_all_provable(&grid, &visible, &flags, &nc, bomb_count)))
+    }
+
+    /// How many more correct flags would let the global count reveal the rest,
+    /// or `undefined` while some remaining mine still needs more logic.
+    #[wasm_bindgen(js_name = "flagsToAutocomplete")]
+    pub fn wasm_flags_to_autocomplete(
+        width: usize,
+        height: usize,
+        visible_flat: &[i8],
+        flags_flat: &[u8],
+        bomb_count: usize,
+    ) -> Result<Option<u32>, JsValue> {
+        let visible = VisibleGrid::from_flat(width, height, visible_flat)?;
+        let flags = Flags::from_flat(width, height, flags_flat)?;
+        let nc = neighbor_cache(width, height)?;
+        Ok(solver::flags_to_autocomplete(&visible, &flags, &nc, bomb_count))
+    }
+
+    /// `allDeterminateActions` as two bit-packed column-major masks, `{ reveals: Uint8Array, flags: Uint8Array }`,
+    /// with a bit set for each cell logic proves safe or a mine. Cell `i = x * height + y`
+    /// is bit `i % 8` (least significant first) of byte `i >> 3`.
+    #[wasm_bindgen(js_name = "determinateActionMasks")]
+    pub fn wasm_determinate_action_masks(
+        width: usize,
+        height: usize,
+        grid_flat: &[i8],
+        visible_flat: &[i8],
+        flags_flat: &[u8],
+        bomb_count: usize,
+    ) -> Result<JsValue, JsValue> {
+        let grid = Grid::from_flat(width, height, grid_flat)?;
+        let visible = VisibleGrid::from_flat(width, height, visible_flat)?;
+        let flags = Flags::from_flat(width, height, flags_flat)?;
+        let nc = neighbor_cache(width, height)?;
+
+        let (reveals, flag_mask) = solver::determinate_action_masks(&grid, &visible, &flags, &nc, bomb_count);
+        let reveals_arr = js_sys::Uint8Array::new_with_length(reveals.len() as u32);
+        reveals_arr.copy_from(&reveals);
+        let flags_arr = js_sys::Uint8Array::new_with_length(flag_mask.len() as u32);
+        flags_arr.copy_from(&flag_mask);
+
+        let obj = js_sys::Object::new();
+        set_field(&obj, "reveals", &reveals_arr.into());
+        set_field(&obj, "flags", &flags_arr.into());
+        Ok(obj.into())
+    }
+
+    /// Number of complete mine layouts consistent with the position, capped at `cap`;
+    /// 1 means the position is uniquely determined. Exact below 2^53.
+    #[wasm_bindgen(js_name = "solutionCount")]
+    pub fn wasm_solution_count(
+        width: usize,
+        height: usize,
+        visible_flat: &[i8],
+        flags_flat: &[u8],
+        bomb_count: usize,
+        cap: f64,
+    ) -> Result<f64, JsValue> {
+        let visible = VisibleGrid::from_flat(width, height, visible_flat)?;
+        let flags = Flags::from_flat(width, height, flags_flat)?;
+        let nc = neighbor_cache(width, height)?;
+        Ok(solver::solution_count(&visible, &flags, &nc, bomb_count, cap.max(0.0) as u64) as f64)
+    }
+
+    /// Pack `[{ action: "reveal" | "flag", x, y }]` (e.g. from `allDeterminateActions`)
+    /// into a compact `Uint8Array` for sending between clients.
+    #[wasm_bindgen(js_name = "encodeActions")]
+    pub fn wasm_encode_actions(actions: js_sys::Array) -> Result<js_sys::Uint8Array, JsValue> {
+        let bytes = solver::encode_actions(&actions_from_js(&actions)?);
+        let arr = js_sys::Uint8Array::new_with_length(bytes.len() as u32);
+        arr.copy_from(&bytes);
+        Ok(arr)
+    }
+
+    /// Decode bytes from `encodeActions` back to `[{ action, x, y }]`; throws if they are corrupt.
+    #[wasm_bindgen(js_name = "decodeActions")]
+    pub fn wasm_decode_actions(bytes: &[u8]) -> Result<js_sys::Array, JsValue> {
+        let actions = solver::decode_actions(bytes)
+            .map_err(|e| JsValue::from(js_sys::Error::new(&e.to_string())))?;
+        Ok(actions_to_js(&actions))
+    }
+
+    /// Hidden cells whose determination could depend on the clue at `(x, y)`,
+    /// as `[x, y]` pairs, for coloring a number's reach. Empty for anything but a revealed number.
+    #[wasm_bindgen(js_name = "influenceRegion")]
+    pub fn wasm_influence_region(
+        width: usize,
+        height: usize,
+        visible_flat: &[i8],
+        flags_flat: &[u8],
+        x: usize,
+        y: usize,
+    ) -> Result<js_sys::Array, JsValue> {
+        let visible = VisibleGrid::from_flat(width, height, visible_flat)?;
+        let flags = Flags::from_flat(width, height, flags_flat)?;
+        let nc = neighbor_cache(width, height)?;
+
+        Ok(pairs_to_js(&solver::influence_region(&visible, &flags, &nc, x, y)))
+    }
+
+    /// What logic concludes about every current frontier cell, for debug overlays.
+    /// Returns an array of `{ x, y, verdict: "safe" | "mine" | "unknown" }` objects.
+    #[wasm_bindgen(js_name = "frontierVerdicts")]
+    pub fn wasm_frontier_verdicts(
+        width: usize,
+        height: usize,
+        grid_flat: &[i8],
+        visible_flat: &[i8],
+        flags_flat: &[u8],
+        bomb_count: usize,
+    ) -> Result<js_sys::Array, JsValue> {
+        let grid = Grid::from_flat(width, height, grid_flat)?;
+        let visible = VisibleGrid::from_flat(width, height, visible_flat)?;
+        let flags = Flags::from_flat(width, height, flags_flat)?;
+        let nc = neighbor_cache(width, height)?;
+
+        let arr = js_sys::Array::new();
+        for (x, y, verdict) in solver::frontier_verdicts(&grid, &visible, &flags, &nc, bomb_count) {
+            let verdict = match verdict {
+                solver::Verdict::Safe => "safe",
+                solver::Verdict::Mine => "mine",
+                solver::Verdict::Unknown => "unknown",
+            };
+            let obj = js_sys::Object::new();
+            set_field(&obj, "x", &(x as u32).into());
+            set_field(&obj, "y", &(y as u32).into());
+            set_field(&obj, "verdict", &verdict.into());
+            arr.push(&obj);
+        }
+        Ok(arr)
+    }
+
+    /// The first provable action, using extra per-cell facts (e.g. x-ray reveals).
+    /// `known_flat` holds 1 for a known mine, 0 for a known-safe cell and -1 for no fact;
+    /// omit it when there are none. Safe reveals are preferred; `action` is
+    /// `"flag"` only when no reveal can be proven.
+    /// Returns `{ action: "reveal" | "flag", x, y }` or `null`.
+    #[wasm_bindgen(js_name = "getLogicalHintWithKnowledge")]
+    #[allow(clippy::too_many_arguments)]
+    pub fn wasm_get_logical_hint_with_knowledge(
+        width: usize,
+        height: usize,
+        grid_flat: &[i8],
+        visible_flat: &[i8],
+        flags_flat: &[u8],
+        known_flat: Option<Vec<i8>>,
+        bomb_count: usize,
+    ) -> Result<JsValue, JsValue> {
+        let grid = Grid::from_flat(width, height, grid_flat)?;
+        let visible = VisibleGrid::from_flat(width, height, visible_flat)?;
+        let flags = Flags::from_flat(width, height, flags_flat)?;
+        let known = known_flat.map(|cells| VisibleGrid::from_flat(width, height, &cells)).transpose()?;
+        let nc = neighbor_cache(width, height)?;
+
+        Ok(match solver::get_logical_hint(&grid, &visible, &flags, &nc, bomb_count, known.as_ref()) {
+            Some(action) => action_to_js(action).into(),
+            None => JsValue::NULL,
+        })
+    }
+
+    /// The deductions that prove `(x, y)` safe or a mine, for a teaching mode.
+    /// Returns `null` if logic cannot determine the cell, otherwise an array of
+    /// `{ strategy, flagged: [[x, y], ...], revealed: [[x, y], ...] }` steps in
+    /// solve order, where `strategy` is the level 1–6 and only cells the target
+    /// depends on are listed. An already-open cell gives an empty array.
+    #[wasm_bindgen(js_name = "explainCell")]
+    #[allow(clippy::too_many_arguments)]
+    pub fn wasm_explain_cell(
+        width: usize,
+        height: usize,
+        grid_flat: &[i8],
+        visible_flat: &[i8],
+        flags_flat: &[u8],
+        bomb_count: usize,
+        x: usize,
+        y: usize,
+    ) -> Result<JsValue, JsValue> {
+        let grid = Grid::from_flat(width, height, grid_flat)?;
+        let visible = VisibleGrid::from_flat(width, height, visible_flat)?;
+        let flags = Flags::from_flat(width, height, flags_flat)?;
+        let nc = neighbor_cache(width, height)?;
+
+        let Some(steps) = solver::explain_cell(&grid, &visible, &flags, &nc, bomb_count, x, y) else {
+            return Ok(JsValue::NULL);
+        };
+        Ok(solve_steps_to_js(&steps).into())
+    }
+
+    /// The revealed clues each individually needed to determine `(x, y)`, as
+    /// `[x, y]` pairs: the numbers the player had to notice. Empty if logic
+    /// cannot determine the cell or it is already open.
+    #[wasm_bindgen(js_name = "supportingClues")]
+    #[allow(clippy::too_many_arguments)]
+    pub fn wasm_supporting_clues(
+        width: usize,
+        height: usize,
+        grid_flat: &[i8],
+        visible_flat: &[i8],
+        flags_flat: &[u8],
+        bomb_count: usize,
+        x: usize,
+        y: usize,
+    ) -> Result<js_sys::Array, JsValue> {
+        let grid = Grid::from_flat(width, height, grid_flat)?;
+        let visible = VisibleGrid::from_flat(width, height, visible_flat)?;
+        let flags = Flags::from_flat(width, height, flags_flat)?;
+        let nc = neighbor_cache(width, height)?;
+
+        Ok(pairs_to_js(&solver::supporting_clues(&grid, &visible, &flags, &nc, bomb_count, x, y)))
+    }
+
+    /// A hint about the next logically safe reveal at strength `level`:
+    /// 0 = `{ level: "region", x0, y0, x1, y1 }` (the box of the frontier region
+    /// holding it), 1 = `{ level: "cell", x, y }`, 2 = `{ level: "explanation", x, y, steps }`
+    /// with `steps` as in `explainCell`. Every level names the same move. Returns
+    /// `null` if logic alone reveals nothing.
+    #[wasm_bindgen(js_name = "gradedHint")]
+    pub fn wasm_graded_hint(
+        width: usize,
+        height: usize,
+        grid_flat: &[i8],
+        visible_flat: &[i8],
+        flags_flat: &[u8],
+        bomb_count: usize,
+        level: u8,
+    ) -> Result<JsValue, JsValue> {
+        let level = match level {
+            0 => solver::HintLevel::Region,
+            1 => solver::HintLevel::Cell,
+            2 => solver::HintLevel::Explanation,
+            _ => return Err(js_sys::Error::new("level must be 0 (region), 1 (cell) or 2 (explanation)").into()),
+        };
+        let grid = Grid::from_flat(width, height, grid_flat)?;
+        let visible = VisibleGrid::from_flat(width, height, visible_flat)?;
+        let flags = Flags::from_flat(width, height, flags_flat)?;
+        let nc = neighbor_cache(width, height)?;
+
+        let Some(hint) = solver::graded_hint(&grid, &visible, &flags, &nc, bomb_count, level) else {
+            return Ok(JsValue::NULL);
+        };
+        let obj = js_sys::Object::new();
+        match hint {
+            solver::GradedHint::Region { x0, y0, x1, y1 } => {
+                set_field(&obj, "level", &"region".into());
+                set_field(&obj, "x0", &(x0 as u32).into());
+                set_field(&obj, "y0", &(y0 as u32).into());
+                set_field(&obj, "x1", &(x1 as u32).into());
+                set_field(&obj, "y1", &(y1 as u32).into());
+            }
+            solver::GradedHint::Cell { x, y } => {
+                set_field(&obj, "level", &"cell".into());
+                set_field(&obj, "x", &(x as u32).into());
+                set_field(&obj, "y", &(y as u32).into());
+            }
+            solver::GradedHint::Explanation { x, y, steps } => {
+                set_field(&obj, "level", &"explanation".into());
+                set_field(&obj, "x", &(x as u32).into());
+                set_field(&obj, "y", &(y as u32).into());
+                set_field(&obj, "steps", &solve_steps_to_js(&steps).into());
+            }
+        }
+        Ok(obj.into())
+    }
+
+    /// The position as a constraint problem for an external SAT/ILP solver.
+    /// Returns `{ cells: [[x, y], ...], constraints: [{ vars, target }], mineBudget }`:
+    /// variable `i` (1 = mine) is hidden cell `cells[i]`, each clue needs its `vars`
+    /// to sum to `target`, and all variables together sum to `mineBudget`.
+    #[wasm_bindgen(js_name = "exportConstraints")]
+    pub fn wasm_export_constraints(
+        width: usize,
+        height: usize,
+        visible_flat: &[i8],
+        flags_flat: &[u8],
+        bomb_count: usize,
+    ) -> Result<JsValue, JsValue> {
+        let visible = VisibleGrid::from_flat(width, height, visible_flat)?;
+        let flags = Flags::from_flat(width, height, flags_flat)?;
+        let nc = neighbor_cache(width, height)?;
+        let system = solver::export_constraints(&visible, &flags, &nc, bomb_count);
+
+        let cells = pairs_to_js(&system.cells);
+
+        let constraints = js_sys::Array::new();
+        for constraint in &system.constraints {
+            let vars = js_sys::Array::new();
+            for &v in &constraint.vars {
+                vars.push(&(v as u32).into());
+            }
+            let obj = js_sys::Object::new();
+            set_field(&obj, "vars", &vars.into());
+            set_field(&obj, "target", &constraint.target.into());
+            constraints.push(&obj);
+        }
+
+        let obj = js_sys::Object::new();
+        set_field(&obj, "cells", &cells.into());
+        set_field(&obj, "constraints", &constraints.into());
+        set_field(&obj, "mineBudget", &system.mine_budget.into());
+        Ok(obj.into())
+    }
+
+    /// The linear systems the Gaussian strategy builds, before elimination.
+    /// Returns `{ components: [{ cells: [[x, y], ...], equations: [{ vars, target }] }] }`,
+    /// where `vars` index into that component's `cells`.
+    #[wasm_bindgen(js_name = "exportGaussianSystem")]
+    pub fn wasm_export_gaussian_system(
+        width: usize,
+        height: usize,
+        visible_flat: &[i8],
+        flags_flat: &[u8],
+    ) -> Result<JsValue, JsValue> {
+        let visible = VisibleGrid::from_flat(width, height, visible_flat)?;
+        let flags = Flags::from_flat(width, height, flags_flat)?;
+        let nc = neighbor_cache(width, height)?;
+        let frontier = solver::get_frontier(&visible, &flags, &nc);
+        let dump = gaussian::export_system(&visible, &flags, &frontier, &nc);
+
+        let components = js_sys::Array::new();
+        for component in &dump.components {
+            let cells = js_sys::Array::new();
+            for &(x, y) in &component.cells {
+                let pair = js_sys::Array::new();
+                pair.push(&(x as u32).into());
+                pair.push(&(y as u32).into());
+                cells.push(&pair);
+            }
+
+            let equations = js_sys::Array::new();
+            for eq in &component.equations {
+                let vars = js_sys::Array::new();
+                for &v in &eq.vars {
+                    vars.push(&(v as u32).into());
+                }
+                let obj = js_sys::Object::new();
+                set_field(&obj, "vars", &vars.into());
+                set_field(&obj, "target", &eq.target.into());
+                equations.push(&obj);
+            }
+
+            let obj = js_sys::Object::new();
+            set_field(&obj, "cells", &cells.into());
+            set_field(&obj, "equations", &equations.into());
+            components.push(&obj);
+        }
+
+        let obj = js_sys::Object::new();
+        set_field(&obj, "components", &components.into());
+        Ok(obj.into())
+    }
+
+    /// Cells that are mines or safe in every solution, using the global mine count.
+    /// Returns `{ mines, safe }` as arrays of `[x, y]` pairs.
+    #[wasm_bindgen(js_name = "globallyDefiniteCells")]
+    pub fn wasm_globally_definite_cells(
+        width: usize,
+        height: usize,
+        visible_flat: &[i8],
+        flags_flat: &[u8],
+        bomb_count: usize,
+    ) -> Result<JsValue, JsValue> {
+        let visible = VisibleGrid::from_flat(width, height, visible_flat)?;
+        let flags = Flags::from_flat(width, height, flags_flat)?;
+        let nc = neighbor_cache(width, height)?;
+        let (mines, safe) = solver::globally_definite_cells(&visible, &flags, &nc, bomb_count);
+
+        let obj = js_sys::Object::new();
+        set_field(&obj, "mines", &pairs_to_js(&mines).into());
+        set_field(&obj, "safe", &pairs_to_js(&safe).into());
+        Ok(obj.into())
+    }
+
+    /// Whether revealing `(x, y)` is provably safe from the visible clues, flags
+    /// and mine count alone. Strict no-guess mode checks this before accepting a click.
+    #[wasm_bindgen(js_name = "isMoveForcedSafe")]
+    pub fn wasm_is_move_forced_safe(
+        width: usize,
+        height: usize,
+        visible_flat: &[i8],
+        flags_flat: &[u8],
+        bomb_count: usize,
+        x: usize,
+        y: usize,
+    ) -> Result<bool, JsValue> {
+        let visible = VisibleGrid::from_flat(width, height, visible_flat)?;
+        let flags = Flags::from_flat(width, height, flags_flat)?;
+        let nc = neighbor_cache(width, height)?;
+        Ok(solver::is_move_forced_safe(&visible, &flags, &nc, bomb_count, x, y))
+    }
+
+    /// Provably safe hidden cells the player has not revealed, as `[x, y]`
+    /// pairs in column-major order.
+    #[wasm_bindgen(js_name = "missedSafeCells")]
+    pub fn wasm_missed_safe_cells(
+        width: usize,
+        height: usize,
+        grid_flat: &[i8],
+        visible_flat: &[i8],
+        flags_flat: &[u8],
+        bomb_count: usize,
+    ) -> Result<js_sys::Array, JsValue> {
+        let grid = Grid::from_flat(width, height, grid_flat)?;
+        let visible = VisibleGrid::from_flat(width, height, visible_flat)?;
+        let flags = Flags::from_flat(width, height, flags_flat)?;
+        let nc = neighbor_cache(width, height)?;
+
+        Ok(pairs_to_js(&solver::missed_safe_cells(&grid, &visible, &flags, &nc, bomb_count)))
+    }
+
+    /// Revealed clues implied by the clues that remain, safe to dim together.
+    /// Returns an array of `[x, y]` pairs in column-major order.
+    #[wasm_bindgen(js_name = "redundantClues")]
+    pub fn wasm_redundant_clues(
+        width: usize,
+        height: usize,
+        visible_flat: &[i8],
+        flags_flat: &[u8],
+    ) -> Result<js_sys::Array, JsValue> {
+        let visible = VisibleGrid::from_flat(width, height, visible_flat)?;
+        let flags = Flags::from_flat(width, height, flags_flat)?;
+        let nc = neighbor_cache(width, height)?;
+        Ok(pairs_to_js(&solver::redundant_clues(&visible, &flags, &nc)))
+    }
+
+    /// Find 1-2-1 and 1-2-2-1 wall patterns.
+    /// Returns an array of `{ pattern: "1-2-1" | "1-2-2-1", actions: [{ action, x, y }] }`.
+    #[wasm_bindgen(js_name = "detectKnownPatterns")]
+    pub fn wasm_detect_known_patterns(
+        width: usize,
+        height: usize,
+        visible_flat: &[i8],
+        flags_flat: &[u8],
+    ) -> Result<js_sys::Array, JsValue> {
+        let visible = VisibleGrid::from_flat(width, height, visible_flat)?;
+        let flags = Flags::from_flat(width, height, flags_flat)?;
+        let nc = neighbor_cache(width, height)?;
+
+        let arr = js_sys::Array::new();
+        for (kind, actions) in solver::detect_known_patterns(&visible, &flags, &nc) {
+            let obj = js_sys::Object::new();
+            set_field(&obj, "pattern", &kind.name().into());
+            set_field(&obj, "actions", &actions_to_js(&actions).into());
+            arr.push(&obj);
+        }
+        Ok(arr)
+    }
+
+    /// Exact deduction over the whole remaining board (at most 24 hidden cells,
+    /// unless a single remaining mine is pinned by the clues). Returns `{ mines, safe }` as arrays of `[x, y]` pairs, or `null` if the
+    /// board has too many hidden cells or no consistent placement.
+    #[wasm_bindgen(js_name = "solveEndgameExact")]
+    pub fn wasm_solve_endgame_exact(
+        width: usize,
+        height: usize,
+        visible_flat: &[i8],
+        flags_flat: &[u8],
+        bomb_count: usize,
+    ) -> Result<JsValue, JsValue> {
+        let visible = VisibleGrid::from_flat(width, height, visible_flat)?;
+        let flags = Flags::from_flat(width, height, flags_flat)?;
+        let nc = neighbor_cache(width, height)?;
+
+        let Some(result) = solver::solve_endgame_exact(&visible, &flags, &nc, bomb_count) else {
+            return Ok(JsValue::NULL);
+        };
+        let obj = js_sys::Object::new();
+        set_field(&obj, "mines", &pairs_to_js(&result.mines).into());
+        set_field(&obj, "safe", &pairs_to_js(&result.safe).into());
+        Ok(obj.into())
+    }
+
+    /// With exactly one mine left unflagged, the cell the clues pin it to as
+    /// `[x, y]`, or `null` if it is ambiguous. Every other hidden cell is safe.
+    #[wasm_bindgen(js_name = "solveLastMine")]
+    pub fn wasm_solve_last_mine(
+        width: usize,
+        height: usize,
+        visible_flat: &[i8],
+        flags_flat: &[u8],
+        bomb_count: usize,
+    ) -> Result<JsValue, JsValue> {
+        let visible = VisibleGrid::from_flat(width, height, visible_flat)?;
+        let flags = Flags::from_flat(width, height, flags_flat)?;
+        let nc = neighbor_cache(width, height)?;
+        Ok(match solver::solve_last_mine(&visible, &flags, &nc, bomb_count) {
+            Some((x, y)) => js_sys::Array::of2(&(x as u32).into(), &(y as u32).into()).into(),
+            None => JsValue::NULL,
+        })
+    }
+
+    /// Whether basic or subset logic can determine at least one cell right now.
+    /// Cheap enough to gate the assist button; `false` means a guess may be needed.
+    #[wasm_bindgen(js_name = "hasDeterminateMove")]
+    pub fn wasm_has_determinate_move(
+        width: usize,
+        height: usize,
+        grid_flat: &[i8],
+        visible_flat: &[i8],
+        flags_flat: &[u8],
+    ) -> Result<bool, JsValue> {
+        let grid = Grid::from_flat(width, height, grid_flat)?;
+        let visible = VisibleGrid::from_flat(width, height, visible_flat)?;
+        let flags = Flags::from_flat(width, height, flags_flat)?;
+        let nc = neighbor_cache(width, height)?;
+        Ok(solver::has_determinate_move(&grid, &visible, &flags, &nc))
+    }
+
+    /// Actions newly determinable after the cells in `changed_flat`
+    /// (`[x0, y0, x1, y1, ...]`) changed, checking only constraints near them.
+    /// Returns an array of `{ action: "reveal" | "flag", x, y }`.
+    #[wasm_bindgen(js_name = "incrementalSolve")]
+    #[allow(clippy::too_many_arguments)]
+    pub fn wasm_incremental_solve(
+        width: usize,
+        height: usize,
+        grid_flat: &[i8],
+        visible_flat: &[i8],
+        flags_flat: &[u8],
+        bomb_count: usize,
+        changed_flat: &[u32],
+    ) -> Result<js_sys::Array, JsValue> {
+        let grid = Grid::from_flat(width, height, grid_flat)?;
+        let visible = VisibleGrid::from_flat(width, height, visible_flat)?;
+        let flags = Flags::from_flat(width, height, flags_flat)?;
+        let nc = neighbor_cache(width, height)?;
+        let changed: Vec<(usize, usize)> = changed_flat
+            .chunks_exact(2)
+            .map(|p| (p[0] as usize, p[1] as usize))
+            .collect();
+
+        Ok(actions_to_js(&solver::incremental_solve(&grid, &visible, &flags, &nc, bomb_count, &changed)))
+    }
+
+    /// Categorize the neighbors of `(x, y)`.
+    /// Returns `{ hidden, flagged, revealed, hiddenCount, flaggedCount, revealedCount }`
+    /// where each list is an array of `[x, y]` pairs and `revealed` holds number cells only.
+    #[wasm_bindgen(js_name = "neighborSummary")]
+    pub fn wasm_neighbor_summary(
+        width: usize,
+        height: usize,
+        visible_flat: &[i8],
+        flags_flat: &[u8],
+        x: usize,
+        y: usize,
+    ) -> Result<JsValue, JsValue> {
+        let visible = VisibleGrid::from_flat(width, height, visible_flat)?;
+        let flags = Flags::from_flat(width, height, flags_flat)?;
+        let nc = neighbor_cache(width, height)?;
+        let summary = solver::neighbor_summary(&visible, &flags, &nc, x, y);
+
+        let obj = js_sys::Object::new();
+        set_field(&obj, "hidden", &pairs_to_js(&summary.hidden).into());
+        set_field(&obj, "flagged", &pairs_to_js(&summary.flagged).into());
+        set_field(&obj, "revealed", &pairs_to_js(&summary.revealed).into());
+        set_field(&obj, "hiddenCount", &(summary.hidden.len() as u32).into());
+        set_field(&obj, "flaggedCount", &(summary.flagged.len() as u32).into());
+        set_field(&obj, "revealedCount", &(summary.revealed.len() as u32).into());
+        Ok(obj.into())
+    }
+
+    /// Get a hint (best safe cell to reveal).
+    /// With `preferCascadeOnly`, only safe zero cells are suggested (default false).
+    /// With `frontierOnly`, island cells are never suggested (default false).
+    /// Returns JS object `{ x, y, score }` or `null`.
+    #[wasm_bindgen(js_name = "getHint")]
+    #[allow(clippy::too_many_arguments)]
+    pub fn wasm_get_hint(
+        width: usize,
+        height: usize,
+        grid_flat: &[i8],
+        visible_flat: &[i8],
+        flags_flat: &[u8],
+        mines_flat: &[u8],
+        prefer_cascade_only: Option<bool>,
+        frontier_only: Option<bool>,
+    ) -> Result<JsValue, JsValue> {
+        let grid = Grid::from_flat(width, height, grid_flat)?;
+        let visible = VisibleGrid::from_flat(width, height, visible_flat)?;
+        let flags = Flags::from_flat(width, height, flags_flat)?;
+        let mines = Mines::from_flat(width, height, mines_flat)?;
+        let nc = neighbor_cache(width, height)?;
+
+        Ok(match solver::get_hint(
+            &grid, &visible, &flags, &mines, &nc,
+            prefer_cascade_only.unwrap_or(false), frontier_only.unwrap_or(false),
+        ) {
+            Some(hint) => {
+                let obj = js_sys::Object::new();
+                set_field(&obj, "x", &(hint.x as u32).into());
+                set_field(&obj, "y", &(hint.y as u32).into());
+                set_field(&obj, "score", &hint.score.into());
+                obj.into()
+            }
+            None => JsValue::NULL,
+        })
+    }
+
+    /// `getHint` that picks uniformly among the cells tied for the best score;
+    /// the same `seed` always picks the same cell.
+    /// Returns JS object `{ x, y, score }` or `null`.
+    #[wasm_bindgen(js_name = "getHintRandom")]
+    pub fn wasm_get_hint_random(
+        width: usize,
+        height: usize,
+        grid_flat: &[i8],
+        visible_flat: &[i8],
+        flags_flat: &[u8],
+        mines_flat: &[u8],
+        seed: u32,
+    ) -> Result<JsValue, JsValue> {
+        let grid = Grid::from_flat(width, height, grid_flat)?;
+        let visible = VisibleGrid::from_flat(width, height, visible_flat)?;
+        let flags = Flags::from_flat(width, height, flags_flat)?;
+        let mines = Mines::from_flat(width, height, mines_flat)?;
+        let nc = neighbor_cache(width, height)?;
+        let mut rng = crate::rng::WasmRng::from_seed(seed as u64);
+
+        Ok(match solver::get_hint_random(&grid, &visible, &flags, &mines, &nc, &mut rng) {
+            Some(hint) => {
+                let obj = js_sys::Object::new();
+                set_field(&obj, "x", &(hint.x as u32).into());
+                set_field(&obj, "y", &(hint.y as u32).into());
+                set_field(&obj, "score", &hint.score.into());
+                obj.into()
+            }
+            None => JsValue::NULL,
+        })
+    }
+
+    /// Get a hint for each of N independent boards in one call.
+    /// The boards' flat arrays are concatenated in order. `widths`/`heights` hold
+    /// one entry per board, or a single shared dimension for every board.
+    /// Returns an array with a `{ x, y, score }` object or `null` per board;
+    /// throws if the array lengths do not match the dimensions.
+    #[wasm_bindgen(js_name = "getHintBatch")]
+    pub fn wasm_get_hint_batch(
+        widths: &[u32],
+        heights: &[u32],
+        grid_flat: &[i8],
+        visible_flat: &[i8],
+        flags_flat: &[u8],
+        mines_flat: &[u8],
+    ) -> Result<js_sys::Array, JsValue> {
+        if widths.len() != heights.len() || widths.is_empty() {
+            return Err(js_sys::Error::new("widths and heights must be non-empty and the same length").into());
+        }
+        let mut dims: Vec<(usize, usize)> = widths.iter().zip(heights)
+            .map(|(&w, &h)| (w as usize, h as usize))
+            .collect();
+        for &(w, h) in &dims {
+            if board_cell_count(w, h).is_none() {
+                return Err(board_too_large(w, h));
+            }
+        }
+        if dims.len() == 1 {
+            let (w, h) = dims[0];
+            let cells = w * h;
+            let count = grid_flat.len().checked_div(cells).unwrap_or(0);
+            dims = vec![(w, h); count];
+        }
+
+        let total: usize = dims.iter().map(|&(w, h)| w * h).sum();
+        if [grid_flat.len(), visible_flat.len(), flags_flat.len(), mines_flat.len()].iter().any(|&len| len != total) {
+            return Err(js_sys::Error::new("Board arrays do not match the given dimensions").into());
+        }
+
+        let mut boards = Vec::with_capacity(dims.len());
+        let mut offset = 0;
+        for &(width, height) in &dims {
+            let range = offset..offset + width * height;
+            offset = range.end;
+            boards.push((
+                Grid::from_flat(width, height, &grid_flat[range.clone()])?,
+                VisibleGrid::from_flat(width, height, &visible_flat[range.clone()])?,
+                Flags::from_flat(width, height, &flags_flat[range.clone()])?,
+                Mines::from_flat(width, height, &mines_flat[range])?,
+            ));
+        }
+        let refs: Vec<_> = boards.iter().map(|(g, v, f, m)| (g, v, f, m)).collect();
+
+        let arr = js_sys::Array::new();
+        for hint in solver::get_hint_batch(&refs) {
+            match hint {
+                Some(hint) => {
+                    let obj = js_sys::Object::new();
+                    set_field(&obj, "x", &(hint.x as u32).into());
+                    set_field(&obj, "y", &(hint.y as u32).into());
+                    set_field(&obj, "score", &hint.score.into());
+                    arr.push(&obj);
+                }
+                None => { arr.push(&JsValue::NULL); }
+            }
+        }
+        Ok(arr)
+    }
+
+    /// Compute the exact mine probability of every cell.
+    /// Returns a `Float32Array` (revealed = 0, flagged = 1, hidden = probability).
+    /// Pass `respectUserFlags = false` to treat flags as hidden cells (default true).
+    /// Pass the generator's `safeX`, `safeY`, `safeRadius` to keep its hidden
+    /// safe-zone cells at 0 and out of the off-frontier density.
+    #[wasm_bindgen(js_name = "exactMineProbabilities")]
+    #[allow(clippy::too_many_arguments)]
+    pub fn wasm_exact_mine_probabilities(
+        width: usize,
+        height: usize,
+        visible_flat: &[i8],
+        flags_flat: &[u8],
+        bomb_count: usize,
+        respect_user_flags: Option<bool>,
+        safe_x: Option<usize>,
+        safe_y: Option<usize>,
+        safe_radius: Option<usize>,
+    ) -> Result<js_sys::Float32Array, JsValue> {
+        let visible = VisibleGrid::from_flat(width, height, visible_flat)?;
+        let flags = Flags::from_flat(width, height, flags_flat)?;
+        let nc = neighbor_cache(width, height)?;
+        let zone = safe_x.zip(safe_y).map(|(x, y)| SafeZone::new(x, y, safe_radius.unwrap_or(1)));
+        let probs = probability::exact_mine_probabilities(
+            &visible, &flags, &nc, bomb_count, respect_user_flags.unwrap_or(true), zone.as_ref(), None,
+        );
+
+        let arr = js_sys::Float32Array::new_with_length(probs.len() as u32);
+        arr.copy_from(&probs);
+        Ok(arr)
+    }
+
+    /// Exact number of mine layouts consistent with the position, as a decimal
+    /// string (the count can exceed any JS number). Needs the `bigint` feature.
+    #[cfg(feature = "bigint")]
+    #[wasm_bindgen(js_name = "countConsistentBoardsExact")]
+    pub fn wasm_count_consistent_boards_exact(
+        width: usize,
+        height: usize,
+        visible_flat: &[i8],
+        flags_flat: &[u8],
+        bomb_count: usize,
+    ) -> Result<String, JsValue> {
+        let visible = VisibleGrid::from_flat(width, height, visible_flat)?;
+        let flags = Flags::from_flat(width, height, flags_flat)?;
+        let nc = neighbor_cache(width, height)?;
+        Ok(solver::count_consistent_boards_exact(&visible, &flags, &nc, bomb_count).to_string())
+    }
+
+    /// Rank every hidden, unflagged cell by mine probability, safest first.
+    /// Returns a flat `Float32Array` of `[x, y, prob]` triples.
+    #[wasm_bindgen(js_name = "rankAllHidden")]
+    pub fn wasm_rank_all_hidden(
+        width: usize,
+        height: usize,
+        visible_flat: &[i8],
+        flags_flat: &[u8],
+        bomb_count: usize,
+    ) -> Result<js_sys::Float32Array, JsValue> {
+        let visible = VisibleGrid::from_flat(width, height, visible_flat)?;
+        let flags = Flags::from_flat(width, height, flags_flat)?;
+        let nc = neighbor_cache(width, height)?;
+        let ranking = solver::rank_all_hidden(&visible, &flags, &nc, bomb_count);
+
+        let flat: Vec<f32> = ranking.iter().flat_map(|&(x, y, p)| [x as f32, y as f32, p]).collect();
+        let arr = js_sys::Float32Array::new_with_length(flat.len() as u32);
+        arr.copy_from(&flat);
+        Ok(arr)
+    }
+
+    /// Hidden cells at least `minProbability` likely to be mines, likeliest
+    /// first, as a flat `Float32Array` of `[x, y, prob]` triples.
+    #[wasm_bindgen(js_name = "mineCandidates")]
+    pub fn wasm_mine_candidates(
+        width: usize,
+        height: usize,
+        visible_flat: &[i8],
+        flags_flat: &[u8],
+        bomb_count: usize,
+        min_probability: f32,
+    ) -> Result<js_sys::Float32Array, JsValue> {
+        let visible = VisibleGrid::from_flat(width, height, visible_flat)?;
+        let flags = Flags::from_flat(width, height, flags_flat)?;
+        let nc = neighbor_cache(width, height)?;
+        let candidates = solver::mine_candidates(&visible, &flags, &nc, bomb_count, min_probability);
+
+        let flat: Vec<f32> = candidates.iter().flat_map(|&(x, y, p)| [x as f32, y as f32, p]).collect();
+        let arr = js_sys::Float32Array::new_with_length(flat.len() as u32);
+        arr.copy_from(&flat);
+        Ok(arr)
+    }
+
+    /// Region-local mine probabilities from tank enumeration, as a flat
+    /// `Float32Array` of `[x, y, prob]` triples. Cheaper than `exactMineProbabilities`
+    /// but ignores how each region splits the global mine count.
+    #[wasm_bindgen(js_name = "tankProbabilities")]
+    pub fn wasm_tank_probabilities(
+        width: usize,
+        height: usize,
+        visible_flat: &[i8],
+        flags_flat: &[u8],
+        bomb_count: usize,
+    ) -> Result<js_sys::Float32Array, JsValue> {
+        let visible = VisibleGrid::from_flat(width, height, visible_flat)?;
+        let flags = Flags::from_flat(width, height, flags_flat)?;
+        let nc = neighbor_cache(width, height)?;
+        let probabilities = solver::tank_probabilities(&visible, &flags, &nc, Some(bomb_count));
+
+        let flat: Vec<f32> = probabilities.iter().flat_map(|&(x, y, p)| [x as f32, y as f32, p]).collect();
+        let arr = js_sys::Float32Array::new_with_length(flat.len() as u32);
+        arr.copy_from(&flat);
+        Ok(arr)
+    }
+
+    /// Progress reported by `ResumableSolver.step()`.
+    #[wasm_bindgen(js_name = "StepStatus")]
+    pub enum WasmStepStatus {
+        InProgress,
+        Solvable,
+        Unsolvable,
+    }
+
+    /// `isSolvable` in bounded slices for the main thread: call `step(maxWork)`
+    /// once per animation frame until it returns something other than
+    /// `StepStatus.InProgress`. Each unit of work is one round of the cheap
+    /// strategies, or one frontier cell or region of the heavy passes.
+    #[wasm_bindgen(js_name = "ResumableSolver")]
+    pub struct WasmResumableSolver {
+        inner: solver::ResumableSolver,
+    }
+
+    #[wasm_bindgen(js_class = "ResumableSolver")]
+    impl WasmResumableSolver {
+        /// Same arguments as `isSolvableWithConfig`, `blocked` included (`deadlineMs`
+        /// is ignored); no work is done until `step()`.
+        #[wasm_bindgen(constructor)]
+        pub fn new(
+            width: usize,
+            height: usize,
+            grid_flat: &[i8],
+            mines_flat: &[u8],
+            start_x: usize,
+            start_y: usize,
+            config: Option<js_sys::Object>,
+        ) -> Result<WasmResumableSolver, JsValue> {
+            let mines = Mines::from_flat(width, height, mines_flat)?;
+            let (grid, nc) = blocked_grid_and_cache(
+                Grid::from_flat(width, height, grid_flat)?,
+                blocked_from_js(config.as_ref(), width, height)?,
+            )?;
+            let config = solver_config_from_js(config);
+            Ok(Self { inner: solver::ResumableSolver::new(grid, &mines, nc, start_x, start_y, config) })
+        }
+
+        /// Spend up to `maxWork` units of work and report the status.
+        pub fn step(&mut self, max_work: u32) -> WasmStepStatus {
+            match self.inner.step(max_work) {
+                solver::StepStatus::InProgress => WasmStepStatus::InProgress,
+                solver::StepStatus::Solvable => WasmStepStatus::Solvable,
+                solver::StepStatus::Unsolvable => WasmStepStatus::Unsolvable,
+            }
+        }
+
+        /// The solver's current visible grid, e.g. to animate its progress.
+        pub fn visible(&self) -> js_sys::Int8Array {
+            let cells = &self.inner.state().visible.cells;
+            let arr = js_sys::Int8Array::new_with_length(cells.len() as u32);
+            arr.copy_from(cells);
+            arr
+        }
+    }
+
+    /// Stateful probability calculator for per-frame overlays.
+    /// Only regions whose constraints changed since the last `update()` are re-enumerated.
+    #[wasm_bindgen(js_name = "ProbabilitySession")]
+    pub struct WasmProbabilitySession {
+        width: usize,
+        height: usize,
+        inner: probability::ProbabilitySession,
+    }
+
+    #[wasm_bindgen(js_class = "ProbabilitySession")]
+    impl WasmProbabilitySession {
+        #[wasm_bindgen(constructor)]
+        pub fn new(width: usize, height: usize, bomb_count: usize) -> Result<WasmProbabilitySession, JsValue> {
+            if board_cell_count(width, height).is_none() {
+                return Err(board_too_large(width, height));
+            }
+            Ok(Self { width, height, inner: probability::ProbabilitySession::new(width, height, bomb_count) })
+        }
+
+        /// Recompute probabilities for the current state. Same layout as `exactMineProbabilities`.
+        pub fn update(&mut self, visible_flat: &[i8], flags_flat: &[u8]) -> Result<js_sys::Float32Array, JsValue> {
+            let visible = VisibleGrid::from_flat(self.width, self.height, visible_flat)?;
+            let flags = Flags::from_flat(self.width, self.height, flags_flat)?;
+            let probs = self.inner.update(&visible, &flags);
+
+            let arr = js_sys::Float32Array::new_with_length(probs.len() as u32);
+            arr.copy_from(&probs);
+            Ok(arr)
+        }
+
+        /// Number of regions re-enumerated during the last update.
+        #[wasm_bindgen(getter, js_name = "lastEnumerated")]
+        pub fn last_enumerated(&self) -> u32 {
+            self.inner.last_enumerated as u32
+        }
+
+        /// Number of regions served from the cache during the last update.
+        #[wasm_bindgen(getter, js_name = "lastReused")]
+        pub fn last_reused(&self) -> u32 {
+            self.inner.last_reused as u32
+        }
+    }
+
+    /// Reveal cascade spread over several frames.
+    /// `start(x, y)` queues a reveal; each `step(maxCells)` opens at most
+    /// `maxCells` cells and returns them as a flat `Uint32Array` of `[x, y]` pairs.
+    #[wasm_bindgen(js_name = "CascadeAnimator")]
+    pub struct WasmCascadeAnimator {
+        grid: Grid,
+        visible: VisibleGrid,
+        flags: Flags,
+        nc: NeighborCache,
+        pending: Vec<(usize, usize)>,
+    }
+
+    #[wasm_bindgen(js_class = "CascadeAnimator")]
+    impl WasmCascadeAnimator {
+        #[wasm_bindgen(constructor)]
+        pub fn new(
+            width: usize,
+            height: usize,
+            grid_flat: &[i8],
+            visible_flat: &[i8],
+            flags_flat: &[u8],
+        ) -> Result<WasmCascadeAnimator, JsValue> {
+            Ok(Self {
+                grid: Grid::from_flat(width, height, grid_flat)?,
+                visible: VisibleGrid::from_flat(width, height, visible_flat)?,
+                flags: Flags::from_flat(width, height, flags_flat)?,
+                nc: neighbor_cache(width, height)?,
+                pending: Vec::new(),
+            })
+        }
+
+        /// Queue a reveal at `(x, y)`.
+        pub fn start(&mut self, x: usize, y: usize) {
+            self.pending.push((x, y));
+        }
+
+        /// Open up to `max_cells` more cells of the queued cascades.
+        pub fn step(&mut self, max_cells: usize) -> js_sys::Uint32Array {
+            let mut opened: Vec<u32> = Vec::new();
+            solver::reveal_stack_with(
+                &self.grid, &mut self.visible, &self.flags, &self.nc, &mut self.pending, max_cells,
+                |x, y| opened.extend([x as u32, y as u32]),
+            );
+            let arr = js_sys::Uint32Array::new_with_length(opened.len() as u32);
+            arr.copy_from(&opened);
+            arr
+        }
+
+        /// True once every queued cascade has finished. The stack may hold
+        /// only stale entries, so the last `step` can return no cells.
+        #[wasm_bindgen(getter)]
+        pub fn done(&self) -> bool {
+            self.pending.is_empty()
+        }
+
+        /// The current visible grid.
+        pub fn visible(&self) -> js_sys::Int8Array {
+            let arr = js_sys::Int8Array::new_with_length(self.visible.cells.len() as u32);
+            arr.copy_from(&self.visible.cells);
+            arr
+        }
+    }
+
+    /// A game in progress with an O(1) win check per move.
+    /// `reveal(x, y)` returns the newly revealed cells as a flat `Uint32Array`
+    /// of `[x, y]` pairs; check `won` and `lost` afterwards.
+    #[wasm_bindgen(js_name = "GameState")]
+    pub struct WasmGameState {
+        inner: solver::GameState,
+    }
+
+    #[wasm_bindgen(js_class = "GameState")]
+    impl WasmGameState {
+        #[wasm_bindgen(constructor)]
+        pub fn new(width: usize, height: usize, grid_flat: &[i8], mines_flat: &[u8]) -> Result<WasmGameState, JsValue> {
+            let grid = Grid::from_flat(width, height, grid_flat)?;
+            let mines = Mines::from_flat(width, height, mines_flat)?;
+            Ok(Self { inner: solver::GameState::new(grid, mines, neighbor_cache(width, height)?) })
+        }
+
+        /// A game that forgives the first mine hit by moving the mine; the
+        /// same `seed` always picks the same new position.
+        pub fn forgiving(width: usize, height: usize, grid_flat: &[i8], mines_flat: &[u8], seed: u32) -> Result<WasmGameState, JsValue> {
+            let grid = Grid::from_flat(width, height, grid_flat)?;
+            let mines = Mines::from_flat(width, height, mines_flat)?;
+            Ok(Self { inner: solver::GameState::new(grid, mines, neighbor_cache(width, height)?).with_forgiveness(seed as u64) })
+        }
+
+        /// Reveal `(x, y)` with cascade. Flagged and revealed cells do nothing.
+        pub fn reveal(&mut self, x: usize, y: usize) -> js_sys::Uint32Array {
+            let result = self.inner.reveal(x, y);
+            let flat: Vec<u32> = result.revealed_cells.iter().flat_map(|&(cx, cy)| [cx as u32, cy as u32]).collect();
+            let arr = js_sys::Uint32Array::new_with_length(flat.len() as u32);
+            arr.copy_from(&flat);
+            arr
+        }
+
+        /// Toggle the flag on a hidden cell; false if the cell is revealed.
+        #[wasm_bindgen(js_name = "toggleFlag")]
+        pub fn toggle_flag(&mut self, x: usize, y: usize) -> bool {
+            self.inner.toggle_flag(x, y)
+        }
+
+        #[wasm_bindgen(getter)]
+        pub fn won(&self) -> bool {
+            self.inner.is_won()
+        }
+
+        #[wasm_bindgen(getter)]
+        pub fn lost(&self) -> bool {
+            self.inner.is_lost()
+        }
+
+        /// True once a mine hit was forgiven.
+        #[wasm_bindgen(getter, js_name = "forgivenessUsed")]
+        pub fn forgiveness_used(&self) -> bool {
+            self.inner.forgiveness_used()
+        }
+
+        /// Safe cells revealed so far.
+        #[wasm_bindgen(getter, js_name = "revealedSafe")]
+        pub fn revealed_safe(&self) -> u32 {
+            self.inner.revealed_safe() as u32
+        }
+
+        /// The current visible grid.
+        pub fn visible(&self) -> js_sys::Int8Array {
+            let cells = &self.inner.visible().cells;
+            let arr = js_sys::Int8Array::new_with_length(cells.len() as u32);
+            arr.copy_from(cells);
+            arr
+        }
+
+        /// The current mine layout, which a forgiven hit changes.
+        pub fn mines(&self) -> js_sys::Uint8Array {
+            let cells = &self.inner.mines().cells;
+            let arr = js_sys::Uint8Array::new_with_length(cells.len() as u32);
+            arr.copy_from(cells);
+            arr
+        }
+
+        /// The current flags.
+        pub fn flags(&self) -> js_sys::Uint8Array {
+            let cells = &self.inner.flags().cells;
+            let arr = js_sys::Uint8Array::new_with_length(cells.len() as u32);
+            arr.copy_from(cells);
+            arr
+        }
+    }
+
+    /// Get a hint with custom scoring weights.
+    /// Returns JS object `{ x, y, score }` or `null`.
+    #[wasm_bindgen(js_name = "getHintWeighted")]
+    #[allow(clippy::too_many_arguments)]
+    pub fn wasm_get_hint_weighted(
+        width: usize,
+        height: usize,
+        grid_flat: &[i8],
+        visible_flat: &[i8],
+        flags_flat: &[u8],
+        mines_flat: &[u8],
+        zero_bonus: i32,
+        per_revealed_neighbor: i32,
+        island_penalty: i32,
+    ) -> Result<JsValue, JsValue> {
+        let grid = Grid::from_flat(width, height, grid_flat)?;
+        let visible = VisibleGrid::from_flat(width, height, visible_flat)?;
+        let flags = Flags::from_flat(width, height, flags_flat)?;
+        let mines = Mines::from_flat(width, height, mines_flat)?;
+        let nc = neighbor_cache(width, height)?;
+        let weights = solver::HintWeights { zero_bonus, per_revealed_neighbor, island_penalty };
+
+        Ok(match solver::get_hint_weighted(&grid, &visible, &flags, &mines, &nc, &weights) {
+            Some(hint) => {
+                let obj = js_sys::Object::new();
+                set_field(&obj, "x", &(hint.x as u32).into());
+                set_field(&obj, "y", &(hint.y as u32).into());
+                set_field(&obj, "score", &hint.score.into());
+                obj.into()
+            }
+            None => JsValue::NULL,
+        })
+    }
+
+    /// The solver's adjacency in CSR form: `{ offsets: Uint32Array, neighbors: Uint32Array }`.
+    /// Cell `i = x * height + y` has neighbors `neighbors[offsets[i]..offsets[i + 1]]`,
+    /// each packed as `(x << 16) | y`.
+    #[wasm_bindgen(js_name = "neighborList")]
+    pub fn wasm_neighbor_list(width: usize, height: usize) -> Result<JsValue, JsValue> {
+        let nc = neighbor_cache(width, height)?;
+        let (offsets, keys) = nc.to_csr();
+
+        let offsets_arr = js_sys::Uint32Array::new_with_length(offsets.len() as u32);
+        offsets_arr.copy_from(&offsets);
+        let keys_arr = js_sys::Uint32Array::new_with_length(keys.len() as u32);
+        keys_arr.copy_from(&keys);
+
+        let obj = js_sys::Object::new();
+        set_field(&obj, "offsets", &offsets_arr.into());
+        set_field(&obj, "neighbors", &keys_arr.into());
+        Ok(obj.into())
     }
 
     /// Ping function to verify WASM is loaded.
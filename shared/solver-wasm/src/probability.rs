@@ -0,0 +1,571 @@
+//! Mine probability computation.
+//!
+//! Combines exact per-region configuration counts (the same enumeration the
+//! tank solver uses) with a binomial weighting of the off-frontier cells, so
+//! every hidden cell gets its probability under the global mine count.
+//!
+//! `ProbabilitySession` keeps region distributions between calls so a slowly
+//! changing board only re-enumerates the regions whose constraints changed,
+//! and keeps a `SolverArena` so the per-call scratch buffers are reused too.
+
+use crate::arena::SolverArena;
+use crate::solver::{self, RegionConstraint};
+use crate::types::{cell_key, Flags, NeighborCache, SafeZone, VisibleGrid, BLOCKED};
+use std::collections::HashMap;
+
+/// Configuration counts for one frontier region, grouped by mine count.
+///
+/// Counts are normalized by the total number of valid configurations so that
+/// combining many regions never overflows; probabilities are scale-invariant.
+#[derive(Clone)]
+pub struct RegionDistribution {
+    /// Region cells, sorted by `(x, y)`.
+    pub cells: Vec<(usize, usize)>,
+    /// `by_count[k]` = (normalized) configurations with exactly `k` mines.
+    pub by_count: Vec<f64>,
+    /// `cell_by_count[k][i]` = (normalized) configurations with `k` mines where cell `i` is a mine.
+    pub cell_by_count: Vec<Vec<f64>>,
+}
+
+/// Enumerate a region and bucket its valid configurations by mine count.
+///
+/// Returns `None` if the region has no valid configuration (inconsistent clues).
+pub(crate) fn region_distribution(
+    region: &[(usize, usize)],
+    constraints: &[RegionConstraint],
+    arena: Option<&mut SolverArena>,
+) -> Option<RegionDistribution> {
+    let mut local = SolverArena::default();
+    let masks = &mut arena.unwrap_or(&mut local).masks;
+    solver::enumerate_configurations_into(region, constraints, region.len(), 0, masks);
+    if masks.is_empty() {
+        return None;
+    }
+
+    let n = region.len();
+    let mut by_count = vec![0.0f64; n + 1];
+    let mut cell_by_count = vec![vec![0.0f64; n]; n + 1];
+
+    for &mask in masks.iter() {
+        let k = mask.count_ones() as usize;
+        by_count[k] += 1.0;
+        for (i, slot) in cell_by_count[k].iter_mut().enumerate() {
+            if (mask >> i) & 1 == 1 {
+                *slot += 1.0;
+            }
+        }
+    }
+
+    let total = masks.len() as f64;
+    for v in by_count.iter_mut() { *v /= total; }
+    for row in cell_by_count.iter_mut() {
+        for v in row.iter_mut() { *v /= total; }
+    }
+
+    Some(RegionDistribution { cells: region.to_vec(), by_count, cell_by_count })
+}
+
+/// Canonical key for a region: its cells plus every constraint's shape.
+/// Two regions with equal signatures have identical distributions.
+fn region_signature(region: &[(usize, usize)], constraints: &[RegionConstraint]) -> Vec<u32> {
+    let mut sig: Vec<u32> = region.iter().map(|&(x, y)| cell_key(x, y)).collect();
+    sig.push(u32::MAX); // separator
+
+    let mut parts: Vec<Vec<u32>> = constraints.iter().map(|c| {
+        let mut part = vec![c.remaining as u32, c.cells_outside_count as u32];
+        let mut idx: Vec<u32> = c.cells_in_region_indices.iter().map(|&i| i as u32).collect();
+        idx.sort_unstable();
+        part.extend(idx);
+        part.push(u32::MAX);
+        part
+    }).collect();
+    parts.sort();
+    for part in parts { sig.extend(part); }
+
+    sig
+}
+
+/// A frontier region (sorted by cell) together with its constraints.
+pub(crate) type ConstrainedRegion = (Vec<(usize, usize)>, Vec<RegionConstraint>);
+
+/// Collect the enumerable frontier regions with their constraints.
+///
+/// Regions above the tank size limit are skipped; their cells end up in the
+/// off-frontier pool and are treated as unconstrained when combining.
+pub(crate) fn collect_regions(
+    visible: &VisibleGrid,
+    flags: &Flags,
+    nc: &NeighborCache,
+    arena: Option<&mut SolverArena>,
+) -> Vec<ConstrainedRegion> {
+    let mut local = SolverArena::default();
+    let arena = arena.unwrap_or(&mut local);
+    let mut frontier = std::mem::take(&mut arena.frontier);
+    solver::get_frontier_into(visible, flags, nc, &mut frontier);
+    let grouped = solver::group_frontier_regions(&frontier, visible, nc, Some(&mut *arena));
+    arena.frontier = frontier;
+
+    let mut regions = Vec::new();
+    for mut region in grouped {
+        if region.len() > solver::MAX_REGION_SIZE { continue; }
+        region.sort_unstable();
+        let constraints = solver::get_region_constraints(&region, visible, flags, nc, Some(&mut *arena));
+        regions.push((region, constraints));
+    }
+
+    regions
+}
+
+/// Convolve two mine-count distributions.
+fn convolve(a: &[f64], b: &[f64]) -> Vec<f64> {
+    let mut out = vec![0.0f64; a.len() + b.len() - 1];
+    for (i, &av) in a.iter().enumerate() {
+        if av == 0.0 { continue; }
+        for (j, &bv) in b.iter().enumerate() {
+            out[i + j] += av * bv;
+        }
+    }
+    out
+}
+
+/// `ln(n!)` for all `n` in `0..=max`.
+fn ln_factorials(max: usize) -> Vec<f64> {
+    let mut table = Vec::with_capacity(max + 1);
+    let mut acc = 0.0f64;
+    table.push(0.0);
+    for i in 1..=max {
+        acc += (i as f64).ln();
+        table.push(acc);
+    }
+    table
+}
+
+/// Combine region distributions with the off-frontier pool into a flat
+/// probability map. Returns `None` if no mine split is consistent.
+fn combine(
+    visible: &VisibleGrid,
+    flags: &Flags,
+    dists: &[&RegionDistribution],
+    bomb_count: usize,
+) -> Option<Vec<f32>> {
+    let width = visible.width;
+    let height = visible.height;
+
+    let mut flag_count = 0usize;
+    let mut hidden_count = 0usize;
+    for x in 0..width {
+        for y in 0..height {
+            if flags.get(x, y) {
+                flag_count += 1;
+            } else if visible.get(x, y) == -1 {
+                hidden_count += 1;
+            }
+        }
+    }
+
+    let remaining = bomb_count.checked_sub(flag_count)?;
+    let frontier_cells: usize = dists.iter().map(|d| d.cells.len()).sum();
+    let off = hidden_count - frontier_cells;
+
+    // Weight of each possible total frontier mine count t: C(off, remaining - t)
+    let ln_fact = ln_factorials(off);
+    let ln_choose = |k: usize| -> Option<f64> {
+        if k > off { return None; }
+        Some(ln_fact[off] - ln_fact[k] - ln_fact[off - k])
+    };
+    let max_t = frontier_cells.min(remaining);
+    let ln_weights: Vec<Option<f64>> = (0..=frontier_cells)
+        .map(|t| if t > max_t { None } else { ln_choose(remaining - t) })
+        .collect();
+    let ln_max = ln_weights.iter().flatten().cloned().fold(f64::NEG_INFINITY, f64::max);
+    if ln_max == f64::NEG_INFINITY {
+        return None;
+    }
+    let weights: Vec<f64> = ln_weights.iter()
+        .map(|w| w.map_or(0.0, |lw| (lw - ln_max).exp()))
+        .collect();
+
+    // Full convolution and the total weight
+    let mut full = vec![1.0f64];
+    for d in dists { full = convolve(&full, &d.by_count); }
+    let total: f64 = full.iter().enumerate().map(|(t, &c)| c * weights[t]).sum();
+    if total <= 0.0 {
+        return None;
+    }
+
+    let mut probs = vec![0.0f32; width * height];
+    for (i, p) in probs.iter_mut().enumerate() {
+        if flags.cells[i] != 0 { *p = 1.0; }
+    }
+
+    // Each region's cells, weighted by the convolution of all other regions
+    for (r, d) in dists.iter().enumerate() {
+        let mut others = vec![1.0f64];
+        for (o, od) in dists.iter().enumerate() {
+            if o != r { others = convolve(&others, &od.by_count); }
+        }
+        for (i, &(x, y)) in d.cells.iter().enumerate() {
+            let mut w = 0.0f64;
+            for (k, row) in d.cell_by_count.iter().enumerate() {
+                if row[i] == 0.0 { continue; }
+                for (j, &oc) in others.iter().enumerate() {
+                    w += row[i] * oc * weights[k + j];
+                }
+            }
+            probs[x * height + y] = (w / total) as f32;
+        }
+    }
+
+    // Off-frontier cells share the expected leftover mines uniformly
+    if off > 0 {
+        let expected: f64 = full.iter().enumerate()
+            .filter(|&(t, _)| t <= max_t)
+            .map(|(t, &c)| c * weights[t] * (remaining - t) as f64)
+            .sum::<f64>() / total;
+        let off_prob = (expected / off as f64) as f32;
+
+        let mut in_region = vec![false; width * height];
+        for d in dists {
+            for &(x, y) in &d.cells { in_region[x * height + y] = true; }
+        }
+        for x in 0..width {
+            for y in 0..height {
+                let idx = x * height + y;
+                if visible.get(x, y) == -1 && !flags.get(x, y) && !in_region[idx] {
+                    probs[idx] = off_prob;
+                }
+            }
+        }
+    }
+
+    Some(probs)
+}
+
+/// Natural log of the number of mine layouts consistent with the position,
+/// using the same region enumeration and off-frontier weighting as
+/// `exact_mine_probabilities()` (regions above the tank limit count as
+/// unconstrained). Ratios of these counts give the relative likelihood of
+/// hypothetical positions. `None` if no layout fits, including when a clue
+/// with no hidden neighbours left disagrees with its flags.
+pub(crate) fn ln_layout_count(
+    visible: &VisibleGrid,
+    flags: &Flags,
+    nc: &NeighborCache,
+    bomb_count: usize,
+) -> Option<f64> {
+    let height = visible.height;
+    let mut flag_count = 0usize;
+    let mut hidden_count = 0usize;
+    for (i, (&v, &f)) in visible.cells.iter().zip(&flags.cells).enumerate() {
+        if f != 0 {
+            flag_count += 1;
+        } else if v == -1 {
+            hidden_count += 1;
+        } else if v > 0 {
+            let (x, y) = (i / height, i % height);
+            let around = nc.get(x, y);
+            let flagged = around.iter().filter(|&&(nx, ny)| flags.get(nx, ny)).count();
+            let open = around.iter().all(|&(nx, ny)| flags.get(nx, ny) || visible.get(nx, ny) != -1);
+            if flagged > v as usize || (open && flagged != v as usize) {
+                return None;
+            }
+        }
+    }
+    let remaining = bomb_count.checked_sub(flag_count)?;
+
+    // Regions are normalized as in region_distribution(); ln_scale keeps the totals
+    let mut ln_scale = 0.0f64;
+    let mut full = vec![1.0f64];
+    let mut frontier_cells = 0usize;
+    let mut masks = Vec::new();
+    for (region, constraints) in collect_regions(visible, flags, nc, None) {
+        solver::enumerate_configurations_into(&region, &constraints, region.len(), 0, &mut masks);
+        if masks.is_empty() {
+            return None;
+        }
+        let mut by_count = vec![0.0f64; region.len() + 1];
+        for &mask in &masks {
+            by_count[mask.count_ones() as usize] += 1.0;
+        }
+        let total = masks.len() as f64;
+        for v in by_count.iter_mut() { *v /= total; }
+        ln_scale += total.ln();
+        frontier_cells += region.len();
+        full = convolve(&full, &by_count);
+    }
+
+    let off = hidden_count - frontier_cells;
+    let ln_fact = ln_factorials(off);
+    let terms: Vec<f64> = full.iter().enumerate()
+        .filter(|&(t, &c)| c > 0.0 && t <= remaining && remaining - t <= off)
+        .map(|(t, &c)| {
+            let k = remaining - t;
+            c.ln() + ln_fact[off] - ln_fact[k] - ln_fact[off - k]
+        })
+        .collect();
+    let ln_max = terms.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+    if ln_max == f64::NEG_INFINITY {
+        return None;
+    }
+    let sum: f64 = terms.iter().map(|&t| (t - ln_max).exp()).sum();
+    Some(ln_scale + ln_max + sum.ln())
+}
+
+/// Uniform fallback when the position is inconsistent: remaining mines spread
+/// evenly over all hidden unflagged cells.
+fn uniform_probabilities(visible: &VisibleGrid, flags: &Flags, bomb_count: usize) -> Vec<f32> {
+    let flag_count = flags.cells.iter().filter(|&&f| f != 0).count();
+    let hidden = visible.cells.iter().zip(&flags.cells)
+        .filter(|&(&v, &f)| v == -1 && f == 0)
+        .count();
+    let density = if hidden == 0 {
+        0.0
+    } else {
+        (bomb_count.saturating_sub(flag_count) as f32 / hidden as f32).min(1.0)
+    };
+
+    visible.cells.iter().zip(&flags.cells)
+        .map(|(&v, &f)| if f != 0 { 1.0 } else if v == -1 { density } else { 0.0 })
+        .collect()
+}
+
+/// Compute the exact mine probability of every cell.
+///
+/// Returns a flat column-major map: revealed cells are 0.0, flagged cells 1.0,
+/// and hidden cells carry their probability under the global mine count.
+/// Frontier regions larger than the tank limit are treated as unconstrained.
+///
+/// With `respect_user_flags` false, flags are treated as hidden cells: they
+/// get their own derived probability and do not count against `bomb_count`.
+///
+/// Pass the `safe_zone` the board was generated with to account for its hidden
+/// cells, which can never be mines: they get 0.0 and leave the off-frontier
+/// pool, so the leftover mines spread over the cells that can hold them
+/// instead of diluting over the whole hidden area.
+/// Pass an `arena` when calling repeatedly to reuse the scratch buffers.
+pub fn exact_mine_probabilities(
+    visible: &VisibleGrid,
+    flags: &Flags,
+    nc: &NeighborCache,
+    bomb_count: usize,
+    respect_user_flags: bool,
+    safe_zone: Option<&SafeZone>,
+    arena: Option<&mut SolverArena>,
+) -> Vec<f32> {
+    let no_flags;
+    let flags = if respect_user_flags {
+        flags
+    } else {
+        no_flags = Flags::new(flags.width, flags.height);
+        &no_flags
+    };
+    // Known-safe hidden cells read as revealed cells without a clue
+    let masked;
+    let visible = match safe_zone {
+        Some(zone) => {
+            let mut copy = visible.clone();
+            for x in 0..copy.width {
+                for y in 0..copy.height {
+                    if zone.contains(x, y) && copy.get(x, y) == -1 && !flags.get(x, y) {
+                        copy.set(x, y, BLOCKED);
+                    }
+                }
+            }
+            masked = copy;
+            &masked
+        }
+        None => visible,
+    };
+    let mut local = SolverArena::default();
+    let arena = arena.unwrap_or(&mut local);
+    let regions = collect_regions(visible, flags, nc, Some(&mut *arena));
+    let mut dists = Vec::with_capacity(regions.len());
+    for (region, constraints) in &regions {
+        match region_distribution(region, constraints, Some(&mut *arena)) {
+            Some(d) => dists.push(d),
+            None => return uniform_probabilities(visible, flags, bomb_count),
+        }
+    }
+    let refs: Vec<&RegionDistribution> = dists.iter().collect();
+    combine(visible, flags, &refs, bomb_count)
+        .unwrap_or_else(|| uniform_probabilities(visible, flags, bomb_count))
+}
+
+// ─── ProbabilitySession ─────────────────────────────────────────────────────
+
+/// Stateful probability calculator for per-frame overlays.
+///
+/// Caches each region's distribution keyed by its constraint signature.
+/// On `update()`, regions whose cells and clues are unchanged reuse the cached
+/// distribution; only new or modified regions are re-enumerated. Entries not
+/// seen in the latest update are evicted.
+pub struct ProbabilitySession {
+    nc: NeighborCache,
+    bomb_count: usize,
+    cache: HashMap<Vec<u32>, RegionDistribution>,
+    arena: SolverArena,
+    /// Regions enumerated during the last update.
+    pub last_enumerated: usize,
+    /// Regions served from the cache during the last update.
+    pub last_reused: usize,
+}
+
+impl ProbabilitySession {
+    pub fn new(width: usize, height: usize, bomb_count: usize) -> Self {
+        Self {
+            nc: NeighborCache::new(width, height),
+            bomb_count,
+            cache: HashMap::new(),
+            arena: SolverArena::default(),
+            last_enumerated: 0,
+            last_reused: 0,
+        }
+    }
+
+    /// Recompute probabilities for the current state, reusing unchanged regions.
+    /// Output layout matches `exact_mine_probabilities()`.
+    pub fn update(&mut self, visible: &VisibleGrid, flags: &Flags) -> Vec<f32> {
+        let regions = collect_regions(visible, flags, &self.nc, Some(&mut self.arena));
+        let mut next_cache: HashMap<Vec<u32>, RegionDistribution> = HashMap::with_capacity(regions.len());
+        let mut keys = Vec::with_capacity(regions.len());
+        let mut inconsistent = false;
+
+        self.last_enumerated = 0;
+        self.last_reused = 0;
+
+        for (region, constraints) in &regions {
+            let sig = region_signature(region, constraints);
+            if !next_cache.contains_key(&sig) {
+                let dist = match self.cache.remove(&sig) {
+                    Some(d) => {
+                        self.last_reused += 1;
+                        Some(d)
+                    }
+                    None => {
+                        self.last_enumerated += 1;
+                        region_distribution(region, constraints, Some(&mut self.arena))
+                    }
+                };
+                match dist {
+                    Some(d) => { next_cache.insert(sig.clone(), d); }
+                    None => { inconsistent = true; continue; }
+                }
+            }
+            keys.push(sig);
+        }
+
+        self.cache = next_cache;
+
+        if inconsistent {
+            return uniform_probabilities(visible, flags, self.bomb_count);
+        }
+        let refs: Vec<&RegionDistribution> = keys.iter().map(|k| &self.cache[k]).collect();
+        combine(visible, flags, &refs, self.bomb_count)
+            .unwrap_or_else(|| uniform_probabilities(visible, flags, self.bomb_count))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 4x2 board: revealed "1"s at (0,0) and (0,1) next to hidden column 1;
+    /// the rest hidden.
+    fn strip_state() -> (VisibleGrid, Flags, NeighborCache) {
+        let mut visible = VisibleGrid::new(4, 2);
+        visible.set(0, 0, 1);
+        visible.set(0, 1, 1);
+        (visible, Flags::new(4, 2), NeighborCache::new(4, 2))
+    }
+
+    #[test]
+    fn test_fifty_fifty_region() {
+        let (visible, flags, nc) = strip_state();
+        // Region (1,0),(1,1) holds exactly one mine; 4 off-frontier cells, 1 mine total
+        let probs = exact_mine_probabilities(&visible, &flags, &nc, 1, true, None, None);
+        assert!((probs[2] - 0.5).abs() < 1e-6);
+        assert!((probs[3] - 0.5).abs() < 1e-6);
+        // The only mine is on the frontier, so off-frontier cells are safe
+        assert!(probs[4].abs() < 1e-6);
+        assert_eq!(probs[0], 0.0);
+    }
+
+    #[test]
+    fn test_probabilities_sum_to_remaining_mines() {
+        let (visible, flags, nc) = strip_state();
+        let probs = exact_mine_probabilities(&visible, &flags, &nc, 3, true, None, None);
+        let sum: f32 = probs.iter().sum();
+        assert!((sum - 3.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_ignoring_user_flags_changes_probabilities() {
+        let (visible, mut flags, nc) = strip_state();
+        // An unsupported flag far from the clues
+        flags.set(3, 0, true);
+
+        // Respected: the flag takes one of the two mines, the frontier the other
+        let respected = exact_mine_probabilities(&visible, &flags, &nc, 2, true, None, None);
+        assert_eq!(respected[6], 1.0);
+        assert!(respected[4].abs() < 1e-6);
+
+        // Ignored: the flagged cell is just one of four off-frontier cells sharing a mine
+        let ignored = exact_mine_probabilities(&visible, &flags, &nc, 2, false, None, None);
+        assert!((ignored[6] - 0.25).abs() < 1e-6);
+        assert!((ignored[4] - 0.25).abs() < 1e-6);
+        assert!((ignored[2] - 0.5).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_safe_zone_leaves_the_off_frontier_pool() {
+        // 6x1 "1 ? ? ? ? ?" with two mines: the clue pins one on (1,0), the other
+        // is off the frontier, where the generator's zone kept (2,0) clear
+        let visible = VisibleGrid::from_flat(6, 1, &[1, -1, -1, -1, -1, -1]).unwrap();
+        let flags = Flags::new(6, 1);
+        let nc = NeighborCache::new(6, 1);
+
+        let ignored = exact_mine_probabilities(&visible, &flags, &nc, 2, true, None, None);
+        assert!((ignored[2] - 0.25).abs() < 1e-6);
+
+        let zone = SafeZone::new(2, 0, 0);
+        let probs = exact_mine_probabilities(&visible, &flags, &nc, 2, true, Some(&zone), None);
+        assert!((probs[1] - 1.0).abs() < 1e-6);
+        assert_eq!(probs[2], 0.0);
+        for p in &probs[3..] {
+            assert!((p - 1.0 / 3.0).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_arena_matches_fresh_buffers() {
+        let (mut visible, flags, nc) = strip_state();
+        let mut arena = SolverArena::new();
+        for bombs in 1..=3 {
+            let fresh = exact_mine_probabilities(&visible, &flags, &nc, bombs, true, None, None);
+            assert_eq!(exact_mine_probabilities(&visible, &flags, &nc, bombs, true, None, Some(&mut arena)), fresh);
+        }
+        // Stale contents from a different position must not leak into the next call
+        visible.set(3, 0, 1);
+        let fresh = exact_mine_probabilities(&visible, &flags, &nc, 2, true, None, None);
+        assert_eq!(exact_mine_probabilities(&visible, &flags, &nc, 2, true, None, Some(&mut arena)), fresh);
+    }
+
+    #[test]
+    fn test_session_reuses_unchanged_regions() {
+        let (visible, flags, _nc) = strip_state();
+        let mut session = ProbabilitySession::new(4, 2, 2);
+
+        let first = session.update(&visible, &flags);
+        assert_eq!(session.last_enumerated, 1);
+        assert_eq!(session.last_reused, 0);
+
+        let second = session.update(&visible, &flags);
+        assert_eq!(session.last_enumerated, 0);
+        assert_eq!(session.last_reused, 1);
+        assert_eq!(first, second);
+
+        // Matches the stateless computation
+        let nc = NeighborCache::new(4, 2);
+        assert_eq!(second, exact_mine_probabilities(&visible, &flags, &nc, 2, true, None, None));
+    }
+}
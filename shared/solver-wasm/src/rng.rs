@@ -6,6 +6,16 @@
 use rand::rngs::SmallRng;
 use rand::{Rng, SeedableRng};
 
+/// Source of randomness for mine placement.
+///
+/// Board generation is generic over this trait so callers can inject a
+/// specific algorithm, e.g. one that reproduces legacy JS placements exactly.
+/// `WasmRng` is the default implementation.
+pub trait BoardRng {
+    /// Generate a random usize in [0, max).
+    fn gen_range(&mut self, max: usize) -> usize;
+}
+
 /// A seedable RNG wrapper for WASM.
 ///
 /// Can be seeded for deterministic replay, or created from system entropy.
@@ -36,6 +46,19 @@ impl WasmRng {
     }
 }
 
+impl BoardRng for WasmRng {
+    #[inline(always)]
+    fn gen_range(&mut self, max: usize) -> usize {
+        WasmRng::gen_range(self, max)
+    }
+}
+
+impl Default for WasmRng {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -9,49 +9,227 @@
 //! - Strategy 6: Global mine counting
 //! - Top-level `is_solvable()` and `get_hint()`
 
+use crate::arena::SolverArena;
 use crate::gaussian;
-use crate::types::{cell_key, decode_key, Flags, Grid, Mines, NeighborCache, VisibleGrid};
+use crate::probability;
+use crate::types::{cell_key, decode_key, Flags, BLOCKED, EXPLODED, Grid, Mines, NeighborCache, NeighborWeights, SafeRect, SafeZone, VisibleGrid};
+use std::cell::RefCell;
 use std::collections::{HashMap, HashSet};
 
 /// Maximum region size for tank solver enumeration.
-const MAX_REGION_SIZE: usize = 20;
+pub(crate) const MAX_REGION_SIZE: usize = 20;
 
 /// Maximum configurations to test (2^MAX_REGION_SIZE).
 const MAX_CONFIGURATIONS: u64 = 1 << MAX_REGION_SIZE;
 
+/// Entries kept by the tank solver's constraint-signature cache.
+const TANK_CACHE_CAPACITY: usize = 4096;
+
+// ─── Solver configuration ───────────────────────────────────────────────────
+
+/// Options that change which deductions the solver may use.
+#[derive(Clone, Debug)]
+pub struct SolverConfig {
+    /// Whether the player knows the total mine count. When false ("blind"
+    /// variant), Strategy 6 is skipped and the tank solver enumerates
+    /// without a mine budget.
+    pub know_mine_count: bool,
+    /// How far a clue may be off from the true neighbor count ("noisy" variant).
+    /// With tolerance `t`, a clue `v` means anything in `[v - t, v + t]`: the
+    /// basic rules and the tank solver only deduce what holds across the whole
+    /// range. Subset logic, Gaussian elimination and proof by contradiction
+    /// assume exact clues and are skipped when `t > 0`: a clue range is an
+    /// inequality, which row reduction cannot combine, so the tank's bounded
+    /// enumeration covers those deductions instead.
+    pub clue_tolerance: u8,
+    /// Highest `Strategy` level (1-6) the solver may use; 0 allows none, so
+    /// only the opening counts.
+    pub max_strategy_level: u8,
+    /// Per-cell clue kinds ("boolean clue" variant), indexed `x * height + y`;
+    /// `None` means every clue is exact, as do cells past the overlay's end.
+    /// A `ClueKind::Boolean` clue above 0 only says some neighbor is a mine,
+    /// and the basic rules deduce from just that. The tank solver and
+    /// Strategies 2-4 assume exact clues and are skipped while an overlay is
+    /// set; global counting still runs.
+    pub clue_kinds: Option<Vec<ClueKind>>,
+    /// Let proof by contradiction run subset reasoning inside each hypothesis
+    /// whenever its counting propagation stalls. Finds more contradictions,
+    /// at a noticeable cost per tested cell.
+    pub deep_contradiction: bool,
+    /// Per-neighbor clue weights ("weighted clue" variant, see
+    /// `board::calculate_numbers_weighted()`), built from the same neighbor
+    /// cache the solver is given. The basic rules then reason with weighted
+    /// sums; Strategies 2-5 assume unit weights and are skipped, while global
+    /// counting, which only counts mines, still runs.
+    pub neighbor_weights: Option<NeighborWeights>,
+    /// Wall-clock budget for `run_to_stall()`, in milliseconds. Once spent,
+    /// the solver stops between steps, or between regions and frontier cells
+    /// inside the tank and contradiction passes, and the board counts as "not
+    /// confirmed solvable in budget". `None` runs to the stall however long
+    /// it takes.
+    pub deadline_ms: Option<f64>,
+}
+
+impl Default for SolverConfig {
+    fn default() -> Self {
+        Self {
+            know_mine_count: true,
+            clue_tolerance: 0,
+            max_strategy_level: Strategy::GlobalCount as u8,
+            clue_kinds: None,
+            deep_contradiction: false,
+            neighbor_weights: None,
+            deadline_ms: None,
+        }
+    }
+}
+
+/// Milliseconds on a wall clock: `Date.now()` under wasm, where `Instant`
+/// is unavailable, and a process-local `Instant` natively.
+fn now_ms() -> f64 {
+    #[cfg(target_arch = "wasm32")]
+    {
+        js_sys::Date::now()
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        static EPOCH: std::sync::OnceLock<std::time::Instant> = std::sync::OnceLock::new();
+        EPOCH.get_or_init(std::time::Instant::now).elapsed().as_secs_f64() * 1000.0
+    }
+}
+
+/// What a revealed number tells the player.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ClueKind {
+    /// The exact neighbor mine count.
+    #[default]
+    Exact,
+    /// Only whether any neighbor is a mine (0 still means none).
+    Boolean,
+}
+
 // ─── Helper: simulate_reveal ────────────────────────────────────────────────
 
 /// Simulate revealing a cell with flood fill for zeros.
-/// Updates `visible` in-place, matching JS `simulateReveal()`.
+/// Updates `visible` in-place, matching JS `simulateReveal()`. Flagged cells
+/// are never revealed, neither when clicked nor by the cascade; unflag them
+/// first (see `apply_move()`). The cascade spreads through `nc`, so it wraps
+/// on a toroidal cache and skips the blocked cells of an irregular one.
 pub fn simulate_reveal(
     grid: &Grid,
     visible: &mut VisibleGrid,
     flags: &Flags,
+    nc: &NeighborCache,
     x: usize,
     y: usize,
 ) {
-    let width = grid.width;
-    let height = grid.height;
+    reveal_with(grid, visible, flags, nc, x, y, |_, _| {});
+}
+
+/// Flood-fill reveal that reports every newly revealed cell to `on_reveal`.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn reveal_with<F: FnMut(usize, usize)>(
+    grid: &Grid,
+    visible: &mut VisibleGrid,
+    flags: &Flags,
+    nc: &NeighborCache,
+    x: usize,
+    y: usize,
+    on_reveal: F,
+) {
     let mut stack: Vec<(usize, usize)> = vec![(x, y)];
+    reveal_stack_with(grid, visible, flags, nc, &mut stack, usize::MAX, on_reveal);
+}
+
+/// Like `simulate_reveal()`, but stops after revealing `max_cells` cells.
+///
+/// Returns the flood-fill stack of cells not yet processed; pass it to
+/// `resume_reveal_bounded()` to continue the cascade (e.g. on the next
+/// animation frame). An empty result means the cascade is complete. Running
+/// all the slices yields exactly the cells `simulate_reveal()` would open.
+#[allow(clippy::too_many_arguments)]
+pub fn simulate_reveal_bounded(
+    grid: &Grid,
+    visible: &mut VisibleGrid,
+    flags: &Flags,
+    nc: &NeighborCache,
+    x: usize,
+    y: usize,
+    max_cells: usize,
+) -> Vec<(usize, usize)> {
+    resume_reveal_bounded(grid, visible, flags, nc, vec![(x, y)], max_cells)
+}
+
+/// Continue a cascade from the stack returned by `simulate_reveal_bounded()`.
+pub fn resume_reveal_bounded(
+    grid: &Grid,
+    visible: &mut VisibleGrid,
+    flags: &Flags,
+    nc: &NeighborCache,
+    mut pending: Vec<(usize, usize)>,
+    max_cells: usize,
+) -> Vec<(usize, usize)> {
+    reveal_stack_with(grid, visible, flags, nc, &mut pending, max_cells, |_, _| {});
+    pending
+}
+
+/// Like `simulate_reveal()`, but opens the cascade breadth-first and tags each
+/// revealed cell with its BFS distance from the click (0 for the click itself).
+///
+/// Cells come back in reveal order, so distances never decrease; the UI can
+/// stagger a ripple animation by distance. Reveals exactly the same cells as
+/// `simulate_reveal()`, which the solver keeps using since it ignores order.
+pub fn simulate_reveal_bfs(
+    grid: &Grid,
+    visible: &mut VisibleGrid,
+    flags: &Flags,
+    nc: &NeighborCache,
+    x: usize,
+    y: usize,
+) -> Vec<(usize, usize, u32)> {
+    let mut revealed = Vec::new();
+    let mut queue = std::collections::VecDeque::from([(x, y, 0u32)]);
 
-    while let Some((cx, cy)) = stack.pop() {
-        if cx >= width || cy >= height { continue; }
-        if visible.get(cx, cy) != -1 || flags.get(cx, cy) { continue; }
+    while let Some((cx, cy, dist)) = queue.pop_front() {
+        if visible.get_checked(cx, cy) != Some(-1) || flags.get(cx, cy) { continue; }
 
         let val = grid.get(cx, cy);
         visible.set(cx, cy, val);
+        revealed.push((cx, cy, dist));
 
         if val == 0 {
-            for dx in -1i32..=1 {
-                for dy in -1i32..=1 {
-                    if dx == 0 && dy == 0 { continue; }
-                    let nx = cx as i32 + dx;
-                    let ny = cy as i32 + dy;
-                    if nx >= 0 && nx < width as i32 && ny >= 0 && ny < height as i32 {
-                        stack.push((nx as usize, ny as usize));
-                    }
-                }
-            }
+            queue.extend(nc.get(cx, cy).iter().map(|&(nx, ny)| (nx, ny, dist + 1)));
+        }
+    }
+
+    revealed
+}
+
+/// Flood-fill from `stack`, revealing at most `max_cells` cells and leaving
+/// the unprocessed remainder on the stack.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn reveal_stack_with<F: FnMut(usize, usize)>(
+    grid: &Grid,
+    visible: &mut VisibleGrid,
+    flags: &Flags,
+    nc: &NeighborCache,
+    stack: &mut Vec<(usize, usize)>,
+    max_cells: usize,
+    mut on_reveal: F,
+) {
+    let mut revealed = 0;
+
+    while revealed < max_cells {
+        let Some((cx, cy)) = stack.pop() else { break };
+        if visible.get_checked(cx, cy) != Some(-1) || flags.get(cx, cy) { continue; }
+
+        let val = grid.get(cx, cy);
+        visible.set(cx, cy, val);
+        on_reveal(cx, cy);
+        revealed += 1;
+
+        if val == 0 {
+            stack.extend_from_slice(nc.get(cx, cy));
         }
     }
 }
@@ -59,14 +237,26 @@ pub fn simulate_reveal(
 // ─── Helper: get_frontier ───────────────────────────────────────────────────
 
 /// Get all frontier cells: hidden, unflagged cells adjacent to a revealed number.
-fn get_frontier(
+pub(crate) fn get_frontier(
     visible: &VisibleGrid,
     flags: &Flags,
     nc: &NeighborCache,
 ) -> Vec<(usize, usize)> {
+    let mut frontier = Vec::new();
+    get_frontier_into(visible, flags, nc, &mut frontier);
+    frontier
+}
+
+/// `get_frontier()` into a cleared `frontier`, reusing its allocation.
+pub(crate) fn get_frontier_into(
+    visible: &VisibleGrid,
+    flags: &Flags,
+    nc: &NeighborCache,
+    frontier: &mut Vec<(usize, usize)>,
+) {
     let width = visible.width;
     let height = visible.height;
-    let mut frontier = Vec::new();
+    frontier.clear();
 
     for x in 0..width {
         for y in 0..height {
@@ -80,7 +270,50 @@ fn get_frontier(
             }
         }
     }
-    frontier
+}
+
+/// Whether the clue at `(x, y)` sees more flags than its value: some flag
+/// around it is wrong. The strategies skip such clues instead of working with
+/// a negative remaining count.
+pub(crate) fn is_over_flagged(visible: &VisibleGrid, flags: &Flags, nc: &NeighborCache, x: usize, y: usize) -> bool {
+    let flagged = nc.get(x, y).iter().filter(|&&(nx, ny)| flags.get(nx, ny)).count();
+    flagged as i32 > visible.get(x, y) as i32
+}
+
+// ─── Helper: neighbor_summary ───────────────────────────────────────────────
+
+/// Neighbors of one cell, split the way the counting rules see them.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct NeighborSummary {
+    /// Hidden, unflagged neighbors.
+    pub hidden: Vec<(usize, usize)>,
+    /// Flagged neighbors.
+    pub flagged: Vec<(usize, usize)>,
+    /// Revealed neighbors showing a number (1-8); revealed zeros are omitted.
+    pub revealed: Vec<(usize, usize)>,
+}
+
+/// Categorize the neighbors of `(x, y)`. Out-of-bounds cells have no neighbors.
+pub fn neighbor_summary(
+    visible: &VisibleGrid,
+    flags: &Flags,
+    nc: &NeighborCache,
+    x: usize,
+    y: usize,
+) -> NeighborSummary {
+    let mut summary = NeighborSummary::default();
+    if !visible.in_bounds(x, y) { return summary; }
+
+    for &(nx, ny) in nc.get(x, y) {
+        if flags.get(nx, ny) {
+            summary.flagged.push((nx, ny));
+        } else if visible.get(nx, ny) == -1 {
+            summary.hidden.push((nx, ny));
+        } else if visible.get(nx, ny) > 0 {
+            summary.revealed.push((nx, ny));
+        }
+    }
+    summary
 }
 
 // ─── Strategy 1: Basic Rules ────────────────────────────────────────────────
@@ -91,6 +324,7 @@ struct BasicResult {
     dirty_cells: HashSet<u32>,
 }
 
+#[allow(clippy::too_many_arguments)]
 fn apply_basic_rules(
     grid: &Grid,
     visible: &mut VisibleGrid,
@@ -98,9 +332,10 @@ fn apply_basic_rules(
     nc: &NeighborCache,
     dirty_cells: &HashSet<u32>,
     flag_count: u32,
+    tolerance: i32,
+    clue_kinds: Option<&[ClueKind]>,
+    weights: Option<&NeighborWeights>,
 ) -> BasicResult {
-    let width = grid.width;
-    let height = grid.height;
     let mut progress = false;
     let mut new_dirty = HashSet::new();
     let mut processed = HashSet::new();
@@ -108,9 +343,7 @@ fn apply_basic_rules(
 
     for &key in dirty_cells {
         let (x, y) = decode_key(key);
-        if x >= width || y >= height { continue; }
-
-        let val = visible.get(x, y);
+        let val = match visible.get_checked(x, y) { Some(v) => v, None => continue };
         if val <= 0 { continue; }
         if processed.contains(&key) { continue; }
         processed.insert(key);
@@ -130,8 +363,61 @@ fn apply_basic_rules(
         }
 
         if hidden_count == 0 { continue; }
+        // Over-flagged even at the top of the tolerance band: a flag is wrong
+        if flagged_count > val as i32 + tolerance { continue; }
+
+        // A boolean clue only says "at least one": with no flag yet and a
+        // single hidden neighbor, that neighbor is the mine
+        let boolean = clue_kinds.is_some_and(|k| k.get(x * visible.height + y) == Some(&ClueKind::Boolean));
+        if boolean {
+            if flagged_count == 0 && hidden_count == 1 {
+                let (nx, ny) = hidden_cells[0];
+                flags.set(nx, ny, true);
+                fc += 1;
+                for &(nnx, nny) in nc.get(nx, ny) {
+                    new_dirty.insert(cell_key(nnx, nny));
+                }
+                progress = true;
+            }
+            continue;
+        }
+
+        if let Some(weights) = weights {
+            // The weighted mines still missing lie in [low, high]: a neighbor
+            // heavier than `high` cannot be a mine, and one the others cannot
+            // make up for without must be
+            let mut flagged_weight = 0i32;
+            let mut hidden_weights: Vec<((usize, usize), i32)> = Vec::new();
+            for (&(nx, ny), &w) in neighbors.iter().zip(weights.get(x, y)) {
+                if flags.get(nx, ny) {
+                    flagged_weight += w as i32;
+                } else if visible.get(nx, ny) == -1 {
+                    hidden_weights.push(((nx, ny), w as i32));
+                }
+            }
+            let hidden_weight: i32 = hidden_weights.iter().map(|&(_, w)| w).sum();
+            let high = val as i32 + tolerance - flagged_weight;
+            let low = val as i32 - tolerance - flagged_weight;
+            if high < 0 { continue; }
+            for &((nx, ny), w) in &hidden_weights {
+                if w > high {
+                    simulate_reveal(grid, visible, flags, nc, nx, ny);
+                } else if hidden_weight - w < low {
+                    flags.set(nx, ny, true);
+                    fc += 1;
+                } else {
+                    continue;
+                }
+                for &(nnx, nny) in nc.get(nx, ny) {
+                    new_dirty.insert(cell_key(nnx, nny));
+                }
+                progress = true;
+            }
+            continue;
+        }
 
-        if val as i32 == hidden_count + flagged_count {
+        // The true count lies in [val - tolerance, val + tolerance]
+        if val as i32 - tolerance == hidden_count + flagged_count {
             // All hidden neighbors are mines
             for &(nx, ny) in &hidden_cells {
                 if !flags.get(nx, ny) {
@@ -143,10 +429,10 @@ fn apply_basic_rules(
                 }
             }
             progress = true;
-        } else if val as i32 == flagged_count {
+        } else if val as i32 + tolerance == flagged_count {
             // All hidden neighbors are safe
             for &(nx, ny) in &hidden_cells {
-                simulate_reveal(grid, visible, flags, nx, ny);
+                simulate_reveal(grid, visible, flags, nc, nx, ny);
                 for &(nnx, nny) in nc.get(nx, ny) {
                     new_dirty.insert(cell_key(nnx, nny));
                 }
@@ -178,8 +464,6 @@ fn apply_subset_logic(
     dirty_cells: &HashSet<u32>,
     flag_count: u32,
 ) -> SubsetResult {
-    let width = grid.width;
-    let height = grid.height;
     let mut progress = false;
     let mut new_dirty = HashSet::new();
     let mut fc = flag_count;
@@ -188,8 +472,8 @@ fn apply_subset_logic(
     let mut constraint_keys = HashSet::new();
     for &key in dirty_cells {
         let (x, y) = decode_key(key);
-        if x >= width || y >= height { continue; }
-        if visible.get(x, y) > 0 {
+        let val = match visible.get_checked(x, y) { Some(v) => v, None => continue };
+        if val > 0 {
             constraint_keys.insert(key);
         }
         for &(nx, ny) in nc.get(x, y) {
@@ -236,61 +520,55 @@ fn apply_subset_logic(
         cell_data.insert(key, CellData { x, y, hidden_set, hidden_list, remaining });
     }
 
-    // Compare pairs in 5x5 region
+    // Compare pairs in the 5x5 ring around each clue
+    let ring2 = nc.ring2();
     let keys: Vec<u32> = cell_data.keys().copied().collect();
     for &key_a in &keys {
         let data_a = match cell_data.get(&key_a) { Some(d) => d, None => continue };
         if data_a.hidden_list.is_empty() { continue; }
 
-        for dx in -2i32..=2 {
-            for dy in -2i32..=2 {
-                if dx == 0 && dy == 0 { continue; }
-                let nx = data_a.x as i32 + dx;
-                let ny = data_a.y as i32 + dy;
-                if nx < 0 || nx >= width as i32 || ny < 0 || ny >= height as i32 { continue; }
-
-                let key_b = cell_key(nx as usize, ny as usize);
-                let data_b = match cell_data.get(&key_b) { Some(d) => d, None => continue };
-                if data_b.hidden_list.is_empty() { continue; }
-
-                // Check if A ⊂ B
-                if data_a.hidden_set.len() < data_b.hidden_set.len()
-                    && data_a.hidden_set.is_subset(&data_b.hidden_set)
-                {
-                    // Compute B \ A
-                    let diff: Vec<(usize, usize)> = data_b.hidden_list.iter()
-                        .filter(|&&(bx, by)| !data_a.hidden_set.contains(&cell_key(bx, by)))
-                        .copied()
-                        .collect();
-
-                    let diff_mines = data_b.remaining - data_a.remaining;
-
-                    if diff_mines == 0 && !diff.is_empty() {
-                        // All diff cells are safe
-                        for &(sx, sy) in &diff {
-                            simulate_reveal(grid, visible, flags, sx, sy);
-                            for &(nnx, nny) in nc.get(sx, sy) {
-                                new_dirty.insert(cell_key(nnx, nny));
-                            }
+        for &(nx, ny) in ring2.get(data_a.x, data_a.y) {
+            let key_b = cell_key(nx, ny);
+            let data_b = match cell_data.get(&key_b) { Some(d) => d, None => continue };
+            if data_b.hidden_list.is_empty() { continue; }
+
+            // Check if A ⊂ B
+            if data_a.hidden_set.len() < data_b.hidden_set.len()
+                && data_a.hidden_set.is_subset(&data_b.hidden_set)
+            {
+                // Compute B \ A
+                let diff: Vec<(usize, usize)> = data_b.hidden_list.iter()
+                    .filter(|&&(bx, by)| !data_a.hidden_set.contains(&cell_key(bx, by)))
+                    .copied()
+                    .collect();
+
+                let diff_mines = data_b.remaining - data_a.remaining;
+
+                if diff_mines == 0 && !diff.is_empty() {
+                    // All diff cells are safe
+                    for &(sx, sy) in &diff {
+                        simulate_reveal(grid, visible, flags, nc, sx, sy);
+                        for &(nnx, nny) in nc.get(sx, sy) {
+                            new_dirty.insert(cell_key(nnx, nny));
                         }
-                        progress = true;
-                    } else if diff_mines == diff.len() as i32 && !diff.is_empty() {
-                        // All diff cells are mines
-                        for &(mx, my) in &diff {
-                            if !flags.get(mx, my) {
-                                flags.set(mx, my, true);
-                                fc += 1;
-                                for &(nnx, nny) in nc.get(mx, my) {
-                                    new_dirty.insert(cell_key(nnx, nny));
-                                }
+                    }
+                    progress = true;
+                } else if diff_mines == diff.len() as i32 && !diff.is_empty() {
+                    // All diff cells are mines
+                    for &(mx, my) in &diff {
+                        if !flags.get(mx, my) {
+                            flags.set(mx, my, true);
+                            fc += 1;
+                            for &(nnx, nny) in nc.get(mx, my) {
+                                new_dirty.insert(cell_key(nnx, nny));
                             }
                         }
-                        progress = true;
                     }
+                    progress = true;
+                }
 
-                    if progress {
-                        return SubsetResult { progress, flag_count: fc, dirty_cells: new_dirty };
-                    }
+                if progress {
+                    return SubsetResult { progress, flag_count: fc, dirty_cells: new_dirty };
                 }
             }
         }
@@ -313,13 +591,14 @@ fn solve_by_gaussian_elimination(
     flags: &mut Flags,
     nc: &NeighborCache,
     flag_count: u32,
+    arena: Option<&mut SolverArena>,
 ) -> GaussianWrapperResult {
-    let frontier = get_frontier(visible, flags, nc);
-    if frontier.is_empty() {
-        return GaussianWrapperResult { progress: false, flag_count, changed_cells: vec![] };
-    }
-
-    let result = gaussian::solve(visible, flags, &frontier, nc);
+    let mut local = SolverArena::default();
+    let arena = arena.unwrap_or(&mut local);
+    let mut frontier = std::mem::take(&mut arena.frontier);
+    get_frontier_into(visible, flags, nc, &mut frontier);
+    let result = gaussian::solve(visible, flags, &frontier, nc, Some(&mut *arena));
+    arena.frontier = frontier;
     if !result.progress {
         return GaussianWrapperResult { progress: false, flag_count, changed_cells: vec![] };
     }
@@ -337,7 +616,7 @@ fn solve_by_gaussian_elimination(
 
     for &(sx, sy) in &result.safe {
         if visible.get(sx, sy) == -1 {
-            simulate_reveal(grid, visible, flags, sx, sy);
+            simulate_reveal(grid, visible, flags, nc, sx, sy);
             changed.push((sx, sy));
         }
     }
@@ -347,51 +626,101 @@ fn solve_by_gaussian_elimination(
 
 // ─── Strategy 4: Proof by Contradiction ─────────────────────────────────────
 
+/// How far a contradiction or tank pass may run before it stops early.
+struct PassLimit {
+    /// Absolute `now_ms()` time, checked before each frontier cell or region.
+    deadline: Option<f64>,
+    /// Frontier cells or regions left to examine; one is spent per item.
+    units: usize,
+}
+
+/// Why a pass stopped before examining every item.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum PassStop {
+    TimedOut,
+    /// Out of units; resume at this item index.
+    Paused(usize),
+}
+
+impl PassLimit {
+    fn unbounded(deadline: Option<f64>) -> Self {
+        Self { deadline, units: usize::MAX }
+    }
+
+    /// Spend a unit on item `index`, or say why the pass must stop first.
+    fn spend(&mut self, index: usize) -> Option<PassStop> {
+        if self.deadline.is_some_and(|d| now_ms() >= d) {
+            return Some(PassStop::TimedOut);
+        }
+        if self.units == 0 {
+            return Some(PassStop::Paused(index));
+        }
+        self.units -= 1;
+        None
+    }
+}
+
 struct ContradictionResult {
     progress: bool,
     flag_count: u32,
     changed_cell: Option<(usize, usize)>,
+    stopped: Option<PassStop>,
 }
 
+/// Test frontier cells from index `start` on, as far as `limit` allows.
+/// The position only changes when a cell is decided, so a paused pass can
+/// be resumed later at the index it reports.
+#[allow(clippy::too_many_arguments)]
 fn solve_by_contradiction(
     grid: &Grid,
     visible: &mut VisibleGrid,
     flags: &mut Flags,
     nc: &NeighborCache,
     flag_count: u32,
+    deep: bool,
+    start: usize,
+    limit: &mut PassLimit,
 ) -> ContradictionResult {
     let frontier = get_frontier(visible, flags, nc);
     let max_check = frontier.len().min(50);
 
-    for i in 0..max_check {
-        let (cx, cy) = frontier[i];
+    for (i, &(cx, cy)) in frontier.iter().enumerate().take(max_check).skip(start) {
+        if let Some(stop) = limit.spend(i) {
+            return ContradictionResult { progress: false, flag_count, changed_cell: None, stopped: Some(stop) };
+        }
 
         // Test: assume cell IS a mine → contradiction means cell is SAFE
-        if check_contradiction(visible, flags, nc, cx, cy, true) {
-            simulate_reveal(grid, visible, flags, cx, cy);
+        if check_contradiction(visible, flags, nc, cx, cy, true, deep) {
+            simulate_reveal(grid, visible, flags, nc, cx, cy);
             return ContradictionResult {
                 progress: true,
                 flag_count,
                 changed_cell: Some((cx, cy)),
+                stopped: None,
             };
         }
 
         // Test: assume cell is NOT a mine → contradiction means cell IS a mine
-        if check_contradiction(visible, flags, nc, cx, cy, false) {
+        if check_contradiction(visible, flags, nc, cx, cy, false, deep) {
             flags.set(cx, cy, true);
             return ContradictionResult {
                 progress: true,
                 flag_count: flag_count + 1,
                 changed_cell: Some((cx, cy)),
+                stopped: None,
             };
         }
     }
 
-    ContradictionResult { progress: false, flag_count, changed_cell: None }
+    ContradictionResult { progress: false, flag_count, changed_cell: None, stopped: None }
 }
 
 /// Check if assuming a cell is/isn't a mine leads to a contradiction.
 /// Uses sparse simulation with HashMap overlays (no full grid copies).
+///
+/// With `deep`, each time the counting propagation stalls one round of subset
+/// reasoning runs over the clues it examined; any deduction that round makes
+/// restarts the propagation.
 fn check_contradiction(
     visible: &VisibleGrid,
     flags: &Flags,
@@ -399,6 +728,7 @@ fn check_contradiction(
     ax: usize,
     ay: usize,
     assume_mine: bool,
+    deep: bool,
 ) -> bool {
     let mut sim_flags: HashMap<u32, bool> = HashMap::new();
     let mut sim_revealed: HashSet<u32> = HashSet::new();
@@ -422,6 +752,7 @@ fn check_contradiction(
     let mut changed = true;
     let mut iterations = 0;
     let max_iterations = 20;
+    let mut examined: HashSet<u32> = HashSet::new();
 
     while changed && iterations < max_iterations {
         changed = false;
@@ -433,6 +764,11 @@ fn check_contradiction(
             let (x, y) = decode_key(key);
             let val = visible.get(x, y);
             if val <= 0 { continue; }
+            // A clue the real flags already break proves nothing about the hypothesis
+            if is_over_flagged(visible, flags, nc, x, y) { continue; }
+            if deep {
+                examined.insert(key);
+            }
 
             let neighbors = nc.get(x, y);
             let mut hidden_count = 0i32;
@@ -480,33 +816,257 @@ fn check_contradiction(
                 }
             }
         }
+
+        if !changed && deep && iterations < max_iterations {
+            match subset_round(visible, flags, nc, &examined, &mut sim_flags, &mut sim_revealed) {
+                None => return true,
+                Some(deduced) => {
+                    for key in deduced {
+                        let (dx, dy) = decode_key(key);
+                        for &(nx, ny) in nc.get(dx, dy) {
+                            to_check.insert(cell_key(nx, ny));
+                        }
+                        changed = true;
+                    }
+                }
+            }
+        }
     }
 
     false
 }
 
+/// One round of subset reasoning over the `clues` of a contradiction
+/// hypothesis, applied to its overlays.
+///
+/// For clues A and B where A's unknown neighbors are a subset of B's, the
+/// cells only B sees hold exactly B's remaining mines minus A's. Returns the
+/// newly settled cells, or `None` if some pair is inconsistent.
+fn subset_round(
+    visible: &VisibleGrid,
+    flags: &Flags,
+    nc: &NeighborCache,
+    clues: &HashSet<u32>,
+    sim_flags: &mut HashMap<u32, bool>,
+    sim_revealed: &mut HashSet<u32>,
+) -> Option<Vec<u32>> {
+    // Unknown neighbors (sorted keys) and mines still owed by the clue at `key`
+    let clue_state = |key: u32, sf: &HashMap<u32, bool>, sr: &HashSet<u32>| -> (Vec<u32>, i32) {
+        let (x, y) = decode_key(key);
+        let mut unknown = Vec::new();
+        let mut remaining = visible.get(x, y) as i32;
+        for &(nx, ny) in nc.get(x, y) {
+            let k = cell_key(nx, ny);
+            if sf.get(&k).copied().unwrap_or_else(|| flags.get(nx, ny)) {
+                remaining -= 1;
+            } else if visible.get(nx, ny) == -1 && !sr.contains(&k) {
+                unknown.push(k);
+            }
+        }
+        unknown.sort_unstable();
+        (unknown, remaining)
+    };
+
+    let mut deduced = Vec::new();
+    for &a in clues {
+        let (a_unknown, a_remaining) = clue_state(a, sim_flags, sim_revealed);
+        if a_unknown.is_empty() { continue; }
+
+        // Any clue sharing an unknown cell with A
+        let mut partners: Vec<u32> = Vec::new();
+        for &k in &a_unknown {
+            let (ux, uy) = decode_key(k);
+            for &(bx, by) in nc.get(ux, uy) {
+                let b = cell_key(bx, by);
+                if b != a && visible.get(bx, by) > 0 && !partners.contains(&b) && !is_over_flagged(visible, flags, nc, bx, by) {
+                    partners.push(b);
+                }
+            }
+        }
+
+        for b in partners {
+            let (b_unknown, b_remaining) = clue_state(b, sim_flags, sim_revealed);
+            if !a_unknown.iter().all(|k| b_unknown.binary_search(k).is_ok()) { continue; }
+
+            let only_b: Vec<u32> = b_unknown.iter().copied().filter(|k| a_unknown.binary_search(k).is_err()).collect();
+            let owed = b_remaining - a_remaining;
+            if owed < 0 || owed > only_b.len() as i32 { return None; }
+            if only_b.is_empty() { continue; }
+
+            if owed == 0 {
+                for k in only_b {
+                    sim_revealed.insert(k);
+                    deduced.push(k);
+                }
+            } else if owed == only_b.len() as i32 {
+                for k in only_b {
+                    sim_flags.insert(k, true);
+                    deduced.push(k);
+                }
+            }
+        }
+    }
+    Some(deduced)
+}
+
 // ─── Strategy 5: Tank Solver ────────────────────────────────────────────────
 
 struct TankResult {
     progress: bool,
     flag_count: u32,
     changed_cells: Vec<(usize, usize)>,
+    stopped: Option<PassStop>,
+}
+
+/// Bounded LRU of tank results keyed by `constraint_signature()`.
+///
+/// Values are `(definite_mines, definite_safes)` bitmasks over region indices.
+/// Recency is a logical clock; eviction scans for the oldest entry, which is
+/// cheap next to the enumeration a hit saves.
+struct TankCache {
+    entries: HashMap<Vec<u32>, (u32, u32, u64)>,
+    clock: u64,
+    hits: u64,
+    misses: u64,
+}
+
+impl TankCache {
+    fn new() -> Self {
+        Self { entries: HashMap::new(), clock: 0, hits: 0, misses: 0 }
+    }
+
+    fn get(&mut self, key: &[u32]) -> Option<(u32, u32)> {
+        self.clock += 1;
+        let clock = self.clock;
+        match self.entries.get_mut(key) {
+            Some(entry) => {
+                entry.2 = clock;
+                self.hits += 1;
+                Some((entry.0, entry.1))
+            }
+            None => {
+                self.misses += 1;
+                None
+            }
+        }
+    }
+
+    fn insert(&mut self, key: Vec<u32>, masks: (u32, u32)) {
+        if self.entries.len() >= TANK_CACHE_CAPACITY {
+            let oldest = self.entries.iter().min_by_key(|(_, e)| e.2).map(|(k, _)| k.clone());
+            if let Some(k) = oldest {
+                self.entries.remove(&k);
+            }
+        }
+        self.entries.insert(key, (masks.0, masks.1, self.clock));
+    }
+}
+
+thread_local! {
+    /// Shared across solves and generation attempts; WASM runs single-threaded.
+    static TANK_CACHE: RefCell<TankCache> = RefCell::new(TankCache::new());
+}
+
+/// `(hits, misses)` of the tank cache on this thread since the last clear.
+pub fn tank_cache_stats() -> (u64, u64) {
+    TANK_CACHE.with(|c| {
+        let c = c.borrow();
+        (c.hits, c.misses)
+    })
+}
+
+/// Drop all cached tank results and reset the hit/miss counters.
+pub fn clear_tank_cache() {
+    TANK_CACHE.with(|c| *c.borrow_mut() = TankCache::new());
+}
+
+/// Position-independent key for a region's enumeration: region size, the
+/// effective mine budget, the clue tolerance, and every constraint's shape
+/// (sorted). Equal keys enumerate to identical definite-cell masks.
+fn constraint_signature(
+    region_len: usize,
+    constraints: &[RegionConstraint],
+    max_mines: usize,
+    tolerance: i32,
+) -> Vec<u32> {
+    // A budget at or above the region size never prunes anything
+    let mut sig = vec![region_len as u32, max_mines.min(region_len) as u32, tolerance as u32];
+
+    let mut parts: Vec<Vec<u32>> = constraints.iter().map(|c| {
+        let mut part = vec![c.remaining as u32, c.cells_outside_count as u32];
+        let mut idx: Vec<u32> = c.cells_in_region_indices.iter().map(|&i| i as u32).collect();
+        idx.sort_unstable();
+        part.extend(idx);
+        part.push(u32::MAX);
+        part
+    }).collect();
+    parts.sort();
+    for part in parts { sig.extend(part); }
+
+    sig
+}
+
+/// Definite mine / safe bitmasks for a region, served from the tank cache
+/// when the same constraint signature was enumerated before.
+fn cached_definite_masks(
+    region: &[(usize, usize)],
+    constraints: &[RegionConstraint],
+    max_mines: usize,
+    tolerance: i32,
+) -> (u32, u32) {
+    let key = constraint_signature(region.len(), constraints, max_mines, tolerance);
+    if let Some(masks) = TANK_CACHE.with(|c| c.borrow_mut().get(&key)) {
+        return masks;
+    }
+
+    let valid_configs = enumerate_configurations_with_tolerance(region, constraints, max_mines, tolerance);
+    let mut masks = (0u32, 0u32);
+    if !valid_configs.is_empty() {
+        let (definite_mines, definite_safes) = analyze_configurations(region, &valid_configs);
+        masks = (cells_to_mask(region, &definite_mines), cells_to_mask(region, &definite_safes));
+    }
+
+    TANK_CACHE.with(|c| c.borrow_mut().insert(key, masks));
+    masks
+}
+
+/// Bitmask of `cells` by their index in `region`.
+fn cells_to_mask(region: &[(usize, usize)], cells: &[(usize, usize)]) -> u32 {
+    region.iter().enumerate().filter(|(_, c)| cells.contains(c)).fold(0, |acc, (i, _)| acc | (1 << i))
 }
 
+/// Region cells whose bit is set in `mask`.
+fn mask_to_cells(region: &[(usize, usize)], mask: u32) -> Vec<(usize, usize)> {
+    region.iter().enumerate().filter(|&(i, _)| (mask >> i) & 1 == 1).map(|(_, &c)| c).collect()
+}
+
+/// Enumerate regions, smallest first, from index `start` on, as far as
+/// `limit` allows. Like the contradiction pass, a paused tank pass resumes
+/// at the region index it reports.
+#[allow(clippy::too_many_arguments)]
 fn tank_solver(
     grid: &Grid,
     visible: &mut VisibleGrid,
     flags: &mut Flags,
     nc: &NeighborCache,
-    bomb_count: usize,
+    bomb_count: Option<usize>,
     flag_count: u32,
+    tolerance: i32,
+    arena: Option<&mut SolverArena>,
+    start: usize,
+    limit: &mut PassLimit,
 ) -> TankResult {
-    let frontier = get_frontier(visible, flags, nc);
-    if frontier.is_empty() {
-        return TankResult { progress: false, flag_count, changed_cells: vec![] };
+    let mut local = SolverArena::default();
+    let arena = arena.unwrap_or(&mut local);
+    let mut frontier = std::mem::take(&mut arena.frontier);
+    get_frontier_into(visible, flags, nc, &mut frontier);
+    let regions = group_frontier_regions(&frontier, visible, nc, Some(&mut *arena));
+    let empty = frontier.is_empty();
+    arena.frontier = frontier;
+    if empty {
+        return TankResult { progress: false, flag_count, changed_cells: vec![], stopped: None };
     }
 
-    let regions = group_frontier_regions(&frontier, visible, nc);
     // Sort smallest regions first
     let mut sorted_regions = regions;
     sorted_regions.sort_by_key(|r| r.len());
@@ -514,19 +1074,30 @@ fn tank_solver(
     let mut fc = flag_count;
     let mut changed = Vec::new();
 
-    for region in &sorted_regions {
+    for (i, region) in sorted_regions.iter().enumerate().skip(start) {
+        if let Some(stop) = limit.spend(i) {
+            return TankResult { progress: false, flag_count: fc, changed_cells: changed, stopped: Some(stop) };
+        }
         if region.len() > MAX_REGION_SIZE { continue; }
 
-        let constraints = get_region_constraints(region, visible, flags, nc);
+        let constraints = get_region_constraints(region, visible, flags, nc, Some(&mut *arena));
         if constraints.is_empty() { continue; }
 
-        let remaining_mines = bomb_count as i32 - fc as i32;
-        if remaining_mines < 0 { continue; }
+        // Without a known mine count, any number of mines fits the region
+        let max_mines = match bomb_count {
+            Some(total) => {
+                let remaining_mines = total as i32 - fc as i32;
+                if remaining_mines < 0 { continue; }
+                remaining_mines as usize
+            }
+            None => region.len(),
+        };
 
-        let valid_configs = enumerate_configurations(region, &constraints, remaining_mines as usize);
-        if valid_configs.is_empty() { continue; }
+        let (mine_mask, safe_mask) = cached_definite_masks(region, &constraints, max_mines, tolerance);
+        if mine_mask == 0 && safe_mask == 0 { continue; }
 
-        let (definite_mines, definite_safes) = analyze_configurations(region, &valid_configs);
+        let definite_mines = mask_to_cells(region, mine_mask);
+        let definite_safes = mask_to_cells(region, safe_mask);
 
         let mut progress = false;
 
@@ -541,32 +1112,81 @@ fn tank_solver(
 
         for &(sx, sy) in &definite_safes {
             if visible.get(sx, sy) == -1 {
-                simulate_reveal(grid, visible, flags, sx, sy);
+                simulate_reveal(grid, visible, flags, nc, sx, sy);
                 changed.push((sx, sy));
                 progress = true;
             }
         }
 
         if progress {
-            return TankResult { progress: true, flag_count: fc, changed_cells: changed };
+            return TankResult { progress: true, flag_count: fc, changed_cells: changed, stopped: None };
+        }
+    }
+
+    TankResult { progress: false, flag_count: fc, changed_cells: changed, stopped: None }
+}
+
+/// Per-region mine probabilities for the tank's stuck position: in each
+/// frontier region small enough to enumerate, the fraction of its valid
+/// configurations with a mine on each cell.
+///
+/// Unlike `probability::exact_mine_probabilities()`, configurations are not
+/// weighted by how they split the remaining mines with the rest of the
+/// board, so this is only an approximation, but a cheap one for a quick risk
+/// overlay. `bomb_count` only caps the mines a region may hold. Sorted by `(x, y)`.
+pub fn tank_probabilities(
+    visible: &VisibleGrid,
+    flags: &Flags,
+    nc: &NeighborCache,
+    bomb_count: Option<usize>,
+) -> Vec<(usize, usize, f32)> {
+    let frontier = get_frontier(visible, flags, nc);
+    let flag_count = flags.as_flat().iter().filter(|&&f| f != 0).count();
+    let max_mines = match bomb_count {
+        Some(total) if total < flag_count => return vec![],
+        Some(total) => total - flag_count,
+        None => usize::MAX,
+    };
+
+    let mut probabilities = Vec::new();
+    for region in group_frontier_regions(&frontier, visible, nc, None) {
+        if region.len() > MAX_REGION_SIZE { continue; }
+        let constraints = get_region_constraints(&region, visible, flags, nc, None);
+        if constraints.is_empty() { continue; }
+
+        let valid_configs = enumerate_configurations_with_tolerance(&region, &constraints, max_mines, 0);
+        if valid_configs.is_empty() { continue; }
+        let total = valid_configs.len() as f32;
+        for (i, &(x, y)) in region.iter().enumerate() {
+            let mine_configs = valid_configs.iter().filter(|&&m| (m >> i) & 1 == 1).count();
+            probabilities.push((x, y, mine_configs as f32 / total));
         }
     }
 
-    TankResult { progress: false, flag_count: fc, changed_cells: changed }
+    probabilities.sort_unstable_by_key(|&(x, y, _)| (x, y));
+    probabilities
 }
 
 /// Group frontier cells into connected regions.
-fn group_frontier_regions(
+///
+/// Each region's cells are sorted by `(x, y)` and the regions by their first
+/// cell, so configuration mask bit `i` maps to the same cell on every run
+/// regardless of frontier or traversal order.
+pub(crate) fn group_frontier_regions(
     frontier: &[(usize, usize)],
     visible: &VisibleGrid,
     nc: &NeighborCache,
+    arena: Option<&mut SolverArena>,
 ) -> Vec<Vec<(usize, usize)>> {
     if frontier.is_empty() { return vec![]; }
 
-    let mut regions = Vec::new();
-    let mut visited: HashSet<u32> = HashSet::new();
-    let mut frontier_set: HashSet<u32> = HashSet::new();
+    let mut local = SolverArena::default();
+    let arena = arena.unwrap_or(&mut local);
+    let SolverArena { queue, visited, members: frontier_set, seen: queue_set, .. } = arena;
+    visited.clear();
+    frontier_set.clear();
 
+    let mut regions = Vec::new();
     for &(x, y) in frontier {
         frontier_set.insert(cell_key(x, y));
     }
@@ -576,8 +1196,9 @@ fn group_frontier_regions(
         if visited.contains(&start_key) { continue; }
 
         let mut region = Vec::new();
-        let mut queue = vec![(sx, sy)];
-        let mut queue_set: HashSet<u32> = HashSet::new();
+        queue.clear();
+        queue.push((sx, sy));
+        queue_set.clear();
         queue_set.insert(start_key);
 
         while let Some((cx, cy)) = queue.pop() {
@@ -601,32 +1222,38 @@ fn group_frontier_regions(
         }
 
         if !region.is_empty() {
+            region.sort_unstable();
             regions.push(region);
         }
     }
 
+    regions.sort_unstable_by_key(|r| r[0]);
     regions
 }
 
 /// Constraint for a region: a revealed number cell with its relationship to the region.
-struct RegionConstraint {
-    remaining: i32,
-    cells_in_region_indices: Vec<usize>,
-    cells_outside_count: usize,
+pub(crate) struct RegionConstraint {
+    pub(crate) remaining: i32,
+    pub(crate) cells_in_region_indices: Vec<usize>,
+    pub(crate) cells_outside_count: usize,
 }
 
-fn get_region_constraints(
+pub(crate) fn get_region_constraints(
     region: &[(usize, usize)],
     visible: &VisibleGrid,
     flags: &Flags,
     nc: &NeighborCache,
+    arena: Option<&mut SolverArena>,
 ) -> Vec<RegionConstraint> {
-    let mut constraint_set: HashSet<u32> = HashSet::new();
-    let mut constraints = Vec::new();
+    let mut local = SolverArena::default();
+    let arena = arena.unwrap_or(&mut local);
+    let SolverArena { seen: constraint_set, index_of, .. } = arena;
+    constraint_set.clear();
+    index_of.clear();
 
-    let mut region_set: HashSet<u32> = HashSet::new();
-    for &(rx, ry) in region {
-        region_set.insert(cell_key(rx, ry));
+    let mut constraints = Vec::new();
+    for (i, &(rx, ry)) in region.iter().enumerate() {
+        index_of.entry(cell_key(rx, ry)).or_insert(i);
     }
 
     for &(cx, cy) in region {
@@ -644,21 +1271,15 @@ fn get_region_constraints(
                 if flags.get(cnx, cny) {
                     flagged_count += 1;
                 } else if visible.get(cnx, cny) == -1 {
-                    let cnk = cell_key(cnx, cny);
-                    if region_set.contains(&cnk) {
-                        // Find index in region
-                        for (i, &(rx, ry)) in region.iter().enumerate() {
-                            if rx == cnx && ry == cny {
-                                cells_in_region_indices.push(i);
-                                break;
-                            }
-                        }
-                    } else {
-                        cells_outside_count += 1;
+                    match index_of.get(&cell_key(cnx, cny)) {
+                        Some(&i) => cells_in_region_indices.push(i),
+                        None => cells_outside_count += 1,
                     }
                 }
             }
 
+            // Over-flagged clues constrain nothing (a flag is wrong)
+            if flagged_count > val as i32 { continue; }
             constraints.push(RegionConstraint {
                 remaining: val as i32 - flagged_count,
                 cells_in_region_indices,
@@ -670,19 +1291,34 @@ fn get_region_constraints(
     constraints
 }
 
-/// Enumerate all valid mine configurations using bit masks.
-fn enumerate_configurations(
+/// Enumerate all valid mine configurations using bit masks, where each
+/// constraint's `remaining` may be off by up to `tolerance`.
+fn enumerate_configurations_with_tolerance(
     region: &[(usize, usize)],
     constraints: &[RegionConstraint],
     max_mines: usize,
+    tolerance: i32,
 ) -> Vec<u32> {
+    let mut valid_masks = Vec::new();
+    enumerate_configurations_into(region, constraints, max_mines, tolerance, &mut valid_masks);
+    valid_masks
+}
+
+/// `enumerate_configurations_with_tolerance()` into a cleared `valid_masks`,
+/// reusing its allocation.
+pub(crate) fn enumerate_configurations_into(
+    region: &[(usize, usize)],
+    constraints: &[RegionConstraint],
+    max_mines: usize,
+    tolerance: i32,
+    valid_masks: &mut Vec<u32>,
+) {
+    valid_masks.clear();
     let total_combinations: u64 = 1u64 << region.len();
     if total_combinations > MAX_CONFIGURATIONS {
-        return vec![];
+        return;
     }
 
-    let mut valid_masks = Vec::new();
-
     for mask in 0..total_combinations as u32 {
         let mine_count = mask.count_ones() as usize;
         if mine_count > max_mines { continue; }
@@ -695,8 +1331,11 @@ fn enumerate_configurations(
                     mines_in_region += 1;
                 }
             }
-            let mines_needed_outside = c.remaining - mines_in_region;
-            if mines_needed_outside < 0 || mines_needed_outside > c.cells_outside_count as i32 {
+            // Some count in [remaining - tolerance, remaining + tolerance] must be
+            // reachable with 0..=cells_outside_count mines outside the region
+            let max_needed_outside = c.remaining + tolerance - mines_in_region;
+            let min_needed_outside = c.remaining - tolerance - mines_in_region;
+            if max_needed_outside < 0 || min_needed_outside > c.cells_outside_count as i32 {
                 valid = false;
                 break;
             }
@@ -706,19 +1345,20 @@ fn enumerate_configurations(
             valid_masks.push(mask);
         }
     }
+}
 
-    valid_masks
-}
+/// Cells proven to be mines and cells proven to be safe, in that order.
+pub type DefiniteCells = (Vec<(usize, usize)>, Vec<(usize, usize)>);
 
 /// Analyze configurations to find cells that are ALWAYS mine or ALWAYS safe.
 fn analyze_configurations(
     region: &[(usize, usize)],
     valid_masks: &[u32],
-) -> (Vec<(usize, usize)>, Vec<(usize, usize)>) {
+) -> DefiniteCells {
     let mut definite_mines = Vec::new();
     let mut definite_safes = Vec::new();
 
-    for i in 0..region.len() {
+    for (i, &cell) in region.iter().enumerate() {
         let mut always_mine = true;
         let mut always_safe = true;
 
@@ -727,8 +1367,8 @@ fn analyze_configurations(
             if (mask >> i) & 1 == 1 { always_safe = false; }
         }
 
-        if always_mine { definite_mines.push(region[i]); }
-        if always_safe { definite_safes.push(region[i]); }
+        if always_mine { definite_mines.push(cell); }
+        if always_safe { definite_safes.push(cell); }
     }
 
     (definite_mines, definite_safes)
@@ -746,9 +1386,15 @@ fn apply_global_mine_count(
     visible: &mut VisibleGrid,
     flags: &mut Flags,
     nc: &NeighborCache,
-    bomb_count: usize,
+    bomb_count: Option<usize>,
     flag_count: u32,
 ) -> GlobalResult {
+    // Global counting needs the total mine count
+    let bomb_count = match bomb_count {
+        Some(total) => total,
+        None => return GlobalResult { progress: false, flag_count },
+    };
+
     let width = grid.width;
     let height = grid.height;
     let mut hidden_cells: Vec<(usize, usize)> = Vec::new();
@@ -775,7 +1421,7 @@ fn apply_global_mine_count(
         } else if remaining_mines == 0 {
             // All hidden cells are safe
             for &(hx, hy) in &hidden_cells {
-                simulate_reveal(grid, visible, flags, hx, hy);
+                simulate_reveal(grid, visible, flags, nc, hx, hy);
             }
             return GlobalResult { progress: true, flag_count: fc };
         }
@@ -787,242 +1433,5563 @@ fn apply_global_mine_count(
     GlobalResult { progress: false, flag_count: fc }
 }
 
-// ─── Top-level: is_solvable ─────────────────────────────────────────────────
+// ─── Solve state ────────────────────────────────────────────────────────────
+
+/// The strategy that made progress in one solver iteration.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Strategy {
+    Basic = 1,
+    Subset = 2,
+    Gaussian = 3,
+    Contradiction = 4,
+    Tank = 5,
+    GlobalCount = 6,
+}
 
-/// Check if a board is solvable without guessing from the given start position.
+/// Deterministic work counts from a solve, for regression tests that assert
+/// the solver did not start doing more work.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SolveMetrics {
+    /// Calls to `SolveState::step()`, including the final one that stalled.
+    pub iterations: u32,
+    /// Times each strategy ran, indexed by `Strategy as usize - 1`.
+    pub invocations: [u32; 6],
+    /// Times each strategy made progress, indexed like `invocations`.
+    pub successes: [u32; 6],
+}
+
+impl SolveMetrics {
+    /// Times `strategy` ran.
+    pub fn invocations_of(&self, strategy: Strategy) -> u32 {
+        self.invocations[strategy as usize - 1]
+    }
+
+    /// Times `strategy` made progress.
+    pub fn successes_of(&self, strategy: Strategy) -> u32 {
+        self.successes[strategy as usize - 1]
+    }
+
+    fn record(&mut self, strategy: Strategy, progress: bool) {
+        self.invocations[strategy as usize - 1] += 1;
+        if progress {
+            self.successes[strategy as usize - 1] += 1;
+        }
+    }
+
+    /// Add the counts of a later solve on.
+    fn absorb(&mut self, other: &SolveMetrics) {
+        self.iterations += other.iterations;
+        for i in 0..6 {
+            self.invocations[i] += other.invocations[i];
+            self.successes[i] += other.successes[i];
+        }
+    }
+}
+
+/// Outcome of one `SolveState::step_slice()`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Slice {
+    Progress(Strategy),
+    /// Every strategy is stuck, or the deadline ran out.
+    Stalled,
+    /// Out of work units partway through a pass.
+    Paused,
+}
+
+/// Solver-side view of a board: what has been revealed/flagged so far, plus
+/// the dirty set that steers Strategies 1 and 2.
 ///
-/// This is the main entry point, mirroring `MinesweeperSolver.isSolvable()`.
-/// Simulates revealing the 3×3 safe zone, then iteratively applies all strategies.
-pub fn is_solvable(
-    grid: &Grid,
-    mines: &Mines,
-    nc: &NeighborCache,
-    start_x: usize,
-    start_y: usize,
-) -> bool {
-    let width = grid.width;
-    let height = grid.height;
-    let bomb_count = mines.count();
+/// `is_solvable()` drives one of these to a stall; keeping it as a value lets
+/// other entry points stop early or inspect the position where logic ran out.
+pub struct SolveState {
+    pub visible: VisibleGrid,
+    pub flags: Flags,
+    pub flag_count: u32,
+    pub config: SolverConfig,
+    pub metrics: SolveMetrics,
+    /// Set when `run_to_stall()` stopped because `config.deadline_ms` ran out.
+    pub timed_out: bool,
+    /// Absolute `now_ms()` deadline of the running `run_to_stall()`, if any.
+    deadline: Option<f64>,
+    /// Where a paused `step_slice()` picks up: the pass and its item index.
+    pending: Option<(Strategy, usize)>,
+    dirty_cells: HashSet<u32>,
+    /// Scratch buffers for the tank and Gaussian strategies, kept across steps.
+    arena: SolverArena,
+}
+
+impl SolveState {
+    /// Fresh state with the 3×3 safe zone around `(start_x, start_y)` revealed.
+    pub fn from_start(grid: &Grid, nc: &NeighborCache, start_x: usize, start_y: usize) -> Self {
+        Self::from_zones(grid, nc, &[SafeZone::new(start_x, start_y, 1)])
+    }
 
-    let mut visible = VisibleGrid::new(width, height);
-    let mut flags = Flags::new(width, height);
-    let mut flag_count: u32 = 0;
+    /// Fresh state with every cell of every zone revealed (multi-start boards).
+    pub fn from_zones(grid: &Grid, nc: &NeighborCache, zones: &[SafeZone]) -> Self {
+        let rects: Vec<SafeRect> = zones.iter().map(|&z| z.into()).collect();
+        Self::from_rects(grid, nc, &rects)
+    }
+
+    /// Fresh state with every in-bounds cell of every rectangle revealed.
+    /// `BLOCKED` cells of the grid start out as `BLOCKED` in the visible grid.
+    pub fn from_rects(grid: &Grid, nc: &NeighborCache, rects: &[SafeRect]) -> Self {
+        let mut visible = VisibleGrid::new(grid.width, grid.height);
+        let flags = Flags::new(grid.width, grid.height);
+        for (v, &g) in visible.cells.iter_mut().zip(&grid.cells) {
+            if g == BLOCKED { *v = BLOCKED; }
+        }
 
-    // Reveal 3×3 safe zone around start
-    for dx in -1i32..=1 {
-        for dy in -1i32..=1 {
-            let sx = start_x as i32 + dx;
-            let sy = start_y as i32 + dy;
-            if sx >= 0 && sx < width as i32 && sy >= 0 && sy < height as i32 {
-                simulate_reveal(grid, &mut visible, &flags, sx as usize, sy as usize);
+        for rect in rects {
+            let x_max = rect.x1.min(grid.width.saturating_sub(1));
+            let y_max = rect.y1.min(grid.height.saturating_sub(1));
+            for sx in rect.x0..=x_max {
+                for sy in rect.y0..=y_max {
+                    simulate_reveal(grid, &mut visible, &flags, nc, sx, sy);
+                }
             }
         }
+
+        Self::from_position(&visible, &flags, nc)
     }
 
-    // Build initial dirty cells
-    let mut dirty_cells: HashSet<u32> = HashSet::new();
-    for x in 0..width {
-        for y in 0..height {
-            if visible.get(x, y) != -1 {
-                dirty_cells.insert(cell_key(x, y));
-                for &(nx, ny) in nc.get(x, y) {
-                    dirty_cells.insert(cell_key(nx, ny));
+    /// Resume from an existing position. Every revealed cell starts dirty.
+    pub fn from_position(visible: &VisibleGrid, flags: &Flags, nc: &NeighborCache) -> Self {
+        let mut dirty_cells: HashSet<u32> = HashSet::new();
+        for x in 0..visible.width {
+            for y in 0..visible.height {
+                if visible.get(x, y) != -1 {
+                    dirty_cells.insert(cell_key(x, y));
+                    for &(nx, ny) in nc.get(x, y) {
+                        dirty_cells.insert(cell_key(nx, ny));
+                    }
                 }
             }
         }
+
+        let flag_count = flags.cells.iter().filter(|&&f| f != 0).count() as u32;
+        Self {
+            visible: visible.clone(),
+            flags: flags.clone(),
+            flag_count,
+            config: SolverConfig::default(),
+            metrics: SolveMetrics::default(),
+            timed_out: false,
+            deadline: None,
+            pending: None,
+            dirty_cells,
+            arena: SolverArena::default(),
+        }
     }
 
-    let max_iterations = width * height * 2;
-    let mut progress = true;
-    let mut iterations = 0;
+    /// Resume from a position where only `changed` cells moved since the last
+    /// query: just they and their neighbors start dirty, so Strategies 1 and 2
+    /// look nowhere else until their own deductions spread the dirty set.
+    /// Out-of-bounds cells are ignored.
+    pub fn from_changes(
+        visible: &VisibleGrid,
+        flags: &Flags,
+        nc: &NeighborCache,
+        changed: &[(usize, usize)],
+    ) -> Self {
+        let flag_count = flags.cells.iter().filter(|&&f| f != 0).count() as u32;
+        let mut state = Self {
+            visible: visible.clone(),
+            flags: flags.clone(),
+            flag_count,
+            config: SolverConfig::default(),
+            metrics: SolveMetrics::default(),
+            timed_out: false,
+            deadline: None,
+            pending: None,
+            dirty_cells: HashSet::new(),
+            arena: SolverArena::default(),
+        };
+        let in_bounds: Vec<(usize, usize)> = changed.iter()
+            .copied()
+            .filter(|&(x, y)| visible.in_bounds(x, y))
+            .collect();
+        state.mark_dirty(&in_bounds, nc);
+        state
+    }
 
-    while progress && iterations < max_iterations {
-        progress = false;
-        iterations += 1;
+    /// Replace the solver configuration.
+    pub fn with_config(mut self, config: SolverConfig) -> Self {
+        self.config = config;
+        self
+    }
 
-        // Strategy 1: Basic counting rules (fast)
-        let basic = apply_basic_rules(grid, &mut visible, &mut flags, nc, &dirty_cells, flag_count);
-        if basic.progress {
-            progress = true;
-            flag_count = basic.flag_count;
-            dirty_cells = basic.dirty_cells;
-            continue;
+    /// Mark changed cells and their neighbors dirty.
+    fn mark_dirty(&mut self, cells: &[(usize, usize)], nc: &NeighborCache) {
+        for &(cx, cy) in cells {
+            self.dirty_cells.insert(cell_key(cx, cy));
+            for &(nx, ny) in nc.get(cx, cy) {
+                self.dirty_cells.insert(cell_key(nx, ny));
+            }
         }
+    }
 
-        // Strategy 2: Subset logic
-        let subset = apply_subset_logic(grid, &mut visible, &mut flags, nc, &dirty_cells, flag_count);
-        if subset.progress {
-            progress = true;
-            flag_count = subset.flag_count;
-            dirty_cells = subset.dirty_cells;
-            continue;
+    /// Run one iteration: try each strategy in order, cheapest first, and
+    /// return the first one that made progress, or `None` if all are stuck.
+    ///
+    /// `bomb_count` is the true mine count; it is only shown to the strategies
+    /// when `config.know_mine_count` is set.
+    pub fn step(&mut self, grid: &Grid, nc: &NeighborCache, bomb_count: usize) -> Option<Strategy> {
+        self.pending = None;
+        match self.step_slice(grid, nc, bomb_count, &mut PassLimit::unbounded(self.deadline)) {
+            Slice::Progress(strategy) => Some(strategy),
+            Slice::Stalled | Slice::Paused => None,
         }
+    }
 
-        // Strategy 3: Gaussian Elimination
-        let gauss = solve_by_gaussian_elimination(grid, &mut visible, &mut flags, nc, flag_count);
-        if gauss.progress {
-            progress = true;
-            flag_count = gauss.flag_count;
-            for &(cx, cy) in &gauss.changed_cells {
-                dirty_cells.insert(cell_key(cx, cy));
-                for &(nx, ny) in nc.get(cx, cy) {
-                    dirty_cells.insert(cell_key(nx, ny));
-                }
+    /// `step()` in bounded work: the cheap strategies together cost one of
+    /// `limit`'s units, and the contradiction and tank passes one per frontier
+    /// cell or region. A pass that runs out returns `Slice::Paused` and the
+    /// next call resumes it where it stopped, skipping the strategies before it.
+    fn step_slice(&mut self, grid: &Grid, nc: &NeighborCache, bomb_count: usize, limit: &mut PassLimit) -> Slice {
+        let known_count = if self.config.know_mine_count { Some(bomb_count) } else { None };
+        let tolerance = self.config.clue_tolerance as i32;
+        let max_level = self.config.max_strategy_level;
+        let (resume_at, start) = self.pending.take().unwrap_or((Strategy::Basic, 0));
+        let clue_kinds = self.config.clue_kinds.as_deref();
+        let weights = self.config.neighbor_weights.as_ref();
+
+        if resume_at == Strategy::Basic {
+            limit.units = limit.units.saturating_sub(1);
+            self.metrics.iterations += 1;
+
+            // Strategy 1: Basic counting rules (fast)
+            if max_level < Strategy::Basic as u8 { return Slice::Stalled; }
+            let basic = apply_basic_rules(
+                grid, &mut self.visible, &mut self.flags, nc, &self.dirty_cells, self.flag_count, tolerance, clue_kinds, weights,
+            );
+            self.metrics.record(Strategy::Basic, basic.progress);
+            if basic.progress {
+                self.flag_count = basic.flag_count;
+                self.dirty_cells = basic.dirty_cells;
+                return Slice::Progress(Strategy::Basic);
             }
-            continue;
         }
 
-        // Strategy 4: Proof by contradiction
-        let contra = solve_by_contradiction(grid, &mut visible, &mut flags, nc, flag_count);
-        if contra.progress {
-            progress = true;
-            flag_count = contra.flag_count;
-            if let Some((cx, cy)) = contra.changed_cell {
-                dirty_cells.insert(cell_key(cx, cy));
-                for &(nx, ny) in nc.get(cx, cy) {
-                    dirty_cells.insert(cell_key(nx, ny));
-                }
+        // Strategies 2-4 need exact clues; the tank can widen for tolerance
+        // but not for boolean clues. None of them know about weights.
+        let inexact_clues = clue_kinds.is_some() || weights.is_some();
+        if tolerance == 0 && !inexact_clues && resume_at <= Strategy::Contradiction {
+            if let Some(slice) = self.step_exact(grid, nc, max_level, resume_at, start, limit) {
+                return slice;
             }
-            continue;
         }
 
         // Strategy 5: Tank Solver
-        let tank = tank_solver(grid, &mut visible, &mut flags, nc, bomb_count, flag_count);
-        if tank.progress {
-            progress = true;
-            flag_count = tank.flag_count;
-            for &(cx, cy) in &tank.changed_cells {
-                dirty_cells.insert(cell_key(cx, cy));
-                for &(nx, ny) in nc.get(cx, cy) {
-                    dirty_cells.insert(cell_key(nx, ny));
-                }
+        if max_level < Strategy::Tank as u8 { return Slice::Stalled; }
+        if !inexact_clues {
+            let start = if resume_at == Strategy::Tank { start } else { 0 };
+            let tank = tank_solver(
+                grid, &mut self.visible, &mut self.flags, nc, known_count, self.flag_count, tolerance,
+                Some(&mut self.arena), start, limit,
+            );
+            if let Some(slice) = self.pass_stopped(Strategy::Tank, tank.stopped) {
+                return slice;
+            }
+            self.metrics.record(Strategy::Tank, tank.progress);
+            if tank.progress {
+                self.flag_count = tank.flag_count;
+                self.mark_dirty(&tank.changed_cells, nc);
+                return Slice::Progress(Strategy::Tank);
             }
-            continue;
         }
 
         // Strategy 6: Global mine counting
-        let global = apply_global_mine_count(grid, &mut visible, &mut flags, nc, bomb_count, flag_count);
+        if max_level < Strategy::GlobalCount as u8 { return Slice::Stalled; }
+        let global = apply_global_mine_count(grid, &mut self.visible, &mut self.flags, nc, known_count, self.flag_count);
+        self.metrics.record(Strategy::GlobalCount, global.progress);
         if global.progress {
-            progress = true;
-            flag_count = global.flag_count;
-            continue;
+            self.flag_count = global.flag_count;
+            return Slice::Progress(Strategy::GlobalCount);
         }
+
+        Slice::Stalled
     }
 
-    // Check if all non-mine cells are revealed
-    let mut revealed = 0usize;
-    for x in 0..width {
-        for y in 0..height {
-            if visible.get(x, y) != -1 {
-                revealed += 1;
+    /// Strategies 2-4, which rely on every clue being the exact neighbor count.
+    /// `None` means none of them made progress and the step goes on.
+    fn step_exact(
+        &mut self,
+        grid: &Grid,
+        nc: &NeighborCache,
+        max_level: u8,
+        resume_at: Strategy,
+        start: usize,
+        limit: &mut PassLimit,
+    ) -> Option<Slice> {
+        if resume_at < Strategy::Contradiction {
+            // Strategy 2: Subset logic
+            if max_level < Strategy::Subset as u8 { return Some(Slice::Stalled); }
+            let subset = apply_subset_logic(grid, &mut self.visible, &mut self.flags, nc, &self.dirty_cells, self.flag_count);
+            self.metrics.record(Strategy::Subset, subset.progress);
+            if subset.progress {
+                self.flag_count = subset.flag_count;
+                self.dirty_cells = subset.dirty_cells;
+                return Some(Slice::Progress(Strategy::Subset));
             }
+
+            // Strategy 3: Gaussian Elimination
+            if max_level < Strategy::Gaussian as u8 { return Some(Slice::Stalled); }
+            let gauss = solve_by_gaussian_elimination(grid, &mut self.visible, &mut self.flags, nc, self.flag_count, Some(&mut self.arena));
+            self.metrics.record(Strategy::Gaussian, gauss.progress);
+            if gauss.progress {
+                self.flag_count = gauss.flag_count;
+                self.mark_dirty(&gauss.changed_cells, nc);
+                return Some(Slice::Progress(Strategy::Gaussian));
+            }
+        }
+
+        // Strategy 4: Proof by contradiction
+        if max_level < Strategy::Contradiction as u8 { return Some(Slice::Stalled); }
+        let start = if resume_at == Strategy::Contradiction { start } else { 0 };
+        let contra = solve_by_contradiction(
+            grid, &mut self.visible, &mut self.flags, nc, self.flag_count, self.config.deep_contradiction,
+            start, limit,
+        );
+        if let Some(slice) = self.pass_stopped(Strategy::Contradiction, contra.stopped) {
+            return Some(slice);
+        }
+        self.metrics.record(Strategy::Contradiction, contra.progress);
+        if contra.progress {
+            self.flag_count = contra.flag_count;
+            if let Some(cell) = contra.changed_cell {
+                self.mark_dirty(&[cell], nc);
+            }
+            return Some(Slice::Progress(Strategy::Contradiction));
         }
+
+        None
     }
 
-    revealed == (width * height - bomb_count)
-}
+    /// Turn a pass that stopped early into the slice's outcome: a timeout
+    /// ends the solve, a pause is remembered so the next slice resumes it.
+    fn pass_stopped(&mut self, strategy: Strategy, stopped: Option<PassStop>) -> Option<Slice> {
+        match stopped? {
+            PassStop::TimedOut => {
+                self.timed_out = true;
+                Some(Slice::Stalled)
+            }
+            PassStop::Paused(next) => {
+                self.pending = Some((strategy, next));
+                Some(Slice::Paused)
+            }
+        }
+    }
 
-// ─── get_hint ───────────────────────────────────────────────────────────────
+    /// Step until no strategy makes progress (or the iteration cap or the
+    /// configured deadline is hit). Returns the number of successful iterations.
+    pub fn run_to_stall(&mut self, grid: &Grid, nc: &NeighborCache, bomb_count: usize) -> usize {
+        let max_iterations = grid.width * grid.height * 2;
+        let deadline = self.config.deadline_ms.map(|ms| now_ms() + ms);
+        self.deadline = deadline;
+        let mut iterations = 0;
+        while iterations < max_iterations {
+            if deadline.is_some_and(|d| now_ms() >= d) {
+                self.timed_out = true;
+                break;
+            }
+            if self.step(grid, nc, bomb_count).is_none() {
+                break;
+            }
+            iterations += 1;
+        }
+        self.deadline = None;
+        iterations
+    }
 
-/// Hint result for the UI.
-pub struct Hint {
-    pub x: usize,
-    pub y: usize,
-    pub score: i32,
+    /// Number of revealed cells.
+    pub fn revealed_count(&self) -> usize {
+        self.visible.cells.iter().filter(|&&v| v != -1).count()
+    }
+
+    /// True once every non-mine cell is revealed.
+    pub fn is_complete(&self, bomb_count: usize) -> bool {
+        self.revealed_count() == self.visible.width * self.visible.height - bomb_count
+    }
 }
 
-/// Find the best safe cell to reveal (God Mode / Best Move).
+// ─── Top-level: is_solvable ─────────────────────────────────────────────────
+
+/// Check if a board is solvable without guessing from the given start position.
 ///
-/// Prioritizes: frontier safe cells > zero cells > island safe cells.
-pub fn get_hint(
+/// This is the main entry point, mirroring `MinesweeperSolver.isSolvable()`.
+/// Simulates revealing the 3×3 safe zone, then iteratively applies all strategies.
+pub fn is_solvable(
     grid: &Grid,
-    visible: &VisibleGrid,
-    flags: &Flags,
     mines: &Mines,
     nc: &NeighborCache,
-) -> Option<Hint> {
-    let width = grid.width;
-    let height = grid.height;
+    start_x: usize,
+    start_y: usize,
+) -> bool {
+    is_solvable_with_config(grid, mines, nc, start_x, start_y, &SolverConfig::default())
+}
 
-    // Phase 1: Safe frontier cells (adjacent to revealed cells)
-    let mut safe_frontier: Vec<Hint> = Vec::new();
+/// `is_solvable()` with explicit solver options.
+pub fn is_solvable_with_config(
+    grid: &Grid,
+    mines: &Mines,
+    nc: &NeighborCache,
+    start_x: usize,
+    start_y: usize,
+    config: &SolverConfig,
+) -> bool {
+    let bomb_count = mines.count();
+    let mut state = SolveState::from_start(grid, nc, start_x, start_y).with_config(config.clone());
+    state.run_to_stall(grid, nc, bomb_count);
+    state.is_complete(bomb_count)
+}
 
-    for x in 0..width {
-        for y in 0..height {
-            if visible.get(x, y) == -1 && !flags.get(x, y) && !mines.get(x, y) {
-                let neighbors = nc.get(x, y);
-                let revealed_count = neighbors.iter()
-                    .filter(|&&(nx, ny)| visible.get(nx, ny) > -1)
-                    .count();
+/// `is_solvable()` that also reports how much work the solver did.
+pub fn is_solvable_instrumented(
+    grid: &Grid,
+    mines: &Mines,
+    nc: &NeighborCache,
+    start_x: usize,
+    start_y: usize,
+) -> (bool, SolveMetrics) {
+    let bomb_count = mines.count();
+    let mut state = SolveState::from_start(grid, nc, start_x, start_y);
+    state.run_to_stall(grid, nc, bomb_count);
+    (state.is_complete(bomb_count), state.metrics)
+}
 
-                if revealed_count > 0 {
-                    let mut score = revealed_count as i32;
-                    if grid.get(x, y) == 0 { score += 10; } // Prefer zeros (cascade)
-                    safe_frontier.push(Hint { x, y, score });
+/// Progress of a `ResumableSolver`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StepStatus {
+    /// More strategy work remains; call `step()` again.
+    InProgress,
+    /// Logic cleared the board.
+    Solvable,
+    /// Logic stalled with cells left.
+    Unsolvable,
+}
+
+/// `is_solvable_with_config()` split into bounded slices, so a UI thread can
+/// spread a large solve across animation frames.
+///
+/// Owns the board and a `SolveState`, whose visible grid, flags and dirty set
+/// carry over between `step()` calls. One unit of work is one round of the
+/// cheap strategies, or one frontier cell of the contradiction pass or one
+/// region of the tank, so no call runs a whole heavy pass; a pass cut short
+/// resumes on the next call. The iteration cap matches `run_to_stall()`, so
+/// driving it to the end gives the one-shot verdict. `config.deadline_ms` is
+/// ignored: the caller bounds the work instead.
+pub struct ResumableSolver {
+    grid: Grid,
+    nc: NeighborCache,
+    bomb_count: usize,
+    state: SolveState,
+    iterations: usize,
+    status: StepStatus,
+}
+
+impl ResumableSolver {
+    /// A solve from the 3×3 safe zone around `(start_x, start_y)`, not started yet.
+    pub fn new(grid: Grid, mines: &Mines, nc: NeighborCache, start_x: usize, start_y: usize, config: SolverConfig) -> Self {
+        let state = SolveState::from_start(&grid, &nc, start_x, start_y).with_config(config);
+        Self { grid, nc, bomb_count: mines.count(), state, iterations: 0, status: StepStatus::InProgress }
+    }
+
+    /// Spend up to `max_work` units of work (at least one) and report where
+    /// the solve stands. Once finished, further calls return the verdict again.
+    pub fn step(&mut self, max_work: u32) -> StepStatus {
+        if self.status != StepStatus::InProgress {
+            return self.status;
+        }
+        let max_iterations = self.grid.width * self.grid.height * 2;
+        let mut limit = PassLimit { deadline: None, units: max_work.max(1) as usize };
+        let mut stalled = false;
+        while limit.units > 0 && self.iterations < max_iterations {
+            match self.state.step_slice(&self.grid, &self.nc, self.bomb_count, &mut limit) {
+                Slice::Progress(_) => self.iterations += 1,
+                Slice::Paused => break,
+                Slice::Stalled => {
+                    stalled = true;
+                    break;
                 }
             }
         }
+        if stalled || self.iterations >= max_iterations {
+            self.status = if self.state.is_complete(self.bomb_count) { StepStatus::Solvable } else { StepStatus::Unsolvable };
+        }
+        self.status
     }
 
-    if !safe_frontier.is_empty() {
-        safe_frontier.sort_by(|a, b| b.score.cmp(&a.score));
-        return Some(safe_frontier.remove(0));
+    /// The solver's current position.
+    pub fn state(&self) -> &SolveState {
+        &self.state
     }
+}
 
-    // Phase 2: Any safe cell (island, not adjacent to revealed)
-    let mut safe_island: Vec<Hint> = Vec::new();
+/// Stable hash of everything the solver deduces on a board, for golden tests.
+///
+/// Per-step results depend on hash-set iteration order, so the trace is built
+/// from order-independent pieces instead: for each strategy level in turn the
+/// position is run to its fixpoint with strategies up to that level, and the
+/// new determinations are hashed as `(level, x, y, kind)` in column-major
+/// order (kind 0 = revealed, 1 = flagged). Any progress restarts from level 1,
+/// so each cell is tagged with the cheapest level that reaches it. FNV-1a, so
+/// the value is the same on every platform.
+pub fn solution_fingerprint(
+    grid: &Grid,
+    mines: &Mines,
+    nc: &NeighborCache,
+    start_x: usize,
+    start_y: usize,
+) -> u64 {
+    let bomb_count = mines.count();
+    let opening = SolveState::from_start(grid, nc, start_x, start_y);
+    let (mut visible, mut flags) = (opening.visible, opening.flags);
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    let mut feed = |bytes: &[u8]| {
+        for &b in bytes {
+            hash ^= b as u64;
+            hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+        }
+    };
 
-    for x in 0..width {
-        for y in 0..height {
-            if visible.get(x, y) == -1 && !flags.get(x, y) && !mines.get(x, y) {
-                let score = if grid.get(x, y) == 0 { 10 } else { 0 };
-                safe_island.push(Hint { x, y, score });
+    // The opening itself, tagged as level 0
+    for (i, &v) in visible.cells.iter().enumerate() {
+        if v == -1 { continue; }
+        let (x, y) = (i / grid.height, i % grid.height);
+        feed(&[0]);
+        feed(&(x as u32).to_le_bytes());
+        feed(&(y as u32).to_le_bytes());
+        feed(&[0]);
+    }
+
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for level in Strategy::Basic as u8..=Strategy::GlobalCount as u8 {
+            let config = SolverConfig { max_strategy_level: level, ..SolverConfig::default() };
+            let state = run_to_fixpoint(grid, nc, bomb_count, &visible, &flags, &config);
+
+            for i in 0..visible.cells.len() {
+                let kind = if state.flags.cells[i] != flags.cells[i] {
+                    1
+                } else if state.visible.cells[i] != visible.cells[i] {
+                    0
+                } else {
+                    continue;
+                };
+                let (x, y) = (i / grid.height, i % grid.height);
+                feed(&[level]);
+                feed(&(x as u32).to_le_bytes());
+                feed(&(y as u32).to_le_bytes());
+                feed(&[kind]);
+            }
+            if state.visible.cells != visible.cells || state.flags.cells != flags.cells {
+                (visible, flags) = (state.visible, state.flags);
+                changed = true;
+                break;
             }
         }
     }
 
-    if !safe_island.is_empty() {
-        safe_island.sort_by(|a, b| b.score.cmp(&a.score));
-        return Some(safe_island.remove(0));
+    hash
+}
+
+/// Solve from `visible`/`flags` with `config` to the level's fixpoint.
+///
+/// A stall can leave cells a cascade opened un-dirty, and where a single
+/// `run_to_stall()` stalls depends on hash-set order, so this reruns from a
+/// fully dirty state until nothing changes. The result is the same every run;
+/// its metrics add up over the reruns.
+fn run_to_fixpoint(
+    grid: &Grid,
+    nc: &NeighborCache,
+    bomb_count: usize,
+    visible: &VisibleGrid,
+    flags: &Flags,
+    config: &SolverConfig,
+) -> SolveState {
+    let mut state = SolveState::from_position(visible, flags, nc).with_config(config.clone());
+    loop {
+        let before = (state.visible.cells.clone(), state.flags.cells.clone());
+        state.run_to_stall(grid, nc, bomb_count);
+        if state.visible.cells == before.0 && state.flags.cells == before.1 { break; }
+        let metrics = std::mem::take(&mut state.metrics);
+        state = SolveState::from_position(&state.visible, &state.flags, nc).with_config(config.clone());
+        state.metrics = metrics;
     }
+    state
+}
 
-    None
+/// `is_solvable()` restricted to strategies up to `max_level` (see `Strategy`).
+/// Stops at the first stall, so it is cheaper than a full difficulty report.
+pub fn solvable_with_max_strategy(
+    grid: &Grid,
+    mines: &Mines,
+    nc: &NeighborCache,
+    start_x: usize,
+    start_y: usize,
+    max_level: u8,
+) -> bool {
+    let config = SolverConfig { max_strategy_level: max_level, ..SolverConfig::default() };
+    is_solvable_with_config(grid, mines, nc, start_x, start_y, &config)
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/// Cells logic settles from the start with strategies up to `strategy` but
+/// not with the cheaper ones alone: the moves that practise `strategy`.
+///
+/// The difference of two capped solves to a stall, column-major, flags and
+/// reveals alike. For `Strategy::Basic` the cheaper solve is just the opening.
+/// Later cells can need `strategy` only because an earlier one did, so the
+/// list is every cell that first becomes reachable at this level.
+pub fn cells_requiring_strategy(
+    grid: &Grid,
+    mines: &Mines,
+    nc: &NeighborCache,
+    start_x: usize,
+    start_y: usize,
+    strategy: Strategy,
+) -> Vec<(usize, usize)> {
+    let bomb_count = mines.count();
+    let settle = |max_level: u8| {
+        let config = SolverConfig { max_strategy_level: max_level, ..SolverConfig::default() };
+        let mut state = SolveState::from_start(grid, nc, start_x, start_y).with_config(config);
+        state.run_to_stall(grid, nc, bomb_count);
+        state
+    };
+    let (below, upto) = (settle(strategy as u8 - 1), settle(strategy as u8));
 
-    /// Create a simple 3x3 board with one mine at (0,0), start at (2,2).
-    fn make_simple_board() -> (Grid, Mines, NeighborCache) {
-        let mut mines = Mines::new(3, 3);
-        mines.set(0, 0, true);
+    let height = grid.height;
+    let settled = |state: &SolveState, i: usize| state.visible.cells[i] != -1 || state.flags.cells[i] != 0;
+    (0..grid.cells.len())
+        .filter(|&i| settled(&upto, i) && !settled(&below, i))
+        .map(|i| (i / height, i % height))
+        .collect()
+}
 
-        let nc = NeighborCache::new(3, 3);
-        let grid = crate::board::calculate_numbers(&mines, &nc);
+/// True if the board needs more than Basic, Subset and Gaussian: it stalls at
+/// `Strategy::Gaussian` but is solvable with every strategy (contradiction,
+/// tank or the global mine count). For "hard but fair" challenge boards.
+pub fn requires_advanced_strategy(
+    grid: &Grid,
+    mines: &Mines,
+    nc: &NeighborCache,
+    start_x: usize,
+    start_y: usize,
+) -> bool {
+    !solvable_with_max_strategy(grid, mines, nc, start_x, start_y, Strategy::Gaussian as u8)
+        && solvable_with_max_strategy(grid, mines, nc, start_x, start_y, Strategy::GlobalCount as u8)
+}
 
-        (grid, mines, nc)
+/// The hardest strategy a board needs, or `None` if it needs a guess. A
+/// board the opening clears on its own rates `Strategy::Basic`.
+///
+/// The solve starts capped at `Strategy::Basic` and raises the cap one level
+/// each time it reaches that level's fixpoint without clearing the board; see
+/// `DifficultyReport::solve()`. Fixpoints, as in `solution_fingerprint()`,
+/// make the rating the same on every run.
+pub fn difficulty_rating(
+    grid: &Grid,
+    mines: &Mines,
+    nc: &NeighborCache,
+    start_x: usize,
+    start_y: usize,
+) -> Option<Strategy> {
+    DifficultyReport::solve(grid, mines, nc, start_x, start_y).map(|report| report.rating)
+}
+
+/// `difficulty_rating()` of a board together with the metrics of the solve
+/// that found it, so a generator can rate boards with its acceptance check.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DifficultyReport {
+    /// The hardest strategy the board needs.
+    pub rating: Strategy,
+    /// Work counts of the whole escalating solve.
+    pub metrics: SolveMetrics,
+}
+
+impl DifficultyReport {
+    /// Solve and rate a board in one pass. The strategy cap starts at `Basic`;
+    /// each time the solve reaches that level's fixpoint short of a clear, the
+    /// cap rises one level and the solve carries on from where it stopped.
+    /// The level that clears the board is the rating, so a generator's
+    /// acceptance check rates the board with no further solve. `None` if the
+    /// board needs a guess.
+    pub fn solve(grid: &Grid, mines: &Mines, nc: &NeighborCache, start_x: usize, start_y: usize) -> Option<Self> {
+        let bomb_count = mines.count();
+        let opening = SolveState::from_start(grid, nc, start_x, start_y);
+        let (mut visible, mut flags) = (opening.visible, opening.flags);
+        let mut metrics = SolveMetrics::default();
+
+        for rating in [
+            Strategy::Basic, Strategy::Subset, Strategy::Gaussian,
+            Strategy::Contradiction, Strategy::Tank, Strategy::GlobalCount,
+        ] {
+            let config = SolverConfig { max_strategy_level: rating as u8, ..SolverConfig::default() };
+            let state = run_to_fixpoint(grid, nc, bomb_count, &visible, &flags, &config);
+            metrics.absorb(&state.metrics);
+            if state.is_complete(bomb_count) {
+                return Some(DifficultyReport { rating, metrics });
+            }
+            (visible, flags) = (state.visible, state.flags);
+        }
+        None
     }
+}
 
-    #[test]
-    fn test_simulate_reveal_zero_cascade() {
-        let (grid, _mines, _nc) = make_simple_board();
-        let flags = Flags::new(3, 3);
-        let mut visible = VisibleGrid::new(3, 3);
+/// How many boards of a pack rate at each difficulty tier.
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DifficultyProfile {
+    /// Boards whose hardest strategy is each one, indexed by `Strategy as usize - 1`.
+    pub by_strategy: [usize; 6],
+    /// Boards that need a guess.
+    pub needs_guess: usize,
+}
 
-        // Reveal (2,2) which is 0 → should cascade
-        simulate_reveal(&grid, &mut visible, &flags, 2, 2);
+#[cfg(not(target_arch = "wasm32"))]
+impl DifficultyProfile {
+    /// Boards whose hardest strategy is `strategy`.
+    pub fn boards_at(&self, strategy: Strategy) -> usize {
+        self.by_strategy[strategy as usize - 1]
+    }
+}
 
-        // (2,2) is 0, so all connected zeros and their neighbors should be revealed
-        assert_ne!(visible.get(2, 2), -1);
+/// `difficulty_rating()` over a pack of `(grid, mines, start_x, start_y)`
+/// boards sharing one size, tallied per tier. Offline pack validation.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn batch_difficulty_profile(boards: &[(Grid, Mines, usize, usize)], nc: &NeighborCache) -> DifficultyProfile {
+    let mut profile = DifficultyProfile::default();
+    for (grid, mines, start_x, start_y) in boards {
+        match difficulty_rating(grid, mines, nc, *start_x, *start_y) {
+            Some(strategy) => profile.by_strategy[strategy as usize - 1] += 1,
+            None => profile.needs_guess += 1,
+        }
     }
+    profile
+}
 
-    #[test]
+/// Layouts `bench_generate()` may try per board before settling for a failure.
+#[cfg(not(target_arch = "wasm32"))]
+const BENCH_MAX_ATTEMPTS: u32 = 10_000;
+
+/// Wall time to generate `iterations` no-guess boards, opening at the center
+/// with a 3×3 safe zone. Board `i` uses seed `seed + i`, so a run is
+/// reproducible; the hot path is the same `is_solvable()` the generators use.
+/// Performance-regression harness for `benches/generate.rs` and tests.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn bench_generate(width: usize, height: usize, bomb_count: usize, seed: u64, iterations: u32) -> std::time::Duration {
+    let nc = NeighborCache::new(width, height);
+    let start = std::time::Instant::now();
+    for i in 0..iterations as u64 {
+        let mut rng = crate::rng::WasmRng::from_seed(seed.wrapping_add(i));
+        let result = crate::board::generate_solvable_board_with_rng(
+            width, height, bomb_count, width / 2, height / 2, 1, 0,
+            BENCH_MAX_ATTEMPTS, &nc, &mut rng, is_solvable,
+        );
+        std::hint::black_box(result);
+    }
+    start.elapsed()
+}
+
+/// `is_solvable()` for multi-start boards: all `zones` are revealed up front.
+pub fn is_solvable_multi_start(
+    grid: &Grid,
+    mines: &Mines,
+    nc: &NeighborCache,
+    zones: &[SafeZone],
+) -> bool {
+    let bomb_count = mines.count();
+    let mut state = SolveState::from_zones(grid, nc, zones);
+    state.run_to_stall(grid, nc, bomb_count);
+    state.is_complete(bomb_count)
+}
+
+/// `is_solvable()` for a rectangular opening: every cell of `rect` is revealed up front.
+pub fn is_solvable_rect(
+    grid: &Grid,
+    mines: &Mines,
+    nc: &NeighborCache,
+    rect: &SafeRect,
+) -> bool {
+    let bomb_count = mines.count();
+    let mut state = SolveState::from_rects(grid, nc, std::slice::from_ref(rect));
+    state.run_to_stall(grid, nc, bomb_count);
+    state.is_complete(bomb_count)
+}
+
+// ─── Server-side context ────────────────────────────────────────────────────
+
+/// Read-only solver setup for validating many boards of one size, shareable
+/// across threads behind an `Arc`.
+///
+/// Every call builds its own `SolveState`, and the tank cache and Gaussian
+/// scratch buffer are thread-local, so concurrent calls share nothing mutable.
+#[cfg(not(target_arch = "wasm32"))]
+pub struct SolverContext {
+    nc: std::sync::Arc<NeighborCache>,
+    config: SolverConfig,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl SolverContext {
+    /// Context for `width × height` boards, or `None` if the board is too large.
+    pub fn new(width: usize, height: usize) -> Option<Self> {
+        NeighborCache::try_new(width, height).map(|nc| Self::from_cache(std::sync::Arc::new(nc)))
+    }
+
+    /// Context around an existing shared neighbor cache.
+    pub fn from_cache(nc: std::sync::Arc<NeighborCache>) -> Self {
+        Self { nc, config: SolverConfig::default() }
+    }
+
+    /// Replace the solver configuration.
+    pub fn with_config(mut self, config: SolverConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// The shared neighbor cache, e.g. for `calculate_numbers()`.
+    pub fn neighbor_cache(&self) -> &NeighborCache {
+        &self.nc
+    }
+
+    /// `is_solvable_with_config()` with this context's cache and options.
+    pub fn is_solvable(&self, grid: &Grid, mines: &Mines, start_x: usize, start_y: usize) -> bool {
+        is_solvable_with_config(grid, mines, &self.nc, start_x, start_y, &self.config)
+    }
+}
+
+// The board types are plain `Vec`s; keep it that way so servers can share them
+#[cfg(not(target_arch = "wasm32"))]
+const _: () = {
+    const fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<Grid>();
+    assert_send_sync::<Mines>();
+    assert_send_sync::<VisibleGrid>();
+    assert_send_sync::<Flags>();
+    assert_send_sync::<NeighborCache>();
+    assert_send_sync::<SolverConfig>();
+    assert_send_sync::<SolverContext>();
+};
+
+/// Panic if the solver's position contradicts the true mine layout: a
+/// revealed cell that is a mine, or a flagged cell that is not.
+#[cfg(debug_assertions)]
+pub fn debug_check_consistency(visible: &VisibleGrid, flags: &Flags, mines: &Mines) {
+    for x in 0..visible.width {
+        for y in 0..visible.height {
+            let is_mine = mines.get(x, y);
+            if visible.get(x, y) != -1 && is_mine {
+                panic!("solver revealed mine at ({}, {})", x, y);
+            }
+            if flags.get(x, y) && !is_mine {
+                panic!("solver flagged safe cell at ({}, {})", x, y);
+            }
+        }
+    }
+}
+
+/// `is_solvable()` that checks every deduction against `mines` after each
+/// strategy application. Debug builds only; panics on the first false deduction.
+#[cfg(debug_assertions)]
+pub fn is_solvable_checked(
+    grid: &Grid,
+    mines: &Mines,
+    nc: &NeighborCache,
+    start_x: usize,
+    start_y: usize,
+) -> bool {
+    let bomb_count = mines.count();
+    let mut state = SolveState::from_start(grid, nc, start_x, start_y);
+    debug_check_consistency(&state.visible, &state.flags, mines);
+
+    let max_iterations = grid.width * grid.height * 2;
+    for _ in 0..max_iterations {
+        if state.step(grid, nc, bomb_count).is_none() { break; }
+        debug_check_consistency(&state.visible, &state.flags, mines);
+    }
+
+    state.is_complete(bomb_count)
+}
+
+/// True if the opening plus Strategy 1 alone clears the board.
+///
+/// A narrow, fast "too trivial" check for the generator: no subset logic,
+/// no enumeration, no mine counting.
+pub fn is_trivially_solved_by_opening(
+    grid: &Grid,
+    mines: &Mines,
+    nc: &NeighborCache,
+    start_x: usize,
+    start_y: usize,
+) -> bool {
+    let bomb_count = mines.count();
+    let mut state = SolveState::from_start(grid, nc, start_x, start_y);
+    let max_iterations = grid.width * grid.height * 2;
+
+    for _ in 0..max_iterations {
+        if state.is_complete(bomb_count) { return true; }
+        let tolerance = state.config.clue_tolerance as i32;
+        let basic = apply_basic_rules(grid, &mut state.visible, &mut state.flags, nc, &state.dirty_cells, state.flag_count, tolerance, None, None);
+        if !basic.progress { break; }
+        state.flag_count = basic.flag_count;
+        state.dirty_cells = basic.dirty_cells;
+    }
+
+    state.is_complete(bomb_count)
+}
+
+// ─── solve_until_guess ──────────────────────────────────────────────────────
+
+/// A recommended guess: the hidden cell with the lowest mine probability.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Guess {
+    pub x: usize,
+    pub y: usize,
+    pub mine_probability: f32,
+    /// `opening_adjacency_score()` of the cell, the tiebreaker between equally safe guesses.
+    pub adjacency_score: u32,
+}
+
+/// How many of the cell's revealed neighbors belong to an already-opened
+/// region: a zero, or a number bordering a revealed zero. A hidden cell never
+/// touches a revealed zero once its cascade has run, so in play this counts the
+/// opening's edge numbers. Guesses next to an opening tend to extend it.
+pub fn opening_adjacency_score(visible: &VisibleGrid, nc: &NeighborCache, x: usize, y: usize) -> u32 {
+    let in_opening = |nx: usize, ny: usize| match visible.get(nx, ny) {
+        0 => true,
+        1..=8 => nc.get(nx, ny).iter().any(|&(ox, oy)| visible.get(ox, oy) == 0),
+        _ => false,
+    };
+    nc.get(x, y).iter().filter(|&&(nx, ny)| in_opening(nx, ny)).count() as u32
+}
+
+/// Pick the safest hidden, unflagged cell by exact mine probability.
+/// Ties go to the higher `opening_adjacency_score()`, then to the first cell
+/// in column-major order.
+pub fn best_guess(
+    visible: &VisibleGrid,
+    flags: &Flags,
+    nc: &NeighborCache,
+    bomb_count: usize,
+) -> Option<Guess> {
+    let probs = probability::exact_mine_probabilities(visible, flags, nc, bomb_count, true, None, None);
+    let height = visible.height;
+    let mut best: Option<Guess> = None;
+
+    for x in 0..visible.width {
+        for y in 0..height {
+            if visible.get(x, y) != -1 || flags.get(x, y) { continue; }
+            let p = probs[x * height + y];
+            let score = opening_adjacency_score(visible, nc, x, y);
+            let better = best.is_none_or(|b| {
+                p < b.mine_probability || (p == b.mine_probability && score > b.adjacency_score)
+            });
+            if better {
+                best = Some(Guess { x, y, mine_probability: p, adjacency_score: score });
+            }
+        }
+    }
+
+    best
+}
+
+/// Every hidden, unflagged cell with its exact mine probability, safest first.
+///
+/// Cells off the frontier share the uniform leftover density. Ties keep
+/// column-major order.
+pub fn rank_all_hidden(
+    visible: &VisibleGrid,
+    flags: &Flags,
+    nc: &NeighborCache,
+    bomb_count: usize,
+) -> Vec<(usize, usize, f32)> {
+    let probs = probability::exact_mine_probabilities(visible, flags, nc, bomb_count, true, None, None);
+    let height = visible.height;
+
+    let mut ranking = Vec::new();
+    for x in 0..visible.width {
+        for y in 0..height {
+            if visible.get(x, y) != -1 || flags.get(x, y) { continue; }
+            ranking.push((x, y, probs[x * height + y]));
+        }
+    }
+    ranking.sort_by(|a, b| a.2.total_cmp(&b.2));
+    ranking
+}
+
+/// Hidden, unflagged cells whose exact mine probability is at least
+/// `min_probability`, likeliest first, for "flag everything above 95%".
+///
+/// Ties keep column-major order. Probabilities are `f32`, so a threshold of
+/// exactly 1.0 can miss certain mines by rounding; pass a hair below it.
+pub fn mine_candidates(
+    visible: &VisibleGrid,
+    flags: &Flags,
+    nc: &NeighborCache,
+    bomb_count: usize,
+    min_probability: f32,
+) -> Vec<(usize, usize, f32)> {
+    let mut candidates: Vec<_> = rank_all_hidden(visible, flags, nc, bomb_count)
+        .into_iter()
+        .filter(|&(_, _, p)| p >= min_probability)
+        .collect();
+    candidates.sort_by(|a, b| b.2.total_cmp(&a.2).then((a.0, a.1).cmp(&(b.0, b.1))));
+    candidates
+}
+
+/// Where a logical solve from the start position first runs out of moves.
+#[derive(Debug, PartialEq)]
+pub enum GuessPoint {
+    /// Logic alone clears the board.
+    FullySolved,
+    /// Logic stalled; a guess is required to continue.
+    StuckAt {
+        /// Revealed safe cells / total safe cells at the stall.
+        revealed_fraction: f32,
+        /// Number of connected frontier regions at the stall.
+        frontier_components: usize,
+        /// The safest cell to guess, if any hidden cell remains.
+        best_guess: Option<Guess>,
+    },
+}
+
+/// Solve from the start position and stop at the first stall.
+///
+/// Unlike `is_solvable()`, reports the stuck position: how much of the
+/// board was cleared, how fragmented the frontier is, and the recommended guess.
+pub fn solve_until_guess(
+    grid: &Grid,
+    mines: &Mines,
+    nc: &NeighborCache,
+    start_x: usize,
+    start_y: usize,
+) -> GuessPoint {
+    let bomb_count = mines.count();
+    let mut state = SolveState::from_start(grid, nc, start_x, start_y);
+    state.run_to_stall(grid, nc, bomb_count);
+
+    if state.is_complete(bomb_count) {
+        return GuessPoint::FullySolved;
+    }
+
+    let total_safe = grid.width * grid.height - bomb_count;
+    let revealed_fraction = if total_safe == 0 { 1.0 } else { state.revealed_count() as f32 / total_safe as f32 };
+    let frontier = get_frontier(&state.visible, &state.flags, nc);
+    let frontier_components = group_frontier_regions(&frontier, &state.visible, nc, None).len();
+
+    GuessPoint::StuckAt {
+        revealed_fraction,
+        frontier_components,
+        best_guess: best_guess(&state.visible, &state.flags, nc, bomb_count),
+    }
+}
+
+/// Difficulty badge for a board: solvable without guessing, or the risk of
+/// the safest guess at the first stall.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SolvabilitySummary {
+    pub no_guess: bool,
+    /// Mine probability of the safest guess at the first stall; `None` when
+    /// `no_guess` is set or nothing is left to guess.
+    pub first_guess_risk: Option<f32>,
+}
+
+/// `solve_until_guess()` reduced to the two numbers a difficulty badge shows.
+pub fn solvability_summary(
+    grid: &Grid,
+    mines: &Mines,
+    nc: &NeighborCache,
+    start_x: usize,
+    start_y: usize,
+) -> SolvabilitySummary {
+    match solve_until_guess(grid, mines, nc, start_x, start_y) {
+        GuessPoint::FullySolved => SolvabilitySummary { no_guess: true, first_guess_risk: None },
+        GuessPoint::StuckAt { best_guess, .. } => SolvabilitySummary {
+            no_guess: false,
+            first_guess_risk: best_guess.map(|g| g.mine_probability),
+        },
+    }
+}
+
+/// Uncertainty left on the frontier after the opening and a full logical
+/// solve, in bits: the entropy of the frontier's joint layout, with every
+/// consistent board equally likely. 0 for no-guess boards; a lone 50/50 pair
+/// gives 1. Complements the discrete `difficulty_rating()` with a smooth scalar.
+pub fn opening_entropy(
+    grid: &Grid,
+    mines: &Mines,
+    nc: &NeighborCache,
+    start_x: usize,
+    start_y: usize,
+) -> f32 {
+    let bomb_count = mines.count();
+    let mut state = SolveState::from_start(grid, nc, start_x, start_y);
+    state.run_to_stall(grid, nc, bomb_count);
+    frontier_entropy(&state.visible, &state.flags, nc, bomb_count) as f32
+}
+
+/// `log2(2^a + 2^b)`, with `-inf` standing for a zero count.
+fn log2_add(a: f64, b: f64) -> f64 {
+    let (hi, lo) = if a >= b { (a, b) } else { (b, a) };
+    if lo == f64::NEG_INFINITY { return hi; }
+    hi + (lo - hi).exp2().ln_1p() / std::f64::consts::LN_2
+}
+
+/// Entropy in bits of the frontier's layout as a whole.
+///
+/// Regions are counted by mine total with `count_region_layouts()` and
+/// convolved as in `solution_count()`, but in log2 space so large boards
+/// cannot overflow. Every frontier layout with `t` mines has the same
+/// probability, proportional to its `C(off_frontier, remaining - t)` fills.
+fn frontier_entropy(visible: &VisibleGrid, flags: &Flags, nc: &NeighborCache, bomb_count: usize) -> f64 {
+    let frontier = get_frontier(visible, flags, nc);
+    if frontier.is_empty() {
+        return 0.0;
+    }
+    let flag_count = flags.cells.iter().filter(|&&f| f != 0).count();
+    let hidden = visible.cells.iter().zip(&flags.cells).filter(|&(&v, &f)| f == 0 && v == -1).count();
+    let Some(remaining) = bomb_count.checked_sub(flag_count) else { return 0.0 };
+    let off = hidden - frontier.len();
+
+    // by_total[t] = log2 of the frontier layouts with t mines
+    let mut by_total = vec![0.0f64];
+    for region in group_frontier_regions(&frontier, visible, nc, None) {
+        let constraints = get_region_constraints(&region, visible, flags, nc, None);
+        let counts = count_region_layouts(region.len(), &constraints, f64::NEG_INFINITY, 0.0, |a, b| log2_add(*a, *b));
+        let mut next = vec![f64::NEG_INFINITY; by_total.len() + counts.len() - 1];
+        for (i, &a) in by_total.iter().enumerate() {
+            for (j, &b) in counts.iter().enumerate() {
+                next[i + j] = log2_add(next[i + j], a + b);
+            }
+        }
+        by_total = next;
+    }
+
+    let log2_binomial = |n: usize, k: usize| -> f64 {
+        (0..k.min(n - k)).map(|i| ((n - i) as f64 / (i + 1) as f64).log2()).sum()
+    };
+    // (log2 layouts, log2 weight of each) per feasible frontier total
+    let totals: Vec<(f64, f64)> = by_total.iter().enumerate()
+        .filter(|&(t, &ways)| t <= remaining && remaining - t <= off && ways > f64::NEG_INFINITY)
+        .map(|(t, &ways)| (ways, log2_binomial(off, remaining - t)))
+        .collect();
+    if totals.is_empty() {
+        return 0.0;
+    }
+    let all = totals.iter().fold(f64::NEG_INFINITY, |acc, &(ways, w)| log2_add(acc, ways + w));
+    // Layouts with t mines hold 2^(ways + w - all) of the probability, each 2^(w - all)
+    let bits: f64 = totals.iter().map(|&(ways, w)| -(ways + w - all).exp2() * (w - all)).sum();
+    bits.max(0.0)
+}
+
+/// Guesses needed to clear the board from the start position when every
+/// guess goes well: at each stall the safest cell that is actually safe is
+/// revealed and the solve resumes. 0 means no-guess solvable.
+pub fn min_guesses(
+    grid: &Grid,
+    mines: &Mines,
+    nc: &NeighborCache,
+    start_x: usize,
+    start_y: usize,
+) -> u32 {
+    let bomb_count = mines.count();
+    let mut state = SolveState::from_start(grid, nc, start_x, start_y);
+    let mut guesses = 0;
+
+    loop {
+        state.run_to_stall(grid, nc, bomb_count);
+        if state.is_complete(bomb_count) {
+            return guesses;
+        }
+
+        let ranking = rank_all_hidden(&state.visible, &state.flags, nc, bomb_count);
+        let Some(&(x, y, _)) = ranking.iter().find(|&&(x, y, _)| !mines.get(x, y)) else {
+            // Every remaining safe cell is flagged; nothing a guess can fix
+            return guesses;
+        };
+        guesses += 1;
+        let mut visible = state.visible.clone();
+        simulate_reveal(grid, &mut visible, &state.flags, nc, x, y);
+        state = SolveState::from_position(&visible, &state.flags, nc);
+    }
+}
+
+/// Layouts per region that `minimal_guess_core()` compares pairwise; past
+/// this only the first ones (in mask order) are searched.
+#[cfg(not(target_arch = "wasm32"))]
+const CORE_MAX_LAYOUTS: usize = 4096;
+
+/// The smallest irreducible ambiguity at the first stall from the start, for
+/// explaining why generation rejected a board.
+///
+/// Enumerates each frontier region's layouts and finds the two that differ
+/// in the fewest cells: swapping those cells changes no clue, so no logic can
+/// tell the layouts apart and a guess is unavoidable. A 50/50 is a core of two
+/// cells; the clues proving it are the revealed numbers around them. The
+/// mine count is only used as an upper bound, so a core may be one the count
+/// would settle. Returns the core's cells in column-major order, or `None`
+/// when the board is solvable or no frontier region (of at most
+/// `MAX_REGION_SIZE` cells) is ambiguous. Offline tool: enumeration is
+/// exponential in the region size.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn minimal_guess_core(
+    grid: &Grid,
+    mines: &Mines,
+    nc: &NeighborCache,
+    start_x: usize,
+    start_y: usize,
+) -> Option<Vec<(usize, usize)>> {
+    let bomb_count = mines.count();
+    let mut state = SolveState::from_start(grid, nc, start_x, start_y);
+    state.run_to_stall(grid, nc, bomb_count);
+    if state.is_complete(bomb_count) {
+        return None;
+    }
+
+    let mines_left = bomb_count.saturating_sub(state.flag_count as usize);
+    let frontier = get_frontier(&state.visible, &state.flags, nc);
+    let mut best: Option<Vec<(usize, usize)>> = None;
+    for region in group_frontier_regions(&frontier, &state.visible, nc, None) {
+        if region.len() > MAX_REGION_SIZE { continue; }
+        let constraints = get_region_constraints(&region, &state.visible, &state.flags, nc, None);
+        let masks = enumerate_configurations_with_tolerance(&region, &constraints, mines_left, 0);
+        let masks = &masks[..masks.len().min(CORE_MAX_LAYOUTS)];
+
+        let best_len = best.as_ref().map_or(usize::MAX, Vec::len);
+        let closest = masks.iter().enumerate()
+            .flat_map(|(i, &a)| masks[i + 1..].iter().map(move |&b| a ^ b))
+            .min_by_key(|diff| diff.count_ones());
+        if let Some(diff) = closest.filter(|d| (d.count_ones() as usize) < best_len) {
+            best = Some(mask_to_cells(&region, diff));
+        }
+    }
+    best
+}
+
+// ─── play_move ──────────────────────────────────────────────────────────────
+
+/// Outcome of a single player reveal.
+#[derive(Debug, Default)]
+pub struct MoveResult {
+    /// Cells newly revealed by this move (cascade included), in reveal order.
+    pub revealed_cells: Vec<(usize, usize)>,
+    /// True if the clicked cell was a mine.
+    pub hit_mine: bool,
+    /// True if every non-mine cell is now revealed.
+    pub won: bool,
+    /// True if the move added or removed a flag instead of revealing.
+    pub flag_changed: bool,
+}
+
+/// Options for `play_move_with`.
+#[derive(Debug, Clone, Copy)]
+pub struct PlayOptions {
+    /// On a mine hit, also expose every other unflagged mine.
+    pub reveal_all_mines: bool,
+    /// Value written at exploded mine cells; must not collide with -1 or 0-8.
+    pub exploded: i8,
+    /// In `apply_move()`, a reveal on a flagged cell removes the flag instead
+    /// of being ignored.
+    pub unflag_on_reveal: bool,
+}
+
+impl Default for PlayOptions {
+    fn default() -> Self {
+        Self { reveal_all_mines: false, exploded: EXPLODED, unflag_on_reveal: false }
+    }
+}
+
+impl PlayOptions {
+    /// Set whether a mine hit exposes the remaining mines.
+    pub fn with_reveal_all_mines(mut self, reveal_all_mines: bool) -> Self {
+        self.reveal_all_mines = reveal_all_mines;
+        self
+    }
+
+    /// Override the exploded-mine sentinel (defaults to `EXPLODED`).
+    pub fn with_exploded(mut self, exploded: i8) -> Self {
+        self.exploded = exploded;
+        self
+    }
+
+    /// Set whether a reveal on a flagged cell unflags it.
+    pub fn with_unflag_on_reveal(mut self, unflag_on_reveal: bool) -> Self {
+        self.unflag_on_reveal = unflag_on_reveal;
+        self
+    }
+}
+
+/// Cells a game has to reveal to be won: those neither mined nor blocked in `nc`.
+fn safe_cell_total(mines: &Mines, nc: &NeighborCache) -> usize {
+    let height = mines.height;
+    (0..mines.cells.len())
+        .filter(|&i| mines.cells[i] == 0 && !nc.is_blocked(i / height, i % height))
+        .count()
+}
+
+/// True once every cell counted by `safe_cell_total()` is revealed.
+fn all_safe_revealed(visible: &VisibleGrid, mines: &Mines, nc: &NeighborCache) -> bool {
+    let height = mines.height;
+    let revealed_safe = visible.cells.iter().zip(&mines.cells).enumerate()
+        .filter(|&(i, (&v, &m))| v != -1 && m == 0 && !nc.is_blocked(i / height, i % height))
+        .count();
+    revealed_safe == safe_cell_total(mines, nc)
+}
+
+/// Toggle the flag on a hidden cell. Revealed and out-of-bounds cells are
+/// left alone; returns whether the flag changed.
+pub fn toggle_flag(visible: &VisibleGrid, flags: &mut Flags, x: usize, y: usize) -> bool {
+    if visible.get_checked(x, y) != Some(-1) {
+        return false;
+    }
+    flags.set(x, y, !flags.get(x, y));
+    true
+}
+
+/// Apply one player action the way the game loop does.
+///
+/// `MoveKind::ToggleFlag` goes through `toggle_flag()`. A reveal on a flagged
+/// cell is ignored, or unflags the cell when `options.unflag_on_reveal` is
+/// set; any other reveal is `play_move_with()`. `flag_changed` reports either
+/// kind of flag edit.
+#[allow(clippy::too_many_arguments)]
+pub fn apply_move(
+    grid: &Grid,
+    mines: &Mines,
+    visible: &mut VisibleGrid,
+    flags: &mut Flags,
+    nc: &NeighborCache,
+    x: usize,
+    y: usize,
+    kind: MoveKind,
+    options: &PlayOptions,
+) -> MoveResult {
+    let on_flag = visible.get_checked(x, y) == Some(-1) && flags.get(x, y);
+    match kind {
+        MoveKind::ToggleFlag => MoveResult { flag_changed: toggle_flag(visible, flags, x, y), ..MoveResult::default() },
+        MoveKind::Reveal if on_flag => {
+            if options.unflag_on_reveal {
+                flags.set(x, y, false);
+            }
+            MoveResult { flag_changed: options.unflag_on_reveal, ..MoveResult::default() }
+        }
+        MoveKind::Reveal => play_move_with(grid, mines, visible, flags, nc, x, y, options),
+    }
+}
+
+/// A game in progress that keeps its win check O(1) per move.
+///
+/// `play_move()` rescans the board after every reveal to decide the win; on
+/// large boards that scan dominates the move. `GameState` instead counts
+/// revealed safe cells as its own reveals open them and wins when the count
+/// reaches the number of safe cells. Edit the position only through its
+/// methods, or the count goes stale.
+pub struct GameState {
+    grid: Grid,
+    mines: Mines,
+    visible: VisibleGrid,
+    flags: Flags,
+    revealed_safe: usize,
+    safe_total: usize,
+    hit_mine: bool,
+    forgiveness: Option<crate::rng::WasmRng>,
+    forgiveness_used: bool,
+    nc: NeighborCache,
+}
+
+impl GameState {
+    /// A fresh game with every cell hidden and unflagged. Cascades spread
+    /// through `nc`, and its blocked cells never count towards the win.
+    pub fn new(grid: Grid, mines: Mines, nc: NeighborCache) -> Self {
+        let (w, h) = (mines.width, mines.height);
+        let safe_total = safe_cell_total(&mines, &nc);
+        Self {
+            grid,
+            mines,
+            visible: VisibleGrid::new(w, h),
+            flags: Flags::new(w, h),
+            revealed_safe: 0,
+            safe_total,
+            hit_mine: false,
+            forgiveness: None,
+            forgiveness_used: false,
+            nc,
+        }
+    }
+
+    /// Forgive the first mine hit as `forgiving_reveal()` does, drawing the
+    /// relocation target from a generator seeded with `seed`.
+    pub fn with_forgiveness(mut self, seed: u64) -> Self {
+        self.forgiveness = Some(crate::rng::WasmRng::from_seed(seed));
+        self
+    }
+
+    /// Reveal `(x, y)` like `play_move()`, without its full-board win scan.
+    /// Flagged, revealed and out-of-bounds cells do nothing, and so does
+    /// every move once a mine was hit.
+    pub fn reveal(&mut self, x: usize, y: usize) -> MoveResult {
+        let mut result = MoveResult::default();
+        if self.hit_mine || self.visible.get_checked(x, y) != Some(-1) || self.flags.get(x, y) {
+            result.won = self.is_won();
+            return result;
+        }
+
+        let forgiven = self.mines.get(x, y) && match self.forgiveness.take() {
+            Some(mut rng) => relocate_hit_mine(&mut self.grid, &mut self.mines, &mut self.visible, &self.nc, x, y, &mut rng),
+            None => false,
+        };
+        self.forgiveness_used |= forgiven;
+        if self.mines.get(x, y) {
+            self.visible.set(x, y, EXPLODED);
+            self.hit_mine = true;
+            result.hit_mine = true;
+            result.revealed_cells.push((x, y));
+            return result;
+        }
+
+        let revealed = &mut result.revealed_cells;
+        reveal_with(&self.grid, &mut self.visible, &self.flags, &self.nc, x, y, |cx, cy| revealed.push((cx, cy)));
+        self.revealed_safe += result.revealed_cells.len();
+        result.won = self.is_won();
+        result
+    }
+
+    /// Toggle the flag on a hidden cell; see `toggle_flag()`.
+    pub fn toggle_flag(&mut self, x: usize, y: usize) -> bool {
+        toggle_flag(&self.visible, &mut self.flags, x, y)
+    }
+
+    /// True once every safe cell is revealed and no mine was hit.
+    pub fn is_won(&self) -> bool {
+        !self.hit_mine && self.revealed_safe == self.safe_total
+    }
+
+    /// True once a reveal hit a mine.
+    pub fn is_lost(&self) -> bool {
+        self.hit_mine
+    }
+
+    /// True once a mine hit was forgiven by relocating the mine.
+    pub fn forgiveness_used(&self) -> bool {
+        self.forgiveness_used
+    }
+
+    /// The current mine layout; a forgiven hit moves a mine.
+    pub fn mines(&self) -> &Mines {
+        &self.mines
+    }
+
+    /// Safe cells revealed so far.
+    pub fn revealed_safe(&self) -> usize {
+        self.revealed_safe
+    }
+
+    /// The current visible grid.
+    pub fn visible(&self) -> &VisibleGrid {
+        &self.visible
+    }
+
+    /// The current flags.
+    pub fn flags(&self) -> &Flags {
+        &self.flags
+    }
+}
+
+/// Apply a player reveal at `(x, y)`, mirroring `GameBase.reveal()` after the first click.
+///
+/// Clicking a flagged or already revealed cell does nothing. Hitting a mine
+/// writes `EXPLODED` at the clicked cell; with `reveal_all_mines` every other
+/// unflagged mine is written as `EXPLODED` too and listed in `revealed_cells`.
+/// Otherwise the cell is revealed with cascade and the win condition checked.
+#[allow(clippy::too_many_arguments)]
+pub fn play_move(
+    grid: &Grid,
+    mines: &Mines,
+    visible: &mut VisibleGrid,
+    flags: &Flags,
+    nc: &NeighborCache,
+    x: usize,
+    y: usize,
+    reveal_all_mines: bool,
+) -> MoveResult {
+    let options = PlayOptions::default().with_reveal_all_mines(reveal_all_mines);
+    play_move_with(grid, mines, visible, flags, nc, x, y, &options)
+}
+
+/// `play_move` with explicit options, e.g. a custom exploded-mine sentinel.
+#[allow(clippy::too_many_arguments)]
+pub fn play_move_with(
+    grid: &Grid,
+    mines: &Mines,
+    visible: &mut VisibleGrid,
+    flags: &Flags,
+    nc: &NeighborCache,
+    x: usize,
+    y: usize,
+    options: &PlayOptions,
+) -> MoveResult {
+    let mut result = MoveResult::default();
+    if visible.get_checked(x, y) != Some(-1) || flags.get(x, y) {
+        return result;
+    }
+
+    if mines.get(x, y) {
+        visible.set(x, y, options.exploded);
+        result.hit_mine = true;
+        result.revealed_cells.push((x, y));
+        if options.reveal_all_mines {
+            for mx in 0..mines.width {
+                for my in 0..mines.height {
+                    if mines.get(mx, my) && !flags.get(mx, my) && visible.get(mx, my) == -1 {
+                        visible.set(mx, my, options.exploded);
+                        result.revealed_cells.push((mx, my));
+                    }
+                }
+            }
+        }
+        return result;
+    }
+
+    let revealed = &mut result.revealed_cells;
+    reveal_with(grid, visible, flags, nc, x, y, |cx, cy| revealed.push((cx, cy)));
+
+    result.won = all_safe_revealed(visible, mines, nc);
+    result
+}
+
+/// `play_move()` that survives the first mine hit (casual modes).
+///
+/// While `forgiveness_used` is false, a click on a mine moves that mine to a
+/// random hidden cell, drawn uniformly in column-major order so a seeded `rng`
+/// replays the same move, then recomputes the numbers around both positions
+/// in `grid` and in any revealed cells of `visible`, sets `forgiveness_used`
+/// and reveals the click safely. Later hits, or a hit with no hidden cell to
+/// move to, explode as usual.
+#[allow(clippy::too_many_arguments)]
+pub fn forgiving_reveal<R: crate::rng::BoardRng>(
+    grid: &mut Grid,
+    mines: &mut Mines,
+    visible: &mut VisibleGrid,
+    flags: &Flags,
+    nc: &NeighborCache,
+    x: usize,
+    y: usize,
+    rng: &mut R,
+    forgiveness_used: &mut bool,
+) -> MoveResult {
+    let clickable = visible.get_checked(x, y) == Some(-1) && !flags.get(x, y);
+    if clickable && !*forgiveness_used && mines.get(x, y) && relocate_hit_mine(grid, mines, visible, nc, x, y, rng) {
+        *forgiveness_used = true;
+    }
+    play_move(grid, mines, visible, flags, nc, x, y, false)
+}
+
+/// Move the mine at `(x, y)` to a random hidden non-mine cell and refresh the
+/// numbers it touched. Returns false, changing nothing, if there is no target.
+fn relocate_hit_mine<R: crate::rng::BoardRng>(
+    grid: &mut Grid,
+    mines: &mut Mines,
+    visible: &mut VisibleGrid,
+    nc: &NeighborCache,
+    x: usize,
+    y: usize,
+    rng: &mut R,
+) -> bool {
+    let mut eligible = Vec::new();
+    for cx in 0..mines.width {
+        for cy in 0..mines.height {
+            if (cx, cy) != (x, y) && !mines.get(cx, cy) && visible.get(cx, cy) == -1 && !nc.is_blocked(cx, cy) {
+                eligible.push((cx, cy));
+            }
+        }
+    }
+    if eligible.is_empty() {
+        return false;
+    }
+    let target = eligible[rng.gen_range(eligible.len())];
+    mines.set(x, y, false);
+    mines.set(target.0, target.1, true);
+
+    let touched = [(x, y), target]
+        .into_iter()
+        .flat_map(|(px, py)| std::iter::once((px, py)).chain(nc.get(px, py).iter().copied()));
+    for (cx, cy) in touched {
+        let value = if mines.get(cx, cy) { 0 } else { nc.get(cx, cy).iter().filter(|&&(nx, ny)| mines.get(nx, ny)).count() as i8 };
+        grid.set(cx, cy, value);
+        if (0..=8).contains(&visible.get(cx, cy)) {
+            visible.set(cx, cy, value);
+        }
+    }
+    true
+}
+
+/// Reveal every hidden unflagged cell once all mines are flagged.
+///
+/// Fires only when the flag count equals `bomb_count` and the flags check out:
+/// against `mines` when given, otherwise against the clues (every revealed
+/// number must see exactly its value in flags, so a flag no clue touches is
+/// taken on trust). Updates `visible` with cascade and returns the newly
+/// revealed cells in reveal order; empty if the check fails.
+pub fn auto_complete(
+    grid: &Grid,
+    visible: &mut VisibleGrid,
+    flags: &Flags,
+    nc: &NeighborCache,
+    bomb_count: usize,
+    mines: Option<&Mines>,
+) -> Vec<(usize, usize)> {
+    let flag_count = flags.cells.iter().filter(|&&f| f != 0).count();
+    if flag_count != bomb_count { return Vec::new(); }
+
+    let consistent = match mines {
+        Some(mines) => flags.cells.iter().zip(&mines.cells).all(|(&f, &m)| f == 0 || m != 0),
+        None => (0..visible.width).all(|x| {
+            (0..visible.height).all(|y| {
+                let val = visible.get(x, y);
+                val <= 0 || nc.get(x, y).iter().filter(|&&(nx, ny)| flags.get(nx, ny)).count() == val as usize
+            })
+        }),
+    };
+    if !consistent { return Vec::new(); }
+
+    let mut revealed = Vec::new();
+    for x in 0..visible.width {
+        for y in 0..visible.height {
+            if visible.get(x, y) != -1 || flags.get(x, y) { continue; }
+            reveal_with(grid, visible, flags, nc, x, y, |cx, cy| revealed.push((cx, cy)));
+        }
+    }
+    revealed
+}
+
+/// Unflag and reveal every flagged cell that logic proves safe (forgiving modes).
+///
+/// Player flags are guesses, not facts, so the position is solved with all
+/// flags lifted; a flagged cell that solve reveals is wrong. Each such cell is
+/// unflagged and revealed with cascade (other flags still block the cascade).
+/// Returns the corrected cells in column-major order.
+pub fn correct_wrong_flags(
+    grid: &Grid,
+    visible: &mut VisibleGrid,
+    flags: &mut Flags,
+    nc: &NeighborCache,
+    bomb_count: usize,
+) -> Vec<(usize, usize)> {
+    let unflagged = Flags::new(flags.width, flags.height);
+    let mut wrong: Vec<(usize, usize)> = all_determinate_actions(grid, visible, &unflagged, nc, bomb_count)
+        .into_iter()
+        .filter_map(|action| match action {
+            Action::Reveal(x, y) if flags.get(x, y) => Some((x, y)),
+            _ => None,
+        })
+        .collect();
+    wrong.sort_unstable();
+
+    for &(x, y) in &wrong {
+        flags.set(x, y, false);
+    }
+    for &(x, y) in &wrong {
+        simulate_reveal(grid, visible, flags, nc, x, y);
+    }
+    wrong
+}
+
+/// Chord every satisfied number on the board until no more chords fire.
+///
+/// A revealed number whose flagged-neighbor count equals its value reveals all
+/// its unflagged hidden neighbors, with cascade; newly revealed numbers may
+/// then chord in turn. `revealed_cells` lists every newly revealed cell.
+///
+/// A wrong flag can make a chord open a mine: the mine is written as
+/// `EXPLODED`, listed in `revealed_cells`, `hit_mine` is set, and chording
+/// stops once the current sweep's chord finishes its neighbors.
+pub fn chord_all(
+    grid: &Grid,
+    mines: &Mines,
+    visible: &mut VisibleGrid,
+    flags: &Flags,
+    nc: &NeighborCache,
+) -> MoveResult {
+    let mut result = MoveResult::default();
+
+    'sweep: loop {
+        let mut fired = false;
+        for x in 0..visible.width {
+            for y in 0..visible.height {
+                let val = visible.get(x, y);
+                if !(1..=8).contains(&val) { continue; }
+
+                let neighbors = nc.get(x, y);
+                let flagged = neighbors.iter().filter(|&&(nx, ny)| flags.get(nx, ny)).count();
+                let has_hidden = neighbors.iter().any(|&(nx, ny)| visible.get(nx, ny) == -1 && !flags.get(nx, ny));
+                if flagged != val as usize || !has_hidden { continue; }
+
+                fired = true;
+                for &(nx, ny) in neighbors {
+                    if visible.get(nx, ny) != -1 || flags.get(nx, ny) { continue; }
+                    if mines.get(nx, ny) {
+                        visible.set(nx, ny, EXPLODED);
+                        result.revealed_cells.push((nx, ny));
+                        result.hit_mine = true;
+                    } else {
+                        let revealed = &mut result.revealed_cells;
+                        reveal_with(grid, visible, flags, nc, nx, ny, |cx, cy| revealed.push((cx, cy)));
+                    }
+                }
+                if result.hit_mine { break 'sweep; }
+            }
+        }
+        if !fired { break; }
+    }
+
+    if !result.hit_mine {
+        result.won = all_safe_revealed(visible, mines, nc);
+    }
+    result
+}
+
+// ─── hint_value ─────────────────────────────────────────────────────────────
+
+/// How many extra cells revealing the safe cell `(x, y)` makes determinable.
+///
+/// Runs logic to a stall from the current position and again after revealing
+/// `(x, y)` (with cascade) on a copy, and returns how many more cells end up
+/// revealed or flagged the second time, not counting `(x, y)` itself. A hint
+/// logic would have found anyway is worth 0. The cell must be safe; hidden
+/// mines are not detected here.
+#[allow(clippy::too_many_arguments)]
+pub fn hint_value(
+    grid: &Grid,
+    visible: &VisibleGrid,
+    flags: &Flags,
+    nc: &NeighborCache,
+    bomb_count: usize,
+    x: usize,
+    y: usize,
+) -> u32 {
+    if visible.get_checked(x, y) != Some(-1) || flags.get(x, y) { return 0; }
+
+    let mut before = SolveState::from_position(visible, flags, nc);
+    before.run_to_stall(grid, nc, bomb_count);
+    let resolved_before = before.revealed_count() + before.flag_count as usize;
+
+    let mut hinted = visible.clone();
+    simulate_reveal(grid, &mut hinted, flags, nc, x, y);
+    let mut after = SolveState::from_position(&hinted, flags, nc);
+    after.run_to_stall(grid, nc, bomb_count);
+    let resolved_after = after.revealed_count() + after.flag_count as usize;
+
+    resolved_after.saturating_sub(resolved_before + 1) as u32
+}
+
+// ─── guess_outcomes ─────────────────────────────────────────────────────────
+
+/// Both branches of guessing one hidden cell, for a "what if" explorer.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GuessOutcomes {
+    /// Chance the guess hits a mine, under the global mine count.
+    pub mine_probability: f32,
+    /// `value_distribution[v]`: chance the cell shows `v` given that it is
+    /// safe, over every consistent layout. Nine entries, all 0 if the cell
+    /// cannot be safe.
+    pub value_distribution: Vec<f32>,
+    /// What the cell shows on this board, or `None` if it is a mine here.
+    pub value: Option<i8>,
+    /// Cells the reveal opens (cascade included), column-major. Empty on a mine.
+    pub revealed: Vec<(usize, usize)>,
+    /// Everything logic settles after the reveal, as `all_determinate_actions()`
+    /// lists it. Empty on a mine.
+    pub closure: Vec<Action>,
+}
+
+/// Both outcomes of guessing `(x, y)`: the mine branch's probability, and for
+/// the safe branch the spread of numbers it could show plus, on this board,
+/// what the reveal opens and what logic then settles.
+///
+/// The value spread weighs each number by how many layouts fit the position
+/// with that clue placed on the cell (probability counts, tank-limited like
+/// `exact_mine_probabilities()`); only the board-specific part reads `mines`.
+/// `None` if the cell is out of bounds, open or flagged.
+#[allow(clippy::too_many_arguments)]
+pub fn guess_outcomes(
+    grid: &Grid,
+    mines: &Mines,
+    visible: &VisibleGrid,
+    flags: &Flags,
+    nc: &NeighborCache,
+    bomb_count: usize,
+    x: usize,
+    y: usize,
+) -> Option<GuessOutcomes> {
+    if visible.get_checked(x, y)? != -1 || flags.get(x, y) {
+        return None;
+    }
+    let height = grid.height;
+    let probs = probability::exact_mine_probabilities(visible, flags, nc, bomb_count, true, None, None);
+
+    // Weigh each clue value by the layouts consistent with it shown on the cell
+    let around = nc.get(x, y);
+    let flagged = around.iter().filter(|&&(nx, ny)| flags.get(nx, ny)).count();
+    let mut ln_counts = [None; 9];
+    for (v, slot) in ln_counts.iter_mut().enumerate().take(around.len() + 1).skip(flagged) {
+        let mut position = visible.clone();
+        if v == 0 {
+            // A 0 is not a constraint to the enumeration, so open its neighbours instead
+            for &(nx, ny) in around {
+                if position.get(nx, ny) == -1 { position.set(nx, ny, BLOCKED); }
+            }
+            position.set(x, y, BLOCKED);
+        } else {
+            position.set(x, y, v as i8);
+        }
+        *slot = probability::ln_layout_count(&position, flags, nc, bomb_count);
+    }
+    let ln_max = ln_counts.iter().flatten().cloned().fold(f64::NEG_INFINITY, f64::max);
+    let weights: Vec<f64> = ln_counts.iter().map(|c| c.map_or(0.0, |c| (c - ln_max).exp())).collect();
+    let total: f64 = weights.iter().sum();
+    let value_distribution = weights.iter()
+        .map(|&w| if total > 0.0 { (w / total) as f32 } else { 0.0 })
+        .collect();
+
+    let mut outcomes = GuessOutcomes {
+        mine_probability: probs[x * height + y],
+        value_distribution,
+        value: None,
+        revealed: Vec::new(),
+        closure: Vec::new(),
+    };
+    if mines.get(x, y) { return Some(outcomes); }
+
+    let mut after = visible.clone();
+    simulate_reveal(grid, &mut after, flags, nc, x, y);
+    outcomes.value = Some(grid.get(x, y));
+    outcomes.revealed = (0..after.cells.len())
+        .filter(|&i| visible.cells[i] == -1 && after.cells[i] != -1)
+        .map(|i| (i / height, i % height))
+        .collect();
+    outcomes.closure = all_determinate_actions(grid, &after, flags, nc, bomb_count);
+    Some(outcomes)
+}
+
+// ─── validate_replay ────────────────────────────────────────────────────────
+
+/// One recorded player action.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MoveKind {
+    /// Reveal the cell (cascading through zeros).
+    Reveal,
+    /// Toggle the flag on a hidden cell.
+    ToggleFlag,
+}
+
+/// Why a replay move was rejected.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IllegalMove {
+    OutOfBounds,
+    /// Revealing a cell that is already revealed.
+    AlreadyRevealed,
+    /// Revealing a flagged cell (the game ignores such clicks).
+    RevealFlagged,
+    /// Flagging a revealed cell.
+    FlagRevealed,
+    /// Revealing a mine.
+    HitMine,
+    /// Any move after the board was already won.
+    AfterWin,
+}
+
+/// Outcome of `validate_replay()`.
+#[derive(Debug, PartialEq, Eq)]
+pub struct ReplayResult {
+    /// Number of moves applied (stops at the first illegal move).
+    pub moves_applied: usize,
+    /// Index and reason of the first illegal move, if any.
+    pub illegal: Option<(usize, IllegalMove)>,
+    /// True if every safe cell was revealed with no illegal move.
+    pub won: bool,
+}
+
+/// Replay a recorded game against `mines` from a fresh board.
+///
+/// Moves are applied in order; the first illegal one stops the replay. A
+/// replay is a valid win when `illegal` is `None` and `won` is set.
+pub fn validate_replay(
+    mines: &Mines,
+    nc: &NeighborCache,
+    moves: &[(usize, usize, MoveKind)],
+) -> ReplayResult {
+    let grid = crate::board::calculate_numbers(mines, nc);
+    let mut visible = VisibleGrid::new(mines.width, mines.height);
+    let mut flags = Flags::new(mines.width, mines.height);
+    let mut won = false;
+
+    for (i, &(x, y, kind)) in moves.iter().enumerate() {
+        let reject = |reason| ReplayResult { moves_applied: i, illegal: Some((i, reason)), won: false };
+        if won { return reject(IllegalMove::AfterWin); }
+        let cell = match visible.get_checked(x, y) {
+            Some(v) => v,
+            None => return reject(IllegalMove::OutOfBounds),
+        };
+
+        match kind {
+            MoveKind::ToggleFlag => {
+                if !toggle_flag(&visible, &mut flags, x, y) { return reject(IllegalMove::FlagRevealed); }
+            }
+            MoveKind::Reveal => {
+                if cell != -1 { return reject(IllegalMove::AlreadyRevealed); }
+                if flags.get(x, y) { return reject(IllegalMove::RevealFlagged); }
+                if mines.get(x, y) { return reject(IllegalMove::HitMine); }
+                won = play_move(&grid, mines, &mut visible, &flags, nc, x, y, false).won;
+            }
+        }
+    }
+
+    ReplayResult { moves_applied: moves.len(), illegal: None, won }
+}
+
+// ─── replay_luck ─────────────────────────────────────────────────────────────
+
+/// How lucky a recorded game was: the product of the survival chances of
+/// every guess the player made. Lower means luckier; 1.0 means no guesses.
+///
+/// A reveal counts as a guess when `exact_mine_probabilities()` (ignoring the
+/// player's flags) gives the cell a nonzero mine probability at that point.
+/// The opening reveal is free, since boards are generated around it. The
+/// replay stops at the first move `validate_replay()` would reject.
+pub fn replay_luck(mines: &Mines, nc: &NeighborCache, moves: &[(usize, usize, MoveKind)]) -> f32 {
+    let grid = crate::board::calculate_numbers(mines, nc);
+    let bomb_count = mines.count();
+    let mut visible = VisibleGrid::new(mines.width, mines.height);
+    let mut flags = Flags::new(mines.width, mines.height);
+    let mut opened = false;
+    let mut luck = 1.0f32;
+
+    for &(x, y, kind) in moves {
+        let Some(cell) = visible.get_checked(x, y) else { break };
+        if cell != -1 { break; }
+        match kind {
+            MoveKind::ToggleFlag => {
+                toggle_flag(&visible, &mut flags, x, y);
+            }
+            MoveKind::Reveal => {
+                if flags.get(x, y) || mines.get(x, y) { break; }
+                if opened {
+                    let probs = probability::exact_mine_probabilities(&visible, &flags, nc, bomb_count, false, None, None);
+                    let p = probs[x * mines.height + y];
+                    if p > 1e-6 { luck *= 1.0 - p; }
+                }
+                opened = true;
+                if play_move(&grid, mines, &mut visible, &flags, nc, x, y, false).won { break; }
+            }
+        }
+    }
+
+    luck
+}
+
+// ─── expected_remaining_clicks ──────────────────────────────────────────────
+
+/// Minimum reveal clicks still needed to win from the current position.
+///
+/// 3BV restricted to hidden cells: one click per hidden zero opening, plus one
+/// per hidden numbered cell that no such opening reaches. Clues alone can't
+/// tell a hidden mine from a hidden zero, so the mine layout is required.
+/// Flags are not taken: they don't block optimal play, since a wrong flag
+/// would just be removed first.
+pub fn expected_remaining_clicks(
+    grid: &Grid,
+    mines: &Mines,
+    visible: &VisibleGrid,
+    nc: &NeighborCache,
+) -> u32 {
+    let no_flags = Flags::new(grid.width, grid.height);
+    let mut visible = visible.clone();
+    let mut clicks = 0u32;
+
+    for x in 0..grid.width {
+        for y in 0..grid.height {
+            if !mines.get(x, y) && grid.get(x, y) == 0 && visible.get(x, y) == -1 {
+                simulate_reveal(grid, &mut visible, &no_flags, nc, x, y);
+                clicks += 1;
+            }
+        }
+    }
+
+    // Blocked cells stay hidden but are not cells to click
+    for x in 0..grid.width {
+        for y in 0..grid.height {
+            if !mines.get(x, y) && visible.get(x, y) == -1 && !nc.is_blocked(x, y) {
+                clicks += 1;
+            }
+        }
+    }
+
+    clicks
+}
+
+/// Non-mine cells still hidden (flagged ones included), leaving out the cells
+/// `nc` has blocked. Zero exactly when the game is won, the same condition
+/// `SolveState::is_complete()` checks.
+pub fn remaining_safe_count(visible: &VisibleGrid, mines: &Mines, nc: &NeighborCache) -> u32 {
+    let height = visible.height;
+    visible.cells.iter()
+        .zip(&mines.cells)
+        .enumerate()
+        .filter(|&(i, (&v, &m))| v == -1 && m == 0 && !nc.is_blocked(i / height, i % height))
+        .count() as u32
+}
+
+/// Fraction of the safe cells revealed so far, in [0, 1]. Counts revealed
+/// non-mine cells over the unblocked cells less `bomb_count`, so it reaches
+/// exactly 1.0 on the same condition `play_move` reports as a win.
+pub fn progress(visible: &VisibleGrid, nc: &NeighborCache, bomb_count: usize) -> f32 {
+    let height = visible.height;
+    let playable = |i: usize| !nc.is_blocked(i / height, i % height);
+    let total_safe = (0..visible.cells.len()).filter(|&i| playable(i)).count().saturating_sub(bomb_count);
+    if total_safe == 0 {
+        return 1.0;
+    }
+    let revealed_safe = visible.cells.iter()
+        .enumerate()
+        .filter(|&(i, &v)| v != -1 && v != EXPLODED && playable(i))
+        .count()
+        .min(total_safe);
+    if revealed_safe == total_safe {
+        return 1.0;
+    }
+    revealed_safe as f32 / total_safe as f32
+}
+
+/// Total of the revealed clue numbers, the score of the "number golf" variant.
+/// Zeros, hidden cells and other negative markers add nothing.
+pub fn visible_clue_sum(visible: &VisibleGrid) -> u32 {
+    visible.cells.iter().filter(|&&v| v > 0).map(|&v| v as u32).sum()
+}
+
+// ─── flag_all_provable ──────────────────────────────────────────────────────
+
+/// Every hidden cell logic can prove is a mine from this position, not just
+/// the ones the solve needed on the way to its reveals. Column-major; cells
+/// already in `flags` are not listed.
+///
+/// Solves to a stall, then makes a final pass flagging each cell still
+/// hidden whose exact mine probability is 1, which also catches the mines
+/// left over once every safe cell is open.
+pub fn flag_all_provable(
+    grid: &Grid,
+    visible: &VisibleGrid,
+    flags: &Flags,
+    nc: &NeighborCache,
+    bomb_count: usize,
+) -> Vec<(usize, usize)> {
+    let mut state = SolveState::from_position(visible, flags, nc);
+    state.run_to_stall(grid, nc, bomb_count);
+
+    let probs = probability::exact_mine_probabilities(&state.visible, &state.flags, nc, bomb_count, true, None, None);
+    let height = grid.height;
+    let mut provable = Vec::new();
+    for (i, &p) in probs.iter().enumerate() {
+        let (x, y) = (i / height, i % height);
+        if flags.get(x, y) { continue; }
+        let hidden = state.visible.get(x, y) == -1;
+        if state.flags.get(x, y) || (hidden && p >= 1.0 - 1e-6) {
+            provable.push((x, y));
+        }
+    }
+    provable
+}
+
+// ─── flags_to_autocomplete ──────────────────────────────────────────────────
+
+/// How many more flags would finish the board: the count of unflagged cells
+/// proven to be mines, when those are all the mines left, so flagging them
+/// brings `remaining_mines` to 0 and the global count reveals everything else.
+///
+/// `None` when some remaining mine cannot be proven yet, or when the flags
+/// already outnumber the mines. `Some(0)` means the global count already applies.
+pub fn flags_to_autocomplete(
+    visible: &VisibleGrid,
+    flags: &Flags,
+    nc: &NeighborCache,
+    bomb_count: usize,
+) -> Option<u32> {
+    let flag_count = flags.cells.iter().filter(|&&f| f != 0).count();
+    let remaining = bomb_count.checked_sub(flag_count)?;
+
+    let probs = probability::exact_mine_probabilities(visible, flags, nc, bomb_count, true, None, None);
+    let proven = visible.cells.iter().zip(&flags.cells).zip(&probs)
+        .filter(|&((&v, &f), &p)| v == -1 && f == 0 && p >= 1.0 - 1e-6)
+        .count();
+    (proven == remaining).then_some(proven as u32)
+}
+
+// ─── all_determinate_actions ────────────────────────────────────────────────
+
+/// A move the solver can prove correct.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Action {
+    Reveal(usize, usize),
+    Flag(usize, usize),
+}
+
+/// Every action logic allows from the current position, in discovery order.
+///
+/// Runs the strategies to a stall on a copy of the position. Each step's new
+/// flags come before its new reveals (cascade cells included), column-major
+/// within each group.
+pub fn all_determinate_actions(
+    grid: &Grid,
+    visible: &VisibleGrid,
+    flags: &Flags,
+    nc: &NeighborCache,
+    bomb_count: usize,
+) -> Vec<Action> {
+    let mut state = SolveState::from_position(visible, flags, nc);
+    let mut actions = Vec::new();
+    let max_iterations = grid.width * grid.height * 2;
+
+    for _ in 0..max_iterations {
+        let before_visible = state.visible.cells.clone();
+        let before_flags = state.flags.cells.clone();
+        if state.step(grid, nc, bomb_count).is_none() { break; }
+
+        let height = grid.height;
+        for (i, (&now, &was)) in state.flags.cells.iter().zip(&before_flags).enumerate() {
+            if now != 0 && was == 0 {
+                actions.push(Action::Flag(i / height, i % height));
+            }
+        }
+        for (i, (&now, &was)) in state.visible.cells.iter().zip(&before_visible).enumerate() {
+            if now != -1 && was == -1 {
+                actions.push(Action::Reveal(i / height, i % height));
+            }
+        }
+    }
+
+    actions
+}
+
+/// `all_determinate_actions()` as two bit-packed masks over the column-major
+/// grid, `(reveals, flags)`, with a set bit where logic proves that action.
+///
+/// Cell `i = x * height + y` is bit `i % 8` (least significant first) of
+/// byte `i / 8`; each mask is `ceil(width * height / 8)` bytes and the unused
+/// high bits of the last byte are 0.
+pub fn determinate_action_masks(
+    grid: &Grid,
+    visible: &VisibleGrid,
+    flags: &Flags,
+    nc: &NeighborCache,
+    bomb_count: usize,
+) -> (Vec<u8>, Vec<u8>) {
+    let height = grid.height;
+    let bytes = grid.cells.len().div_ceil(8);
+    let mut reveals = vec![0u8; bytes];
+    let mut flag_mask = vec![0u8; bytes];
+    for action in all_determinate_actions(grid, visible, flags, nc, bomb_count) {
+        let (mask, i) = match action {
+            Action::Reveal(x, y) => (&mut reveals, x * height + y),
+            Action::Flag(x, y) => (&mut flag_mask, x * height + y),
+        };
+        mask[i / 8] |= 1 << (i % 8);
+    }
+    (reveals, flag_mask)
+}
+
+/// Format version written as the first byte of an encoded action list.
+const ACTIONS_VERSION: u8 = 1;
+
+/// Why an encoded action list could not be decoded.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DecodeError {
+    /// The stream was written by an unknown format version.
+    UnsupportedVersion(u8),
+    /// The stream ends inside the header, an action or the checksum.
+    Truncated,
+    /// A varint runs past 64 bits or its value does not fit a coordinate.
+    InvalidVarint,
+    /// Bytes remain after the checksum.
+    TrailingData,
+    /// The trailing checksum does not match (corruption in transit).
+    ChecksumMismatch,
+}
+
+impl std::fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DecodeError::UnsupportedVersion(v) => write!(f, "Unsupported action stream version {}", v),
+            DecodeError::Truncated => write!(f, "Action stream is truncated"),
+            DecodeError::InvalidVarint => write!(f, "Action stream has an invalid varint"),
+            DecodeError::TrailingData => write!(f, "Action stream has trailing data"),
+            DecodeError::ChecksumMismatch => write!(f, "Action stream checksum mismatch"),
+        }
+    }
+}
+
+/// Append `value` as an LEB128 varint: 7 bits per byte, high bit set on all but the last.
+fn push_varint(out: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        out.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+/// Read an LEB128 varint at `*pos`, advancing past it.
+fn read_varint(bytes: &[u8], pos: &mut usize) -> Result<u64, DecodeError> {
+    let mut value = 0u64;
+    for shift in (0..64).step_by(7) {
+        let &b = bytes.get(*pos).ok_or(DecodeError::Truncated)?;
+        *pos += 1;
+        if shift == 63 && b > 1 {
+            return Err(DecodeError::InvalidVarint);
+        }
+        value |= ((b & 0x7f) as u64) << shift;
+        if b & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    Err(DecodeError::InvalidVarint)
+}
+
+/// Pack actions (e.g. from `all_determinate_actions()`) into a compact byte
+/// stream for sending between clients.
+///
+/// Layout: version byte, the action count as a varint, then per action
+/// `x * 2 + kind` (0 = reveal, 1 = flag) and `y` as varints, and finally a
+/// one-byte wrapping sum of everything before it. Coordinates under 64 and
+/// 128 take one byte each, so most actions cost two bytes.
+pub fn encode_actions(actions: &[Action]) -> Vec<u8> {
+    let mut out = vec![ACTIONS_VERSION];
+    push_varint(&mut out, actions.len() as u64);
+    for &action in actions {
+        let (x, y, kind) = match action {
+            Action::Reveal(x, y) => (x, y, 0),
+            Action::Flag(x, y) => (x, y, 1),
+        };
+        push_varint(&mut out, (x as u64) << 1 | kind);
+        push_varint(&mut out, y as u64);
+    }
+    out.push(out.iter().fold(0u8, |acc, &b| acc.wrapping_add(b)));
+    out
+}
+
+/// Decode a stream produced by `encode_actions()`.
+pub fn decode_actions(bytes: &[u8]) -> Result<Vec<Action>, DecodeError> {
+    let (&checksum, body) = bytes.split_last().ok_or(DecodeError::Truncated)?;
+    let &version = body.first().ok_or(DecodeError::Truncated)?;
+    if version != ACTIONS_VERSION {
+        return Err(DecodeError::UnsupportedVersion(version));
+    }
+    if body.iter().fold(0u8, |acc, &b| acc.wrapping_add(b)) != checksum {
+        return Err(DecodeError::ChecksumMismatch);
+    }
+
+    let mut pos = 1;
+    let coordinate = |v: u64| usize::try_from(v).map_err(|_| DecodeError::InvalidVarint);
+    let count = coordinate(read_varint(body, &mut pos)?)?;
+    // Every action takes at least two bytes, so a corrupt count cannot over-allocate
+    let mut actions = Vec::with_capacity(count.min(body.len() / 2));
+    for _ in 0..count {
+        let head = read_varint(body, &mut pos)?;
+        let y = coordinate(read_varint(body, &mut pos)?)?;
+        let x = coordinate(head >> 1)?;
+        actions.push(if head & 1 == 0 { Action::Reveal(x, y) } else { Action::Flag(x, y) });
+    }
+    if pos != body.len() {
+        return Err(DecodeError::TrailingData);
+    }
+    Ok(actions)
+}
+
+/// What logic concludes about one frontier cell.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Verdict {
+    Safe,
+    Mine,
+    Unknown,
+}
+
+/// The verdict for every current frontier cell in one pass, for debug overlays.
+///
+/// Runs the full strategy set to a stall on a copy of the position, then labels
+/// each cell of the current frontier by whether it ended up revealed (`Safe`),
+/// flagged (`Mine`) or neither (`Unknown`). Column-major order.
+pub fn frontier_verdicts(
+    grid: &Grid,
+    visible: &VisibleGrid,
+    flags: &Flags,
+    nc: &NeighborCache,
+    bomb_count: usize,
+) -> Vec<(usize, usize, Verdict)> {
+    let frontier = get_frontier(visible, flags, nc);
+    let mut state = SolveState::from_position(visible, flags, nc);
+    state.run_to_stall(grid, nc, bomb_count);
+
+    frontier
+        .into_iter()
+        .map(|(x, y)| {
+            let verdict = if state.flags.get(x, y) {
+                Verdict::Mine
+            } else if state.visible.get(x, y) != -1 {
+                Verdict::Safe
+            } else {
+                Verdict::Unknown
+            };
+            (x, y, verdict)
+        })
+        .collect()
+}
+
+/// The hidden cells whose determination could depend on the clue at `(x, y)`,
+/// sorted by `(x, y)`, for showing how far a number's influence reaches.
+///
+/// The transitive closure clue → its hidden neighbours → their other clues →
+/// ..., which is the frontier region `group_frontier_regions()` builds around
+/// the clue. Empty unless `(x, y)` is a revealed number with a hidden,
+/// unflagged neighbour.
+pub fn influence_region(
+    visible: &VisibleGrid,
+    flags: &Flags,
+    nc: &NeighborCache,
+    x: usize,
+    y: usize,
+) -> Vec<(usize, usize)> {
+    if !matches!(visible.get_checked(x, y), Some(1..=8)) {
+        return Vec::new();
+    }
+    let Some(&seed) = nc.get(x, y).iter().find(|&&(nx, ny)| visible.get(nx, ny) == -1 && !flags.get(nx, ny)) else {
+        return Vec::new();
+    };
+    let frontier = get_frontier(visible, flags, nc);
+    group_frontier_regions(&frontier, visible, nc, None)
+        .into_iter()
+        .find(|region| region.binary_search(&seed).is_ok())
+        .unwrap_or_default()
+}
+
+// ─── explain_cell ───────────────────────────────────────────────────────────
+
+/// One solver iteration on the way to a target cell, keeping only the
+/// determinations the target depends on. Cells are column-major.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SolveStep {
+    pub strategy: Strategy,
+    pub flagged: Vec<(usize, usize)>,
+    pub revealed: Vec<(usize, usize)>,
+}
+
+/// Why `(x, y)` must be safe or a mine: the deductions leading to it, for a
+/// teaching mode.
+///
+/// Runs the solver from the position until the target is determined, noting
+/// which iteration settled each cell, then backtracks from the target over the
+/// cells each deduction could have read: for basic and subset rules the clues
+/// around the cell (subset logic also the clues overlapping those), for
+/// Gaussian, contradiction and tank the cell's whole frontier component, and
+/// for global counting everything settled before it. A zero cascade links the
+/// cells it opened. The strategies don't report which clue they used and one
+/// pass can make many deductions, so this is approximate: it may keep a few
+/// extra cells, and only flags (not new clues) count as inputs within a pass.
+///
+/// Returns `Some(vec![])` if the cell is already revealed or flagged, and
+/// `None` if logic cannot determine it.
+pub fn explain_cell(
+    grid: &Grid,
+    visible: &VisibleGrid,
+    flags: &Flags,
+    nc: &NeighborCache,
+    bomb_count: usize,
+    x: usize,
+    y: usize,
+) -> Option<Vec<SolveStep>> {
+    let height = grid.height;
+    let target = x * height + y;
+    if visible.get_checked(x, y)? != -1 || flags.get(x, y) {
+        return Some(Vec::new());
+    }
+
+    // settled_at[i]: the iteration that revealed or flagged cell i
+    let mut state = SolveState::from_position(visible, flags, nc);
+    let mut settled_at: Vec<Option<usize>> = vec![None; grid.cells.len()];
+    let mut strategies = Vec::new();
+    let max_iterations = grid.width * grid.height * 2;
+
+    while settled_at[target].is_none() && strategies.len() < max_iterations {
+        let before_visible = state.visible.cells.clone();
+        let before_flags = state.flags.cells.clone();
+        let strategy = state.step(grid, nc, bomb_count)?;
+        for i in 0..grid.cells.len() {
+            if state.visible.cells[i] != before_visible[i] || state.flags.cells[i] != before_flags[i] {
+                settled_at[i] = Some(strategies.len());
+            }
+        }
+        strategies.push(strategy);
+    }
+    settled_at[target]?;
+
+    // Cells as they stood when iteration `step` began
+    let revealed_by = |i: usize, step: usize| {
+        visible.cells[i] != -1 || (state.visible.cells[i] != -1 && settled_at[i].is_some_and(|t| t < step))
+    };
+    let hidden_at = |i: usize, step: usize| {
+        visible.cells[i] == -1 && flags.cells[i] == 0 && settled_at[i].is_none_or(|t| t >= step)
+    };
+    let is_clue = |i: usize, step: usize| revealed_by(i, step) && state.visible.cells[i] > 0;
+
+    let mut needed = vec![false; grid.cells.len()];
+    needed[target] = true;
+    let mut work = vec![target];
+    while let Some(cell) = work.pop() {
+        let (cx, cy) = (cell / height, cell % height);
+        let Some(step) = settled_at[cell] else { continue };
+        let strategy = strategies[step];
+        // Counting rules can use flags they set earlier in the same pass
+        let same_pass = matches!(strategy, Strategy::Basic | Strategy::Subset);
+
+        // Zero cascade: any same-iteration zero next to this cell
+        let mut read: Vec<usize> = nc.get(cx, cy).iter()
+            .map(|&(nx, ny)| nx * height + ny)
+            .filter(|&n| settled_at[n] == Some(step) && state.visible.cells[n] == 0)
+            .collect();
+
+        if strategy == Strategy::GlobalCount {
+            read.extend((0..grid.cells.len()).filter(|&i| settled_at[i].is_some_and(|t| t < step)));
+        } else {
+            let clues: Vec<usize> = if strategy == Strategy::Basic {
+                nc.get(cx, cy).iter()
+                    .map(|&(nx, ny)| nx * height + ny)
+                    .filter(|&n| is_clue(n, step))
+                    .collect()
+            } else {
+                // Flood the frontier component through shared clues; subset
+                // logic only looks one clue beyond the cell's own
+                let max_hops = if strategy == Strategy::Subset { 2 } else { usize::MAX };
+                let mut seen = vec![false; grid.cells.len()];
+                seen[cell] = true;
+                let mut clues = Vec::new();
+                let mut frontier = vec![cell];
+                let mut hops = 0;
+                while !frontier.is_empty() && hops < max_hops {
+                    hops += 1;
+                    let mut next = Vec::new();
+                    for &h in &frontier {
+                        for &(qx, qy) in nc.get(h / height, h % height) {
+                            let q = qx * height + qy;
+                            if seen[q] || !is_clue(q, step) { continue; }
+                            seen[q] = true;
+                            clues.push(q);
+                            for &(hx, hy) in nc.get(qx, qy) {
+                                let h2 = hx * height + hy;
+                                if !seen[h2] && hidden_at(h2, step) {
+                                    seen[h2] = true;
+                                    next.push(h2);
+                                }
+                            }
+                        }
+                    }
+                    frontier = next;
+                }
+                clues
+            };
+            for &q in &clues {
+                read.push(q);
+                read.extend(nc.get(q / height, q % height).iter().map(|&(nx, ny)| nx * height + ny));
+            }
+        }
+
+        for n in read {
+            let Some(t) = settled_at[n] else { continue };
+            let same_pass_input = if state.flags.cells[n] != 0 { same_pass } else { state.visible.cells[n] == 0 };
+            let earlier = t < step || (t == step && same_pass_input);
+            if earlier && !needed[n] {
+                needed[n] = true;
+                work.push(n);
+            }
+        }
+    }
+
+    let mut steps: Vec<SolveStep> = Vec::new();
+    for (step, &strategy) in strategies.iter().enumerate() {
+        let mut flagged = Vec::new();
+        let mut revealed = Vec::new();
+        for i in 0..grid.cells.len() {
+            if !needed[i] || settled_at[i] != Some(step) { continue; }
+            if state.flags.cells[i] != 0 {
+                flagged.push((i / height, i % height));
+            } else {
+                revealed.push((i / height, i % height));
+            }
+        }
+        if !flagged.is_empty() || !revealed.is_empty() {
+            steps.push(SolveStep { strategy, flagged, revealed });
+        }
+    }
+    Some(steps)
+}
+
+/// The revealed clues each individually needed to determine `(x, y)`: with
+/// any one of them hidden from the solver, logic from this position can no
+/// longer settle the cell. Column-major.
+///
+/// A clue is hidden by turning it into a revealed cell without a number, so
+/// the solver still knows it is safe. Clues the deduction uses but could work
+/// around (e.g. through the global mine count) are not listed. Empty if the
+/// cell is out of bounds, already open or flagged, or not determinable at all.
+pub fn supporting_clues(
+    grid: &Grid,
+    visible: &VisibleGrid,
+    flags: &Flags,
+    nc: &NeighborCache,
+    bomb_count: usize,
+    x: usize,
+    y: usize,
+) -> Vec<(usize, usize)> {
+    if visible.get_checked(x, y) != Some(-1) || flags.get(x, y) {
+        return Vec::new();
+    }
+    let determined = |visible: &VisibleGrid| {
+        let mut state = SolveState::from_position(visible, flags, nc);
+        state.run_to_stall(grid, nc, bomb_count);
+        state.visible.get(x, y) != -1 || state.flags.get(x, y)
+    };
+    if !determined(visible) {
+        return Vec::new();
+    }
+
+    let height = visible.height;
+    let mut masked = visible.clone();
+    let mut supporting = Vec::new();
+    for i in 0..visible.cells.len() {
+        let clue = visible.cells[i];
+        if clue <= 0 { continue; }
+        masked.cells[i] = BLOCKED;
+        if !determined(&masked) {
+            supporting.push((i / height, i % height));
+        }
+        masked.cells[i] = clue;
+    }
+    supporting
+}
+
+// ─── Constraint export ──────────────────────────────────────────────────────
+
+/// One revealed clue as a constraint: exactly `target` of the `vars` are
+/// mines. `target` is the clue minus its flagged neighbors, so wrong flags
+/// can make it negative.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ClueConstraint {
+    pub vars: Vec<usize>,
+    pub target: i32,
+}
+
+/// The position as a constraint problem, for external SAT/ILP solvers.
+///
+/// Variable `i` is the hidden, unflagged cell `cells[i]` (1 = mine); the
+/// variables summed must equal `mine_budget`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ConstraintSystem {
+    pub cells: Vec<(usize, usize)>,
+    pub constraints: Vec<ClueConstraint>,
+    pub mine_budget: i32,
+}
+
+/// Export every clue touching a hidden cell, plus the global mine budget
+/// (`bomb_count` minus the flags).
+///
+/// Variables are the hidden, unflagged cells in column-major order, so cells
+/// no clue touches still count against the budget. Constraints follow their
+/// clues in column-major order, each with ascending variable ids.
+pub fn export_constraints(
+    visible: &VisibleGrid,
+    flags: &Flags,
+    nc: &NeighborCache,
+    bomb_count: usize,
+) -> ConstraintSystem {
+    let height = visible.height;
+    let mut var_ids = vec![usize::MAX; visible.cells.len()];
+    let mut cells = Vec::new();
+    let mut flag_count = 0i32;
+    for x in 0..visible.width {
+        for y in 0..height {
+            if flags.get(x, y) {
+                flag_count += 1;
+            } else if visible.get(x, y) == -1 {
+                var_ids[x * height + y] = cells.len();
+                cells.push((x, y));
+            }
+        }
+    }
+
+    let mut constraints = Vec::new();
+    for x in 0..visible.width {
+        for y in 0..height {
+            let val = visible.get(x, y);
+            if val <= 0 { continue; }
+
+            let summary = neighbor_summary(visible, flags, nc, x, y);
+            if summary.hidden.is_empty() { continue; }
+            let mut vars: Vec<usize> = summary.hidden.iter().map(|&(nx, ny)| var_ids[nx * height + ny]).collect();
+            vars.sort_unstable();
+            constraints.push(ClueConstraint { vars, target: val as i32 - summary.flagged.len() as i32 });
+        }
+    }
+
+    ConstraintSystem { cells, constraints, mine_budget: bomb_count as i32 - flag_count }
+}
+
+// ─── get_logical_hint ───────────────────────────────────────────────────────
+
+/// Overlay value: the cell is known to be safe, though its number is unseen.
+pub const KNOWN_SAFE: i8 = 0;
+/// Overlay value: the cell is known to be a mine, though it is not flagged.
+pub const KNOWN_MINE: i8 = 1;
+
+/// The first action logic can prove, optionally using scattered extra facts.
+///
+/// `known` marks individual cells as `KNOWN_MINE` or `KNOWN_SAFE` (any other
+/// value means no fact; `VisibleGrid::new()` is the empty overlay), e.g. from
+/// an x-ray reveal. The counting rules treat known mines like flags and drop
+/// known-safe cells from the candidates. If they find no safe reveal, the full
+/// solver runs without the overlay; its deductions stay valid because extra
+/// facts only rule configurations out. Cells already covered by a fact are
+/// never suggested.
+///
+/// A safe reveal is always preferred; a forced flag is returned only when no
+/// safe reveal can be proven.
+pub fn get_logical_hint(
+    grid: &Grid,
+    visible: &VisibleGrid,
+    flags: &Flags,
+    nc: &NeighborCache,
+    bomb_count: usize,
+    known: Option<&VisibleGrid>,
+) -> Option<Action> {
+    let fact = |x: usize, y: usize| known.map_or(-1, |k| k.get(x, y));
+    let is_fact = |x: usize, y: usize| matches!(fact(x, y), KNOWN_SAFE | KNOWN_MINE);
+    let mut forced_flag = None;
+
+    for x in 0..visible.width {
+        for y in 0..visible.height {
+            let val = visible.get(x, y) as i32;
+            if val <= 0 { continue; }
+
+            let mut mines = 0i32;
+            let mut candidates: Vec<(usize, usize)> = Vec::new();
+            for &(nx, ny) in nc.get(x, y) {
+                if flags.get(nx, ny) {
+                    mines += 1;
+                } else if visible.get(nx, ny) == -1 {
+                    match fact(nx, ny) {
+                        KNOWN_MINE => mines += 1,
+                        KNOWN_SAFE => {}
+                        _ => candidates.push((nx, ny)),
+                    }
+                }
+            }
+            let Some(&(cx, cy)) = candidates.first() else { continue };
+
+            if val == mines {
+                return Some(Action::Reveal(cx, cy));
+            }
+            if val - mines == candidates.len() as i32 && forced_flag.is_none() {
+                forced_flag = Some(Action::Flag(cx, cy));
+            }
+        }
+    }
+
+    let actions: Vec<Action> = all_determinate_actions(grid, visible, flags, nc, bomb_count)
+        .into_iter()
+        .filter(|&a| match a {
+            Action::Reveal(x, y) | Action::Flag(x, y) => !is_fact(x, y),
+        })
+        .collect();
+    actions.iter().copied().find(|a| matches!(a, Action::Reveal(..)))
+        .or(forced_flag)
+        .or_else(|| actions.first().copied())
+}
+
+// ─── incremental_solve ──────────────────────────────────────────────────────
+
+/// Actions newly determinable after the player changed the cells in `changed`.
+///
+/// Seeds the dirty set from `changed` and their neighbors only, then runs
+/// Strategies 1 and 2 to a stall; both follow the dirty set, so the work is
+/// proportional to the area the move affects. The global strategies are
+/// skipped. Flags come before reveals (cascade cells included), column-major
+/// within each group.
+pub fn incremental_solve(
+    grid: &Grid,
+    visible: &VisibleGrid,
+    flags: &Flags,
+    nc: &NeighborCache,
+    bomb_count: usize,
+    changed: &[(usize, usize)],
+) -> Vec<Action> {
+    let config = SolverConfig { max_strategy_level: Strategy::Subset as u8, ..SolverConfig::default() };
+    let mut state = SolveState::from_changes(visible, flags, nc, changed).with_config(config);
+    state.run_to_stall(grid, nc, bomb_count);
+
+    let height = grid.height;
+    let mut actions = Vec::new();
+    for (i, (&now, &was)) in state.flags.cells.iter().zip(&flags.cells).enumerate() {
+        if now != 0 && was == 0 {
+            actions.push(Action::Flag(i / height, i % height));
+        }
+    }
+    for (i, (&now, &was)) in state.visible.cells.iter().zip(&visible.cells).enumerate() {
+        if now != -1 && was == -1 {
+            actions.push(Action::Reveal(i / height, i % height));
+        }
+    }
+    actions
+}
+
+// ─── has_determinate_move ───────────────────────────────────────────────────
+
+/// Whether Strategy 1 or 2 can determine at least one cell right now.
+///
+/// A cheap gate for the assist button: stops at the first deduction instead
+/// of running the full closure. `false` means only the deeper strategies or a
+/// guess can make progress. Both strategies read only local clues, so no
+/// mine total is needed.
+pub fn has_determinate_move(
+    grid: &Grid,
+    visible: &VisibleGrid,
+    flags: &Flags,
+    nc: &NeighborCache,
+) -> bool {
+    let mut clue_keys = HashSet::new();
+    for x in 0..visible.width {
+        for y in 0..visible.height {
+            let val = visible.get(x, y) as i32;
+            if val <= 0 { continue; }
+            let mut hidden = 0i32;
+            let mut flagged = 0i32;
+            for &(nx, ny) in nc.get(x, y) {
+                if flags.get(nx, ny) {
+                    flagged += 1;
+                } else if visible.get(nx, ny) == -1 {
+                    hidden += 1;
+                }
+            }
+            if hidden == 0 { continue; }
+
+            // Strategy 1: the clue alone fixes every hidden neighbor
+            if val == hidden + flagged || val == flagged { return true; }
+            clue_keys.insert(cell_key(x, y));
+        }
+    }
+
+    // Strategy 2 stops at its first deduction; run it on a scratch copy
+    let mut visible = visible.clone();
+    let mut flags = flags.clone();
+    apply_subset_logic(grid, &mut visible, &mut flags, nc, &clue_keys, 0).progress
+}
+
+// ─── globally_definite_cells ────────────────────────────────────────────────
+
+/// Cells that are a mine (first list) or safe (second list) in every solution,
+/// taking the total mine budget into account across all regions.
+///
+/// Each frontier region is enumerated as for `exact_mine_probabilities()`; a
+/// region's mine count `k` is feasible when some choice of counts for the
+/// other regions leaves `remaining - total` mines that fit off the frontier.
+/// A region cell is definite if it agrees across every configuration of every
+/// feasible `k`. Off-frontier cells are definite mines only when every
+/// feasible split fills all of them, and definite safes when none leaves a
+/// mine for them. Regions above the tank limit count as off-frontier, which
+/// only loses deductions. Both lists are column-major; both are empty for an
+/// inconsistent position.
+pub fn globally_definite_cells(
+    visible: &VisibleGrid,
+    flags: &Flags,
+    nc: &NeighborCache,
+    bomb_count: usize,
+) -> DefiniteCells {
+    let mut dists = Vec::new();
+    for (region, constraints) in probability::collect_regions(visible, flags, nc, None) {
+        match probability::region_distribution(&region, &constraints, None) {
+            Some(d) => dists.push(d),
+            None => return (vec![], vec![]),
+        }
+    }
+
+    let flag_count = flags.cells.iter().filter(|&&f| f != 0).count();
+    let hidden_count = visible.cells.iter().zip(&flags.cells).filter(|&(&v, &f)| v == -1 && f == 0).count();
+    let Some(remaining) = bomb_count.checked_sub(flag_count) else { return (vec![], vec![]) };
+    let frontier_cells: usize = dists.iter().map(|d| d.cells.len()).sum();
+    let off = hidden_count - frontier_cells;
+    let total_ok = |t: usize| t <= remaining && remaining - t <= off;
+
+    // Reachable frontier mine totals over a set of regions
+    let reachable = |skip: Option<usize>| -> Vec<bool> {
+        let mut reach = vec![true];
+        for (r, d) in dists.iter().enumerate() {
+            if Some(r) == skip { continue; }
+            let mut next = vec![false; reach.len() + d.by_count.len() - 1];
+            for (t, _) in reach.iter().enumerate().filter(|&(_, &ok)| ok) {
+                for (k, &c) in d.by_count.iter().enumerate() {
+                    if c > 0.0 { next[t + k] = true; }
+                }
+            }
+            reach = next;
+        }
+        reach
+    };
+
+    let mut definite_mines = Vec::new();
+    let mut definite_safes = Vec::new();
+
+    for (r, d) in dists.iter().enumerate() {
+        let others = reachable(Some(r));
+        let feasible: Vec<usize> = (0..d.by_count.len())
+            .filter(|&k| d.by_count[k] > 0.0)
+            .filter(|&k| others.iter().enumerate().any(|(j, &ok)| ok && total_ok(k + j)))
+            .collect();
+        if feasible.is_empty() { return (vec![], vec![]); }
+
+        for (i, &cell) in d.cells.iter().enumerate() {
+            if feasible.iter().all(|&k| d.cell_by_count[k][i] == d.by_count[k]) {
+                definite_mines.push(cell);
+            } else if feasible.iter().all(|&k| d.cell_by_count[k][i] == 0.0) {
+                definite_safes.push(cell);
+            }
+        }
+    }
+
+    if off > 0 {
+        let off_counts: Vec<usize> = reachable(None).iter().enumerate()
+            .filter(|&(t, &ok)| ok && total_ok(t))
+            .map(|(t, _)| remaining - t)
+            .collect();
+        if off_counts.is_empty() { return (vec![], vec![]); }
+
+        let all_mines = off_counts.iter().all(|&m| m == off);
+        let all_safe = off_counts.iter().all(|&m| m == 0);
+        if all_mines || all_safe {
+            let mut in_region = vec![false; visible.cells.len()];
+            for d in &dists {
+                for &(x, y) in &d.cells { in_region[x * visible.height + y] = true; }
+            }
+            for x in 0..visible.width {
+                for y in 0..visible.height {
+                    if visible.get(x, y) != -1 || flags.get(x, y) || in_region[x * visible.height + y] { continue; }
+                    if all_mines { definite_mines.push((x, y)); } else { definite_safes.push((x, y)); }
+                }
+            }
+        }
+    }
+
+    definite_mines.sort_unstable();
+    definite_safes.sort_unstable();
+    (definite_mines, definite_safes)
+}
+
+/// Whether revealing `(x, y)` is provably safe from what the player can see
+/// now, for a strict no-guess mode that refuses lucky clicks.
+///
+/// True when the cell is safe in every layout consistent with the visible
+/// clues, the flags (taken as correct) and the mine count: the safe list of
+/// `globally_definite_cells()`. It takes no grid, so the answer cannot depend
+/// on the hidden layout, and cells logic would only reach after further reveals
+/// do not count. False for open, flagged or out-of-bounds cells.
+pub fn is_move_forced_safe(
+    visible: &VisibleGrid,
+    flags: &Flags,
+    nc: &NeighborCache,
+    bomb_count: usize,
+    x: usize,
+    y: usize,
+) -> bool {
+    if visible.get_checked(x, y) != Some(-1) || flags.get(x, y) {
+        return false;
+    }
+    globally_definite_cells(visible, flags, nc, bomb_count).1.contains(&(x, y))
+}
+
+/// Hidden cells logic proves safe that the player has not revealed yet, in
+/// column-major order, for measuring missed opportunities.
+///
+/// The reveals of `all_determinate_actions()` from the player's position, flags
+/// taken as correct, cascades and follow-up deductions included. Everything in
+/// that closure is still hidden in `visible`, so nothing needs subtracting.
+pub fn missed_safe_cells(
+    grid: &Grid,
+    visible: &VisibleGrid,
+    flags: &Flags,
+    nc: &NeighborCache,
+    bomb_count: usize,
+) -> Vec<(usize, usize)> {
+    let mut missed: Vec<(usize, usize)> = all_determinate_actions(grid, visible, flags, nc, bomb_count)
+        .into_iter()
+        .filter_map(|action| match action {
+            Action::Reveal(x, y) => Some((x, y)),
+            Action::Flag(..) => None,
+        })
+        .collect();
+    missed.sort_unstable();
+    missed
+}
+
+// ─── redundant_clues ────────────────────────────────────────────────────────
+
+/// Revealed clues whose constraint follows from the clues that remain, for a
+/// "simplify" view that dims non-essential numbers.
+///
+/// A clue is redundant when it has no hidden, unflagged neighbor, or when the
+/// subset rule rebuilds its constraint from neighboring clues: another clue
+/// with the same cells and count, or clues A and B with the cells of B split
+/// exactly into A's and this one's (or this one's split into A's and B's).
+/// Clues are checked in column-major order and a redundant one is dropped
+/// before the next is checked, so the returned clues can all be dimmed at
+/// once without losing a deduction.
+pub fn redundant_clues(visible: &VisibleGrid, flags: &Flags, nc: &NeighborCache) -> Vec<(usize, usize)> {
+    // Hidden neighbors (sorted keys) and mines still owed, per clue
+    let mut constraints: HashMap<u32, (Vec<u32>, i32)> = HashMap::new();
+    let mut clues_of_cell: HashMap<u32, Vec<u32>> = HashMap::new();
+    let mut order = Vec::new();
+    for x in 0..visible.width {
+        for y in 0..visible.height {
+            let val = visible.get(x, y);
+            if val <= 0 { continue; }
+            let key = cell_key(x, y);
+            let mut hidden = Vec::new();
+            let mut remaining = val as i32;
+            for &(nx, ny) in nc.get(x, y) {
+                if flags.get(nx, ny) {
+                    remaining -= 1;
+                } else if visible.get(nx, ny) == -1 {
+                    hidden.push(cell_key(nx, ny));
+                }
+            }
+            // An over-flagged clue states nothing usable, and is not dimmed either
+            if remaining < 0 { continue; }
+            hidden.sort_unstable();
+            for &h in &hidden {
+                clues_of_cell.entry(h).or_default().push(key);
+            }
+            constraints.insert(key, (hidden, remaining));
+            order.push(key);
+        }
+    }
+
+    let mut removed: HashSet<u32> = HashSet::new();
+    // Kept clues other than `skip` touching any of `cells`
+    let touching = |cells: &[u32], skip: u32, removed: &HashSet<u32>| -> Vec<u32> {
+        let mut out: Vec<u32> = cells.iter()
+            .flat_map(|h| clues_of_cell.get(h).into_iter().flatten().copied())
+            .filter(|&k| k != skip && !removed.contains(&k))
+            .collect();
+        out.sort_unstable();
+        out.dedup();
+        out
+    };
+    // Some kept clue other than `skip` states exactly `cells` holds `count`
+    let stated = |cells: &[u32], count: i32, skip: u32, removed: &HashSet<u32>| -> bool {
+        touching(cells, skip, removed).iter().any(|k| constraints[k].0 == cells && constraints[k].1 == count)
+    };
+
+    let mut redundant = Vec::new();
+    for &c in &order {
+        let (sc, rc) = &constraints[&c];
+        let implied = sc.is_empty() || touching(sc, c, &removed).iter().any(|b| {
+            let (sb, rb) = &constraints[b];
+            let diff = |big: &[u32], small: &[u32]| -> Vec<u32> {
+                big.iter().copied().filter(|k| small.binary_search(k).is_err()).collect()
+            };
+            let within = |small: &[u32], big: &[u32]| small.iter().all(|k| big.binary_search(k).is_ok());
+            if sb == sc {
+                rb == rc
+            } else if within(sc, sb) {
+                stated(&diff(sb, sc), rb - rc, c, &removed)
+            } else if within(sb, sc) {
+                stated(&diff(sc, sb), rc - rb, c, &removed)
+            } else {
+                false
+            }
+        });
+        if implied {
+            removed.insert(c);
+            redundant.push(decode_key(c));
+        }
+    }
+    redundant
+}
+
+// ─── count_consistent_boards_exact ──────────────────────────────────────────
+
+/// Number of mine layouts consistent with the position, exactly.
+///
+/// Every frontier region is counted by mine total with
+/// `count_region_layouts()`, the regions are convolved, and each frontier
+/// total `t` is weighted by `C(off_frontier, remaining - t)`. All of it runs
+/// in big-integer arithmetic, so sparse endgames whose counts overflow `u128`
+/// stay exact. Flags count as mines; an inconsistent position gives zero.
+#[cfg(feature = "bigint")]
+pub fn count_consistent_boards_exact(
+    visible: &VisibleGrid,
+    flags: &Flags,
+    nc: &NeighborCache,
+    bomb_count: usize,
+) -> num_bigint::BigUint {
+    use num_bigint::BigUint;
+
+    let flag_count = flags.cells.iter().filter(|&&f| f != 0).count();
+    let Some(remaining) = bomb_count.checked_sub(flag_count) else { return BigUint::ZERO };
+    let hidden = visible.cells.iter().zip(&flags.cells).filter(|&(&v, &f)| v == -1 && f == 0).count();
+
+    let frontier = get_frontier(visible, flags, nc);
+    let off = hidden - frontier.len();
+    // by_total[t] = frontier layouts with t mines
+    let mut by_total = vec![BigUint::from(1u32)];
+    for region in group_frontier_regions(&frontier, visible, nc, None) {
+        let constraints = get_region_constraints(&region, visible, flags, nc, None);
+        let counts = count_region_layouts(region.len(), &constraints, BigUint::ZERO, BigUint::from(1u32), |a, b| a + b);
+        let mut next = vec![BigUint::ZERO; by_total.len() + counts.len() - 1];
+        for (i, a) in by_total.iter().enumerate() {
+            if *a == BigUint::ZERO { continue; }
+            for (j, b) in counts.iter().enumerate() {
+                next[i + j] += a * b;
+            }
+        }
+        by_total = next;
+    }
+
+    // C(n, k) by prefix products, each itself a binomial, so every division is exact
+    let binomial = |n: usize, k: usize| {
+        let k = k.min(n - k);
+        (0..k).fold(BigUint::from(1u32), |c, i| c * (n - i) / (i + 1))
+    };
+    let mut total = BigUint::ZERO;
+    for (t, ways) in by_total.iter().enumerate() {
+        if t > remaining || remaining - t > off || *ways == BigUint::ZERO { continue; }
+        total += ways * binomial(off, remaining - t);
+    }
+    total
+}
+
+/// Layouts of an `n`-cell region by mine count, as `counts[m]` for `m` mines.
+///
+/// Dynamic programming over the cells in order: a constraint is live from its
+/// first region cell to its last, and layouts that agree on the mines placed
+/// so far in every live constraint are merged into one state, so the work
+/// grows with the number of such states rather than with the layouts. Count
+/// arithmetic goes through `add`, which lets callers saturate or go exact.
+fn count_region_layouts<T: Clone>(
+    n: usize,
+    constraints: &[RegionConstraint],
+    zero: T,
+    one: T,
+    add: impl Fn(&T, &T) -> T,
+) -> Vec<T> {
+    let mut of_cell = vec![Vec::new(); n];
+    let mut last = vec![0; constraints.len()];
+    for (c, rc) in constraints.iter().enumerate() {
+        for &i in &rc.cells_in_region_indices {
+            of_cell[i].push(c);
+            last[c] = last[c].max(i);
+        }
+    }
+    // Whether `c` can still be met with `placed` mines and `open` cells to go
+    let feasible = |c: usize, placed: i32, open: i32| {
+        let rc = &constraints[c];
+        placed <= rc.remaining && placed + open + rc.cells_outside_count as i32 >= rc.remaining
+    };
+    let open = |c: usize| constraints[c].cells_in_region_indices.len() as i32;
+    if !(0..constraints.len()).all(|c| feasible(c, 0, open(c))) {
+        return vec![zero; n + 1];
+    }
+
+    // `live` lists the constraints whose cells are partly placed; each state
+    // key holds their placed mine counts in that order
+    let mut live: Vec<usize> = Vec::new();
+    let mut seen = vec![0i32; constraints.len()];
+    let mut states: HashMap<Vec<i32>, Vec<T>> = HashMap::from([(Vec::new(), vec![one])]);
+    for (i, touched) in of_cell.iter().enumerate() {
+        let before = live.len();
+        for &c in touched {
+            if seen[c] == 0 {
+                live.push(c);
+            }
+            seen[c] += 1;
+        }
+        if live.len() > before {
+            states = states.into_iter().map(|(mut key, counts)| {
+                key.resize(live.len(), 0);
+                (key, counts)
+            }).collect();
+        }
+        let slots: Vec<usize> = touched.iter().map(|c| live.iter().position(|l| l == c).unwrap_or(0)).collect();
+        let keep: Vec<bool> = live.iter().map(|&c| last[c] != i).collect();
+
+        let mut next: HashMap<Vec<i32>, Vec<T>> = HashMap::new();
+        for (key, counts) in &states {
+            for mine in [0, 1] {
+                let mut placed = key.clone();
+                for &slot in &slots {
+                    placed[slot] += mine;
+                }
+                if !touched.iter().zip(&slots).all(|(&c, &slot)| feasible(c, placed[slot], open(c) - seen[c])) {
+                    continue;
+                }
+                let key: Vec<i32> = placed.iter().zip(&keep).filter(|&(_, &k)| k).map(|(&p, _)| p).collect();
+                let merged = next.entry(key).or_insert_with(|| vec![zero.clone(); i + 2]);
+                for (m, count) in counts.iter().enumerate() {
+                    let slot = &mut merged[m + mine as usize];
+                    *slot = add(slot, count);
+                }
+            }
+        }
+        states = next;
+        live = live.iter().zip(&keep).filter(|&(_, &k)| k).map(|(&c, _)| c).collect();
+    }
+
+    let mut counts = states.remove(&Vec::new()).unwrap_or_default();
+    counts.resize(n + 1, zero);
+    counts
+}
+
+// ─── solution_count ─────────────────────────────────────────────────────────
+
+/// Number of complete mine layouts consistent with the position, capped at
+/// `cap`; 1 means the position determines every mine.
+///
+/// Counted as in `count_consistent_boards_exact()`: each frontier region by
+/// mine total with `count_region_layouts()`, convolved, and each frontier
+/// total `t` weighted by `C(off_frontier, remaining - t)`. The region counts
+/// grow with the frontier's width, not its layouts, so wide open frontiers
+/// stay cheap. Every intermediate saturates at `cap`, which is only an upper
+/// bound, so no arithmetic overflows. Flags
+/// count as mines; an inconsistent position, including a fully surrounded clue
+/// its flags do not match, gives zero.
+pub fn solution_count(
+    visible: &VisibleGrid,
+    flags: &Flags,
+    nc: &NeighborCache,
+    bomb_count: usize,
+    cap: u64,
+) -> u64 {
+    let height = visible.height;
+    let mut flag_count = 0;
+    let mut hidden = 0;
+    for (i, (&v, &f)) in visible.cells.iter().zip(&flags.cells).enumerate() {
+        if f != 0 {
+            flag_count += 1;
+        } else if v == -1 {
+            hidden += 1;
+        } else if v > 0 {
+            // Clues with hidden neighbours are checked by their region's constraints
+            let around = nc.get(i / height, i % height);
+            let open = around.iter().all(|&(nx, ny)| flags.get(nx, ny) || visible.get(nx, ny) != -1);
+            if open && around.iter().filter(|&&(nx, ny)| flags.get(nx, ny)).count() != v as usize {
+                return 0;
+            }
+        }
+    }
+    let Some(remaining) = bomb_count.checked_sub(flag_count) else { return 0 };
+
+    let cap128 = cap as u128;
+    let frontier = get_frontier(visible, flags, nc);
+    let off = hidden - frontier.len();
+    // by_total[t] = frontier layouts with t mines, saturated at cap
+    let mut by_total = vec![1u128.min(cap128)];
+    for region in group_frontier_regions(&frontier, visible, nc, None) {
+        let constraints = get_region_constraints(&region, visible, flags, nc, None);
+        let counts = count_region_layouts(region.len(), &constraints, 0, 1u128.min(cap128), |a, b| {
+            a.saturating_add(*b).min(cap128)
+        });
+        let mut next = vec![0u128; by_total.len() + counts.len() - 1];
+        for (i, &a) in by_total.iter().enumerate() {
+            if a == 0 { continue; }
+            for (j, &b) in counts.iter().enumerate() {
+                next[i + j] = next[i + j].saturating_add(a.saturating_mul(b)).min(cap128);
+            }
+        }
+        by_total = next;
+    }
+
+    // C(n, k) saturated at cap; the partial products C(n, i) grow up to k <= n / 2
+    let binomial = |n: usize, k: usize| -> u128 {
+        let k = k.min(n - k);
+        let mut c = 1u128;
+        for i in 0..k {
+            if c >= cap128 { return cap128; }
+            c = c * (n - i) as u128 / (i + 1) as u128;
+        }
+        c.min(cap128)
+    };
+    let mut total = 0u128;
+    for (t, &ways) in by_total.iter().enumerate() {
+        if t > remaining || remaining - t > off || ways == 0 { continue; }
+        total = total.saturating_add(ways.saturating_mul(binomial(off, remaining - t))).min(cap128);
+    }
+    total as u64
+}
+
+// ─── minimal_clue_set ───────────────────────────────────────────────────────
+
+/// Prune a fully revealed board down to clues that still force its layout.
+///
+/// Starts with every safe cell showing its number and greedily hides clues,
+/// farthest from `(start_x, start_y)` first, keeping a removal only if every
+/// hidden cell stays definite under `globally_definite_cells()` with the
+/// board's mine count. Hidden cells give no numbers, so the remaining clues
+/// alone must pin down every mine. Returns the clues that must stay visible,
+/// column-major. Offline puzzle-design tool: one exact check per clue.
+/// Regions above the tank limit count as undetermined, so dense boards keep
+/// more clues than strictly needed.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn minimal_clue_set(
+    grid: &Grid,
+    mines: &Mines,
+    nc: &NeighborCache,
+    start_x: usize,
+    start_y: usize,
+) -> Vec<(usize, usize)> {
+    let (w, h) = (grid.width, grid.height);
+    let bomb_count = mines.count();
+    let flags = Flags::new(w, h);
+    let mut visible = VisibleGrid::new(w, h);
+    let mut clues = Vec::new();
+    for x in 0..w {
+        for y in 0..h {
+            if !mines.get(x, y) {
+                visible.set(x, y, grid.get(x, y));
+                clues.push((x, y));
+            }
+        }
+    }
+
+    let forced = |visible: &VisibleGrid| {
+        let hidden = visible.cells.iter().filter(|&&v| v == -1).count();
+        let (m, s) = globally_definite_cells(visible, &flags, nc, bomb_count);
+        m.len() + s.len() == hidden
+    };
+    if !forced(&visible) { return clues; }
+
+    let dist = |&(x, y): &(usize, usize)| x.abs_diff(start_x).max(y.abs_diff(start_y));
+    let mut order = clues.clone();
+    order.sort_by_key(|c| std::cmp::Reverse(dist(c)));
+    for (x, y) in order {
+        visible.set(x, y, -1);
+        if !forced(&visible) {
+            visible.set(x, y, grid.get(x, y));
+        }
+    }
+
+    clues.retain(|&(x, y)| visible.get(x, y) != -1);
+    clues
+}
+
+// ─── detect_known_patterns ──────────────────────────────────────────────────
+
+/// Named wall patterns recognized by `detect_known_patterns()`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PatternKind {
+    /// Clues 1-2-1 along a wall: mines under the 1s, the middle is safe.
+    OneTwoOne,
+    /// Clues 1-2-2-1 along a wall: mines under the 2s, the ends are safe.
+    OneTwoTwoOne,
+}
+
+impl PatternKind {
+    /// Effective clue values, the mine offsets and the safe offsets (relative
+    /// to the first clue, along the line) on the hidden side.
+    fn shape(self) -> (&'static [i32], &'static [i64], &'static [i64]) {
+        match self {
+            PatternKind::OneTwoOne => (&[1, 2, 1], &[0, 2], &[-1, 1, 3]),
+            PatternKind::OneTwoTwoOne => (&[1, 2, 2, 1], &[1, 2], &[-1, 0, 3, 4]),
+        }
+    }
+
+    /// Display name, e.g. for an educational overlay.
+    pub fn name(self) -> &'static str {
+        match self {
+            PatternKind::OneTwoOne => "1-2-1",
+            PatternKind::OneTwoTwoOne => "1-2-2-1",
+        }
+    }
+}
+
+/// Find 1-2-1 and 1-2-2-1 patterns along walls and the moves they prove.
+///
+/// A pattern is a straight run of revealed clues whose values, minus adjacent
+/// flags, read 1-2-1 or 1-2-2-1, where every hidden neighbor of those clues
+/// lies in the parallel line on one side (the other side is revealed or the
+/// board edge) and the cells facing the clues are all hidden. Rows are
+/// scanned before columns. Each hit lists its flags, then its reveals; only
+/// hidden, unflagged cells are included.
+pub fn detect_known_patterns(
+    visible: &VisibleGrid,
+    flags: &Flags,
+    nc: &NeighborCache,
+) -> Vec<(PatternKind, Vec<Action>)> {
+    let (w, h) = (visible.width as i64, visible.height as i64);
+    let mut found = Vec::new();
+
+    for horizontal in [true, false] {
+        let (len, lines) = if horizontal { (w, h) } else { (h, w) };
+        // Map (along, across) line coordinates to a board cell
+        let at = |along: i64, across: i64| -> Option<(usize, usize)> {
+            let (x, y) = if horizontal { (along, across) } else { (across, along) };
+            (x >= 0 && y >= 0 && x < w && y < h).then_some((x as usize, y as usize))
+        };
+        let is_open = |c: (usize, usize)| visible.get(c.0, c.1) == -1 && !flags.get(c.0, c.1);
+
+        for line in 0..lines {
+            for side in [-1i64, 1] {
+                if at(0, line + side).is_none() { continue; }
+                for start in 0..len {
+                    for kind in [PatternKind::OneTwoOne, PatternKind::OneTwoTwoOne] {
+                        let (values, mine_offsets, safe_offsets) = kind.shape();
+                        let n = values.len() as i64;
+
+                        let matches = values.iter().enumerate().all(|(i, &want)| {
+                            let Some((cx, cy)) = at(start + i as i64, line) else { return false };
+                            let val = visible.get(cx, cy);
+                            if !(1..=8).contains(&val) { return false; }
+                            let mut flagged = 0i32;
+                            for &(nx, ny) in nc.get(cx, cy) {
+                                if flags.get(nx, ny) {
+                                    flagged += 1;
+                                } else if visible.get(nx, ny) == -1 {
+                                    // Every hidden neighbor must face the clue row
+                                    let across = if horizontal { ny } else { nx } as i64;
+                                    if across != line + side { return false; }
+                                }
+                            }
+                            val as i32 - flagged == want
+                        }) && (0..n).all(|i| at(start + i, line + side).is_some_and(is_open));
+                        if !matches { continue; }
+
+                        let mut actions = Vec::new();
+                        for &o in mine_offsets {
+                            if let Some(c) = at(start + o, line + side).filter(|&c| is_open(c)) {
+                                actions.push(Action::Flag(c.0, c.1));
+                            }
+                        }
+                        for &o in safe_offsets {
+                            if let Some(c) = at(start + o, line + side).filter(|&c| is_open(c)) {
+                                actions.push(Action::Reveal(c.0, c.1));
+                            }
+                        }
+                        found.push((kind, actions));
+                    }
+                }
+            }
+        }
+    }
+
+    found
+}
+
+// ─── solve_endgame_exact ────────────────────────────────────────────────────
+
+/// Most hidden cells `solve_endgame_exact()` will enumerate jointly.
+pub const ENDGAME_MAX_HIDDEN: usize = 24;
+
+/// Cells fixed in every placement consistent with the whole board.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct EndgameResult {
+    /// Hidden cells that are a mine in every placement, column-major.
+    pub mines: Vec<(usize, usize)>,
+    /// Hidden cells that are safe in every placement, column-major.
+    pub safe: Vec<(usize, usize)>,
+}
+
+/// Exact deduction over all remaining hidden cells at once.
+///
+/// The tank solver enumerates each frontier region separately and Strategy 6
+/// only sees the total, so deductions that need both (e.g. the mine count
+/// choosing between a region's 1- and 2-mine layouts) are missed. With at most
+/// `ENDGAME_MAX_HIDDEN` hidden unflagged cells, this enumerates every placement
+/// of exactly the remaining mine count and checks it against every clue.
+///
+/// Returns `None` above the threshold or when no placement is consistent.
+/// A single remaining mine that `solve_last_mine()` pins is resolved without
+/// enumerating, and without the threshold.
+pub fn solve_endgame_exact(
+    visible: &VisibleGrid,
+    flags: &Flags,
+    nc: &NeighborCache,
+    bomb_count: usize,
+) -> Option<EndgameResult> {
+    let mut hidden = Vec::new();
+    for x in 0..visible.width {
+        for y in 0..visible.height {
+            if visible.get(x, y) == -1 && !flags.get(x, y) {
+                hidden.push((x, y));
+            }
+        }
+    }
+    if let Some(mine) = solve_last_mine(visible, flags, nc, bomb_count) {
+        let safe = hidden.into_iter().filter(|&cell| cell != mine).collect();
+        return Some(EndgameResult { mines: vec![mine], safe });
+    }
+    if hidden.len() > ENDGAME_MAX_HIDDEN { return None; }
+
+    let n = hidden.len();
+    let remaining = bomb_count.checked_sub(flags.cells.iter().filter(|&&f| f != 0).count())?;
+    if remaining > n { return None; }
+
+    // Every hidden cell is in the "region", so no constraint has outside cells
+    let constraints: Vec<(u64, u32)> = get_region_constraints(&hidden, visible, flags, nc, None)
+        .iter()
+        .map(|c| {
+            let mask = c.cells_in_region_indices.iter().fold(0u64, |m, &i| m | (1 << i));
+            // A negative remainder can never be met
+            (mask, u32::try_from(c.remaining).unwrap_or(u32::MAX))
+        })
+        .collect();
+
+    let mut any_mine = 0u64;
+    let mut all_mine = u64::MAX;
+    let mut found = false;
+
+    // Walk the n-bit masks with exactly `remaining` bits set (Gosper's hack)
+    let limit = 1u64 << n;
+    let mut mask = (1u64 << remaining) - 1;
+    while mask < limit {
+        if constraints.iter().all(|&(c, want)| (mask & c).count_ones() == want) {
+            found = true;
+            any_mine |= mask;
+            all_mine &= mask;
+        }
+        if mask == 0 { break; }
+        let low = mask & mask.wrapping_neg();
+        let ripple = mask + low;
+        mask = (((ripple ^ mask) >> 2) / low) | ripple;
+    }
+    if !found { return None; }
+
+    let mut result = EndgameResult::default();
+    for (i, &cell) in hidden.iter().enumerate() {
+        if (all_mine >> i) & 1 == 1 {
+            result.mines.push(cell);
+        } else if (any_mine >> i) & 1 == 0 {
+            result.safe.push(cell);
+        }
+    }
+    Some(result)
+}
+
+/// The last mine, when exactly one is left unflagged and the clues pin it.
+///
+/// Every clue then needs 0 or 1 more mine: the mine lies among the hidden
+/// neighbors of each clue needing 1 and outside those of each clue needing 0,
+/// so intersecting them leaves the candidates. Returns `None` unless exactly
+/// one hidden cell survives, including when a clue needs more than one mine
+/// or is over-flagged. Every other hidden cell is safe once it is found.
+pub fn solve_last_mine(
+    visible: &VisibleGrid,
+    flags: &Flags,
+    nc: &NeighborCache,
+    bomb_count: usize,
+) -> Option<(usize, usize)> {
+    let flag_count = flags.cells.iter().filter(|&&f| f != 0).count();
+    if bomb_count.checked_sub(flag_count) != Some(1) { return None; }
+
+    let height = visible.height;
+    // Per cell: how many "needs 1" clues it touches, or None once a
+    // "needs 0" clue rules it out
+    let mut hits: Vec<Option<u32>> = visible.cells.iter()
+        .zip(&flags.cells)
+        .map(|(&v, &f)| (v == -1 && f == 0).then_some(0))
+        .collect();
+    let mut needs_one = 0;
+
+    for x in 0..visible.width {
+        for y in 0..height {
+            let val = visible.get(x, y);
+            if val <= 0 { continue; }
+            let summary = neighbor_summary(visible, flags, nc, x, y);
+            if summary.hidden.is_empty() { continue; }
+            match val as i32 - summary.flagged.len() as i32 {
+                0 => {
+                    for &(hx, hy) in &summary.hidden {
+                        hits[hx * height + hy] = None;
+                    }
+                }
+                1 => {
+                    needs_one += 1;
+                    for &(hx, hy) in &summary.hidden {
+                        if let Some(h) = &mut hits[hx * height + hy] { *h += 1; }
+                    }
+                }
+                _ => return None,
+            }
+        }
+    }
+
+    let mut survivors = hits.iter().enumerate().filter(|&(_, &h)| h == Some(needs_one));
+    let (i, _) = survivors.next()?;
+    if survivors.next().is_some() { return None; }
+    Some((i / height, i % height))
+}
+
+// ─── graded_hint ────────────────────────────────────────────────────────────
+
+/// How much a graded hint gives away, weakest first.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum HintLevel {
+    /// Where to look, without naming the cell.
+    Region,
+    /// The safe cell itself.
+    Cell,
+    /// The safe cell and the deductions that prove it.
+    Explanation,
+}
+
+/// One step of a graded hint; see `graded_hint()`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum GradedHint {
+    /// Bounding box, corners inclusive, of the frontier region holding a safe move.
+    Region { x0: usize, y0: usize, x1: usize, y1: usize },
+    Cell { x: usize, y: usize },
+    Explanation { x: usize, y: usize, steps: Vec<SolveStep> },
+}
+
+/// A hint about the next logically safe reveal, revealing as much as `level` asks.
+///
+/// Every level refers to the same cell, so asking again at a higher level
+/// after a `Region` nudge names a cell inside that box: the first
+/// (column-major) frontier cell the full logical solve reveals, or the first
+/// revealed cell of all when only off-frontier cells open. The per-step order
+/// depends on hash-set iteration, so the choice is made from the stalled
+/// position instead. The box is that of the cell's frontier region, or of
+/// every hidden cell off the frontier when only the mine count proves it.
+/// `None` if logic alone reveals nothing.
+pub fn graded_hint(
+    grid: &Grid,
+    visible: &VisibleGrid,
+    flags: &Flags,
+    nc: &NeighborCache,
+    bomb_count: usize,
+    level: HintLevel,
+) -> Option<GradedHint> {
+    let mut state = SolveState::from_position(visible, flags, nc);
+    state.run_to_stall(grid, nc, bomb_count);
+    let frontier = get_frontier(visible, flags, nc);
+    let on_frontier: HashSet<(usize, usize)> = frontier.iter().copied().collect();
+    let revealed: Vec<(usize, usize)> = state.visible.cells.iter()
+        .zip(&visible.cells)
+        .enumerate()
+        .filter(|&(_, (&now, &was))| now != -1 && was == -1)
+        .map(|(i, _)| (i / grid.height, i % grid.height))
+        .collect();
+    let (x, y) = revealed.iter()
+        .find(|cell| on_frontier.contains(cell))
+        .or(revealed.first())
+        .copied()?;
+
+    match level {
+        HintLevel::Region => {
+            let region = group_frontier_regions(&frontier, visible, nc, None)
+                .into_iter()
+                .find(|r| r.contains(&(x, y)))
+                .unwrap_or_else(|| {
+                    let mut off_frontier = Vec::new();
+                    for cx in 0..grid.width {
+                        for cy in 0..grid.height {
+                            if visible.get(cx, cy) == -1 && !flags.get(cx, cy) && !on_frontier.contains(&(cx, cy)) {
+                                off_frontier.push((cx, cy));
+                            }
+                        }
+                    }
+                    off_frontier
+                });
+            Some(GradedHint::Region {
+                x0: region.iter().map(|c| c.0).min()?,
+                y0: region.iter().map(|c| c.1).min()?,
+                x1: region.iter().map(|c| c.0).max()?,
+                y1: region.iter().map(|c| c.1).max()?,
+            })
+        }
+        HintLevel::Cell => Some(GradedHint::Cell { x, y }),
+        HintLevel::Explanation => {
+            let steps = explain_cell(grid, visible, flags, nc, bomb_count, x, y).unwrap_or_default();
+            Some(GradedHint::Explanation { x, y, steps })
+        }
+    }
+}
+
+// ─── get_hint ───────────────────────────────────────────────────────────────
+
+/// Hint result for the UI.
+pub struct Hint {
+    pub x: usize,
+    pub y: usize,
+    pub score: i32,
+}
+
+/// Scoring weights for `get_hint_weighted()`.
+#[derive(Clone, Copy, Debug)]
+pub struct HintWeights {
+    /// Bonus for cells whose value is 0 (they cascade).
+    pub zero_bonus: i32,
+    /// Score per revealed neighbor of a frontier cell.
+    pub per_revealed_neighbor: i32,
+    /// Subtracted from island (non-frontier) hint scores. Islands are only
+    /// suggested when no safe frontier cell exists.
+    pub island_penalty: i32,
+}
+
+impl Default for HintWeights {
+    fn default() -> Self {
+        Self { zero_bonus: 10, per_revealed_neighbor: 1, island_penalty: 0 }
+    }
+}
+
+/// Find the best safe cell to reveal (God Mode / Best Move).
+///
+/// Prioritizes: frontier safe cells > zero cells > island safe cells.
+/// With `prefer_cascade_only`, only safe cells showing 0 (which are sure to
+/// cascade) are considered, and `None` is returned if there are none.
+/// With `frontier_only`, island cells are never suggested: they would take a
+/// guess in real play, so `None` is returned when no safe frontier cell exists.
+pub fn get_hint(
+    grid: &Grid,
+    visible: &VisibleGrid,
+    flags: &Flags,
+    mines: &Mines,
+    nc: &NeighborCache,
+    prefer_cascade_only: bool,
+    frontier_only: bool,
+) -> Option<Hint> {
+    find_hint(grid, visible, flags, mines, nc, &HintWeights::default(), prefer_cascade_only, frontier_only, |_| 0)
+}
+
+/// `get_hint()` that picks uniformly among the cells tied for the best score
+/// instead of the first in column-major order, for variety in repeated hints.
+/// A seeded `rng` replays the same picks.
+pub fn get_hint_random<R: crate::rng::BoardRng>(
+    grid: &Grid,
+    visible: &VisibleGrid,
+    flags: &Flags,
+    mines: &Mines,
+    nc: &NeighborCache,
+    rng: &mut R,
+) -> Option<Hint> {
+    find_hint(grid, visible, flags, mines, nc, &HintWeights::default(), false, false, |ties| rng.gen_range(ties))
+}
+
+/// `get_hint()` with custom scoring weights.
+pub fn get_hint_weighted(
+    grid: &Grid,
+    visible: &VisibleGrid,
+    flags: &Flags,
+    mines: &Mines,
+    nc: &NeighborCache,
+    weights: &HintWeights,
+) -> Option<Hint> {
+    find_hint(grid, visible, flags, mines, nc, weights, false, false, |_| 0)
+}
+
+/// Shared search behind the hint functions; `zeros_only` skips numbered cells
+/// and `frontier_only` skips Phase 2. `choose(n)` picks which of the `n` cells
+/// tied for the best score, in column-major order, is returned.
+#[allow(clippy::too_many_arguments)]
+fn find_hint(
+    grid: &Grid,
+    visible: &VisibleGrid,
+    flags: &Flags,
+    mines: &Mines,
+    nc: &NeighborCache,
+    weights: &HintWeights,
+    zeros_only: bool,
+    frontier_only: bool,
+    choose: impl FnOnce(usize) -> usize,
+) -> Option<Hint> {
+    let width = grid.width;
+    let height = grid.height;
+
+    // Phase 1: Safe frontier cells (adjacent to revealed cells)
+    let mut safe_frontier: Vec<Hint> = Vec::new();
+
+    for x in 0..width {
+        for y in 0..height {
+            if zeros_only && grid.get(x, y) != 0 { continue; }
+            if visible.get(x, y) == -1 && !flags.get(x, y) && !mines.get(x, y) {
+                let neighbors = nc.get(x, y);
+                let revealed_count = neighbors.iter()
+                    .filter(|&&(nx, ny)| visible.get(nx, ny) > -1)
+                    .count();
+
+                if revealed_count > 0 {
+                    let mut score = revealed_count as i32 * weights.per_revealed_neighbor;
+                    if grid.get(x, y) == 0 { score += weights.zero_bonus; } // Prefer zeros (cascade)
+                    safe_frontier.push(Hint { x, y, score });
+                }
+            }
+        }
+    }
+
+    if !safe_frontier.is_empty() {
+        return Some(pick_best_hint(safe_frontier, choose));
+    }
+    if frontier_only { return None; }
+
+    // Phase 2: Any safe cell (island, not adjacent to revealed)
+    let mut safe_island: Vec<Hint> = Vec::new();
+
+    for x in 0..width {
+        for y in 0..height {
+            if zeros_only && grid.get(x, y) != 0 { continue; }
+            if visible.get(x, y) == -1 && !flags.get(x, y) && !mines.get(x, y) {
+                let score = if grid.get(x, y) == 0 { weights.zero_bonus } else { 0 };
+                safe_island.push(Hint { x, y, score: score - weights.island_penalty });
+            }
+        }
+    }
+
+    if !safe_island.is_empty() {
+        return Some(pick_best_hint(safe_island, choose));
+    }
+
+    None
+}
+
+/// The `choose(n)`-th of the `n` best-scoring candidates; `candidates` is non-empty.
+fn pick_best_hint(mut candidates: Vec<Hint>, choose: impl FnOnce(usize) -> usize) -> Hint {
+    let best = candidates.iter().map(|h| h.score).max().unwrap_or(0);
+    candidates.retain(|h| h.score == best);
+    let i = choose(candidates.len());
+    candidates.swap_remove(i)
+}
+
+/// `get_hint()` for many independent boards of `(grid, visible, flags, mines)`.
+///
+/// One `NeighborCache` is built per distinct board dimension and shared by
+/// every board of that size. Results are in input order.
+pub fn get_hint_batch(boards: &[(&Grid, &VisibleGrid, &Flags, &Mines)]) -> Vec<Option<Hint>> {
+    let mut caches: HashMap<(usize, usize), NeighborCache> = HashMap::new();
+    boards
+        .iter()
+        .map(|&(grid, visible, flags, mines)| {
+            let nc = caches
+                .entry((grid.width, grid.height))
+                .or_insert_with(|| NeighborCache::new(grid.width, grid.height));
+            get_hint(grid, visible, flags, mines, nc, false, false)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Create a simple 3x3 board with one mine at (0,0), start at (2,2).
+    fn make_simple_board() -> (Grid, Mines, NeighborCache) {
+        let mut mines = Mines::new(3, 3);
+        mines.set(0, 0, true);
+
+        let nc = NeighborCache::new(3, 3);
+        let grid = crate::board::calculate_numbers(&mines, &nc);
+
+        (grid, mines, nc)
+    }
+
+    /// 4x3 with a wall of mines down x = 2: basic rules flag the wall from
+    /// the left, and only the global mine count clears the column behind it.
+    fn make_wall_board() -> (Grid, Mines, NeighborCache) {
+        let mut mines = Mines::new(4, 3);
+        for y in 0..3 {
+            mines.set(2, y, true);
+        }
+
+        let nc = NeighborCache::new(4, 3);
+        let grid = crate::board::calculate_numbers(&mines, &nc);
+
+        (grid, mines, nc)
+    }
+
+    #[test]
+    fn test_simulate_reveal_zero_cascade() {
+        let (grid, _mines, nc) = make_simple_board();
+        let flags = Flags::new(3, 3);
+        let mut visible = VisibleGrid::new(3, 3);
+
+        // Reveal (2,2) which is 0 → should cascade
+        simulate_reveal(&grid, &mut visible, &flags, &nc, 2, 2);
+
+        // (2,2) is 0, so all connected zeros and their neighbors should be revealed
+        assert_ne!(visible.get(2, 2), -1);
+    }
+
+    #[test]
+    fn test_simulate_reveal_bfs_distances() {
+        // Open 4x4 with a mine in the far corner: every reach is a straight
+        // king's walk through zeros, so distance equals Chebyshev distance
+        let nc = NeighborCache::new(4, 4);
+        let mut mines = Mines::new(4, 4);
+        mines.set(3, 3, true);
+        let grid = crate::board::calculate_numbers(&mines, &nc);
+        let flags = Flags::new(4, 4);
+
+        let mut visible = VisibleGrid::new(4, 4);
+        let ripple = simulate_reveal_bfs(&grid, &mut visible, &flags, &nc, 0, 0);
+        assert_eq!(ripple.len(), 15);
+        assert!(ripple.windows(2).all(|w| w[0].2 <= w[1].2));
+        for &(x, y, d) in &ripple {
+            assert_eq!(d as usize, x.max(y));
+        }
+
+        let mut dfs = VisibleGrid::new(4, 4);
+        simulate_reveal(&grid, &mut dfs, &flags, &nc, 0, 0);
+        assert_eq!(visible.cells, dfs.cells);
+    }
+
+    #[test]
+    fn test_neighbor_summary_categories() {
+        let (grid, _mines, nc) = make_simple_board();
+        let mut visible = VisibleGrid::new(3, 3);
+        let mut flags = Flags::new(3, 3);
+        visible.set(1, 0, grid.get(1, 0));
+        visible.set(2, 2, grid.get(2, 2));
+        flags.set(0, 0, true);
+
+        let summary = neighbor_summary(&visible, &flags, &nc, 1, 1);
+        assert_eq!(summary.flagged, vec![(0, 0)]);
+        assert_eq!(summary.revealed, vec![(1, 0)]); // (2,2) is a revealed zero
+        assert_eq!(summary.hidden.len(), 5);
+        assert_eq!(neighbor_summary(&visible, &flags, &nc, 3, 0), NeighborSummary::default());
+    }
+
+    #[test]
     fn test_is_solvable_simple() {
         let (grid, mines, nc) = make_simple_board();
-        // 3x3 with 1 mine at (0,0), start at (2,2)
-        // Basic rules should trivially solve this
-        assert!(is_solvable(&grid, &mines, &nc, 2, 2));
+        // 3x3 with 1 mine at (0,0), start at (2,2)
+        // Basic rules should trivially solve this
+        assert!(is_solvable(&grid, &mines, &nc, 2, 2));
+    }
+
+    #[test]
+    fn test_solve_until_guess_fully_solved() {
+        let (grid, mines, nc) = make_simple_board();
+        assert_eq!(solve_until_guess(&grid, &mines, &nc, 2, 2), GuessPoint::FullySolved);
+    }
+
+    #[test]
+    fn test_solve_until_guess_reports_fifty_fifty() {
+        // 3x2 board, mine at (2,0): the opening leaves (2,0)/(2,1) as a 50/50
+        let mut mines = Mines::new(3, 2);
+        mines.set(2, 0, true);
+        let nc = NeighborCache::new(3, 2);
+        let grid = crate::board::calculate_numbers(&mines, &nc);
+
+        match solve_until_guess(&grid, &mines, &nc, 0, 0) {
+            GuessPoint::StuckAt { revealed_fraction, frontier_components, best_guess } => {
+                assert!((revealed_fraction - 0.8).abs() < 1e-6);
+                assert_eq!(frontier_components, 1);
+                let guess = best_guess.unwrap();
+                assert_eq!(guess.x, 2);
+                assert!((guess.mine_probability - 0.5).abs() < 1e-6);
+            }
+            GuessPoint::FullySolved => panic!("50/50 board must not be fully solved"),
+        }
+    }
+
+    #[test]
+    fn test_best_guess_prefers_cells_next_to_openings() {
+        // 5x3 opened from the top: "00000 / 12221" over a hidden bottom row
+        // with two layouts, so (0,2), (1,2), (3,2) and (4,2) are all 50/50.
+        // (1,2) touches three of the opening's edge numbers, (0,2) only two
+        let nc = NeighborCache::new(5, 3);
+        let mut mines = Mines::new(5, 3);
+        for (x, y) in [(1, 2), (2, 2), (4, 2)] {
+            mines.set(x, y, true);
+        }
+        let grid = crate::board::calculate_numbers(&mines, &nc);
+        let flags = Flags::new(5, 3);
+        let mut visible = VisibleGrid::new(5, 3);
+        simulate_reveal(&grid, &mut visible, &flags, &nc, 0, 0);
+        assert_eq!((0..5).map(|x| visible.get(x, 1)).collect::<Vec<_>>(), vec![1, 2, 2, 2, 1]);
+        assert_eq!(opening_adjacency_score(&visible, &nc, 0, 2), 2);
+        assert_eq!(opening_adjacency_score(&visible, &nc, 1, 2), 3);
+
+        let guess = best_guess(&visible, &flags, &nc, 3).unwrap();
+        assert_eq!((guess.x, guess.y), (1, 2));
+        assert!((guess.mine_probability - 0.5).abs() < 1e-6);
+        assert_eq!(guess.adjacency_score, 3);
+
+        // A number opened on its own is not part of an opening
+        let mut lone = VisibleGrid::new(5, 3);
+        lone.set(1, 1, 2);
+        assert_eq!(opening_adjacency_score(&lone, &nc, 1, 2), 0);
+    }
+
+    #[test]
+    fn test_opening_entropy() {
+        let (grid, mines, nc) = make_simple_board();
+        assert_eq!(opening_entropy(&grid, &mines, &nc, 2, 2), 0.0);
+
+        // The 3x2 50/50 board: two cells at p = 0.5 but only two layouts, one bit
+        let mut mines = Mines::new(3, 2);
+        mines.set(2, 0, true);
+        let nc = NeighborCache::new(3, 2);
+        let grid = crate::board::calculate_numbers(&mines, &nc);
+        assert!((opening_entropy(&grid, &mines, &nc, 0, 0) - 1.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_frontier_entropy_weights_layouts_by_interior_fills() {
+        // 7x1 strip "? 1 ? 1 ? ? ?" with 2 mines: the frontier is either {b}
+        // with a mine among the two interior cells (2 boards) or {a, c} (1 board)
+        let mut visible = VisibleGrid::new(7, 1);
+        visible.set(1, 0, 1);
+        visible.set(3, 0, 1);
+        let flags = Flags::new(7, 1);
+        let nc = NeighborCache::new(7, 1);
+        let expected = 3f64.log2() - 2.0 / 3.0;
+        assert!((frontier_entropy(&visible, &flags, &nc, 2) - expected).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_min_guesses() {
+        let (grid, mines, nc) = make_simple_board();
+        assert_eq!(min_guesses(&grid, &mines, &nc, 2, 2), 0);
+
+        // The 3x2 50/50 board: one guess at the stall, then the rest follows
+        let mut mines = Mines::new(3, 2);
+        mines.set(2, 0, true);
+        let nc = NeighborCache::new(3, 2);
+        let grid = crate::board::calculate_numbers(&mines, &nc);
+        assert_eq!(min_guesses(&grid, &mines, &nc, 0, 0), 1);
+    }
+
+    #[test]
+    fn test_solvability_summary() {
+        let (grid, mines, nc) = make_simple_board();
+        assert_eq!(
+            solvability_summary(&grid, &mines, &nc, 2, 2),
+            SolvabilitySummary { no_guess: true, first_guess_risk: None }
+        );
+
+        // The 3x2 50/50 board from above
+        let mut mines = Mines::new(3, 2);
+        mines.set(2, 0, true);
+        let nc = NeighborCache::new(3, 2);
+        let grid = crate::board::calculate_numbers(&mines, &nc);
+        let summary = solvability_summary(&grid, &mines, &nc, 0, 0);
+        assert!(!summary.no_guess);
+        assert!((summary.first_guess_risk.unwrap() - 0.5).abs() < 1e-6);
+    }
+
+    #[test]
+    #[cfg(not(target_arch = "wasm32"))]
+    fn test_batch_difficulty_profile() {
+        let nc = NeighborCache::new(4, 3);
+        let board = |mine_cells: &[(usize, usize)]| {
+            let mut mines = Mines::new(4, 3);
+            for &(x, y) in mine_cells {
+                mines.set(x, y, true);
+            }
+            (crate::board::calculate_numbers(&mines, &nc), mines, 0, 0)
+        };
+        let boards = [
+            board(&[(0, 2), (1, 2), (2, 2)]),
+            board(&[(0, 2), (1, 2), (3, 1)]),
+            board(&[(0, 2), (1, 2), (3, 1)]),
+            // The mine wall: only the global count finishes it
+            board(&[(2, 0), (2, 1), (2, 2)]),
+            board(&[(0, 2), (2, 0), (2, 2)]),
+        ];
+        let profile = batch_difficulty_profile(&boards, &nc);
+        assert_eq!(profile.by_strategy, [1, 2, 0, 0, 0, 1]);
+        assert_eq!(profile.boards_at(Strategy::Subset), 2);
+        assert_eq!(profile.needs_guess, 1);
+    }
+
+    #[test]
+    fn test_resumable_solver_matches_one_shot() {
+        // Unfiltered random boards, so both verdicts turn up
+        let nc = NeighborCache::new(16, 16);
+        let mut verdicts = [false; 2];
+        let mut paused = false;
+        for seed in 0..12 {
+            let mut rng = crate::rng::WasmRng::from_seed(seed);
+            let board = crate::board::generate_solvable_board_with_rng(
+                16, 16, 40, 8, 8, 1, 0, 1, &nc, &mut rng, |_, _, _, _, _| true,
+            );
+            let expected = is_solvable(&board.grid, &board.mines, &nc, 8, 8);
+
+            let mut solver = ResumableSolver::new(
+                board.grid.clone(), &board.mines, NeighborCache::new(16, 16), 8, 8, SolverConfig::default(),
+            );
+            let mut calls = 0;
+            let status = loop {
+                calls += 1;
+                match solver.step(2) {
+                    StepStatus::InProgress => paused |= solver.state.pending.is_some(),
+                    done => break done,
+                }
+            };
+            assert_eq!(status == StepStatus::Solvable, expected, "seed {}", seed);
+            // Clearing a 16x16 takes many iterations, so a solvable board spans several calls
+            assert!(!expected || calls > 1);
+            assert_eq!(solver.step(2), status);
+            verdicts[expected as usize] = true;
+        }
+        assert_eq!(verdicts, [true, true]);
+        // Some call ran out of units inside a contradiction or tank pass and resumed it
+        assert!(paused);
+    }
+
+    #[test]
+    fn test_is_solvable_instrumented_counts_work() {
+        // The mine-wall board: Basic opens it, global counting finishes it
+        let (grid, mines, nc) = make_wall_board();
+        let (solved, metrics) = is_solvable_instrumented(&grid, &mines, &nc, 0, 0);
+        assert!(solved);
+        assert!(metrics.successes_of(Strategy::GlobalCount) >= 1);
+
+        // Every step starts with Basic, and every step but the last one succeeds
+        assert_eq!(metrics.invocations_of(Strategy::Basic), metrics.iterations);
+        assert_eq!(metrics.successes.iter().sum::<u32>() + 1, metrics.iterations);
+        for i in 0..6 {
+            assert!(metrics.successes[i] <= metrics.invocations[i]);
+        }
+    }
+
+    #[test]
+    fn test_solution_fingerprint_golden() {
+        // (width, height, mines, start, fingerprint)
+        type Case = (usize, usize, &'static [(usize, usize)], (usize, usize), u64);
+        let cases: [Case; 3] = [
+            (3, 3, &[(0, 0)], (2, 2), 0x3ab8_af44_ca37_9887),
+            (4, 3, &[(2, 0), (2, 1), (2, 2)], (0, 0), 0x29ec_e8d1_8c66_5421),
+            (8, 8, &[(0, 7), (7, 0), (1, 1), (6, 6), (2, 5)], (4, 3), 0xd37f_93a1_8bb1_a4c3),
+        ];
+        for (width, height, cells, (sx, sy), expected) in cases {
+            let mut mines = Mines::new(width, height);
+            for &(x, y) in cells {
+                mines.set(x, y, true);
+            }
+            let nc = NeighborCache::new(width, height);
+            let grid = crate::board::calculate_numbers(&mines, &nc);
+            assert_eq!(solution_fingerprint(&grid, &mines, &nc, sx, sy), expected, "{}x{} board", width, height);
+        }
+    }
+
+    #[test]
+    fn test_solution_fingerprint_ignores_set_iteration_order() {
+        // A board the solver gets stuck on; per-step results vary run to run here
+        let nc = NeighborCache::new(16, 16);
+        let mut rng = crate::rng::WasmRng::from_seed(0);
+        let mines = crate::board::place_mines_random(16, 16, 40, &[SafeZone::new(8, 8, 1)], 0, &mut rng).unwrap();
+        let grid = crate::board::calculate_numbers(&mines, &nc);
+        assert!(!is_solvable(&grid, &mines, &nc, 8, 8));
+        for _ in 0..10 {
+            assert_eq!(solution_fingerprint(&grid, &mines, &nc, 8, 8), 0x8461_32d3_e49f_e7e9);
+        }
+    }
+
+    #[test]
+    fn test_difficulty_rating_ignores_set_iteration_order() {
+        // Plain random layouts, so guess boards and every tier can turn up
+        let nc = NeighborCache::new(16, 16);
+        for seed in 0..8 {
+            let mut rng = crate::rng::WasmRng::from_seed(seed);
+            let mines = crate::board::place_mines_random(16, 16, 40, &[SafeZone::new(8, 8, 1)], 0, &mut rng).unwrap();
+            let grid = crate::board::calculate_numbers(&mines, &nc);
+            let rating = difficulty_rating(&grid, &mines, &nc, 8, 8);
+            for _ in 0..10 {
+                assert_eq!(difficulty_rating(&grid, &mines, &nc, 8, 8), rating, "seed {}", seed);
+            }
+        }
+    }
+
+    #[test]
+    fn test_requires_advanced_strategy() {
+        let (grid, mines, nc) = make_simple_board();
+        assert!(!requires_advanced_strategy(&grid, &mines, &nc, 2, 2));
+
+        // The mine-wall board's last column needs global counting
+        let (grid, mines, nc) = make_wall_board();
+        assert!(requires_advanced_strategy(&grid, &mines, &nc, 0, 0));
+    }
+
+    #[test]
+    #[cfg(not(target_arch = "wasm32"))]
+    fn test_solver_context_across_threads() {
+        use std::sync::Arc;
+
+        // The mine-wall board is solvable; the 5x2 board ends in a 50/50
+        let (wall_grid, wall, wall_nc) = make_wall_board();
+        let (wall_grid, wall, wall_nc) = (Arc::new(wall_grid), Arc::new(wall), Arc::new(wall_nc));
+
+        let ctx = Arc::new(SolverContext::new(5, 2).unwrap());
+        let mut coin = Mines::new(5, 2);
+        coin.set(4, 0, true);
+        let coin_grid = Arc::new(crate::board::calculate_numbers(&coin, ctx.neighbor_cache()));
+        let coin = Arc::new(coin);
+
+        let handles: Vec<_> = (0..4).map(|_| {
+            let (wall, wall_grid, wall_nc) = (Arc::clone(&wall), Arc::clone(&wall_grid), Arc::clone(&wall_nc));
+            let (coin, coin_grid, ctx) = (Arc::clone(&coin), Arc::clone(&coin_grid), Arc::clone(&ctx));
+            std::thread::spawn(move || {
+                (0..20).all(|_| {
+                    SolverContext::from_cache(Arc::clone(&wall_nc)).is_solvable(&wall_grid, &wall, 0, 0)
+                        && !ctx.is_solvable(&coin_grid, &coin, 0, 0)
+                })
+            })
+        }).collect();
+        for handle in handles {
+            assert!(handle.join().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_solvable_with_max_strategy() {
+        let (grid, mines, nc) = make_simple_board();
+        assert!(solvable_with_max_strategy(&grid, &mines, &nc, 2, 2, Strategy::Basic as u8));
+
+        // The mine-wall board needs global counting for its last column
+        let (grid, mines, nc) = make_wall_board();
+        assert!(solvable_with_max_strategy(&grid, &mines, &nc, 0, 0, Strategy::GlobalCount as u8));
+        assert!(!solvable_with_max_strategy(&grid, &mines, &nc, 0, 0, Strategy::Tank as u8));
+    }
+
+    #[test]
+    fn test_rank_all_hidden_sorted_safest_first() {
+        // 4x1 strip "* 1 . .": (1,0) says one mine among (0,0)/(2,0); with a
+        // total of one mine, (3,0) must be safe
+        let nc = NeighborCache::new(4, 1);
+        let mut visible = VisibleGrid::new(4, 1);
+        visible.set(1, 0, 1);
+        let flags = Flags::new(4, 1);
+
+        let ranking = rank_all_hidden(&visible, &flags, &nc, 1);
+        let cells: Vec<(usize, usize)> = ranking.iter().map(|&(x, y, _)| (x, y)).collect();
+        assert_eq!(cells, vec![(3, 0), (0, 0), (2, 0)]);
+        assert!(ranking[0].2.abs() < 1e-6);
+        assert!((ranking[1].2 - 0.5).abs() < 1e-6);
+        assert!((ranking[2].2 - 0.5).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_blind_mode_disables_global_count() {
+        // 4x3 board with a full mine wall at x=2: column x=3 is only
+        // reachable by counting that all mines are already flagged
+        let (grid, mines, nc) = make_wall_board();
+
+        assert!(is_solvable(&grid, &mines, &nc, 0, 0));
+        let blind = SolverConfig { know_mine_count: false, ..SolverConfig::default() };
+        assert!(!is_solvable_with_config(&grid, &mines, &nc, 0, 0, &blind));
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    fn test_is_solvable_checked_matches_unchecked() {
+        let (grid, mines, nc) = make_simple_board();
+        assert!(is_solvable_checked(&grid, &mines, &nc, 2, 2));
+
+        let (grid, mines, nc) = make_wall_board();
+        assert_eq!(is_solvable_checked(&grid, &mines, &nc, 0, 0), is_solvable(&grid, &mines, &nc, 0, 0));
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "solver flagged safe cell at (1, 1)")]
+    fn test_debug_check_consistency_catches_false_flag() {
+        let (_grid, mines, _nc) = make_simple_board();
+        let visible = VisibleGrid::new(3, 3);
+        let mut flags = Flags::new(3, 3);
+        flags.set(1, 1, true);
+        debug_check_consistency(&visible, &flags, &mines);
+    }
+
+    #[test]
+    fn test_trivially_solved_by_opening() {
+        let (grid, mines, nc) = make_simple_board();
+        assert!(is_trivially_solved_by_opening(&grid, &mines, &nc, 2, 2));
+
+        // The mine-wall board needs global counting, so it is solvable but not trivial
+        let (grid, mines, nc) = make_wall_board();
+        assert!(is_solvable(&grid, &mines, &nc, 0, 0));
+        assert!(!is_trivially_solved_by_opening(&grid, &mines, &nc, 0, 0));
+    }
+
+    #[test]
+    fn test_clue_tolerance_reduces_deductions() {
+        // Mine wall at x=2: exact clues flag the whole wall, but a clue that may
+        // be off by one no longer forces any of the wall cells
+        let (grid, mines, nc) = make_wall_board();
+
+        let mut exact = SolveState::from_start(&grid, &nc, 0, 0);
+        exact.run_to_stall(&grid, &nc, 3);
+        assert_eq!(exact.flag_count, 3);
+
+        let noisy = SolverConfig { clue_tolerance: 1, ..SolverConfig::default() };
+        let mut tolerant = SolveState::from_start(&grid, &nc, 0, 0).with_config(noisy.clone());
+        tolerant.run_to_stall(&grid, &nc, 3);
+        assert_eq!(tolerant.flag_count, 0);
+        assert!(tolerant.revealed_count() < exact.revealed_count());
+        assert!(!is_solvable_with_config(&grid, &mines, &nc, 0, 0, &noisy));
+
+        // The exact-clue strategies never run; the tank takes the stalls
+        let skipped = [Strategy::Subset, Strategy::Gaussian, Strategy::Contradiction];
+        assert!(skipped.iter().all(|&s| tolerant.metrics.invocations_of(s) == 0));
+        assert!(tolerant.metrics.invocations_of(Strategy::Tank) > 0);
+    }
+
+    #[test]
+    fn test_boolean_clues_in_basic_rules() {
+        let basic_only = |kinds: Vec<ClueKind>| SolverConfig {
+            max_strategy_level: Strategy::Basic as u8,
+            clue_kinds: Some(kinds),
+            ..SolverConfig::default()
+        };
+
+        // "1 *" with the 1 shown as a boolean: one hidden neighbor, so it's the mine
+        let mut mines = Mines::new(2, 1);
+        mines.set(1, 0, true);
+        let nc = NeighborCache::new(2, 1);
+        let grid = crate::board::calculate_numbers(&mines, &nc);
+        let mut visible = VisibleGrid::new(2, 1);
+        visible.set(0, 0, grid.get(0, 0));
+        let mut state = SolveState::from_position(&visible, &Flags::new(2, 1), &nc)
+            .with_config(basic_only(vec![ClueKind::Boolean, ClueKind::Exact]));
+        assert_eq!(state.step(&grid, &nc, 1), Some(Strategy::Basic));
+        assert!(state.flags.get(1, 0));
+
+        // "* 2 *": as an exact clue both neighbors are mines, as a boolean it
+        // only says one of them is
+        let mut mines = Mines::new(3, 1);
+        mines.set(0, 0, true);
+        mines.set(2, 0, true);
+        let nc = NeighborCache::new(3, 1);
+        let grid = crate::board::calculate_numbers(&mines, &nc);
+        let mut visible = VisibleGrid::new(3, 1);
+        visible.set(1, 0, grid.get(1, 0));
+        let kinds = vec![ClueKind::Exact, ClueKind::Boolean, ClueKind::Exact];
+        let mut state = SolveState::from_position(&visible, &Flags::new(3, 1), &nc).with_config(basic_only(kinds));
+        assert_eq!(state.step(&grid, &nc, 2), None);
+        let mut state = SolveState::from_position(&visible, &Flags::new(3, 1), &nc)
+            .with_config(basic_only(vec![ClueKind::Exact; 3]));
+        assert_eq!(state.step(&grid, &nc, 2), Some(Strategy::Basic));
+    }
+
+    #[test]
+    fn test_play_move_cascade_and_win() {
+        let (grid, mines, nc) = make_simple_board();
+        let mut visible = VisibleGrid::new(3, 3);
+        let flags = Flags::new(3, 3);
+
+        // (2,2) is a zero: the cascade opens every safe cell and wins
+        let result = play_move(&grid, &mines, &mut visible, &flags, &nc, 2, 2, false);
+        assert!(!result.hit_mine);
+        assert!(result.won);
+        assert_eq!(result.revealed_cells.len(), 8);
+
+        // Clicking an already revealed cell is a no-op
+        let again = play_move(&grid, &mines, &mut visible, &flags, &nc, 2, 2, false);
+        assert!(again.revealed_cells.is_empty());
+    }
+
+    #[test]
+    fn test_hint_value() {
+        // 5x3 board, mines at (0,2) and (3,2): from (0,0) logic stalls on
+        // "##1##"; revealing (1,2) settles the remaining three cells
+        let mut mines = Mines::new(5, 3);
+        mines.set(0, 2, true);
+        mines.set(3, 2, true);
+        let nc = NeighborCache::new(5, 3);
+        let grid = crate::board::calculate_numbers(&mines, &nc);
+        let mut stalled = SolveState::from_start(&grid, &nc, 0, 0);
+        stalled.run_to_stall(&grid, &nc, 2);
+        assert!(!stalled.is_complete(2));
+
+        assert_eq!(hint_value(&grid, &stalled.visible, &stalled.flags, &nc, 2, 1, 2), 3);
+        assert_eq!(hint_value(&grid, &stalled.visible, &stalled.flags, &nc, 2, 2, 2), 0);
+    }
+
+    #[test]
+    fn test_validate_replay() {
+        // 5x1 strip "0 1 * 1 0"
+        let mut mines = Mines::new(5, 1);
+        mines.set(2, 0, true);
+        let nc = NeighborCache::new(5, 1);
+
+        let win = [(0, 0, MoveKind::Reveal), (2, 0, MoveKind::ToggleFlag), (4, 0, MoveKind::Reveal)];
+        assert_eq!(
+            validate_replay(&mines, &nc, &win),
+            ReplayResult { moves_applied: 3, illegal: None, won: true }
+        );
+
+        // (1,0) was already opened by the first cascade
+        let replay = [(0, 0, MoveKind::Reveal), (1, 0, MoveKind::Reveal)];
+        assert_eq!(validate_replay(&mines, &nc, &replay).illegal, Some((1, IllegalMove::AlreadyRevealed)));
+
+        let replay = [(4, 0, MoveKind::Reveal), (2, 0, MoveKind::Reveal)];
+        assert_eq!(validate_replay(&mines, &nc, &replay).illegal, Some((1, IllegalMove::HitMine)));
+
+        let unfinished = validate_replay(&mines, &nc, &[(0, 0, MoveKind::Reveal)]);
+        assert!(unfinished.illegal.is_none() && !unfinished.won);
+    }
+
+    #[test]
+    fn test_replay_luck() {
+        // 4x1 strip "* 1 1 *": after the opening, (0, 0) and (2, 0) are a coin flip
+        let mut mines = Mines::new(4, 1);
+        mines.set(0, 0, true);
+        mines.set(3, 0, true);
+        let nc = NeighborCache::new(4, 1);
+        let guess = [(1, 0, MoveKind::Reveal), (2, 0, MoveKind::Reveal)];
+        assert!((replay_luck(&mines, &nc, &guess) - 0.5).abs() < 1e-4);
+
+        // 5x1 strip "0 1 * 1 0": everything after the opening is forced
+        let mut mines = Mines::new(5, 1);
+        mines.set(2, 0, true);
+        let nc = NeighborCache::new(5, 1);
+        let forced = [(0, 0, MoveKind::Reveal), (2, 0, MoveKind::ToggleFlag), (4, 0, MoveKind::Reveal)];
+        assert_eq!(replay_luck(&mines, &nc, &forced), 1.0);
+    }
+
+    #[test]
+    fn test_expected_remaining_clicks() {
+        // 5x1 strip "0 1 * 1 0": two separate openings
+        let mut mines = Mines::new(5, 1);
+        mines.set(2, 0, true);
+        let nc = NeighborCache::new(5, 1);
+        let grid = crate::board::calculate_numbers(&mines, &nc);
+        let mut visible = VisibleGrid::new(5, 1);
+        let flags = Flags::new(5, 1);
+
+        assert_eq!(expected_remaining_clicks(&grid, &mines, &visible, &nc), 2);
+        assert_eq!(crate::board::compute_3bv(&grid, &mines, &nc), 2);
+
+        play_move(&grid, &mines, &mut visible, &flags, &nc, 0, 0, false);
+        assert_eq!(expected_remaining_clicks(&grid, &mines, &visible, &nc), 1);
+
+        play_move(&grid, &mines, &mut visible, &flags, &nc, 4, 0, false);
+        assert_eq!(expected_remaining_clicks(&grid, &mines, &visible, &nc), 0);
+
+        // Mine-free 3x3 with the centre blocked: one click opens the ring
+        let mut blocked = crate::types::Blocked::new(3, 3);
+        blocked.set(1, 1, true);
+        let nc = NeighborCache::with_blocked(&blocked);
+        let mines = Mines::new(3, 3);
+        let grid = crate::board::calculate_numbers(&mines, &nc);
+        assert_eq!(expected_remaining_clicks(&grid, &mines, &VisibleGrid::new(3, 3), &nc), 1);
+    }
+
+    #[test]
+    fn test_remaining_safe_count() {
+        // 5x1 strip "0 1 * 1 0": four safe cells
+        let mut mines = Mines::new(5, 1);
+        mines.set(2, 0, true);
+        let nc = NeighborCache::new(5, 1);
+        let grid = crate::board::calculate_numbers(&mines, &nc);
+        let mut visible = VisibleGrid::new(5, 1);
+        let flags = Flags::new(5, 1);
+        assert_eq!(remaining_safe_count(&visible, &mines, &nc), 4);
+
+        play_move(&grid, &mines, &mut visible, &flags, &nc, 0, 0, false);
+        assert_eq!(remaining_safe_count(&visible, &mines, &nc), 2);
+
+        let result = play_move(&grid, &mines, &mut visible, &flags, &nc, 4, 0, false);
+        assert_eq!(remaining_safe_count(&visible, &mines, &nc), 0);
+        assert!(result.won);
+    }
+
+    #[test]
+    fn test_guess_outcomes_on_fifty_fifty() {
+        // 2x2, left column open as 1s, mine at (1, 1): guessing (1, 0) is a coin
+        // flip, and if safe it must show 1 and pin the mine on (1, 1)
+        let (mut mines, nc) = (Mines::new(2, 2), NeighborCache::new(2, 2));
+        mines.set(1, 1, true);
+        let grid = crate::board::calculate_numbers(&mines, &nc);
+        let visible = VisibleGrid::from_flat(2, 2, &[1, 1, -1, -1]).unwrap();
+        let flags = Flags::new(2, 2);
+
+        let safe = guess_outcomes(&grid, &mines, &visible, &flags, &nc, 1, 1, 0).unwrap();
+        assert_eq!(safe.mine_probability, 0.5);
+        assert_eq!(safe.value_distribution, vec![0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0]);
+        assert_eq!(safe.value, Some(1));
+        assert_eq!(safe.revealed, vec![(1, 0)]);
+        assert_eq!(safe.closure, vec![Action::Flag(1, 1)]);
+
+        let mine = guess_outcomes(&grid, &mines, &visible, &flags, &nc, 1, 1, 1).unwrap();
+        assert_eq!(mine.mine_probability, 0.5);
+        assert_eq!((mine.value, mine.closure.len()), (None, 0));
+        assert_eq!(guess_outcomes(&grid, &mines, &visible, &flags, &nc, 1, 0, 0), None);
+
+        // Blank 3x3 with one mine: a safe corner sees it in 3 of the 8 places left
+        let (mines, nc) = (make_simple_board().1, NeighborCache::new(3, 3));
+        let grid = crate::board::calculate_numbers(&mines, &nc);
+        let blank = guess_outcomes(&grid, &mines, &VisibleGrid::new(3, 3), &Flags::new(3, 3), &nc, 1, 2, 2).unwrap();
+        assert!((blank.value_distribution[0] - 5.0 / 8.0).abs() < 1e-6);
+        assert!((blank.value_distribution[1] - 3.0 / 8.0).abs() < 1e-6);
+        assert_eq!(blank.value, Some(0));
+        assert_eq!(blank.revealed.len(), 8);
+    }
+
+    #[test]
+    fn test_cells_requiring_strategy() {
+        // Opened at (0, 0): the 2 at (0, 1) flags both bottom mines by counting,
+        // and only then does comparing the clues clear (2, 2)
+        let mines = Mines::from_rows(&["....", "..*.", "**.."]);
+        let nc = NeighborCache::new(4, 3);
+        let grid = crate::board::calculate_numbers(&mines, &nc);
+        assert_eq!(cells_requiring_strategy(&grid, &mines, &nc, 0, 0, Strategy::Basic), vec![(0, 2), (1, 2)]);
+        assert_eq!(cells_requiring_strategy(&grid, &mines, &nc, 0, 0, Strategy::Subset), vec![(2, 2)]);
+    }
+
+    #[test]
+    fn test_visible_clue_sum() {
+        // 5x1 strip "0 1 * 1 0": the left click opens "0 1"
+        let mut mines = Mines::new(5, 1);
+        mines.set(2, 0, true);
+        let nc = NeighborCache::new(5, 1);
+        let grid = crate::board::calculate_numbers(&mines, &nc);
+        let mut visible = VisibleGrid::new(5, 1);
+        assert_eq!(visible_clue_sum(&visible), 0);
+
+        play_move(&grid, &mines, &mut visible, &Flags::new(5, 1), &nc, 0, 0, false);
+        assert_eq!(visible_clue_sum(&visible), 1);
+        visible.set(3, 0, grid.get(3, 0));
+        assert_eq!(visible_clue_sum(&visible), crate::board::total_clue_sum(&grid, &mines));
+    }
+
+    #[test]
+    fn test_progress_reaches_one_on_win() {
+        // 5x1 strip "0 1 * 1 0": four safe cells
+        let mut mines = Mines::new(5, 1);
+        mines.set(2, 0, true);
+        let nc = NeighborCache::new(5, 1);
+        let grid = crate::board::calculate_numbers(&mines, &nc);
+        let mut visible = VisibleGrid::new(5, 1);
+        let flags = Flags::new(5, 1);
+        assert_eq!(progress(&visible, &nc, 1), 0.0);
+
+        play_move(&grid, &mines, &mut visible, &flags, &nc, 0, 0, false);
+        assert_eq!(progress(&visible, &nc, 1), 0.5);
+
+        let result = play_move(&grid, &mines, &mut visible, &flags, &nc, 4, 0, false);
+        assert!(result.won);
+        assert_eq!(progress(&visible, &nc, 1), 1.0);
+
+        // Mine-free 3x3 with the centre blocked: the hole stays hidden and
+        // play_move agrees with GameState that one reveal wins
+        let mut blocked = crate::types::Blocked::new(3, 3);
+        blocked.set(1, 1, true);
+        let nc = NeighborCache::with_blocked(&blocked);
+        let mines = Mines::new(3, 3);
+        let grid = crate::board::calculate_numbers(&mines, &nc);
+        let mut visible = VisibleGrid::new(3, 3);
+        let flags = Flags::new(3, 3);
+        assert!(play_move(&grid, &mines, &mut visible, &flags, &nc, 0, 0, false).won);
+        assert_eq!(visible.get(1, 1), -1);
+        assert_eq!(progress(&visible, &nc, 0), 1.0);
+        assert_eq!(remaining_safe_count(&visible, &mines, &nc), 0);
+        assert!(GameState::new(grid, mines, nc).reveal(0, 0).won);
+    }
+
+    #[test]
+    fn test_flag_all_provable_on_solved_board() {
+        let nc = NeighborCache::new(9, 9);
+        let mut rng = crate::rng::WasmRng::from_seed(11);
+        let board = crate::board::generate_solvable_board_with_rng(
+            9, 9, 10, 4, 4, 1, 0, 200, &nc, &mut rng, is_solvable,
+        );
+        assert!(board.success);
+
+        // Every safe cell open and nothing flagged: all ten mines are provable
+        let mut visible = VisibleGrid::new(9, 9);
+        for (i, &m) in board.mines.cells.iter().enumerate() {
+            if m == 0 { visible.cells[i] = board.grid.cells[i]; }
+        }
+        let mut flags = Flags::new(9, 9);
+        let all_mines: Vec<(usize, usize)> = (0..81)
+            .filter(|&i| board.mines.cells[i] != 0)
+            .map(|i| (i / 9, i % 9))
+            .collect();
+        assert_eq!(flag_all_provable(&board.grid, &visible, &flags, &nc, 10), all_mines);
+
+        // Existing flags are not repeated
+        flags.set(all_mines[0].0, all_mines[0].1, true);
+        assert_eq!(flag_all_provable(&board.grid, &visible, &flags, &nc, 10), all_mines[1..]);
+    }
+
+    #[test]
+    fn test_mine_candidates_lists_certain_mine_first() {
+        // 3x1: the left cell shows 1, so (1,0) is a certain mine and (2,0) is safe
+        let (w, h) = (3, 1);
+        let nc = NeighborCache::new(w, h);
+        let mut visible = VisibleGrid::new(w, h);
+        visible.set(0, 0, 1);
+        let flags = Flags::new(w, h);
+
+        let candidates = mine_candidates(&visible, &flags, &nc, 1, 0.95);
+        assert_eq!(candidates.len(), 1);
+        assert_eq!((candidates[0].0, candidates[0].1), (1, 0));
+        assert!(candidates[0].2 > 0.999);
+
+        let all = mine_candidates(&visible, &flags, &nc, 1, 0.0);
+        assert_eq!(all.len(), 2);
+        assert!(all[0].2 >= all[1].2);
+    }
+
+    #[test]
+    fn test_forgiving_reveal_survives_only_the_first_hit() {
+        let nc = NeighborCache::new(9, 9);
+        let mut rng = crate::rng::WasmRng::from_seed(3);
+        let board = crate::board::generate_solvable_board_with_rng(
+            9, 9, 10, 4, 4, 1, 0, 200, &nc, &mut rng, is_solvable,
+        );
+        let (mut grid, mut mines) = (board.grid.clone(), board.mines.clone());
+        let mut visible = VisibleGrid::new(9, 9);
+        let flags = Flags::new(9, 9);
+        let mut used = false;
+        play_move(&grid, &mines, &mut visible, &flags, &nc, 4, 4, false);
+
+        let mine_at = |mines: &Mines, visible: &VisibleGrid| {
+            (0..81).map(|i| (i / 9, i % 9)).find(|&(x, y)| mines.get(x, y) && visible.get(x, y) == -1).unwrap()
+        };
+        let (x, y) = mine_at(&mines, &visible);
+        let first = forgiving_reveal(&mut grid, &mut mines, &mut visible, &flags, &nc, x, y, &mut rng, &mut used);
+        assert!(!first.hit_mine && used);
+        assert!(!mines.get(x, y));
+        assert_eq!(mines.count(), 10);
+        assert_eq!(grid.cells, crate::board::calculate_numbers(&mines, &nc).cells);
+        for i in 0..81 {
+            assert!(visible.cells[i] == -1 || visible.cells[i] == grid.cells[i], "stale number at {}", i);
+        }
+
+        let (x, y) = mine_at(&mines, &visible);
+        let second = forgiving_reveal(&mut grid, &mut mines, &mut visible, &flags, &nc, x, y, &mut rng, &mut used);
+        assert!(second.hit_mine);
+        assert_eq!(visible.get(x, y), EXPLODED);
+    }
+
+    #[test]
+    fn test_forgiving_game_recounts_across_wrap() {
+        // A corner mine on a torus touches the opposite corner; moving it
+        // must refresh the wrapped neighbors too
+        let mut mines = Mines::new(5, 5);
+        mines.set(0, 0, true);
+        let grid = crate::board::calculate_numbers_toroidal(&mines);
+        let mut game = GameState::new(grid, mines, NeighborCache::new_toroidal(5, 5)).with_forgiveness(1);
+        assert!(!game.reveal(0, 0).hit_mine);
+        assert!(game.forgiveness_used());
+        let expected = crate::board::calculate_numbers_toroidal(game.mines());
+        let safe: Vec<usize> = (0..25).filter(|&i| game.mines().cells[i] == 0).collect();
+        for &i in &safe {
+            game.reveal(i / 5, i % 5);
+        }
+        assert!(game.is_won());
+        for &i in &safe {
+            assert_eq!(game.visible().cells[i], expected.cells[i], "stale number at {}", i);
+        }
+    }
+
+    #[test]
+    fn test_game_state_counter_matches_recount() {
+        let nc = NeighborCache::new(9, 9);
+        let mut rng = crate::rng::WasmRng::from_seed(3);
+        let board = crate::board::generate_solvable_board_with_rng(
+            9, 9, 10, 4, 4, 1, 0, 200, &nc, &mut rng, is_solvable,
+        );
+        let safe: Vec<(usize, usize)> = (0..81)
+            .filter(|&i| board.mines.cells[i] == 0)
+            .map(|i| (i / 9, i % 9))
+            .collect();
+        let recount = |game: &GameState| {
+            game.visible().cells.iter().zip(&board.mines.cells).filter(|&(&v, &m)| v != -1 && m == 0).count()
+        };
+
+        // A flagged safe cell is skipped until unflagged; repeat clicks change nothing
+        let mut game = GameState::new(board.grid.clone(), board.mines.clone(), nc);
+        let (fx, fy) = *safe.last().unwrap();
+        game.toggle_flag(fx, fy);
+        for &(x, y) in &safe {
+            game.reveal(x, y);
+            game.reveal(x, y);
+            assert_eq!(game.revealed_safe(), recount(&game));
+        }
+        assert_eq!(game.visible().get(fx, fy), -1);
+        assert!(!game.is_won());
+        game.toggle_flag(fx, fy);
+        assert!(game.reveal(fx, fy).won);
+        assert!(game.is_won());
+        assert_eq!(game.revealed_safe(), recount(&game));
+    }
+
+    #[test]
+    fn test_toggle_flag_twice_restores_state() {
+        let (grid, mines, nc) = make_simple_board();
+        let mut visible = VisibleGrid::new(3, 3);
+        let mut flags = Flags::new(3, 3);
+        let options = PlayOptions::default();
+
+        for expect_flag in [true, false] {
+            let result = apply_move(&grid, &mines, &mut visible, &mut flags, &nc, 0, 0, MoveKind::ToggleFlag, &options);
+            assert!(result.flag_changed);
+            assert_eq!(flags.get(0, 0), expect_flag);
+        }
+        assert!(flags.cells.iter().all(|&f| f == 0));
+        assert!(visible.cells.iter().all(|&v| v == -1));
+
+        // A reveal on a flag is ignored unless the mode unflags it
+        flags.set(1, 1, true);
+        let ignored = apply_move(&grid, &mines, &mut visible, &mut flags, &nc, 1, 1, MoveKind::Reveal, &options);
+        assert!(!ignored.flag_changed && flags.get(1, 1) && visible.get(1, 1) == -1);
+        let unflag = options.with_unflag_on_reveal(true);
+        let result = apply_move(&grid, &mines, &mut visible, &mut flags, &nc, 1, 1, MoveKind::Reveal, &unflag);
+        assert!(result.flag_changed && !flags.get(1, 1) && visible.get(1, 1) == -1);
+    }
+
+    #[test]
+    fn test_play_move_hits_mine() {
+        let (grid, mines, nc) = make_simple_board();
+        let mut visible = VisibleGrid::new(3, 3);
+        let flags = Flags::new(3, 3);
+
+        let result = play_move(&grid, &mines, &mut visible, &flags, &nc, 0, 0, true);
+        assert!(result.hit_mine);
+        assert!(!result.won);
+        assert_eq!(visible.get(0, 0), EXPLODED);
+        assert_eq!(result.revealed_cells, vec![(0, 0)]);
+    }
+
+    #[test]
+    fn test_play_move_with_custom_exploded_sentinel() {
+        let (grid, mines, nc) = make_simple_board();
+        let mut visible = VisibleGrid::new(3, 3);
+        let flags = Flags::new(3, 3);
+
+        let options = PlayOptions::default().with_exploded(-9);
+        let result = play_move_with(&grid, &mines, &mut visible, &flags, &nc, 0, 0, &options);
+        assert!(result.hit_mine);
+        assert_eq!(visible.get(0, 0), -9);
+        assert_eq!(visible.cells.iter().filter(|&&v| v != -1).count(), 1);
+    }
+
+    #[test]
+    fn test_all_determinate_actions_flags_then_counts() {
+        // Mine wall at x=2: basic rules flag the wall, then global counting clears x=3
+        let (grid, _, nc) = make_wall_board();
+        let opening = SolveState::from_start(&grid, &nc, 0, 0);
+
+        let actions = all_determinate_actions(&grid, &opening.visible, &opening.flags, &nc, 3);
+        assert_eq!(
+            actions,
+            vec![
+                Action::Flag(2, 0), Action::Flag(2, 1), Action::Flag(2, 2),
+                Action::Reveal(3, 0), Action::Reveal(3, 1), Action::Reveal(3, 2),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_encode_actions_roundtrip() {
+        // Large coordinates need multi-byte varints; small ones take two bytes per action
+        let actions: Vec<Action> = (0..5000)
+            .map(|i| if i % 3 == 0 { Action::Flag(i * 7, i % 200) } else { Action::Reveal(i % 50, i * 13) })
+            .collect();
+        let bytes = encode_actions(&actions);
+        assert_eq!(decode_actions(&bytes), Ok(actions));
+
+        let small = [Action::Reveal(3, 4), Action::Flag(0, 127)];
+        assert_eq!(encode_actions(&small).len(), 1 + 1 + 4 + 1);
+        assert_eq!(decode_actions(&encode_actions(&[])), Ok(Vec::new()));
+    }
+
+    #[test]
+    fn test_decode_actions_rejects_corruption() {
+        let bytes = encode_actions(&[Action::Reveal(300, 5), Action::Flag(1, 2)]);
+
+        let mut flipped = bytes.clone();
+        flipped[3] ^= 0x10;
+        assert_eq!(decode_actions(&flipped), Err(DecodeError::ChecksumMismatch));
+
+        let mut version = bytes.clone();
+        version[0] = 9;
+        assert_eq!(decode_actions(&version), Err(DecodeError::UnsupportedVersion(9)));
+
+        // Dropping the last action byte and re-summing still leaves the count short
+        let mut truncated = bytes[..bytes.len() - 2].to_vec();
+        truncated.push(truncated.iter().fold(0u8, |acc, &b| acc.wrapping_add(b)));
+        assert_eq!(decode_actions(&truncated), Err(DecodeError::Truncated));
+        assert_eq!(decode_actions(&[]), Err(DecodeError::Truncated));
+    }
+
+    #[test]
+    fn test_determinate_action_masks() {
+        let (grid, mines, nc) = make_wall_board();
+        let opening = SolveState::from_start(&grid, &nc, 0, 0);
+
+        // The wall is cells 6-8 and the cleared column cells 9-11 of the 12
+        let (reveals, flags) = determinate_action_masks(&grid, &opening.visible, &opening.flags, &nc, 3);
+        assert_eq!(flags, vec![0b1100_0000, 0b0000_0001]);
+        assert_eq!(reveals, vec![0b0000_0000, 0b0000_1110]);
+        let unpacked: Vec<u8> = (0..12).map(|i| (flags[i / 8] >> (i % 8)) & 1).collect();
+        assert_eq!(unpacked, mines.cells);
+    }
+
+    #[test]
+    fn test_influence_region_stops_at_unshared_clues() {
+        // 8x1 "1 ? 1 ? 1 1 ? 1": the clue at (2,0) links (1,0) and (3,0), but
+        // no clue sees both (3,0) and (6,0)
+        let visible = VisibleGrid::from_flat(8, 1, &[1, -1, 1, -1, 1, 1, -1, 1]).unwrap();
+        let flags = Flags::new(8, 1);
+        let nc = NeighborCache::new(8, 1);
+        assert_eq!(influence_region(&visible, &flags, &nc, 0, 0), vec![(1, 0), (3, 0)]);
+        assert_eq!(influence_region(&visible, &flags, &nc, 7, 0), vec![(6, 0)]);
+        assert!(influence_region(&visible, &flags, &nc, 1, 0).is_empty());
+
+        // A flag is not part of the reach
+        let mut flags = Flags::new(8, 1);
+        flags.set(1, 0, true);
+        assert!(influence_region(&visible, &flags, &nc, 0, 0).is_empty());
+    }
+
+    #[test]
+    fn test_frontier_verdicts() {
+        // Mines at (2,0) and (2,1): the 2 at (1,0) flags both, which satisfies
+        // the 1 at (1,2) and clears (2,2)
+        let mut mines = Mines::new(4, 3);
+        mines.set(2, 0, true);
+        mines.set(2, 1, true);
+        let nc = NeighborCache::new(4, 3);
+        let grid = crate::board::calculate_numbers(&mines, &nc);
+        let opening = SolveState::from_start(&grid, &nc, 0, 0);
+        assert_eq!(
+            frontier_verdicts(&grid, &opening.visible, &opening.flags, &nc, 2),
+            vec![(2, 0, Verdict::Mine), (2, 1, Verdict::Mine), (2, 2, Verdict::Safe)]
+        );
+
+        // A 50/50 in the last column stays unknown
+        let mut mines = Mines::new(5, 2);
+        mines.set(4, 0, true);
+        let nc = NeighborCache::new(5, 2);
+        let grid = crate::board::calculate_numbers(&mines, &nc);
+        let opening = SolveState::from_start(&grid, &nc, 0, 0);
+        assert_eq!(
+            frontier_verdicts(&grid, &opening.visible, &opening.flags, &nc, 1),
+            vec![(4, 0, Verdict::Unknown), (4, 1, Verdict::Unknown)]
+        );
+    }
+
+    #[test]
+    fn test_redundant_clues() {
+        // 3x3 with a hidden mine in the middle: eight identical "1" clues
+        let mut visible = VisibleGrid::new(3, 3);
+        for x in 0..3 {
+            for y in 0..3 {
+                if (x, y) != (1, 1) { visible.set(x, y, 1); }
+            }
+        }
+        let nc = NeighborCache::new(3, 3);
+        let redundant = redundant_clues(&visible, &Flags::new(3, 3), &nc);
+        assert_eq!(redundant.len(), 7);
+        assert!(!redundant.contains(&(2, 2)));
+
+        // 3x2, mines at (0, 1) and (2, 1): the 1s each see one mine, the 2s
+        // both. (1, 0) duplicates (1, 1), and (0, 0) is (1, 1) minus (2, 0)
+        let mut visible = VisibleGrid::new(3, 2);
+        for (x, y, v) in [(0, 0, 1), (1, 0, 2), (2, 0, 1), (1, 1, 2)] {
+            visible.set(x, y, v);
+        }
+        let nc = NeighborCache::new(3, 2);
+        assert_eq!(redundant_clues(&visible, &Flags::new(3, 2), &nc), vec![(0, 0), (1, 0)]);
+    }
+
+    #[test]
+    fn test_solution_count() {
+        // Every safe cell open around the lone mine at (0, 0): the endgame is unique
+        let (grid, _mines, nc) = make_simple_board();
+        let flags = Flags::new(3, 3);
+        let mut visible = VisibleGrid { width: 3, height: 3, cells: grid.cells.clone() };
+        visible.set(0, 0, -1);
+        assert_eq!(solution_count(&visible, &flags, &nc, 1, 100), 1);
+        assert_eq!(solution_count(&visible, &flags, &nc, 2, 100), 0);
+
+        // 2x2 "1 1" over two hidden cells: a 50/50
+        let visible = VisibleGrid::from_flat(2, 2, &[1, 1, -1, -1]).unwrap();
+        assert_eq!(solution_count(&visible, &Flags::new(2, 2), &NeighborCache::new(2, 2), 1, 100), 2);
+
+        // A blank 4x4 with three mines has C(16, 3) = 560 layouts
+        let nc = NeighborCache::new(4, 4);
+        let blank = VisibleGrid::new(4, 4);
+        assert_eq!(solution_count(&blank, &Flags::new(4, 4), &nc, 3, 1000), 560);
+        assert_eq!(solution_count(&blank, &Flags::new(4, 4), &nc, 3, 100), 100);
+    }
+
+    #[test]
+    fn test_solution_count_wide_frontier() {
+        // 90x3 with the middle row all "1"s: one mine per three columns,
+        // above or below, in a single 180-cell region with 2^30 layouts
+        let nc = NeighborCache::new(90, 3);
+        let mut visible = VisibleGrid::new(90, 3);
+        for x in 0..90 {
+            visible.set(x, 1, 1);
+        }
+        let flags = Flags::new(90, 3);
+        assert_eq!(solution_count(&visible, &flags, &nc, 30, u64::MAX), 1 << 30);
+        assert_eq!(solution_count(&visible, &flags, &nc, 30, 1000), 1000);
+        assert_eq!(solution_count(&visible, &flags, &nc, 31, 1000), 0);
+    }
+
+    #[test]
+    fn test_region_layout_counts_match_brute_force() {
+        // Reveal some numbers of small random boards and count every
+        // assignment of the hidden cells, per mine total, the slow way
+        let nc = NeighborCache::new(4, 4);
+        for seed in 0..6 {
+            let mut rng = crate::rng::WasmRng::from_seed(seed);
+            let mines = crate::board::place_mines_random(4, 4, 4, &[], 0, &mut rng).unwrap();
+            let grid = crate::board::calculate_numbers(&mines, &nc);
+            let mut visible = VisibleGrid::new(4, 4);
+            // Zeros stay hidden: a revealed zero would have cascaded
+            for i in (0..16).filter(|&i| mines.cells[i] == 0 && grid.cells[i] > 0 && rng.gen_range(2) == 0) {
+                visible.cells[i] = grid.cells[i];
+            }
+
+            let hidden: Vec<usize> = (0..16).filter(|&i| visible.cells[i] == -1).collect();
+            let mut expected = vec![0u64; hidden.len() + 1];
+            for mask in 0u32..1 << hidden.len() {
+                let mine = |x: usize, y: usize| {
+                    hidden.iter().position(|&i| i == x * 4 + y).is_some_and(|b| mask >> b & 1 == 1)
+                };
+                let consistent = (0..16).filter(|&i| visible.cells[i] >= 0).all(|i| {
+                    nc.get(i / 4, i % 4).iter().filter(|&&(nx, ny)| mine(nx, ny)).count() == visible.cells[i] as usize
+                });
+                if consistent {
+                    expected[mask.count_ones() as usize] += 1;
+                }
+            }
+            for (bombs, &count) in expected.iter().enumerate() {
+                let counted = solution_count(&visible, &Flags::new(4, 4), &nc, bombs, u64::MAX);
+                assert_eq!(counted, count, "seed {} with {} mines", seed, bombs);
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "bigint")]
+    fn test_count_consistent_boards_exact_beyond_u128() {
+        let nc = NeighborCache::new(20, 20);
+        let mut visible = VisibleGrid::new(20, 20);
+        let flags = Flags::new(20, 20);
+        // C(400, 100), about 2.2e96
+        assert_eq!(
+            count_consistent_boards_exact(&visible, &flags, &nc, 100).to_string(),
+            "2241854791554337561923210387201698554845411177476295990399942258896013007429693894018935107174320"
+        );
+
+        // A corner 1: one of its three neighbors, then 99 of the other 396 cells
+        visible.set(0, 0, 1);
+        assert_eq!(
+            count_consistent_boards_exact(&visible, &flags, &nc, 100).to_string(),
+            "712903874820999655013868888677085111526538918202400489419226115635822020468626151003956197626000"
+        );
+        assert_eq!(count_consistent_boards_exact(&visible, &flags, &nc, 0), num_bigint::BigUint::ZERO);
+    }
+
+    #[test]
+    #[cfg(not(target_arch = "wasm32"))]
+    fn test_minimal_clue_set_is_forcing_and_minimal() {
+        let mut mines = Mines::new(5, 5);
+        for (x, y) in [(0, 0), (2, 2), (4, 3)] {
+            mines.set(x, y, true);
+        }
+        let nc = NeighborCache::new(5, 5);
+        let grid = crate::board::calculate_numbers(&mines, &nc);
+        let clues = minimal_clue_set(&grid, &mines, &nc, 2, 0);
+        assert!(!clues.is_empty() && clues.len() < 22);
+        assert!(clues.iter().all(|&(x, y)| !mines.get(x, y)));
+
+        let forced = |shown: &[(usize, usize)]| {
+            let mut visible = VisibleGrid::new(5, 5);
+            for &(x, y) in shown {
+                visible.set(x, y, grid.get(x, y));
+            }
+            let (m, s) = globally_definite_cells(&visible, &Flags::new(5, 5), &nc, 3);
+            m.len() + s.len() == 25 - shown.len()
+        };
+        assert!(forced(&clues));
+        for i in 0..clues.len() {
+            let mut fewer = clues.clone();
+            fewer.remove(i);
+            assert!(!forced(&fewer), "clue {:?} was removable", clues[i]);
+        }
+    }
+
+    #[test]
+    fn test_over_flagged_clue_yields_no_deduction() {
+        // A "1" in the middle of a 3x3 with two (wrong) flags above it
+        let mut mines = Mines::new(3, 3);
+        mines.set(1, 2, true);
+        let nc = NeighborCache::new(3, 3);
+        let grid = crate::board::calculate_numbers(&mines, &nc);
+        let mut visible = VisibleGrid::new(3, 3);
+        visible.set(1, 1, 1);
+        let mut flags = Flags::new(3, 3);
+        flags.set(0, 0, true);
+        flags.set(2, 0, true);
+
+        for deep in [false, true] {
+            let config = SolverConfig { deep_contradiction: deep, ..SolverConfig::default() };
+            let mut state = SolveState::from_position(&visible, &flags, &nc).with_config(config);
+            assert_eq!(state.step(&grid, &nc, 3), None);
+            assert_eq!(state.visible.cells, visible.cells);
+            assert_eq!(state.flags.cells, flags.cells);
+        }
+        let probs = probability::exact_mine_probabilities(&visible, &flags, &nc, 3, true, None, None);
+        assert!((probs[1] - 1.0 / 6.0).abs() < 1e-6);
+    }
+
+    #[test]
+    #[cfg(not(target_arch = "wasm32"))]
+    fn test_minimal_guess_core_finds_fifty_fifty() {
+        // The right column of a 4x2 board hides one mine behind two 1s
+        let nc = NeighborCache::new(4, 2);
+        let mut mines = Mines::new(4, 2);
+        mines.set(3, 0, true);
+        let grid = crate::board::calculate_numbers(&mines, &nc);
+        assert_eq!(minimal_guess_core(&grid, &mines, &nc, 0, 0), Some(vec![(3, 0), (3, 1)]));
+
+        let (grid, mines, nc) = make_simple_board();
+        assert_eq!(minimal_guess_core(&grid, &mines, &nc, 2, 2), None);
+    }
+
+    #[test]
+    fn test_bench_generate_beginner() {
+        // Loose bound, meant to catch order-of-magnitude regressions even in debug builds
+        let elapsed = bench_generate(9, 9, 10, 0, 20);
+        assert!(elapsed < std::time::Duration::from_secs(20), "20 beginner boards took {:?}", elapsed);
+    }
+
+    #[test]
+    fn test_weighted_basic_rules() {
+        // 2x2 with the mine at (1,1), diagonals weighing 3: the corner's 3
+        // can only be the diagonal, which two orthogonal mines cannot match
+        let nc = NeighborCache::new(2, 2);
+        let mut mines = Mines::new(2, 2);
+        mines.set(1, 1, true);
+        let grid = crate::board::calculate_numbers_weighted(&mines, &nc, 3, 1);
+        assert_eq!(grid.get(0, 0), 3);
+
+        let mut visible = VisibleGrid::new(2, 2);
+        visible.set(0, 0, 3);
+        let config = SolverConfig {
+            max_strategy_level: Strategy::Basic as u8,
+            neighbor_weights: Some(NeighborWeights::new(&nc, 3, 1)),
+            ..SolverConfig::default()
+        };
+        let mut state = SolveState::from_changes(&visible, &Flags::new(2, 2), &nc, &[(0, 0)]).with_config(config);
+        state.run_to_stall(&grid, &nc, 1);
+        assert!(state.is_complete(1));
+        assert!(state.flags.get(1, 1));
+    }
+
+    #[test]
+    fn test_deadline_stops_solve() {
+        let nc = NeighborCache::new(16, 16);
+        let mut rng = crate::rng::WasmRng::from_seed(5);
+        let board = crate::board::generate_solvable_board_with_rng(
+            16, 16, 40, 8, 8, 1, 0, 200, &nc, &mut rng, is_solvable,
+        );
+        assert!(board.success);
+
+        // A spent budget bails before the first step, well inside any deadline
+        let config = SolverConfig { deadline_ms: Some(0.0), ..SolverConfig::default() };
+        let start = std::time::Instant::now();
+        assert!(!is_solvable_with_config(&board.grid, &board.mines, &nc, 8, 8, &config));
+        assert!(start.elapsed() < std::time::Duration::from_millis(50));
+
+        let mut state = SolveState::from_start(&board.grid, &nc, 8, 8).with_config(config);
+        assert_eq!(state.run_to_stall(&board.grid, &nc, 40), 0);
+        assert!(state.timed_out);
+
+        // A generous budget changes nothing
+        let config = SolverConfig { deadline_ms: Some(60_000.0), ..SolverConfig::default() };
+        assert!(is_solvable_with_config(&board.grid, &board.mines, &nc, 8, 8, &config));
+    }
+
+    #[test]
+    fn test_deadline_stops_tank_and_contradiction() {
+        let nc = NeighborCache::new(30, 16);
+        let mut rng = crate::rng::WasmRng::from_seed(7);
+        let board = crate::board::generate_solvable_board_with_rng(
+            30, 16, 99, 15, 8, 1, 0, 200, &nc, &mut rng, is_solvable,
+        );
+        assert!(board.success);
+
+        // Stall the cheap strategies so the next step falls through to the heavy ones
+        let config = SolverConfig { max_strategy_level: Strategy::Subset as u8, ..SolverConfig::default() };
+        let mut state = SolveState::from_start(&board.grid, &nc, 15, 8).with_config(config);
+        state.run_to_stall(&board.grid, &nc, 99);
+        assert!(!state.is_complete(99));
+        let (visible, flags) = (state.visible.clone(), state.flags.clone());
+
+        // A spent deadline bails inside each pass before touching the position
+        let mut spent = PassLimit::unbounded(Some(now_ms() - 1.0));
+        let (mut v, mut f) = (visible.clone(), flags.clone());
+        let tank = tank_solver(&board.grid, &mut v, &mut f, &nc, Some(99), state.flag_count, 0, None, 0, &mut spent);
+        assert!(tank.stopped == Some(PassStop::TimedOut) && !tank.progress);
+        let contra = solve_by_contradiction(&board.grid, &mut v, &mut f, &nc, state.flag_count, false, 0, &mut spent);
+        assert!(contra.stopped == Some(PassStop::TimedOut) && !contra.progress);
+        assert_eq!((v.cells, f.cells), (visible.cells.clone(), flags.cells.clone()));
+
+        let (mut v, mut f) = (visible.clone(), flags.clone());
+        let tank = tank_solver(
+            &board.grid, &mut v, &mut f, &nc, Some(99), state.flag_count, 0, None, 0, &mut PassLimit::unbounded(None),
+        );
+        assert!(tank.stopped.is_none() && tank.progress);
+
+        // A small nonzero budget stops the full solve promptly and says so
+        let config = SolverConfig { deadline_ms: Some(1.0), ..SolverConfig::default() };
+        let mut state = SolveState::from_start(&board.grid, &nc, 15, 8).with_config(config);
+        let start = std::time::Instant::now();
+        state.run_to_stall(&board.grid, &nc, 99);
+        assert!(start.elapsed() < std::time::Duration::from_millis(250));
+        assert!(state.timed_out || state.is_complete(99));
+    }
+
+    #[test]
+    fn test_deep_contradiction_uses_subset_logic() {
+        let positions = [
+            (0, 2), (0, 3), (0, 5), (1, 3), (1, 4), (1, 7), (2, 2),
+            (2, 7), (3, 0), (5, 1), (5, 7), (6, 4), (7, 1), (7, 4),
+        ];
+        let mut mines = Mines::new(8, 8);
+        for (x, y) in positions {
+            mines.set(x, y, true);
+        }
+        let nc = NeighborCache::new(8, 8);
+        let grid = crate::board::calculate_numbers(&mines, &nc);
+
+        // Strategies 1-4 only, run to a true fixpoint
+        let solve = |deep: bool| {
+            let config = SolverConfig { max_strategy_level: 4, deep_contradiction: deep, ..SolverConfig::default() };
+            let mut state = SolveState::from_start(&grid, &nc, 4, 4).with_config(config.clone());
+            while state.run_to_stall(&grid, &nc, 14) > 0 {
+                state = SolveState::from_position(&state.visible, &state.flags, &nc).with_config(config.clone());
+            }
+            state
+        };
+        let shallow = solve(false);
+        let deep = solve(true);
+
+        // Assuming (2, 0) is a mine only breaks a clue once subset logic runs
+        // inside the hypothesis
+        assert_eq!(shallow.visible.get(2, 0), -1);
+        assert_ne!(deep.visible.get(2, 0), -1);
+        assert!(deep.revealed_count() > shallow.revealed_count());
+        for x in 0..8 {
+            for y in 0..8 {
+                assert!(!deep.flags.get(x, y) || mines.get(x, y));
+                assert!(deep.visible.get(x, y) == -1 || !mines.get(x, y));
+            }
+        }
+    }
+
+    #[test]
+    fn test_explain_cell_keeps_only_dependencies() {
+        // Mirrored pairs of mines at x = 2 and x = 6, opened from both ends:
+        // (2, 2) follows from the left pair alone
+        let mut mines = Mines::new(9, 3);
+        for (x, y) in [(2, 0), (2, 1), (6, 0), (6, 1)] {
+            mines.set(x, y, true);
+        }
+        let nc = NeighborCache::new(9, 3);
+        let grid = crate::board::calculate_numbers(&mines, &nc);
+        let opening = SolveState::from_zones(&grid, &nc, &[SafeZone::new(0, 0, 1), SafeZone::new(8, 0, 1)]);
+
+        let steps = explain_cell(&grid, &opening.visible, &opening.flags, &nc, 4, 2, 2).unwrap();
+        let mut flagged: Vec<(usize, usize)> = steps.iter().flat_map(|s| s.flagged.clone()).collect();
+        let revealed: Vec<(usize, usize)> = steps.iter().flat_map(|s| s.revealed.clone()).collect();
+        flagged.sort_unstable();
+        assert_eq!(flagged, vec![(2, 0), (2, 1)]);
+        assert_eq!(revealed, vec![(2, 2)]);
+
+        assert_eq!(explain_cell(&grid, &opening.visible, &opening.flags, &nc, 4, 0, 0), Some(Vec::new()));
+
+        // A 50/50 cannot be explained
+        let mut mines = Mines::new(5, 2);
+        mines.set(4, 0, true);
+        let nc = NeighborCache::new(5, 2);
+        let grid = crate::board::calculate_numbers(&mines, &nc);
+        let opening = SolveState::from_start(&grid, &nc, 0, 0);
+        assert_eq!(explain_cell(&grid, &opening.visible, &opening.flags, &nc, 1, 4, 0), None);
+    }
+
+    #[test]
+    fn test_supporting_clues() {
+        // 1 M 1 1 M with both 1s on the left open: the first proves the mine,
+        // the second then clears (3, 0), and neither works alone
+        let mut mines = Mines::new(5, 1);
+        mines.set(1, 0, true);
+        mines.set(4, 0, true);
+        let nc = NeighborCache::new(5, 1);
+        let grid = crate::board::calculate_numbers(&mines, &nc);
+        let visible = VisibleGrid::from_flat(5, 1, &[1, -1, 1, -1, -1]).unwrap();
+        let flags = Flags::new(5, 1);
+        assert_eq!(supporting_clues(&grid, &visible, &flags, &nc, 2, 3, 0), vec![(0, 0), (2, 0)]);
+
+        // With a single mine the global count can stand in for the second clue
+        let mut mines = Mines::new(4, 1);
+        mines.set(1, 0, true);
+        let nc = NeighborCache::new(4, 1);
+        let grid = crate::board::calculate_numbers(&mines, &nc);
+        let visible = VisibleGrid::from_flat(4, 1, &[1, -1, 1, -1]).unwrap();
+        let flags = Flags::new(4, 1);
+        assert_eq!(supporting_clues(&grid, &visible, &flags, &nc, 1, 3, 0), vec![(0, 0)]);
+        assert!(supporting_clues(&grid, &visible, &flags, &nc, 1, 0, 0).is_empty());
+    }
+
+    #[test]
+    fn test_graded_hint_levels_agree() {
+        // The mirrored 9x3 board from above, opened from both ends
+        let mut mines = Mines::new(9, 3);
+        for (x, y) in [(2, 0), (2, 1), (6, 0), (6, 1)] {
+            mines.set(x, y, true);
+        }
+        let nc = NeighborCache::new(9, 3);
+        let grid = crate::board::calculate_numbers(&mines, &nc);
+        let opening = SolveState::from_zones(&grid, &nc, &[SafeZone::new(0, 0, 1), SafeZone::new(8, 0, 1)]);
+        let hint = |level| graded_hint(&grid, &opening.visible, &opening.flags, &nc, 4, level).unwrap();
+
+        let GradedHint::Cell { x, y } = hint(HintLevel::Cell) else { panic!("expected a cell hint") };
+        assert!(!mines.get(x, y));
+        let GradedHint::Region { x0, y0, x1, y1 } = hint(HintLevel::Region) else { panic!("expected a region") };
+        assert!((x0..=x1).contains(&x) && (y0..=y1).contains(&y));
+        assert!(x1 - x0 < 8, "box should cover one frontier region, not the board");
+        match hint(HintLevel::Explanation) {
+            GradedHint::Explanation { x: ex, y: ey, steps } => {
+                assert_eq!((ex, ey), (x, y));
+                assert!(steps.last().is_some_and(|s| s.revealed.contains(&(x, y))));
+            }
+            other => panic!("expected an explanation, got {:?}", other),
+        }
+
+        // "0 1 * ? ?": only the mine count clears the two cells off the frontier
+        let mut mines = Mines::new(5, 1);
+        mines.set(2, 0, true);
+        let nc = NeighborCache::new(5, 1);
+        let grid = crate::board::calculate_numbers(&mines, &nc);
+        let opening = SolveState::from_start(&grid, &nc, 0, 0);
+        assert_eq!(
+            graded_hint(&grid, &opening.visible, &opening.flags, &nc, 1, HintLevel::Region),
+            Some(GradedHint::Region { x0: 3, y0: 0, x1: 4, y1: 0 })
+        );
+
+        // A 50/50 gives no hint
+        let mut mines = Mines::new(5, 2);
+        mines.set(4, 0, true);
+        let nc = NeighborCache::new(5, 2);
+        let grid = crate::board::calculate_numbers(&mines, &nc);
+        let opening = SolveState::from_start(&grid, &nc, 0, 0);
+        assert_eq!(graded_hint(&grid, &opening.visible, &opening.flags, &nc, 1, HintLevel::Cell), None);
+    }
+
+    #[test]
+    fn test_export_constraints() {
+        let (grid, _mines, nc) = make_simple_board();
+        let mut visible = VisibleGrid::new(3, 3);
+        visible.set(1, 0, grid.get(1, 0));
+        visible.set(1, 1, grid.get(1, 1));
+        let mut flags = Flags::new(3, 3);
+        flags.set(0, 0, true);
+
+        let system = export_constraints(&visible, &flags, &nc, 1);
+        assert_eq!(system.cells, vec![(0, 1), (0, 2), (1, 2), (2, 0), (2, 1), (2, 2)]);
+        assert_eq!(
+            system.constraints,
+            vec![
+                ClueConstraint { vars: vec![0, 3, 4], target: 0 },
+                ClueConstraint { vars: vec![0, 1, 2, 3, 4, 5], target: 0 },
+            ]
+        );
+        assert_eq!(system.mine_budget, 0);
+    }
+
+    #[test]
+    fn test_logical_hint_uses_known_mine() {
+        // 2x2 with mine at (0,0) and the bottom row revealed: a 50/50 on its own
+        let mut mines = Mines::new(2, 2);
+        mines.set(0, 0, true);
+        let nc = NeighborCache::new(2, 2);
+        let grid = crate::board::calculate_numbers(&mines, &nc);
+        let mut visible = VisibleGrid::new(2, 2);
+        visible.set(0, 1, grid.get(0, 1));
+        visible.set(1, 1, grid.get(1, 1));
+        let flags = Flags::new(2, 2);
+        assert_eq!(get_logical_hint(&grid, &visible, &flags, &nc, 1, None), None);
+
+        // Knowing (0,0) is a mine clears (1,0)
+        let mut known = VisibleGrid::new(2, 2);
+        known.set(0, 0, KNOWN_MINE);
+        assert_eq!(
+            get_logical_hint(&grid, &visible, &flags, &nc, 1, Some(&known)),
+            Some(Action::Reveal(1, 0))
+        );
+
+        // Knowing (1,0) is safe pins the mine on (0,0)
+        let mut known = VisibleGrid::new(2, 2);
+        known.set(1, 0, KNOWN_SAFE);
+        assert_eq!(
+            get_logical_hint(&grid, &visible, &flags, &nc, 1, Some(&known)),
+            Some(Action::Flag(0, 0))
+        );
+    }
+
+    #[test]
+    fn test_logical_hint_prefers_reveal_then_flag() {
+        // "1 *": the only determinate move is flagging (1,0)
+        let mut mines = Mines::new(2, 1);
+        mines.set(1, 0, true);
+        let nc = NeighborCache::new(2, 1);
+        let grid = crate::board::calculate_numbers(&mines, &nc);
+        let mut visible = VisibleGrid::new(2, 1);
+        visible.set(0, 0, 1);
+        let flags = Flags::new(2, 1);
+        assert_eq!(get_logical_hint(&grid, &visible, &flags, &nc, 1, None), Some(Action::Flag(1, 0)));
+
+        // "1 * 2 * 1" with (3,0) flagged: the left 1 forces a flag first in scan
+        // order, but the mine count then proves (2,0) safe, and that wins
+        let mut mines = Mines::new(5, 1);
+        mines.set(1, 0, true);
+        mines.set(3, 0, true);
+        let nc = NeighborCache::new(5, 1);
+        let grid = crate::board::calculate_numbers(&mines, &nc);
+        let mut visible = VisibleGrid::new(5, 1);
+        visible.set(0, 0, 1);
+        visible.set(4, 0, 1);
+        let mut flags = Flags::new(5, 1);
+        flags.set(3, 0, true);
+        assert_eq!(get_logical_hint(&grid, &visible, &flags, &nc, 2, None), Some(Action::Reveal(2, 0)));
+    }
+
+    #[test]
+    fn test_missed_safe_cells() {
+        // The mine at (0, 0) is flagged and its three 1s are open, so the rest is safe
+        let (grid, _mines, nc) = make_simple_board();
+        let mut visible = VisibleGrid::new(3, 3);
+        let mut flags = Flags::new(3, 3);
+        flags.set(0, 0, true);
+        for (x, y) in [(0, 1), (1, 0), (1, 1)] {
+            visible.set(x, y, grid.get(x, y));
+        }
+        assert_eq!(missed_safe_cells(&grid, &visible, &flags, &nc, 1), vec![(0, 2), (1, 2), (2, 0), (2, 1), (2, 2)]);
+
+        // Once the player opens them there is nothing left to miss
+        simulate_reveal(&grid, &mut visible, &flags, &nc, 2, 2);
+        assert!(missed_safe_cells(&grid, &visible, &flags, &nc, 1).is_empty());
+    }
+
+    #[test]
+    fn test_is_move_forced_safe() {
+        // 2x2 50/50 with the mine at (1, 1): (1, 0) is safe but nothing proves it
+        let nc = NeighborCache::new(2, 2);
+        let visible = VisibleGrid::from_flat(2, 2, &[1, 1, -1, -1]).unwrap();
+        assert!(!is_move_forced_safe(&visible, &Flags::new(2, 2), &nc, 1, 1, 0));
+        assert!(!is_move_forced_safe(&visible, &Flags::new(2, 2), &nc, 1, 0, 0));
+
+        // 3x1 "1 * 1" with the left 1 open: the mine is pinned, so the count clears (2, 0)
+        let nc = NeighborCache::new(3, 1);
+        let visible = VisibleGrid::from_flat(3, 1, &[1, -1, -1]).unwrap();
+        assert!(is_move_forced_safe(&visible, &Flags::new(3, 1), &nc, 1, 2, 0));
+        assert!(!is_move_forced_safe(&visible, &Flags::new(3, 1), &nc, 1, 1, 0));
+    }
+
+    #[test]
+    fn test_globally_definite_cells_off_frontier() {
+        // 4x2: the "1"s at (0,0),(0,1) put exactly one mine in column 1;
+        // columns 2-3 are off the frontier
+        let mut visible = VisibleGrid::new(4, 2);
+        visible.set(0, 0, 1);
+        visible.set(0, 1, 1);
+        let flags = Flags::new(4, 2);
+        let nc = NeighborCache::new(4, 2);
+        let off: Vec<(usize, usize)> = vec![(2, 0), (2, 1), (3, 0), (3, 1)];
+
+        // One mine total: the frontier takes it, so the off-frontier cells are safe
+        let (mines, safes) = globally_definite_cells(&visible, &flags, &nc, 1);
+        assert!(mines.is_empty());
+        assert_eq!(safes, off);
+
+        // Five mines: the four off-frontier cells are all mines
+        let (mines, safes) = globally_definite_cells(&visible, &flags, &nc, 5);
+        assert_eq!(mines, off);
+        assert!(safes.is_empty());
+
+        // Three mines: nothing is forced
+        let (mines, safes) = globally_definite_cells(&visible, &flags, &nc, 3);
+        assert!(mines.is_empty() && safes.is_empty());
+    }
+
+    #[test]
+    fn test_detect_known_patterns() {
+        // 5x2, hidden top row, bottom row "1 1 2 1 1": a 1-2-1 on x = 1..3
+        let mut mines = Mines::new(5, 2);
+        mines.set(1, 0, true);
+        mines.set(3, 0, true);
+        let nc = NeighborCache::new(5, 2);
+        let grid = crate::board::calculate_numbers(&mines, &nc);
+        let mut visible = VisibleGrid::new(5, 2);
+        for x in 0..5 { visible.set(x, 1, grid.get(x, 1)); }
+        let flags = Flags::new(5, 2);
+        assert_eq!(
+            detect_known_patterns(&visible, &flags, &nc),
+            vec![(PatternKind::OneTwoOne, vec![
+                Action::Flag(1, 0), Action::Flag(3, 0),
+                Action::Reveal(0, 0), Action::Reveal(2, 0), Action::Reveal(4, 0),
+            ])]
+        );
+
+        // 2x4, right column revealed: a vertical 1-2-2-1 against the left edge
+        let mut mines = Mines::new(2, 4);
+        mines.set(0, 1, true);
+        mines.set(0, 2, true);
+        let nc = NeighborCache::new(2, 4);
+        let grid = crate::board::calculate_numbers(&mines, &nc);
+        let mut visible = VisibleGrid::new(2, 4);
+        for y in 0..4 { visible.set(1, y, grid.get(1, y)); }
+        let flags = Flags::new(2, 4);
+        assert_eq!(
+            detect_known_patterns(&visible, &flags, &nc),
+            vec![(PatternKind::OneTwoTwoOne, vec![
+                Action::Flag(0, 1), Action::Flag(0, 2),
+                Action::Reveal(0, 0), Action::Reveal(0, 3),
+            ])]
+        );
+
+        // A hidden cell behind the clues breaks the wall
+        visible.set(1, 3, -1);
+        assert!(detect_known_patterns(&visible, &flags, &nc).is_empty());
+    }
+
+    #[test]
+    fn test_solve_last_mine_pinned_by_one_clue() {
+        // 4x1 strip "0 1 ? ?" with one mine: the 1 sees only (2,0)
+        let nc = NeighborCache::new(4, 1);
+        let visible = VisibleGrid { width: 4, height: 1, cells: vec![0, 1, -1, -1] };
+        let flags = Flags::new(4, 1);
+        assert_eq!(solve_last_mine(&visible, &flags, &nc, 1), Some((2, 0)));
+        assert_eq!(
+            solve_endgame_exact(&visible, &flags, &nc, 1),
+            Some(EndgameResult { mines: vec![(2, 0)], safe: vec![(3, 0)] })
+        );
+
+        // Two mines left is not the last-mine endgame
+        assert_eq!(solve_last_mine(&visible, &flags, &nc, 2), None);
+
+        // "? 1 ? ?": the 1 leaves (0,0) and (2,0) open
+        let ambiguous = VisibleGrid { width: 4, height: 1, cells: vec![-1, 1, -1, -1] };
+        assert_eq!(solve_last_mine(&ambiguous, &flags, &nc, 1), None);
+    }
+
+    #[test]
+    fn test_endgame_exact_uses_global_count() {
+        // 4x3, 4 mines; '#' hidden, '*' hidden mine:
+        //   1 # * *
+        //   * 2 # *
+        //   # 1 1 1
+        let mut mines = Mines::new(4, 3);
+        for &(x, y) in &[(2, 0), (3, 0), (0, 1), (3, 1)] { mines.set(x, y, true); }
+        let nc = NeighborCache::new(4, 3);
+        let grid = crate::board::calculate_numbers(&mines, &nc);
+        let mut visible = VisibleGrid::new(4, 3);
+        for &(x, y) in &[(0, 0), (1, 1), (1, 2), (2, 2), (3, 2)] {
+            visible.set(x, y, grid.get(x, y));
+        }
+        let flags = Flags::new(4, 3);
+
+        // The per-region strategies and Strategy 6 are stuck
+        assert!(all_determinate_actions(&grid, &visible, &flags, &nc, 4).is_empty());
+
+        let result = solve_endgame_exact(&visible, &flags, &nc, 4).unwrap();
+        assert_eq!(result.mines, vec![(3, 0), (3, 1)]);
+        assert_eq!(result.safe, vec![(2, 1)]);
+
+        // Too many hidden cells to enumerate
+        let big = VisibleGrid::new(5, 5);
+        assert!(solve_endgame_exact(&big, &Flags::new(5, 5), &NeighborCache::new(5, 5), 4).is_none());
+    }
+
+    #[test]
+    fn test_incremental_solve_is_local() {
+        let mut rng = crate::rng::WasmRng::from_seed(11);
+        let nc = NeighborCache::new(16, 16);
+        let mines = crate::board::place_mines_random(16, 16, 40, &[SafeZone::new(8, 8, 1)], 0, &mut rng).unwrap();
+        let grid = crate::board::calculate_numbers(&mines, &nc);
+        let state = SolveState::from_start(&grid, &nc, 8, 8);
+        let (visible, flags) = (state.visible, state.flags);
+        let revealed: Vec<(usize, usize)> = (0..16)
+            .flat_map(|x| (0..16).map(move |y| (x, y)))
+            .filter(|&(x, y)| visible.get(x, y) != -1)
+            .collect();
+
+        // Seeding the opening finds sound deductions
+        let incremental = incremental_solve(&grid, &visible, &flags, &nc, 40, &revealed);
+        assert!(!incremental.is_empty());
+        for action in &incremental {
+            match *action {
+                Action::Flag(x, y) => assert!(mines.get(x, y)),
+                Action::Reveal(x, y) => assert!(!mines.get(x, y) && visible.get(x, y) == -1),
+            }
+        }
+
+        // Nothing changed: nothing is re-examined
+        assert!(incremental_solve(&grid, &visible, &flags, &nc, 40, &[]).is_empty());
+    }
+
+    #[test]
+    fn test_correct_wrong_flags_reveals_with_cascade() {
+        // Mine wall at x=2 (flagged correctly) and a wrong flag on the zero at
+        // (4, 1); counting proves the right side safe
+        let mut mines = Mines::new(6, 3);
+        for y in 0..3 {
+            mines.set(2, y, true);
+        }
+        let nc = NeighborCache::new(6, 3);
+        let grid = crate::board::calculate_numbers(&mines, &nc);
+        let opening = SolveState::from_start(&grid, &nc, 0, 0);
+        let mut visible = opening.visible;
+        let mut flags = Flags::new(6, 3);
+        for y in 0..3 {
+            flags.set(2, y, true);
+        }
+        flags.set(4, 1, true);
+
+        assert_eq!(correct_wrong_flags(&grid, &mut visible, &mut flags, &nc, 3), vec![(4, 1)]);
+        assert_eq!(flags.cells, mines.cells);
+        for x in 3..6 {
+            for y in 0..3 {
+                assert_eq!(visible.get(x, y), grid.get(x, y));
+            }
+        }
+    }
+
+    #[test]
+    fn test_auto_complete_after_correct_flags() {
+        // "* 1 1 *" with both mines flagged and only (1,0) revealed
+        let mut mines = Mines::new(4, 1);
+        mines.set(0, 0, true);
+        mines.set(3, 0, true);
+        let nc = NeighborCache::new(4, 1);
+        let grid = crate::board::calculate_numbers(&mines, &nc);
+        let mut flags = Flags::new(4, 1);
+        flags.set(0, 0, true);
+        flags.set(3, 0, true);
+
+        let mut visible = VisibleGrid::new(4, 1);
+        visible.set(1, 0, 1);
+        assert_eq!(auto_complete(&grid, &mut visible, &flags, &nc, 2, None), vec![(2, 0)]);
+        assert_eq!(visible.get(2, 0), 1);
+
+        // A flag off a mine is refused, whether caught by a clue or by the mines
+        let mut wrong = Flags::new(4, 1);
+        wrong.set(0, 0, true);
+        wrong.set(2, 0, true);
+        let mut visible = VisibleGrid::new(4, 1);
+        visible.set(1, 0, 1);
+        assert!(auto_complete(&grid, &mut visible, &wrong, &nc, 2, None).is_empty());
+        let mut visible = VisibleGrid::new(4, 1);
+        assert!(auto_complete(&grid, &mut visible, &wrong, &nc, 2, Some(&mines)).is_empty());
+        assert_eq!(visible.get(1, 0), -1);
+    }
+
+    #[test]
+    fn test_chord_all_cascades_and_reports_mines() {
+        // 4x1 strip "* 1 1 *" with (1,0) revealed and (0,0) flagged:
+        // the chord opens (2,0), the last safe cell
+        let mut mines = Mines::new(4, 1);
+        mines.set(0, 0, true);
+        mines.set(3, 0, true);
+        let nc = NeighborCache::new(4, 1);
+        let grid = crate::board::calculate_numbers(&mines, &nc);
+        let mut visible = VisibleGrid::new(4, 1);
+        visible.set(1, 0, 1);
+        let mut flags = Flags::new(4, 1);
+        flags.set(0, 0, true);
+
+        let result = chord_all(&grid, &mines, &mut visible, &flags, &nc);
+        assert_eq!(result.revealed_cells, vec![(2, 0)]);
+        assert!(!result.hit_mine);
+        assert!(result.won);
+
+        // The same strip with a blocked (4,0) still wins on that chord
+        let mut blocked = crate::types::Blocked::new(5, 1);
+        blocked.set(4, 0, true);
+        let holed = NeighborCache::with_blocked(&blocked);
+        let mut mines5 = Mines::new(5, 1);
+        mines5.set(0, 0, true);
+        mines5.set(3, 0, true);
+        let grid5 = crate::board::calculate_numbers(&mines5, &holed);
+        let mut visible5 = VisibleGrid::new(5, 1);
+        visible5.set(1, 0, 1);
+        let mut flags5 = Flags::new(5, 1);
+        flags5.set(0, 0, true);
+        assert!(chord_all(&grid5, &mines5, &mut visible5, &flags5, &holed).won);
+
+        // A wrong flag on (2,0) instead makes the chord open the mine at (0,0)
+        let mut visible = VisibleGrid::new(4, 1);
+        visible.set(1, 0, 1);
+        let mut flags = Flags::new(4, 1);
+        flags.set(2, 0, true);
+        let result = chord_all(&grid, &mines, &mut visible, &flags, &nc);
+        assert!(result.hit_mine);
+        assert!(!result.won);
+        assert_eq!(result.revealed_cells, vec![(0, 0)]);
+        assert_eq!(visible.get(0, 0), EXPLODED);
+    }
+
+    #[test]
+    fn test_bounded_reveal_resumes_to_full_cascade() {
+        // 10x10 with one corner mine: a reveal at the far corner cascades widely
+        let mut mines = Mines::new(10, 10);
+        mines.set(0, 0, true);
+        let nc = NeighborCache::new(10, 10);
+        let grid = crate::board::calculate_numbers(&mines, &nc);
+        let flags = Flags::new(10, 10);
+
+        let mut full = VisibleGrid::new(10, 10);
+        simulate_reveal(&grid, &mut full, &flags, &nc, 9, 9);
+
+        let mut visible = VisibleGrid::new(10, 10);
+        let mut pending = simulate_reveal_bounded(&grid, &mut visible, &flags, &nc, 9, 9, 10);
+        assert_eq!(visible.cells.iter().filter(|&&v| v != -1).count(), 10);
+        assert!(!pending.is_empty());
+
+        let mut slices = 1;
+        while !pending.is_empty() {
+            pending = resume_reveal_bounded(&grid, &mut visible, &flags, &nc, pending, 10);
+            slices += 1;
+        }
+        assert_eq!(visible.cells, full.cells);
+        assert!(slices >= 10);
+    }
+
+    #[test]
+    fn test_has_determinate_move() {
+        // 3x2 board, mine at (1,0), bottom row revealed as "1 1 1":
+        // no clue is satisfied alone, but subset logic clears (0,0) and (2,0)
+        let mut mines = Mines::new(3, 2);
+        mines.set(1, 0, true);
+        let nc = NeighborCache::new(3, 2);
+        let grid = crate::board::calculate_numbers(&mines, &nc);
+        let mut visible = VisibleGrid::new(3, 2);
+        for x in 0..3 { visible.set(x, 1, grid.get(x, 1)); }
+        let flags = Flags::new(3, 2);
+        assert!(has_determinate_move(&grid, &visible, &flags, &nc));
+
+        // 2x2 with mine at (0,0): both clues see the same two hidden cells, a 50/50
+        let mut mines = Mines::new(2, 2);
+        mines.set(0, 0, true);
+        let nc = NeighborCache::new(2, 2);
+        let grid = crate::board::calculate_numbers(&mines, &nc);
+        let mut visible = VisibleGrid::new(2, 2);
+        visible.set(0, 1, grid.get(0, 1));
+        visible.set(1, 1, grid.get(1, 1));
+        let flags = Flags::new(2, 2);
+        assert!(!has_determinate_move(&grid, &visible, &flags, &nc));
     }
 
     #[test]
@@ -1031,13 +6998,126 @@ mod tests {
         let visible = VisibleGrid::new(3, 3);
         let flags = Flags::new(3, 3);
 
-        let hint = get_hint(&grid, &visible, &flags, &mines, &nc);
+        let hint = get_hint(&grid, &visible, &flags, &mines, &nc, false, false);
         assert!(hint.is_some());
         let h = hint.unwrap();
         // Should not suggest (0,0) which is a mine
         assert!(!(h.x == 0 && h.y == 0));
     }
 
+    #[test]
+    fn test_get_hint_batch_matches_single_calls() {
+        let (grid, mines, nc) = make_simple_board();
+        let visible = VisibleGrid::new(3, 3);
+        let flags = Flags::new(3, 3);
+
+        // A fully revealed 4x1 strip has nothing left to hint
+        let strip_mines = Mines::new(4, 1);
+        let strip_nc = NeighborCache::new(4, 1);
+        let strip_grid = crate::board::calculate_numbers(&strip_mines, &strip_nc);
+        let strip_visible = VisibleGrid { width: 4, height: 1, cells: vec![0; 4] };
+        let strip_flags = Flags::new(4, 1);
+
+        let hints = get_hint_batch(&[
+            (&grid, &visible, &flags, &mines),
+            (&strip_grid, &strip_visible, &strip_flags, &strip_mines),
+            (&grid, &visible, &flags, &mines),
+        ]);
+        assert_eq!(hints.len(), 3);
+        let single = get_hint(&grid, &visible, &flags, &mines, &nc, false, false).unwrap();
+        for h in [&hints[0], &hints[2]] {
+            let h = h.as_ref().unwrap();
+            assert_eq!((h.x, h.y, h.score), (single.x, single.y, single.score));
+        }
+        assert!(hints[1].is_none());
+    }
+
+    #[test]
+    fn test_get_hint_prefer_cascade_only() {
+        // 5x1 strip "* 1 0 1 *" with nothing revealed: (1,0) and (3,0) are
+        // safe numbers, (2,0) the only safe zero
+        let mut mines = Mines::new(5, 1);
+        mines.set(0, 0, true);
+        mines.set(4, 0, true);
+        let nc = NeighborCache::new(5, 1);
+        let grid = crate::board::calculate_numbers(&mines, &nc);
+        let mut visible = VisibleGrid::new(5, 1);
+        let flags = Flags::new(5, 1);
+
+        let h = get_hint(&grid, &visible, &flags, &mines, &nc, true, false).unwrap();
+        assert_eq!((h.x, h.y), (2, 0));
+
+        // Once the zero is open only numbers remain: no cascade hint
+        simulate_reveal(&grid, &mut visible, &flags, &nc, 2, 0);
+        visible.set(1, 0, -1);
+        assert!(get_hint(&grid, &visible, &flags, &mines, &nc, true, false).is_none());
+        assert!(get_hint(&grid, &visible, &flags, &mines, &nc, false, false).is_some());
+    }
+
+    #[test]
+    fn test_get_hint_frontier_only_skips_islands() {
+        // 5x1 strip "* 1 0 1 *" with only the mine at (0,0) flagged: every
+        // safe cell is an island
+        let mut mines = Mines::new(5, 1);
+        mines.set(0, 0, true);
+        mines.set(4, 0, true);
+        let nc = NeighborCache::new(5, 1);
+        let grid = crate::board::calculate_numbers(&mines, &nc);
+        let visible = VisibleGrid::new(5, 1);
+        let mut flags = Flags::new(5, 1);
+        flags.set(0, 0, true);
+
+        assert!(get_hint(&grid, &visible, &flags, &mines, &nc, false, false).is_some());
+        assert!(get_hint(&grid, &visible, &flags, &mines, &nc, false, true).is_none());
+    }
+
+    #[test]
+    fn test_get_hint_random_picks_among_ties() {
+        // Nothing revealed: the five zeros are islands tied at the cascade bonus
+        let (grid, mines, nc) = make_simple_board();
+        let visible = VisibleGrid::new(3, 3);
+        let flags = Flags::new(3, 3);
+        let zeros = [(0, 2), (1, 2), (2, 0), (2, 1), (2, 2)];
+
+        let mut picked = std::collections::BTreeSet::new();
+        for seed in 0..20 {
+            let pick = |seed| {
+                let h = get_hint_random(&grid, &visible, &flags, &mines, &nc, &mut crate::rng::WasmRng::from_seed(seed)).unwrap();
+                (h.x, h.y, h.score)
+            };
+            let (x, y, score) = pick(seed);
+            assert_eq!(pick(seed), (x, y, score));
+            assert!(zeros.contains(&(x, y)) && score == 10);
+            picked.insert((x, y));
+        }
+        assert!(picked.len() > 1);
+
+        // The deterministic hint keeps the first tied cell
+        let h = get_hint(&grid, &visible, &flags, &mines, &nc, false, false).unwrap();
+        assert_eq!((h.x, h.y), (0, 2));
+    }
+
+    #[test]
+    fn test_get_hint_weighted_changes_choice() {
+        // 4x1 strip "* 1 0 0" with only (2,0) revealed: (1,0) and (3,0) both
+        // touch one revealed cell, but only (3,0) is a zero
+        let mut mines = Mines::new(4, 1);
+        mines.set(0, 0, true);
+        let nc = NeighborCache::new(4, 1);
+        let grid = crate::board::calculate_numbers(&mines, &nc);
+        let mut visible = VisibleGrid::new(4, 1);
+        visible.set(2, 0, 0);
+        let flags = Flags::new(4, 1);
+
+        let h = get_hint(&grid, &visible, &flags, &mines, &nc, false, false).unwrap();
+        assert_eq!((h.x, h.score), (3, 11));
+
+        // Without the cascade bonus the tie goes to the first cell
+        let weights = HintWeights { zero_bonus: 0, ..HintWeights::default() };
+        let h = get_hint_weighted(&grid, &visible, &flags, &mines, &nc, &weights).unwrap();
+        assert_eq!((h.x, h.score), (1, 1));
+    }
+
     #[test]
     fn test_enumerate_configurations() {
         // 2 cells, constraint: exactly 1 mine among both
@@ -1048,13 +7128,59 @@ mod tests {
             cells_outside_count: 0,
         }];
 
-        let configs = enumerate_configurations(&region, &constraints, 5);
+        let configs = enumerate_configurations_with_tolerance(&region, &constraints, 5, 0);
         // Valid: 01 (mask=1) and 10 (mask=2)
         assert_eq!(configs.len(), 2);
         assert!(configs.contains(&1)); // cell 0 is mine
         assert!(configs.contains(&2)); // cell 1 is mine
     }
 
+    #[test]
+    fn test_tank_cache_reuses_identical_region() {
+        // Two regions at different positions with the same constraint shape:
+        // a clue of 1 over both cells, plus a clue of 1 over the first only
+        let constraints = || vec![
+            RegionConstraint { remaining: 1, cells_in_region_indices: vec![0, 1], cells_outside_count: 0 },
+            RegionConstraint { remaining: 1, cells_in_region_indices: vec![0], cells_outside_count: 0 },
+        ];
+        let a = vec![(0usize, 0usize), (1, 0)];
+        let b = vec![(5usize, 7usize), (6, 7)];
+
+        clear_tank_cache();
+        let first = cached_definite_masks(&a, &constraints(), 10, 0);
+        let second = cached_definite_masks(&b, &constraints(), 10, 0);
+        assert_eq!(first, (0b01, 0b10));
+        assert_eq!(first, second);
+        assert_eq!(tank_cache_stats(), (1, 1));
+
+        // A tighter mine budget is part of the key
+        cached_definite_masks(&a, &constraints(), 0, 0);
+        assert_eq!(tank_cache_stats(), (1, 2));
+    }
+
+    #[test]
+    fn test_region_order_is_stable() {
+        // 7x2 with clue columns at x=1 and x=5: two frontier regions,
+        // columns {0, 2} and {4, 6}
+        let nc = NeighborCache::new(7, 2);
+        let mut visible = VisibleGrid::new(7, 2);
+        for y in 0..2 {
+            visible.set(1, y, 1);
+            visible.set(5, y, 1);
+        }
+        let flags = Flags::new(7, 2);
+        let frontier = get_frontier(&visible, &flags, &nc);
+        let reversed: Vec<_> = frontier.iter().rev().copied().collect();
+
+        let a = group_frontier_regions(&frontier, &visible, &nc, None);
+        let b = group_frontier_regions(&reversed, &visible, &nc, None);
+        assert_eq!(a, b);
+        assert_eq!(a, vec![
+            vec![(0, 0), (0, 1), (2, 0), (2, 1)],
+            vec![(4, 0), (4, 1), (6, 0), (6, 1)],
+        ]);
+    }
+
     #[test]
     fn test_analyze_finds_definite() {
         let region = vec![(0usize, 0usize), (1, 0), (2, 0)];
@@ -1068,4 +7194,35 @@ mod tests {
         assert!(definite_mines.contains(&(0, 0))); // always mine
         assert!(definite_safes.contains(&(2, 0))); // always safe
     }
+
+    #[test]
+    fn test_flags_to_autocomplete_near_complete() {
+        // Everything open but the mine and the far corner: one flag finishes it
+        let (grid, _mines, nc) = make_simple_board();
+        let mut visible = VisibleGrid::new(3, 3);
+        for x in 0..3 {
+            for y in 0..3 {
+                if (x, y) != (0, 0) && (x, y) != (2, 2) { visible.set(x, y, grid.get(x, y)); }
+            }
+        }
+        let mut flags = Flags::new(3, 3);
+        assert_eq!(flags_to_autocomplete(&visible, &flags, &nc, 1), Some(1));
+        flags.set(0, 0, true);
+        assert_eq!(flags_to_autocomplete(&visible, &flags, &nc, 1), Some(0));
+
+        // A 50/50 proves no mine, so flags alone cannot finish it
+        let nc = NeighborCache::new(2, 2);
+        let visible = VisibleGrid::from_flat(2, 2, &[1, 1, -1, -1]).unwrap();
+        assert_eq!(flags_to_autocomplete(&visible, &Flags::new(2, 2), &nc, 1), None);
+    }
+
+    #[test]
+    fn test_tank_probabilities_fifty_fifty() {
+        // 2x2, left column revealed as 1s: one mine in the right column, either cell
+        let nc = NeighborCache::new(2, 2);
+        let visible = VisibleGrid::from_flat(2, 2, &[1, 1, -1, -1]).unwrap();
+        let flags = Flags::new(2, 2);
+        assert_eq!(tank_probabilities(&visible, &flags, &nc, Some(1)), vec![(1, 0, 0.5), (1, 1, 0.5)]);
+        assert_eq!(tank_probabilities(&visible, &flags, &nc, None), vec![(1, 0, 0.5), (1, 1, 0.5)]);
+    }
 }
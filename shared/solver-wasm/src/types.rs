@@ -3,6 +3,8 @@
 //! All grid types use flat `Vec` storage with column-major layout:
 //! `cells[x * height + y]` maps to the JS equivalent `grid[x][y]`.
 
+use std::sync::OnceLock;
+
 /// Bit-pack (x, y) into a single u32 key, matching JS `(x << 16) | y`.
 #[inline(always)]
 pub fn cell_key(x: usize, y: usize) -> u32 {
@@ -15,6 +17,56 @@ pub fn decode_key(key: u32) -> (usize, usize) {
     ((key >> 16) as usize, (key & 0xFFFF) as usize)
 }
 
+/// Largest board (in cells) any constructor accepts. Guards against
+/// malformed dimensions allocating gigabytes of neighbor data.
+pub const MAX_BOARD_CELLS: usize = 1 << 20;
+
+/// Longest side any constructor accepts: `cell_key()` packs each coordinate
+/// into 16 bits.
+pub const MAX_BOARD_SIDE: usize = 0xFFFF;
+
+/// `width * height`, or `None` if it overflows, exceeds `MAX_BOARD_CELLS`, or
+/// either side exceeds `MAX_BOARD_SIDE`.
+pub fn board_cell_count(width: usize, height: usize) -> Option<usize> {
+    if width > MAX_BOARD_SIDE || height > MAX_BOARD_SIDE {
+        return None;
+    }
+    width.checked_mul(height).filter(|&total| total <= MAX_BOARD_CELLS)
+}
+
+/// Why a flat cell array could not become a grid type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GridError {
+    /// `width * height` overflows or exceeds `MAX_BOARD_CELLS`, or a side
+    /// exceeds `MAX_BOARD_SIDE`.
+    TooLarge { width: usize, height: usize },
+    /// The array does not hold exactly `width * height` cells.
+    LengthMismatch { kind: &'static str, width: usize, height: usize, actual: usize },
+}
+
+impl std::fmt::Display for GridError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            GridError::TooLarge { width, height } => write!(
+                f, "Board dimensions {}x{} exceed the {}-cell or {}-per-side limit",
+                width, height, MAX_BOARD_CELLS, MAX_BOARD_SIDE
+            ),
+            GridError::LengthMismatch { kind, width, height, actual } => write!(
+                f, "{} array has {} cells, expected {} for {}x{}", kind, actual, width * height, width, height
+            ),
+        }
+    }
+}
+
+/// Shared validation for the `from_flat()` constructors.
+fn check_flat_len(kind: &'static str, width: usize, height: usize, actual: usize) -> Result<(), GridError> {
+    let total = board_cell_count(width, height).ok_or(GridError::TooLarge { width, height })?;
+    if actual != total {
+        return Err(GridError::LengthMismatch { kind, width, height, actual });
+    }
+    Ok(())
+}
+
 /// The actual grid values: 0 = empty, 1-8 = neighbor mine count.
 /// For mine cells during placement, the value is set to the count after `calculate_numbers`.
 #[derive(Clone)]
@@ -33,6 +85,18 @@ impl Grid {
         }
     }
 
+    /// Copy a flat column-major array, checking it holds exactly `width * height` cells.
+    pub fn from_flat(width: usize, height: usize, cells: &[i8]) -> Result<Self, GridError> {
+        check_flat_len("Grid", width, height, cells.len())?;
+        Ok(Self { width, height, cells: cells.to_vec() })
+    }
+
+    /// The cells as a flat column-major slice, the layout `from_flat()` takes.
+    #[inline(always)]
+    pub fn as_flat(&self) -> &[i8] {
+        &self.cells
+    }
+
     #[inline(always)]
     pub fn get(&self, x: usize, y: usize) -> i8 {
         self.cells[x * self.height + y]
@@ -47,9 +111,31 @@ impl Grid {
     pub fn in_bounds(&self, x: usize, y: usize) -> bool {
         x < self.width && y < self.height
     }
+
+    /// Bounds-checked `get`: `None` if `(x, y)` is outside the grid.
+    #[inline(always)]
+    pub fn get_checked(&self, x: usize, y: usize) -> Option<i8> {
+        if self.in_bounds(x, y) { Some(self.get(x, y)) } else { None }
+    }
+
+    /// Bounds-checked `set`: returns false (and does nothing) if out of bounds.
+    #[inline(always)]
+    pub fn set_checked(&mut self, x: usize, y: usize, val: i8) -> bool {
+        if !self.in_bounds(x, y) { return false; }
+        self.set(x, y, val);
+        true
+    }
 }
 
-/// Visible state of each cell: -1 = hidden, 0-8 = revealed number, 9 = exploded bomb.
+/// Sentinel written into a `VisibleGrid` cell when a revealed mine explodes.
+pub const EXPLODED: i8 = 9;
+
+/// Sentinel for cells that are not part of an irregular board, in both `Grid`
+/// and `VisibleGrid`. Negative, so it is never read as a clue, and not -1,
+/// so it is never hidden, frontier or counted against the mines.
+pub const BLOCKED: i8 = -2;
+
+/// Visible state of each cell: -1 = hidden, 0-8 = revealed number, `EXPLODED` = exploded bomb.
 #[derive(Clone)]
 pub struct VisibleGrid {
     pub width: usize,
@@ -67,6 +153,18 @@ impl VisibleGrid {
         }
     }
 
+    /// Copy a flat column-major array, checking it holds exactly `width * height` cells.
+    pub fn from_flat(width: usize, height: usize, cells: &[i8]) -> Result<Self, GridError> {
+        check_flat_len("VisibleGrid", width, height, cells.len())?;
+        Ok(Self { width, height, cells: cells.to_vec() })
+    }
+
+    /// The cells as a flat column-major slice, the layout `from_flat()` takes.
+    #[inline(always)]
+    pub fn as_flat(&self) -> &[i8] {
+        &self.cells
+    }
+
     #[inline(always)]
     pub fn get(&self, x: usize, y: usize) -> i8 {
         self.cells[x * self.height + y]
@@ -76,6 +174,25 @@ impl VisibleGrid {
     pub fn set(&mut self, x: usize, y: usize, val: i8) {
         self.cells[x * self.height + y] = val;
     }
+
+    #[inline(always)]
+    pub fn in_bounds(&self, x: usize, y: usize) -> bool {
+        x < self.width && y < self.height
+    }
+
+    /// Bounds-checked `get`: `None` if `(x, y)` is outside the grid.
+    #[inline(always)]
+    pub fn get_checked(&self, x: usize, y: usize) -> Option<i8> {
+        if self.in_bounds(x, y) { Some(self.get(x, y)) } else { None }
+    }
+
+    /// Bounds-checked `set`: returns false (and does nothing) if out of bounds.
+    #[inline(always)]
+    pub fn set_checked(&mut self, x: usize, y: usize, val: i8) -> bool {
+        if !self.in_bounds(x, y) { return false; }
+        self.set(x, y, val);
+        true
+    }
 }
 
 /// Boolean flag state for each cell.
@@ -96,6 +213,18 @@ impl Flags {
         }
     }
 
+    /// Copy a flat column-major array, checking it holds exactly `width * height` cells.
+    pub fn from_flat(width: usize, height: usize, cells: &[u8]) -> Result<Self, GridError> {
+        check_flat_len("Flags", width, height, cells.len())?;
+        Ok(Self { width, height, cells: cells.to_vec() })
+    }
+
+    /// The cells as a flat column-major slice, the layout `from_flat()` takes.
+    #[inline(always)]
+    pub fn as_flat(&self) -> &[u8] {
+        &self.cells
+    }
+
     #[inline(always)]
     pub fn get(&self, x: usize, y: usize) -> bool {
         self.cells[x * self.height + y] != 0
@@ -105,6 +234,25 @@ impl Flags {
     pub fn set(&mut self, x: usize, y: usize, val: bool) {
         self.cells[x * self.height + y] = val as u8;
     }
+
+    #[inline(always)]
+    pub fn in_bounds(&self, x: usize, y: usize) -> bool {
+        x < self.width && y < self.height
+    }
+
+    /// Bounds-checked `get`: `None` if `(x, y)` is outside the grid.
+    #[inline(always)]
+    pub fn get_checked(&self, x: usize, y: usize) -> Option<bool> {
+        if self.in_bounds(x, y) { Some(self.get(x, y)) } else { None }
+    }
+
+    /// Bounds-checked `set`: returns false (and does nothing) if out of bounds.
+    #[inline(always)]
+    pub fn set_checked(&mut self, x: usize, y: usize, val: bool) -> bool {
+        if !self.in_bounds(x, y) { return false; }
+        self.set(x, y, val);
+        true
+    }
 }
 
 /// Mine positions for each cell (same layout as Flags).
@@ -124,6 +272,32 @@ impl Mines {
         }
     }
 
+    /// Copy a flat column-major array, checking it holds exactly `width * height` cells.
+    pub fn from_flat(width: usize, height: usize, cells: &[u8]) -> Result<Self, GridError> {
+        check_flat_len("Mines", width, height, cells.len())?;
+        Ok(Self { width, height, cells: cells.to_vec() })
+    }
+
+    /// Test fixture from one string per row, top to bottom: `'*'` is a mine,
+    /// anything else is clear. Every row must be as long as the first.
+    #[cfg(test)]
+    pub(crate) fn from_rows(rows: &[&str]) -> Self {
+        let mut mines = Self::new(rows[0].len(), rows.len());
+        for (y, row) in rows.iter().enumerate() {
+            assert_eq!(row.len(), mines.width, "row {} has the wrong length", y);
+            for (x, c) in row.chars().enumerate() {
+                mines.set(x, y, c == '*');
+            }
+        }
+        mines
+    }
+
+    /// The cells as a flat column-major slice, the layout `from_flat()` takes.
+    #[inline(always)]
+    pub fn as_flat(&self) -> &[u8] {
+        &self.cells
+    }
+
     #[inline(always)]
     pub fn get(&self, x: usize, y: usize) -> bool {
         self.cells[x * self.height + y] != 0
@@ -134,10 +308,142 @@ impl Mines {
         self.cells[x * self.height + y] = val as u8;
     }
 
+    #[inline(always)]
+    pub fn in_bounds(&self, x: usize, y: usize) -> bool {
+        x < self.width && y < self.height
+    }
+
+    /// Bounds-checked `get`: `None` if `(x, y)` is outside the grid.
+    #[inline(always)]
+    pub fn get_checked(&self, x: usize, y: usize) -> Option<bool> {
+        if self.in_bounds(x, y) { Some(self.get(x, y)) } else { None }
+    }
+
+    /// Bounds-checked `set`: returns false (and does nothing) if out of bounds.
+    #[inline(always)]
+    pub fn set_checked(&mut self, x: usize, y: usize, val: bool) -> bool {
+        if !self.in_bounds(x, y) { return false; }
+        self.set(x, y, val);
+        true
+    }
+
     /// Count total mines on the board.
     pub fn count(&self) -> usize {
         self.cells.iter().filter(|&&v| v != 0).count()
     }
+
+    /// Count mines in the `w` x `h` rectangle whose top-left cell is `(x, y)`,
+    /// clipped to the board. Each column of the rectangle is one contiguous
+    /// slice of `cells`.
+    pub fn count_in_region(&self, x: usize, y: usize, w: usize, h: usize) -> usize {
+        let x_end = x.saturating_add(w).min(self.width);
+        let y_end = y.saturating_add(h).min(self.height);
+        if y >= y_end { return 0; }
+
+        (x..x_end)
+            .map(|cx| {
+                let col = cx * self.height;
+                self.cells[col + y..col + y_end].iter().filter(|&&v| v != 0).count()
+            })
+            .sum()
+    }
+}
+
+/// Cells cut out of the board for irregular shapes (same layout as Flags):
+/// neither mine nor playable. See `NeighborCache::with_blocked()`.
+#[derive(Clone)]
+pub struct Blocked {
+    pub width: usize,
+    pub height: usize,
+    pub cells: Vec<u8>,
+}
+
+impl Blocked {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            cells: vec![0; width * height],
+        }
+    }
+
+    /// Copy a flat column-major array, checking it holds exactly `width * height` cells.
+    pub fn from_flat(width: usize, height: usize, cells: &[u8]) -> Result<Self, GridError> {
+        check_flat_len("Blocked", width, height, cells.len())?;
+        Ok(Self { width, height, cells: cells.to_vec() })
+    }
+
+    #[inline(always)]
+    pub fn get(&self, x: usize, y: usize) -> bool {
+        self.cells[x * self.height + y] != 0
+    }
+
+    #[inline(always)]
+    pub fn set(&mut self, x: usize, y: usize, val: bool) {
+        self.cells[x * self.height + y] = val as u8;
+    }
+
+    /// Count blocked cells.
+    pub fn count(&self) -> usize {
+        self.cells.iter().filter(|&&v| v != 0).count()
+    }
+}
+
+/// A square region around a start click where no mines may be placed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SafeZone {
+    pub x: usize,
+    pub y: usize,
+    /// Chebyshev radius: radius 1 is the 3×3 block around `(x, y)`.
+    pub radius: usize,
+}
+
+impl SafeZone {
+    pub fn new(x: usize, y: usize, radius: usize) -> Self {
+        Self { x, y, radius }
+    }
+
+    /// True if `(x, y)` lies within the zone.
+    #[inline]
+    pub fn contains(&self, x: usize, y: usize) -> bool {
+        x.abs_diff(self.x) <= self.radius && y.abs_diff(self.y) <= self.radius
+    }
+}
+
+/// A rectangle of cells, corners inclusive, where no mines may be placed.
+///
+/// Generalizes `SafeZone` to openings that are not square, e.g. a whole row.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SafeRect {
+    pub x0: usize,
+    pub y0: usize,
+    pub x1: usize,
+    pub y1: usize,
+}
+
+impl SafeRect {
+    /// The rectangle spanned by two opposite corners, in either order.
+    pub fn new(x0: usize, y0: usize, x1: usize, y1: usize) -> Self {
+        Self { x0: x0.min(x1), y0: y0.min(y1), x1: x0.max(x1), y1: y0.max(y1) }
+    }
+
+    /// True if `(x, y)` lies within the rectangle.
+    #[inline]
+    pub fn contains(&self, x: usize, y: usize) -> bool {
+        (self.x0..=self.x1).contains(&x) && (self.y0..=self.y1).contains(&y)
+    }
+}
+
+impl From<SafeZone> for SafeRect {
+    /// The zone's square, unclipped; cells past the board edge are never asked about.
+    fn from(zone: SafeZone) -> Self {
+        Self {
+            x0: zone.x.saturating_sub(zone.radius),
+            y0: zone.y.saturating_sub(zone.radius),
+            x1: zone.x.saturating_add(zone.radius),
+            y1: zone.y.saturating_add(zone.radius),
+        }
+    }
 }
 
 /// Pre-computed neighbor cache for all cells.
@@ -152,26 +458,104 @@ pub struct NeighborCache {
     /// offsets[i] = start index in `data` for cell i.
     /// offsets[i+1] - offsets[i] = number of neighbors for cell i.
     offsets: Vec<usize>,
+    wrap: bool,
+    /// Blocked cells left out of every neighborhood, if any.
+    blocked: Option<Vec<u8>>,
+    /// Distance-2 neighborhoods, built on first use by `ring2()`.
+    ring2: OnceLock<Box<NeighborCache>>,
 }
 
 impl NeighborCache {
     /// Build the neighbor cache for a grid of the given dimensions.
+    ///
+    /// Panics if the board exceeds `MAX_BOARD_CELLS`; use `try_new()` for
+    /// untrusted dimensions.
     pub fn new(width: usize, height: usize) -> Self {
-        let total = width * height;
-        // Each cell has at most 8 neighbors, pre-allocate generously
-        let mut data = Vec::with_capacity(total * 8);
+        Self::try_new(width, height).expect("board dimensions exceed MAX_BOARD_CELLS")
+    }
+
+    /// Build the neighbor cache, or `None` if `width * height` overflows or
+    /// exceeds `MAX_BOARD_CELLS`.
+    pub fn try_new(width: usize, height: usize) -> Option<Self> {
+        Self::build(width, height, false, 1)
+    }
+
+    /// Build a cache of distance-2 neighborhoods: every cell within Chebyshev
+    /// distance 2 (up to 24), clipped to grid bounds, in the same `dx`-major
+    /// order as the 8-neighbor cache. Panics like `new()` on oversized boards.
+    pub fn new_ring2(width: usize, height: usize) -> Self {
+        Self::build(width, height, false, 2).expect("board dimensions exceed MAX_BOARD_CELLS")
+    }
+
+    /// Build a neighbor cache for a torus: edges wrap, so every cell has 8
+    /// neighbors (fewer on boards narrower than 3, where wrapped neighbors
+    /// coincide). Panics like `new()` on oversized boards.
+    pub fn new_toroidal(width: usize, height: usize) -> Self {
+        Self::build(width, height, true, 1).expect("board dimensions exceed MAX_BOARD_CELLS")
+    }
+
+    /// Build a neighbor cache for an irregular board: blocked cells have no
+    /// neighbors and appear in no neighborhood, so `calculate_numbers()`
+    /// skips them and the strategies never see them. Panics like `new()` on
+    /// oversized boards.
+    pub fn with_blocked(blocked: &Blocked) -> Self {
+        Self::try_with_blocked(blocked).expect("board dimensions exceed MAX_BOARD_CELLS")
+    }
+
+    /// `with_blocked()`, or `None` for oversized dimensions.
+    pub fn try_with_blocked(blocked: &Blocked) -> Option<Self> {
+        let mut cache = Self::build(blocked.width, blocked.height, false, 1)?;
+        cache.remove_blocked(&blocked.cells);
+        Some(cache)
+    }
+
+    /// True if `(x, y)` was blocked when this cache was built.
+    #[inline(always)]
+    pub fn is_blocked(&self, x: usize, y: usize) -> bool {
+        self.blocked.as_ref().is_some_and(|b| b[x * self.height + y] != 0)
+    }
+
+    /// Drop blocked cells from the layout and remember them for `ring2()`.
+    fn remove_blocked(&mut self, blocked: &[u8]) {
+        let height = self.height;
+        let mut data = Vec::with_capacity(self.data.len());
+        let mut offsets = Vec::with_capacity(self.offsets.len());
+        for (i, window) in self.offsets.windows(2).enumerate() {
+            offsets.push(data.len());
+            if blocked[i] != 0 { continue; }
+            data.extend(self.data[window[0]..window[1]].iter().filter(|&&(nx, ny)| blocked[nx * height + ny] == 0));
+        }
+        offsets.push(data.len());
+        self.data = data;
+        self.offsets = offsets;
+        self.blocked = Some(blocked.to_vec());
+    }
+
+    fn build(width: usize, height: usize, wrap: bool, radius: i32) -> Option<Self> {
+        let total = board_cell_count(width, height)?;
+        // Each cell has at most (2r+1)^2 - 1 neighbors, pre-allocate generously
+        let side = 2 * radius as usize + 1;
+        let mut data = Vec::with_capacity(total * (side * side - 1));
         let mut offsets = Vec::with_capacity(total + 1);
 
         for x in 0..width {
             for y in 0..height {
                 offsets.push(data.len());
-                for dx in -1i32..=1 {
-                    for dy in -1i32..=1 {
+                for dx in -radius..=radius {
+                    for dy in -radius..=radius {
                         if dx == 0 && dy == 0 {
                             continue;
                         }
-                        let nx = x as i32 + dx;
-                        let ny = y as i32 + dy;
+                        let mut nx = x as i32 + dx;
+                        let mut ny = y as i32 + dy;
+                        if wrap {
+                            nx = nx.rem_euclid(width as i32);
+                            ny = ny.rem_euclid(height as i32);
+                            let n = (nx as usize, ny as usize);
+                            if n == (x, y) || data[offsets[offsets.len() - 1]..].contains(&n) {
+                                continue;
+                            }
+                        }
                         if nx >= 0 && nx < width as i32 && ny >= 0 && ny < height as i32 {
                             data.push((nx as usize, ny as usize));
                         }
@@ -181,12 +565,29 @@ impl NeighborCache {
         }
         offsets.push(data.len()); // sentinel
 
-        Self {
+        Some(Self {
             width,
             height,
             data,
             offsets,
-        }
+            wrap,
+            blocked: None,
+            ring2: OnceLock::new(),
+        })
+    }
+
+    /// The distance-2 neighborhoods of this board (see `new_ring2()`), wrapping
+    /// like this cache does. Built once on first call and kept with the cache,
+    /// so callers that reuse one cache across solves pay for it once.
+    pub fn ring2(&self) -> &NeighborCache {
+        self.ring2.get_or_init(|| {
+            let mut ring = Self::build(self.width, self.height, self.wrap, 2)
+                .expect("dimensions were validated when this cache was built");
+            if let Some(blocked) = &self.blocked {
+                ring.remove_blocked(blocked);
+            }
+            Box::new(ring)
+        })
     }
 
     /// Get the pre-computed neighbors for cell (x, y).
@@ -197,12 +598,119 @@ impl NeighborCache {
         let end = self.offsets[idx + 1];
         &self.data[start..end]
     }
+
+    /// Export the CSR layout: `offsets` (length `width * height + 1`) and the
+    /// neighbors of every cell as packed `cell_key`s.
+    pub fn to_csr(&self) -> (Vec<u32>, Vec<u32>) {
+        let offsets = self.offsets.iter().map(|&o| o as u32).collect();
+        let keys = self.data.iter().map(|&(x, y)| cell_key(x, y)).collect();
+        (offsets, keys)
+    }
+}
+
+/// Per-neighbor clue weights, laid out like the `NeighborCache` they were
+/// built from ("weighted clue" variant): `get(x, y)[i]` is the weight of
+/// `nc.get(x, y)[i]`.
+///
+/// A neighbor sharing a row or column with its cell is orthogonal, any other
+/// diagonal. Weights below 1 are raised to 1, so a 0 clue still means no
+/// adjacent mines and the opening cascade stays sound.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NeighborWeights {
+    pub width: usize,
+    pub height: usize,
+    weights: Vec<u8>,
+    offsets: Vec<usize>,
+}
+
+impl NeighborWeights {
+    /// Tag every neighbor in `nc` with `diagonal_weight` or `orthogonal_weight`.
+    pub fn new(nc: &NeighborCache, diagonal_weight: u8, orthogonal_weight: u8) -> Self {
+        let (diagonal, orthogonal) = (diagonal_weight.max(1), orthogonal_weight.max(1));
+        let mut weights = Vec::with_capacity(nc.data.len());
+        for x in 0..nc.width {
+            for y in 0..nc.height {
+                weights.extend(nc.get(x, y).iter().map(|&(nx, ny)| {
+                    if nx == x || ny == y { orthogonal } else { diagonal }
+                }));
+            }
+        }
+        Self { width: nc.width, height: nc.height, weights, offsets: nc.offsets.clone() }
+    }
+
+    /// Weights of the neighbors of cell (x, y), in `NeighborCache::get()` order.
+    #[inline(always)]
+    pub fn get(&self, x: usize, y: usize) -> &[u8] {
+        let idx = x * self.height + y;
+        &self.weights[self.offsets[idx]..self.offsets[idx + 1]]
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_neighbor_cache_csr_export() {
+        let nc = NeighborCache::new(3, 2);
+        let (offsets, keys) = nc.to_csr();
+        assert_eq!(offsets.len(), 7);
+        assert_eq!(*offsets.last().unwrap() as usize, keys.len());
+
+        // Cell (1,0) is index 1*2+0 = 2
+        let (start, end) = (offsets[2] as usize, offsets[3] as usize);
+        let decoded: Vec<(usize, usize)> = keys[start..end].iter().map(|&k| decode_key(k)).collect();
+        assert_eq!(decoded, nc.get(1, 0));
+    }
+
+    #[test]
+    fn test_ring2_neighborhood_sizes() {
+        let ring = NeighborCache::new_ring2(7, 6);
+        assert_eq!(ring.get(0, 0).len(), 8); // corner: 3x3 block minus itself
+        assert_eq!(ring.get(3, 0).len(), 14); // edge: 5x3 block minus itself
+        assert_eq!(ring.get(1, 1).len(), 15); // one in from the corner: 4x4 minus itself
+        assert_eq!(ring.get(3, 3).len(), 24); // center: full 5x5 minus itself
+        assert!(!ring.get(3, 3).contains(&(3, 3)));
+
+        let nc = NeighborCache::new(7, 6);
+        assert_eq!(nc.ring2().get(3, 3), ring.get(3, 3));
+        assert_eq!(NeighborCache::new_toroidal(7, 6).ring2().get(0, 0).len(), 24);
+    }
+
+    #[test]
+    fn test_from_flat_checks_length() {
+        let grid = Grid::from_flat(3, 2, &[0, 1, 2, 3, 4, 5]).unwrap();
+        assert_eq!(grid.get(1, 0), 2);
+        assert_eq!(grid.as_flat(), &[0, 1, 2, 3, 4, 5]);
+
+        assert_eq!(
+            Flags::from_flat(3, 2, &[0; 5]).err(),
+            Some(GridError::LengthMismatch { kind: "Flags", width: 3, height: 2, actual: 5 })
+        );
+        assert!(VisibleGrid::from_flat(3, 2, &[-1; 7]).is_err());
+        assert_eq!(
+            Mines::from_flat(usize::MAX, 2, &[]).err(),
+            Some(GridError::TooLarge { width: usize::MAX, height: 2 })
+        );
+        assert_eq!(
+            Flags::from_flat(3, 2, &[0; 5]).err().map(|e| e.to_string()).as_deref(),
+            Some("Flags array has 5 cells, expected 6 for 3x2")
+        );
+    }
+
+    #[test]
+    fn test_oversized_board_rejected() {
+        assert!(NeighborCache::try_new(usize::MAX, 2).is_none());
+        assert!(NeighborCache::try_new(100_000, 100_000).is_none());
+        assert_eq!(board_cell_count(usize::MAX, 2), None);
+        assert_eq!(board_cell_count(30, 16), Some(480));
+        // Within the cell budget, but too long a side for `cell_key()`
+        assert_eq!(board_cell_count(MAX_BOARD_SIDE + 1, 1), None);
+        assert_eq!(board_cell_count(1, MAX_BOARD_SIDE + 1), None);
+        assert_eq!(board_cell_count(MAX_BOARD_SIDE, 1), Some(MAX_BOARD_SIDE));
+        assert!(NeighborCache::try_new(30, 16).is_some());
+    }
+
     #[test]
     fn test_cell_key_roundtrip() {
         for x in 0..50 {
@@ -222,6 +730,23 @@ mod tests {
         assert_eq!(g.get(0, 0), 0);
     }
 
+    #[test]
+    fn test_checked_access_out_of_bounds() {
+        let mut g = Grid::new(4, 3);
+        assert!(g.set_checked(3, 2, 5));
+        assert_eq!(g.get_checked(3, 2), Some(5));
+        assert_eq!(g.get_checked(4, 0), None);
+        assert_eq!(g.get_checked(0, 3), None);
+        assert!(!g.set_checked(4, 0, 1));
+
+        let mut f = Flags::new(4, 3);
+        assert!(!f.set_checked(0, 3, true));
+        assert_eq!(f.get_checked(usize::MAX, 0), None);
+
+        let v = VisibleGrid::new(4, 3);
+        assert_eq!(v.get_checked(1, 1), Some(-1));
+    }
+
     #[test]
     fn test_neighbor_cache_corners() {
         let nc = NeighborCache::new(5, 5);
@@ -255,4 +780,19 @@ mod tests {
         m.set(4, 4, true);
         assert_eq!(m.count(), 3);
     }
+
+    #[test]
+    fn test_mines_count_in_region() {
+        let mut m = Mines::new(5, 4);
+        m.set(0, 0, true);
+        m.set(1, 2, true);
+        m.set(2, 1, true);
+        m.set(4, 3, true);
+        assert_eq!(m.count_in_region(1, 1, 2, 2), 2);
+        assert_eq!(m.count_in_region(0, 0, 5, 4), m.count());
+        // Clipped at the far edges; empty when it starts off the board
+        assert_eq!(m.count_in_region(3, 2, 10, 10), 1);
+        assert_eq!(m.count_in_region(5, 0, 1, 1), 0);
+        assert_eq!(m.count_in_region(0, 4, 1, 1), 0);
+    }
 }